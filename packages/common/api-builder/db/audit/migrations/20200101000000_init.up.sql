@@ -0,0 +1,17 @@
+CREATE TABLE IF NOT EXISTS requests
+(
+	ts DateTime64(3),
+	ray_id String,
+	method LowCardinality(String),
+	path String,
+	namespace LowCardinality(String),
+	client_ip String,
+	user_agent String,
+	token_hash String,
+	status UInt16
+)
+ENGINE = ReplicatedMergeTree()
+PARTITION BY toStartOfDay(ts)
+ORDER BY (namespace, ts, ray_id)
+TTL toDate(ts + toIntervalDay(90))
+SETTINGS index_granularity = 8192, ttl_only_drop_parts = 1;
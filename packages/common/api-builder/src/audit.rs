@@ -0,0 +1,199 @@
+use std::{collections::HashMap, net::SocketAddr};
+
+use anyhow::*;
+use axum::{
+	extract::{ConnectInfo, Query, Request, State},
+	http::Method,
+	middleware::Next,
+	response::Response,
+};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tokio::{
+	fs::File,
+	io::{AsyncWriteExt, BufWriter},
+	sync::mpsc,
+};
+
+/// Records mutating requests to the sink configured in `api_public.audit_log`.
+#[derive(Clone)]
+pub struct AuditLogger {
+	sink: AuditSink,
+	respect_forwarded_for: bool,
+}
+
+#[derive(Clone)]
+enum AuditSink {
+	/// Lines are written by a background task so requests never wait on the file.
+	File(mpsc::UnboundedSender<Vec<u8>>),
+	ClickHouse(rivet_pools::ClickHouseInserterHandle),
+}
+
+#[derive(Serialize)]
+struct AuditEntry {
+	/// Milliseconds since epoch.
+	ts: i64,
+	ray_id: String,
+	method: String,
+	path: String,
+	namespace: String,
+	client_ip: String,
+	user_agent: String,
+	/// Identifies the caller by the first 16 hex characters of the SHA-256 of the bearer token.
+	/// Empty if the request has no token.
+	token_hash: String,
+	status: u16,
+}
+
+impl AuditLogger {
+	/// Returns `None` if audit logging is not configured.
+	pub async fn from_config(
+		config: &rivet_config::Config,
+		pools: &rivet_pools::Pools,
+	) -> Result<Option<Self>> {
+		let api_public = config.api_public();
+		let Some(audit_log) = &api_public.audit_log else {
+			return Ok(None);
+		};
+
+		let sink = match audit_log {
+			rivet_config::config::AuditLog::File { path } => {
+				let file = tokio::fs::OpenOptions::new()
+					.create(true)
+					.append(true)
+					.open(path)
+					.await
+					.with_context(|| format!("failed to open audit log file {}", path.display()))?;
+
+				let (tx, rx) = mpsc::unbounded_channel();
+				tokio::spawn(write_file(file, rx));
+
+				AuditSink::File(tx)
+			}
+			rivet_config::config::AuditLog::ClickHouse => {
+				AuditSink::ClickHouse(pools.clickhouse_inserter()?)
+			}
+		};
+
+		Ok(Some(AuditLogger {
+			sink,
+			respect_forwarded_for: api_public.respect_forwarded_for(),
+		}))
+	}
+
+	fn write(&self, entry: AuditEntry) -> Result<()> {
+		match &self.sink {
+			AuditSink::File(tx) => {
+				let mut line = serde_json::to_vec(&entry)?;
+				line.push(b'\n');
+
+				tx.send(line)
+					.map_err(|_| anyhow!("audit log writer stopped"))?;
+			}
+			AuditSink::ClickHouse(inserter) => {
+				inserter.insert("db_api_audit", "requests", entry)?;
+			}
+		}
+
+		Ok(())
+	}
+}
+
+/// Appends lines to the audit log file. Flushes once no more lines are queued so bursts of
+/// requests share a single flush.
+async fn write_file(file: File, mut rx: mpsc::UnboundedReceiver<Vec<u8>>) {
+	let mut file = BufWriter::new(file);
+
+	while let Some(line) = rx.recv().await {
+		let mut res = file.write_all(&line).await;
+
+		// Write the rest of the queued lines before flushing
+		while res.is_ok() {
+			let Result::Ok(line) = rx.try_recv() else {
+				break;
+			};
+			res = file.write_all(&line).await;
+		}
+
+		if let Err(err) = res {
+			tracing::error!(?err, "failed to write audit log file");
+		} else if let Err(err) = file.flush().await {
+			tracing::error!(?err, "failed to flush audit log file");
+		}
+	}
+}
+
+/// Writes an audit entry for every POST, PUT, PATCH and DELETE request once the response is
+/// ready. Must be installed outside of `http_logging_middleware` so the ray id header is set.
+pub async fn audit_log_middleware(
+	State(logger): State<AuditLogger>,
+	req: Request,
+	next: Next,
+) -> Response {
+	if !matches!(
+		*req.method(),
+		Method::POST | Method::PUT | Method::PATCH | Method::DELETE
+	) {
+		return next.run(req).await;
+	}
+
+	let ts = chrono::Utc::now().timestamp_millis();
+	let method = req.method().to_string();
+	let path = req.uri().path().to_string();
+	let namespace = Query::<HashMap<String, String>>::try_from_uri(req.uri())
+		.ok()
+		.and_then(|Query(mut query)| query.remove("namespace"))
+		.unwrap_or_default();
+
+	let headers = req.headers();
+	let forwarded_for = logger
+		.respect_forwarded_for
+		.then(|| headers.get("x-forwarded-for"))
+		.flatten()
+		.and_then(|h| h.to_str().ok())
+		.and_then(|h| h.split(',').next())
+		.map(|ip| ip.trim().to_string());
+	let client_ip = forwarded_for
+		.or_else(|| {
+			req.extensions()
+				.get::<ConnectInfo<SocketAddr>>()
+				.map(|ci| ci.0.ip().to_string())
+		})
+		.unwrap_or_default();
+	let user_agent = headers
+		.get("user-agent")
+		.and_then(|h| h.to_str().ok())
+		.unwrap_or_default()
+		.to_string();
+	let token_hash = headers
+		.get("authorization")
+		.and_then(|h| h.to_str().ok())
+		.and_then(|h| h.strip_prefix("Bearer "))
+		.map(|token| hex::encode(&Sha256::digest(token.trim().as_bytes())[..8]))
+		.unwrap_or_default();
+
+	let response = next.run(req).await;
+
+	let entry = AuditEntry {
+		ts,
+		ray_id: response
+			.headers()
+			.get("rvt-ray-id")
+			.and_then(|h| h.to_str().ok())
+			.unwrap_or_default()
+			.to_string(),
+		method,
+		path,
+		namespace,
+		client_ip,
+		user_agent,
+		token_hash,
+		status: response.status().as_u16(),
+	};
+
+	if let Err(err) = logger.write(entry) {
+		tracing::error!(?err, "failed to write audit log entry");
+	}
+
+	response
+}
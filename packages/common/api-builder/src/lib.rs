@@ -1,3 +1,4 @@
+pub mod audit;
 pub mod context;
 pub mod error_response;
 pub mod errors;
@@ -9,6 +10,7 @@ pub mod request_ids;
 pub mod router;
 pub mod wrappers;
 
+pub use audit::*;
 pub use context::*;
 pub use error_response::*;
 pub use errors::*;
@@ -1,14 +1,14 @@
-use anyhow::Result;
+use anyhow::{Context, Result, ensure};
 use axum::{
 	Router,
 	extract::{Request, State},
-	http::StatusCode,
+	http::{HeaderName, HeaderValue, StatusCode},
 	middleware::{self, Next},
 	response::{IntoResponse, Json, Response},
 	routing::get as axum_get,
 };
 use serde_json::json;
-use tower_http::cors::CorsLayer;
+use tower_http::cors::{AllowHeaders, AllowMethods, AllowOrigin, CorsLayer, ExposeHeaders};
 
 use crate::{
 	ApiError, RequestIds, context::ApiCtx, create_trace_layer, errors::ApiNotFound,
@@ -46,6 +46,17 @@ pub async fn create_router(
 	config: rivet_config::Config,
 	pools: rivet_pools::Pools,
 	builder: fn(ApiRouter) -> ApiRouter,
+) -> Result<Router> {
+	create_router_with_cors(name, config, pools, CorsLayer::permissive(), builder).await
+}
+
+/// Same as `create_router` but with a custom CORS layer instead of allowing everything.
+pub async fn create_router_with_cors(
+	name: &'static str,
+	config: rivet_config::Config,
+	pools: rivet_pools::Pools,
+	cors_layer: CorsLayer,
+	builder: fn(ApiRouter) -> ApiRouter,
 ) -> Result<Router> {
 	let ctx = GlobalApiCtx::new(config.clone(), pools, name).await?;
 
@@ -54,7 +65,7 @@ pub async fn create_router(
 	// Add standard middleware
 	let router = Router::new()
 		.fallback(not_found_handler)
-		.layer(CorsLayer::permissive())
+		.layer(cors_layer)
 		.layer(create_trace_layer())
 		.route("/health", axum_get(health_check))
 		.merge(user_router)
@@ -74,6 +85,69 @@ pub async fn create_router(
 	Ok(router)
 }
 
+/// Builds a CORS layer from the `api_public.cors` config. Allows everything if not configured.
+pub fn create_cors_layer(cors: Option<&rivet_config::config::Cors>) -> Result<CorsLayer> {
+	let Some(cors) = cors else {
+		return Ok(CorsLayer::permissive());
+	};
+
+	// Wildcards are not allowed in combination with credentials, so mirror the request instead
+	let credentials = cors.allow_credentials();
+
+	let allow_origin = if let Some(origins) = &cors.allowed_origins {
+		let origins = origins
+			.iter()
+			.map(|origin| {
+				ensure!(
+					origin != "*",
+					"`*` is not a valid cors origin, omit `allowed_origins` to allow any origin"
+				);
+
+				HeaderValue::from_str(origin)
+					.with_context(|| format!("invalid cors origin: {origin}"))
+			})
+			.collect::<Result<Vec<_>>>()?;
+
+		AllowOrigin::list(origins)
+	} else if credentials {
+		AllowOrigin::mirror_request()
+	} else {
+		AllowOrigin::any()
+	};
+
+	let allow_headers = if let Some(headers) = &cors.allowed_headers {
+		let headers = headers
+			.iter()
+			.map(|header| {
+				HeaderName::try_from(header.as_str())
+					.with_context(|| format!("invalid cors header: {header}"))
+			})
+			.collect::<Result<Vec<_>>>()?;
+
+		AllowHeaders::list(headers)
+	} else if credentials {
+		AllowHeaders::mirror_request()
+	} else {
+		AllowHeaders::any()
+	};
+
+	let (allow_methods, expose_headers) = if credentials {
+		(
+			AllowMethods::mirror_request(),
+			ExposeHeaders::list([HeaderName::from_static("rvt-ray-id")]),
+		)
+	} else {
+		(AllowMethods::any(), ExposeHeaders::any())
+	};
+
+	Ok(CorsLayer::new()
+		.allow_origin(allow_origin)
+		.allow_headers(allow_headers)
+		.allow_methods(allow_methods)
+		.expose_headers(expose_headers)
+		.allow_credentials(credentials))
+}
+
 /// Health check endpoint
 pub async fn health_check() -> impl IntoResponse {
 	Json(json!({
@@ -0,0 +1,96 @@
+use std::time::Duration;
+
+use axum::http::{HeaderName, HeaderValue};
+use axum_test::TestServer;
+use rivet_api_builder::{AuditLogger, audit_log_middleware, create_router, prelude::*};
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize)]
+struct IdPath {
+	id: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Response {
+	id: String,
+}
+
+async fn handle_get(_ctx: ApiCtx, path: IdPath, _query: ()) -> Result<Response> {
+	Ok(Response { id: path.id })
+}
+
+async fn handle_delete(_ctx: ApiCtx, path: IdPath, _query: ()) -> Result<Response> {
+	Ok(Response { id: path.id })
+}
+
+#[tokio::test]
+async fn test_audit_log_file() {
+	let path = std::env::temp_dir().join(format!("rivet-audit-{}.log", uuid::Uuid::new_v4()));
+
+	let mut root = rivet_config::config::Root::default();
+	root.api_public = Some(rivet_config::config::ApiPublic {
+		audit_log: Some(rivet_config::config::AuditLog::File { path: path.clone() }),
+		..Default::default()
+	});
+	let config = rivet_config::Config::from_root(root);
+	let pools = rivet_pools::Pools::new(config.clone())
+		.await
+		.expect("Failed to create test pools");
+
+	let audit_logger = AuditLogger::from_config(&config, &pools)
+		.await
+		.expect("Failed to create audit logger")
+		.expect("audit log not configured");
+
+	let app = create_router("test", config, pools, |router| {
+		router
+			.route("/items/{id}", get(handle_get))
+			.route("/items/{id}", delete(handle_delete))
+	})
+	.await
+	.expect("Failed to create router")
+	.layer(axum::middleware::from_fn_with_state(
+		audit_logger,
+		audit_log_middleware,
+	));
+	let server = TestServer::new(app).unwrap();
+
+	// Reads are not audited
+	server.get("/items/1").await.assert_status_ok();
+
+	server
+		.delete("/items/2")
+		.add_query_param("namespace", "default")
+		.add_header(
+			HeaderName::from_static("authorization"),
+			HeaderValue::from_static("Bearer secret-token"),
+		)
+		.await
+		.assert_status_ok();
+
+	// Entries are written in the background
+	let mut contents = String::new();
+	for _ in 0..50 {
+		contents = tokio::fs::read_to_string(&path).await.unwrap_or_default();
+		if !contents.is_empty() {
+			break;
+		}
+		tokio::time::sleep(Duration::from_millis(100)).await;
+	}
+
+	let lines = contents.lines().collect::<Vec<_>>();
+	assert_eq!(lines.len(), 1, "expected a single entry: {contents}");
+
+	let entry: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+	assert_eq!(entry["method"], "DELETE");
+	assert_eq!(entry["path"], "/items/2");
+	assert_eq!(entry["namespace"], "default");
+	assert_eq!(entry["status"], 200);
+
+	// The caller is identified without storing the token
+	let token_hash = entry["token_hash"].as_str().unwrap();
+	assert_eq!(token_hash.len(), 16);
+	assert!(!contents.contains("secret-token"));
+
+	let _ = tokio::fs::remove_file(&path).await;
+}
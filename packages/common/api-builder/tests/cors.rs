@@ -0,0 +1,83 @@
+use axum::http::{HeaderName, HeaderValue};
+use axum_test::TestServer;
+use rivet_api_builder::{create_cors_layer, create_router, create_router_with_cors};
+
+const ALLOWED_ORIGIN: &str = "https://dashboard.example.com";
+
+fn origin(value: &'static str) -> (HeaderName, HeaderValue) {
+	(
+		HeaderName::from_static("origin"),
+		HeaderValue::from_static(value),
+	)
+}
+
+#[tokio::test]
+async fn test_default_cors_allows_any_origin() {
+	let config = rivet_config::Config::from_root(rivet_config::config::Root::default());
+	let pools = rivet_pools::Pools::new(config.clone())
+		.await
+		.expect("Failed to create test pools");
+
+	let app = create_router("test", config, pools, |router| router)
+		.await
+		.expect("Failed to create router");
+	let server = TestServer::new(app).unwrap();
+
+	let (name, value) = origin("https://other.example.com");
+	let res = server.get("/missing").add_header(name, value).await;
+	assert_eq!(
+		res.headers().get("access-control-allow-origin").unwrap(),
+		"*"
+	);
+}
+
+#[tokio::test]
+async fn test_configured_cors_only_allows_listed_origins() {
+	let mut root = rivet_config::config::Root::default();
+	root.api_public = Some(rivet_config::config::ApiPublic {
+		cors: Some(rivet_config::config::Cors {
+			allowed_origins: Some(vec![ALLOWED_ORIGIN.to_string()]),
+			allowed_headers: None,
+			allow_credentials: Some(true),
+		}),
+		..Default::default()
+	});
+	let config = rivet_config::Config::from_root(root);
+	let pools = rivet_pools::Pools::new(config.clone())
+		.await
+		.expect("Failed to create test pools");
+
+	let cors_layer =
+		create_cors_layer(config.api_public().cors.as_ref()).expect("Failed to build cors layer");
+	let app = create_router_with_cors("test", config, pools, cors_layer, |router| router)
+		.await
+		.expect("Failed to create router");
+	let server = TestServer::new(app).unwrap();
+
+	let (name, value) = origin(ALLOWED_ORIGIN);
+	let res = server.get("/missing").add_header(name, value).await;
+	assert_eq!(
+		res.headers().get("access-control-allow-origin").unwrap(),
+		ALLOWED_ORIGIN
+	);
+	assert_eq!(
+		res.headers()
+			.get("access-control-allow-credentials")
+			.unwrap(),
+		"true"
+	);
+
+	let (name, value) = origin("https://other.example.com");
+	let res = server.get("/missing").add_header(name, value).await;
+	assert!(res.headers().get("access-control-allow-origin").is_none());
+}
+
+#[test]
+fn test_wildcard_origin_is_rejected() {
+	let cors = rivet_config::config::Cors {
+		allowed_origins: Some(vec!["*".to_string()]),
+		allowed_headers: None,
+		allow_credentials: None,
+	};
+	assert!(create_cors_layer(Some(&cors)).is_err());
+}
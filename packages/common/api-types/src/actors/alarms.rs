@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Debug, Serialize, Deserialize, Clone, IntoParams)]
+#[serde(deny_unknown_fields)]
+#[into_params(parameter_in = Query)]
+pub struct AlarmsQuery {
+	pub namespace: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+#[schema(as = ActorsAlarmsResponse)]
+pub struct AlarmsResponse {
+	/// Pending named alarms ordered by the time they fire.
+	pub alarms: Vec<rivet_types::actors::ActorAlarm>,
+}
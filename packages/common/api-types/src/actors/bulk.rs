@@ -0,0 +1,70 @@
+use rivet_util::Id;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use super::create::CreateRequest;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+#[schema(as = ActorsBulkCreateRequest)]
+pub struct BulkCreateRequest {
+	pub actors: Vec<BulkCreateActor>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+#[schema(as = ActorsBulkCreateActor)]
+pub struct BulkCreateActor {
+	/// Datacenter to create this actor in. Defaults to the `datacenter` query parameter.
+	pub datacenter: Option<String>,
+	#[serde(flatten)]
+	pub actor: CreateRequest,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+#[schema(as = ActorsBulkCreateResponse)]
+pub struct BulkCreateResponse {
+	/// One result per requested actor, in the same order as the request.
+	pub results: Vec<BulkCreateResult>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+#[schema(as = ActorsBulkCreateResult)]
+pub struct BulkCreateResult {
+	/// Set if the actor was created.
+	pub actor: Option<rivet_types::actors::Actor>,
+	/// Set if the actor failed to be created.
+	pub error: Option<BulkError>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+#[schema(as = ActorsBulkError)]
+pub struct BulkError {
+	pub group: String,
+	pub code: String,
+	pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, IntoParams)]
+#[serde(deny_unknown_fields)]
+#[into_params(parameter_in = Query)]
+pub struct BulkDestroyQuery {
+	pub namespace: String,
+	pub name: String,
+	/// Only destroy actors whose key starts with this prefix.
+	pub key_prefix: Option<String>,
+	/// Only destroy actors created at or before this timestamp (ms).
+	pub created_before: Option<i64>,
+	/// Only destroy actors created at or after this timestamp (ms).
+	pub created_after: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+#[schema(as = ActorsBulkDestroyResponse)]
+pub struct BulkDestroyResponse {
+	/// Actors that were sent a destroy signal.
+	pub actor_ids: Vec<Id>,
+}
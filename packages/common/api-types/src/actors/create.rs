@@ -2,7 +2,7 @@ use rivet_util::Id;
 use serde::{Deserialize, Serialize};
 use utoipa::{IntoParams, ToSchema};
 
-#[derive(Debug, Serialize, Deserialize, IntoParams)]
+#[derive(Debug, Clone, Serialize, Deserialize, IntoParams)]
 #[serde(deny_unknown_fields)]
 #[into_params(parameter_in = Query)]
 pub struct CreateQuery {
@@ -18,6 +18,24 @@ pub struct CreateRequest {
 	pub input: Option<String>,
 	pub runner_name_selector: String,
 	pub crash_policy: rivet_types::actors::CrashPolicy,
+	/// Constraints on which runners the actor can be allocated to.
+	pub placement: Option<rivet_types::actors::ActorPlacement>,
+	/// Amount of runner slots the actor consumes. Defaults to 1.
+	pub slots: Option<u32>,
+	/// Destroy the actor if it waits longer than this for a runner with capacity. Defaults to the
+	/// runner config's `max_pending_duration_ms`. At most 30 days.
+	pub max_pending_duration_ms: Option<i64>,
+	/// Actors with a higher priority are allocated first when there is no runner capacity.
+	pub priority: Option<rivet_types::actors::ActorPriority>,
+	/// Limits restarts when `crash_policy` is `restart`.
+	pub restart_policy: Option<rivet_types::actors::RestartPolicy>,
+	/// Destroy the actor this long after it was created. At most 1 year.
+	pub ttl_ms: Option<i64>,
+	/// Timestamp (in ms) at which to destroy the actor. If `ttl_ms` is also set, the actor is
+	/// destroyed at whichever is earlier. At most 1 year in the future.
+	pub destroy_at_ts: Option<i64>,
+	/// Destroy the actor once it has been sleeping for this long. At most 1 year.
+	pub idle_timeout_ms: Option<i64>,
 }
 
 #[derive(Serialize, Deserialize, ToSchema)]
@@ -0,0 +1,66 @@
+use rivet_util::Id;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Debug, Serialize, Deserialize, Clone, IntoParams)]
+#[serde(deny_unknown_fields)]
+#[into_params(parameter_in = Query)]
+pub struct EventsQuery {
+	pub namespace: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, IntoParams)]
+#[serde(deny_unknown_fields)]
+#[into_params(parameter_in = Query)]
+pub struct ListEventsQuery {
+	pub namespace: String,
+	/// Only include events for actors with this name.
+	pub name: Option<String>,
+}
+
+/// Sent as the data of a server-sent event. The event name matches the `type` of `event`.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(deny_unknown_fields)]
+#[schema(as = ActorsLifecycleEvent)]
+pub struct LifecycleEvent {
+	pub actor_id: Id,
+	pub ts: i64,
+	pub event: LifecycleEventKind,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "snake_case", tag = "type")]
+#[schema(as = ActorsLifecycleEventKind)]
+pub enum LifecycleEventKind {
+	CreateComplete,
+	Ready {
+		runner_id: Id,
+	},
+	/// The actor failed to be created or allocated.
+	Failed {
+		group: String,
+		code: String,
+		message: String,
+	},
+	/// The actor stopped with an error or its runner was lost.
+	Crashed {
+		lost: bool,
+	},
+	Sleeping,
+	DestroyStarted,
+	DestroyComplete,
+}
+
+impl LifecycleEventKind {
+	pub fn name(&self) -> &'static str {
+		match self {
+			LifecycleEventKind::CreateComplete => "create_complete",
+			LifecycleEventKind::Ready { .. } => "ready",
+			LifecycleEventKind::Failed { .. } => "failed",
+			LifecycleEventKind::Crashed { .. } => "crashed",
+			LifecycleEventKind::Sleeping => "sleeping",
+			LifecycleEventKind::DestroyStarted => "destroy_started",
+			LifecycleEventKind::DestroyComplete => "destroy_complete",
+		}
+	}
+}
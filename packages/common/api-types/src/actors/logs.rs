@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Debug, Serialize, Deserialize, Clone, IntoParams)]
+#[serde(deny_unknown_fields)]
+#[into_params(parameter_in = Query)]
+pub struct LogsQuery {
+	pub namespace: Option<String>,
+	/// Number of most recent lines to return. Defaults to 100.
+	pub tail: Option<usize>,
+	/// Stream new lines as server-sent events until the actor is destroyed.
+	pub follow: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+#[schema(as = ActorsLogsResponse)]
+pub struct LogsResponse {
+	pub entries: Vec<rivet_types::actors::ActorLogEntry>,
+}
@@ -0,0 +1,25 @@
+use rivet_util::Id;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Debug, Serialize, Deserialize, Clone, IntoParams)]
+#[serde(deny_unknown_fields)]
+#[into_params(parameter_in = Query)]
+pub struct MigrateQuery {
+	pub namespace: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(deny_unknown_fields)]
+#[schema(as = ActorsMigrateRequest)]
+pub struct MigrateRequest {
+	/// Runner to move the actor to. Must be a different runner with the same name as the actor's
+	/// runner name selector. If not set, any other runner is chosen. If the runner does not have
+	/// enough capacity, the actor is not migrated.
+	pub runner_id: Option<Id>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+#[schema(as = ActorsMigrateResponse)]
+pub struct MigrateResponse {}
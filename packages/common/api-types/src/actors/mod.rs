@@ -1,4 +1,10 @@
+pub mod alarms;
+pub mod bulk;
 pub mod create;
+pub mod events;
 pub mod get;
 pub mod list;
 pub mod list_names;
+pub mod logs;
+pub mod migrate;
+pub mod queue;
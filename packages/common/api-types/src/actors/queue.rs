@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Debug, Serialize, Deserialize, Clone, IntoParams)]
+#[serde(deny_unknown_fields)]
+#[into_params(parameter_in = Query)]
+pub struct QueueQuery {
+	pub namespace: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+#[schema(as = ActorsQueueResponse)]
+pub struct QueueResponse {
+	pub runner_name_selector: String,
+	/// Set while the actor is waiting for a runner with capacity.
+	pub pending_allocation_ts: Option<i64>,
+	/// 0-based position of the actor in the allocation queue. Null if the actor is not waiting for
+	/// allocation or is more than 10,000 actors back.
+	pub position: Option<usize>,
+	/// Amount of actors waiting for a runner with this name in the actor's datacenter.
+	pub depth: usize,
+}
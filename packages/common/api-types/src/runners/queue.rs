@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Debug, Serialize, Deserialize, Clone, IntoParams)]
+#[serde(deny_unknown_fields)]
+#[into_params(parameter_in = Query)]
+pub struct QueueQuery {
+	pub namespace: String,
+	pub runner_name: String,
+}
+
+#[derive(Default, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+#[schema(as = RunnersQueueResponse)]
+pub struct QueueResponse {
+	/// Amount of actors waiting for a runner with this name.
+	pub depth: usize,
+	pub oldest_pending_allocation_ts: Option<i64>,
+}
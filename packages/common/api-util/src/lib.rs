@@ -47,6 +47,48 @@ pub async fn request_remote_datacenter_raw(
 		.context("failed parsing response from remote dc")
 }
 
+/// Same as `request_remote_datacenter_raw` but streams the response body instead of buffering it.
+///
+/// Used for long-lived responses like server-sent events, so the request has no timeout.
+pub async fn request_remote_datacenter_stream(
+	ctx: &ApiCtx,
+	dc_label: u16,
+	endpoint: &str,
+	method: Method,
+	headers: HeaderMap,
+	query: Option<&impl Serialize>,
+) -> Result<Response> {
+	let dc = ctx
+		.config()
+		.dc_for_label(dc_label)
+		.ok_or_else(|| errors::Datacenter::NotFound.build())?;
+
+	let client = rivet_pools::reqwest::client_no_timeout().await?;
+	let mut url = dc.api_peer_url.join(endpoint)?;
+
+	// NOTE: We don't use reqwest's `.query` because it doesn't support list query parameters
+	if let Some(q) = query {
+		url.set_query(Some(&serde_html_form::to_string(q)?));
+	}
+
+	let res = client
+		.request(method, url)
+		.headers(headers)
+		.send()
+		.await
+		.context("failed sending request to remote dc")?;
+
+	let status = res.status();
+	let headers = res.headers().clone();
+
+	let mut response = Response::builder()
+		.status(status)
+		.body(Body::from_stream(res.bytes_stream()))?;
+	*response.headers_mut() = headers;
+
+	Ok(response)
+}
+
 /// Generic function to make requests to a specific datacenter
 pub async fn request_remote_datacenter<T>(
 	config: &rivet_config::Config,
@@ -97,6 +139,36 @@ pub async fn fanout_to_datacenters<I, Q, F, Fut, A, R>(
 	local_handler: F,
 	aggregator: A,
 ) -> Result<R>
+where
+	I: DeserializeOwned + Send + 'static,
+	Q: Serialize + Clone + Send + 'static,
+	F: Fn(ApiCtx, Q) -> Fut + Clone + Send + 'static,
+	Fut: Future<Output = Result<I>> + Send,
+	A: Fn(I, &mut R),
+	R: Default + Send + 'static,
+{
+	fanout_to_datacenters_with_method(
+		ctx,
+		headers,
+		Method::GET,
+		endpoint,
+		query,
+		local_handler,
+		aggregator,
+	)
+	.await
+}
+
+/// Same as `fanout_to_datacenters` but with a custom method for remote requests (e.g. `DELETE`).
+pub async fn fanout_to_datacenters_with_method<I, Q, F, Fut, A, R>(
+	ctx: ApiCtx,
+	headers: HeaderMap,
+	method: Method,
+	endpoint: &str,
+	query: Q,
+	local_handler: F,
+	aggregator: A,
+) -> Result<R>
 where
 	I: DeserializeOwned + Send + 'static,
 	Q: Serialize + Clone + Send + 'static,
@@ -113,6 +185,7 @@ where
 		let query = query.clone();
 		let endpoint = endpoint.to_string();
 		let local_handler = local_handler.clone();
+		let method = method.clone();
 
 		async move {
 			if dc.datacenter_label == ctx.config().dc_label() {
@@ -124,7 +197,7 @@ where
 					ctx.config(),
 					dc.datacenter_label,
 					&endpoint,
-					Method::GET,
+					method,
 					headers,
 					Some(&query),
 					Option::<&()>::None,
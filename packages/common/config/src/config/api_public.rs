@@ -1,5 +1,6 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 
 /// Configuration for the public API service.
 #[derive(Debug, Serialize, Deserialize, Clone, Default, JsonSchema)]
@@ -12,6 +13,10 @@ pub struct ApiPublic {
 	/// Will be ignored in favor of CF-Connecting-IP if DNS provider is
 	/// configured as Cloudflare.
 	pub respect_forwarded_for: Option<bool>,
+	/// CORS configuration. All origins, methods and headers are allowed if not set.
+	pub cors: Option<Cors>,
+	/// Records every mutating request (POST, PUT, PATCH, DELETE). Disabled if not set.
+	pub audit_log: Option<AuditLog>,
 }
 
 impl ApiPublic {
@@ -23,3 +28,34 @@ impl ApiPublic {
 		self.respect_forwarded_for.unwrap_or(false)
 	}
 }
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Cors {
+	/// Origins allowed to make cross-origin requests (e.g. `https://dashboard.example.com`).
+	///
+	/// Any origin is allowed if not set.
+	pub allowed_origins: Option<Vec<String>>,
+	/// Request headers allowed in cross-origin requests.
+	///
+	/// Any header is allowed if not set.
+	pub allowed_headers: Option<Vec<String>>,
+	/// Whether to allow cookies and authorization headers in cross-origin requests.
+	pub allow_credentials: Option<bool>,
+}
+
+impl Cors {
+	pub fn allow_credentials(&self) -> bool {
+		self.allow_credentials.unwrap_or(false)
+	}
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum AuditLog {
+	/// Appends one JSON object per line to the given file.
+	File { path: PathBuf },
+	/// Inserts into the `db_api_audit.requests` table. Requires ClickHouse to be configured.
+	#[serde(rename = "clickhouse")]
+	ClickHouse,
+}
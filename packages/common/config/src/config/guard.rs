@@ -1,6 +1,6 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::{net::IpAddr, path::PathBuf};
+use std::{net::IpAddr, path::PathBuf, time::Duration};
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default, JsonSchema)]
 #[serde(deny_unknown_fields)]
@@ -11,6 +11,9 @@ pub struct Guard {
 	pub port: Option<u16>,
 	/// Enable & configure HTTPS
 	pub https: Option<Https>,
+	/// Route requests to actors by hostname or path in addition to the `x-rivet-target` and
+	/// `x-rivet-actor` headers.
+	pub actor_routing: Option<ActorRouting>,
 }
 
 impl Guard {
@@ -23,6 +26,59 @@ impl Guard {
 	}
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Default, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ActorRouting {
+	/// Routes `{actor_id}.actor.{domain}` hostnames to the given actor.
+	///
+	/// Also used as the default wildcard hostname for the actor TLS certificate.
+	pub domain: Option<String>,
+	/// Routes `{path_prefix}/{actor_id}/...` paths to the given actor (e.g. `/gateway/actors`).
+	/// The prefix and actor ID are stripped from the path before it is forwarded to the actor.
+	///
+	/// Must not overlap with api-public routes, which are matched after actor routes.
+	pub path_prefix: Option<String>,
+}
+
+/// Top level path segments served by api-public.
+const API_PUBLIC_PATH_SEGMENTS: &[&str] = &[
+	"actors",
+	"runners",
+	"runner-configs",
+	"namespaces",
+	"datacenters",
+	"health",
+	"ui",
+];
+
+impl ActorRouting {
+	/// Returns an error if the path prefix would shadow api-public routes.
+	pub fn validate(&self) -> anyhow::Result<()> {
+		if let Some(path_prefix) = &self.path_prefix {
+			let first_segment = path_prefix
+				.trim_start_matches('/')
+				.split('/')
+				.next()
+				.unwrap_or_default();
+
+			if first_segment.is_empty() || API_PUBLIC_PATH_SEGMENTS.contains(&first_segment) {
+				anyhow::bail!(
+					"guard.actor_routing.path_prefix `{path_prefix}` overlaps with api-public routes, use a prefix such as `/gateway/actors` instead"
+				);
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Wildcard hostname matching all actor hostnames.
+	pub fn wildcard_hostname(&self) -> Option<String> {
+		self.domain
+			.as_ref()
+			.map(|domain| format!("*.actor.{domain}"))
+	}
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 #[serde(deny_unknown_fields)]
 #[derive(Default)]
@@ -39,4 +95,22 @@ pub struct Tls {
 	pub actor_key_path: PathBuf,
 	pub api_cert_path: PathBuf,
 	pub api_key_path: PathBuf,
+	/// Wildcard hostname served with the actor certificate (e.g. `*.actor.rivet.example.com`).
+	///
+	/// Defaults to `*.actor.{domain}` if `actor_routing.domain` is set. Otherwise, the actor
+	/// certificate is never selected.
+	pub actor_hostname: Option<String>,
+	/// Hostname served with the API certificate.
+	///
+	/// If not set, the API certificate is served for every hostname that does not match
+	/// `actor_hostname`.
+	pub api_hostname: Option<String>,
+	/// Interval in milliseconds at which certificate files are checked for changes.
+	pub reload_interval_ms: Option<u64>,
+}
+
+impl Tls {
+	pub fn reload_interval(&self) -> Duration {
+		Duration::from_millis(self.reload_interval_ms.unwrap_or(30_000))
+	}
 }
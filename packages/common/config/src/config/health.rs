@@ -0,0 +1,23 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+
+/// Configuration for the health check server.
+///
+/// Serves `/health/live` and `/health/ready` for orchestrators.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Health {
+	pub host: Option<IpAddr>,
+	pub port: Option<u16>,
+}
+
+impl Health {
+	pub fn host(&self) -> IpAddr {
+		self.host.unwrap_or(crate::defaults::hosts::HEALTH)
+	}
+
+	pub fn port(&self) -> u16 {
+		self.port.unwrap_or(crate::defaults::ports::HEALTH)
+	}
+}
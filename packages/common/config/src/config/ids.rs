@@ -0,0 +1,25 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Configuration for generated IDs.
+///
+/// Time-ordered IDs can only be parsed by engine versions that support them, so only enable these
+/// once every node in the cluster has been upgraded.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Ids {
+	/// Use time-ordered IDs for new actors.
+	pub time_ordered_actor_ids: Option<bool>,
+	/// Use time-ordered IDs for new workflows.
+	pub time_ordered_workflow_ids: Option<bool>,
+}
+
+impl Ids {
+	pub fn time_ordered_actor_ids(&self) -> bool {
+		self.time_ordered_actor_ids.unwrap_or(false)
+	}
+
+	pub fn time_ordered_workflow_ids(&self) -> bool {
+		self.time_ordered_workflow_ids.unwrap_or(false)
+	}
+}
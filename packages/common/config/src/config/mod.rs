@@ -9,6 +9,8 @@ pub mod cache;
 pub mod clickhouse;
 pub mod db;
 pub mod guard;
+pub mod health;
+pub mod ids;
 pub mod logs;
 pub mod pegboard;
 pub mod pegboard_gateway;
@@ -24,6 +26,8 @@ pub use cache::*;
 pub use clickhouse::*;
 pub use db::Database;
 pub use guard::*;
+pub use health::*;
+pub use ids::*;
 pub use logs::*;
 pub use pegboard::*;
 pub use pegboard_gateway::*;
@@ -82,9 +86,15 @@ pub struct Root {
 	#[serde(default)]
 	pub pegboard_tunnel: Option<PegboardTunnel>,
 
+	#[serde(default)]
+	pub health: Option<Health>,
+
 	#[serde(default)]
 	pub logs: Option<Logs>,
 
+	#[serde(default)]
+	pub ids: Option<Ids>,
+
 	#[serde(default)]
 	pub topology: Option<Topology>,
 
@@ -116,7 +126,9 @@ impl Default for Root {
 			pegboard: None,
 			pegboard_gateway: None,
 			pegboard_tunnel: None,
+			health: None,
 			logs: None,
+			ids: None,
 			topology: None,
 			database: None,
 			pubsub: None,
@@ -159,11 +171,21 @@ impl Root {
 		self.pegboard_tunnel.as_ref().unwrap_or(&DEFAULT)
 	}
 
+	pub fn health(&self) -> &Health {
+		static DEFAULT: LazyLock<Health> = LazyLock::new(Health::default);
+		self.health.as_ref().unwrap_or(&DEFAULT)
+	}
+
 	pub fn logs(&self) -> &Logs {
 		static DEFAULT: LazyLock<Logs> = LazyLock::new(Logs::default);
 		self.logs.as_ref().unwrap_or(&DEFAULT)
 	}
 
+	pub fn ids(&self) -> &Ids {
+		static DEFAULT: LazyLock<Ids> = LazyLock::new(Ids::default);
+		self.ids.as_ref().unwrap_or(&DEFAULT)
+	}
+
 	pub fn topology(&self) -> &Topology {
 		static DEFAULT: LazyLock<Topology> = LazyLock::new(Topology::default);
 		self.topology.as_ref().unwrap_or(&DEFAULT)
@@ -203,6 +225,10 @@ impl Root {
 			}));
 		}
 
+		if let Some(actor_routing) = self.guard.as_ref().and_then(|x| x.actor_routing.as_ref()) {
+			actor_routing.validate()?;
+		}
+
 		Ok(())
 	}
 
@@ -7,6 +7,7 @@ pub mod hosts {
 	pub const PEGBOARD_RUNNER_WS: IpAddr = IpAddr::V6(Ipv6Addr::UNSPECIFIED);
 	pub const PEGBOARD_GATEWAY: IpAddr = IpAddr::V6(Ipv6Addr::UNSPECIFIED);
 	pub const PEGBOARD_TUNNEL: IpAddr = IpAddr::V6(Ipv6Addr::UNSPECIFIED);
+	pub const HEALTH: IpAddr = IpAddr::V6(Ipv6Addr::UNSPECIFIED);
 
 	pub const API_PUBLIC_LAN: &str = "::1";
 	pub const PEGBOARD_RUNNER_LAN: &str = "::1";
@@ -20,5 +21,6 @@ pub mod ports {
 	pub const PEGBOARD_RUNNER_WS: u16 = 6423;
 	pub const PEGBOARD_GATEWAY: u16 = 6424;
 	pub const PEGBOARD_TUNNEL: u16 = 6425;
+	pub const HEALTH: u16 = 6426;
 	pub const GUARD: u16 = 6420;
 }
@@ -100,7 +100,10 @@ where
 		}
 
 		let workflow_name = I::Workflow::NAME;
-		let workflow_id = Id::new_v1(self.config.dc_label());
+		let workflow_id = Id::new_with_ordering(
+			self.config.ids().time_ordered_workflow_ids(),
+			self.config.dc_label(),
+		);
 		let start_instant = Instant::now();
 		let input = self.repr.as_input()?;
 
@@ -15,6 +15,7 @@ pub struct MessageBuilder<'a, M: Message> {
 
 	body: M,
 	tags: serde_json::Map<String, serde_json::Value>,
+	topic: Option<String>,
 	wait: bool,
 	error: Option<BuilderError>,
 }
@@ -27,6 +28,7 @@ impl<'a, M: Message> MessageBuilder<'a, M> {
 
 			body,
 			tags: serde_json::Map::new(),
+			topic: None,
 			wait: false,
 			error: None,
 		}
@@ -62,6 +64,17 @@ impl<'a, M: Message> MessageBuilder<'a, M> {
 		self
 	}
 
+	/// Also publishes the message to the given topic. The topic is not stored in history.
+	pub fn topic(mut self, topic: impl Display) -> Self {
+		if self.error.is_some() {
+			return self;
+		}
+
+		self.topic = Some(topic.to_string());
+
+		self
+	}
+
 	pub fn wait(mut self) -> Self {
 		if self.error.is_some() {
 			return self;
@@ -117,9 +130,15 @@ impl<'a, M: Message> MessageBuilder<'a, M> {
 				.await?;
 
 			if self.wait {
-				self.ctx.msg_ctx().message_wait(tags2, self.body).await?;
+				self.ctx
+					.msg_ctx()
+					.message_wait_opt(tags2, self.body, self.topic)
+					.await?;
 			} else {
-				self.ctx.msg_ctx().message(tags2, self.body).await?;
+				self.ctx
+					.msg_ctx()
+					.message_opt(tags2, self.body, self.topic)
+					.await?;
 			}
 
 			let dt = start_instant.elapsed().as_secs_f64();
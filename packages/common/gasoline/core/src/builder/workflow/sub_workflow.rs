@@ -145,7 +145,10 @@ where
 		// Dispatch new workflow
 		else {
 			let sub_workflow_name = I::Workflow::NAME;
-			let sub_workflow_id = Id::new_v1(ctx.config().dc_label());
+			let sub_workflow_id = Id::new_with_ordering(
+				ctx.config().ids().time_ordered_workflow_ids(),
+				ctx.config().dc_label(),
+			);
 			let start_instant = Instant::now();
 
 			if unique {
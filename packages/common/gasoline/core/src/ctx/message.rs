@@ -60,6 +60,20 @@ impl MessageCtx {
 		tags: impl AsTags + 'static,
 		message_body: M,
 	) -> WorkflowResult<()>
+	where
+		M: Message,
+	{
+		self.message_opt(tags, message_body, None).await
+	}
+
+	/// Same as `message` but also publishes the message to the given topic. See `subscribe_topic`.
+	#[tracing::instrument(skip_all, fields(message=M::NAME))]
+	pub async fn message_opt<M>(
+		&self,
+		tags: impl AsTags + 'static,
+		message_body: M,
+		topic: Option<String>,
+	) -> WorkflowResult<()>
 	where
 		M: Message,
 	{
@@ -68,7 +82,10 @@ impl MessageCtx {
 			.name("gasoline::message_async")
 			.spawn(
 				async move {
-					match client.message_wait::<M>(tags, message_body).await {
+					match client
+						.message_wait_opt::<M>(tags, message_body, topic)
+						.await
+					{
 						Ok(_) => {}
 						Err(err) => {
 							tracing::error!(?err, "failed to publish message");
@@ -91,6 +108,20 @@ impl MessageCtx {
 	/// a large number of tasks to Tokio at once.
 	#[tracing::instrument(skip_all, fields(message = M::NAME))]
 	pub async fn message_wait<M>(&self, tags: impl AsTags, message_body: M) -> WorkflowResult<()>
+	where
+		M: Message,
+	{
+		self.message_wait_opt(tags, message_body, None).await
+	}
+
+	/// Same as `message_wait` but also publishes the message to the given topic.
+	#[tracing::instrument(skip_all, fields(message = M::NAME))]
+	pub async fn message_wait_opt<M>(
+		&self,
+		tags: impl AsTags,
+		message_body: M,
+		topic: Option<String>,
+	) -> WorkflowResult<()>
 	where
 		M: Message,
 	{
@@ -128,9 +159,14 @@ impl MessageCtx {
 		// It's important to write to the stream as fast as possible in order to
 		// ensure messages are handled quickly.
 		let message_buf = Arc::new(message_buf);
-		self.message_publish_pubsub::<M>(&subject, message_buf)
+		self.message_publish_pubsub::<M>(&subject, message_buf.clone())
 			.await;
 
+		if let Some(topic) = topic {
+			self.message_publish_pubsub::<M>(&M::topic_subject(&topic), message_buf)
+				.await;
+		}
+
 		Ok(())
 	}
 
@@ -189,6 +225,26 @@ impl MessageCtx {
 		self.subscribe_opt::<M>(SubscribeOpts {
 			tags: tags.as_tags()?,
 			flush: true,
+			topic: None,
+		})
+		.in_current_span()
+		.await
+	}
+
+	/// Listens for gasoline messages that were published to the given topic.
+	#[tracing::instrument(skip_all, fields(message = M::NAME))]
+	pub async fn subscribe_topic<M>(
+		&self,
+		topic: impl std::fmt::Display,
+		tags: impl AsTags,
+	) -> WorkflowResult<SubscriptionHandle<M>>
+	where
+		M: Message,
+	{
+		self.subscribe_opt::<M>(SubscribeOpts {
+			tags: tags.as_tags()?,
+			flush: true,
+			topic: Some(topic.to_string()),
 		})
 		.in_current_span()
 		.await
@@ -203,7 +259,11 @@ impl MessageCtx {
 	where
 		M: Message,
 	{
-		let subject = M::subject();
+		let subject = if let Some(topic) = &opts.topic {
+			M::topic_subject(topic)
+		} else {
+			M::subject()
+		};
 
 		// Create subscription and flush immediately.
 		tracing::debug!(%subject, tags = ?opts.tags, "creating subscription");
@@ -229,6 +289,8 @@ impl MessageCtx {
 pub struct SubscribeOpts {
 	pub tags: serde_json::Value,
 	pub flush: bool,
+	/// Only receive messages published to this topic.
+	pub topic: Option<String>,
 }
 
 /// Used to receive messages from other contexts.
@@ -176,6 +176,23 @@ impl StandaloneCtx {
 			.await
 			.map_err(Into::into)
 	}
+
+	/// Subscribes to messages published to the given topic.
+	#[tracing::instrument(skip_all, fields(message=M::NAME))]
+	pub async fn subscribe_topic<M>(
+		&self,
+		topic: impl std::fmt::Display,
+		tags: impl AsTags,
+	) -> Result<SubscriptionHandle<M>>
+	where
+		M: Message,
+	{
+		self.msg_ctx
+			.subscribe_topic::<M>(topic, tags)
+			.in_current_span()
+			.await
+			.map_err(Into::into)
+	}
 }
 
 impl StandaloneCtx {
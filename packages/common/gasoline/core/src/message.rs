@@ -12,6 +12,12 @@ pub trait Message: Debug + Send + Sync + Serialize + DeserializeOwned + 'static
 	fn subject() -> String {
 		format!("gasoline.msg.{}", Self::NAME)
 	}
+
+	/// Subject for messages that are also published to a topic. Lets subscribers listen to a subset
+	/// of messages without receiving every message with this name.
+	fn topic_subject(topic: &str) -> String {
+		format!("gasoline.msg.{}.topic.{}", Self::NAME, topic)
+	}
 }
 
 /// A message received from a pubsub subscription.
@@ -22,6 +28,7 @@ where
 {
 	pub(crate) ray_id: Id,
 	pub(crate) req_id: Id,
+	pub(crate) tags: serde_json::Value,
 	pub(crate) ts: i64,
 	pub(crate) body: M,
 }
@@ -41,6 +48,7 @@ where
 		Ok(PubsubMessage {
 			ray_id: wrapper.ray_id,
 			req_id: wrapper.req_id,
+			tags: wrapper.tags,
 			ts: wrapper.ts,
 			body,
 		})
@@ -78,6 +86,11 @@ where
 		self.req_id
 	}
 
+	/// Tags the message was published with.
+	pub fn tags(&self) -> &serde_json::Value {
+		&self.tags
+	}
+
 	/// Timestamp at which the message was created.
 	pub fn msg_ts(&self) -> i64 {
 		self.ts
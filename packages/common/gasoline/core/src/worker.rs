@@ -55,7 +55,10 @@ impl Worker {
 		}
 	}
 
-	/// Polls the database periodically or wakes immediately when `Database::wake` finishes
+	/// Polls the database periodically or wakes immediately when `Database::wake` finishes.
+	///
+	/// If `shutdown_rx` is provided, the caller is responsible for shutting down the worker and
+	/// termination signals are ignored.
 	#[tracing::instrument(skip_all, fields(worker_instance_id=%self.worker_instance_id))]
 	pub async fn start(mut self, mut shutdown_rx: Option<watch::Receiver<()>>) -> Result<()> {
 		tracing::debug!(
@@ -77,6 +80,7 @@ impl Worker {
 		let mut metrics_handle = self.publish_metrics();
 
 		let res = loop {
+			let external_shutdown = shutdown_rx.is_some();
 			let shutdown_fut = async {
 				if let Some(shutdown_rx) = &mut shutdown_rx {
 					shutdown_rx.changed().await
@@ -111,8 +115,8 @@ impl Worker {
 						break Ok(());
 					}
 				}
-				_ = ctrl_c() => break Ok(()),
-				_ = term_signal.recv() => break Ok(()),
+				_ = ctrl_c(), if !external_shutdown => break Ok(()),
+				_ = term_signal.recv(), if !external_shutdown => break Ok(()),
 			}
 
 			if let Err(err) = self.tick(&cache).await {
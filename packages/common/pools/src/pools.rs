@@ -111,4 +111,15 @@ impl Pools {
 	pub fn udb(&self) -> Result<UdbPool> {
 		self.0.udb.clone().ok_or(Error::MissingUdbPool.into())
 	}
+
+	// MARK: Shutdown
+	/// Flushes pending messages before shutting down. Connections are closed once all clones of the
+	/// pools are dropped.
+	pub async fn close(self) -> Result<()> {
+		if let Some(ups) = &self.0.ups {
+			ups.flush().await?;
+		}
+
+		Ok(())
+	}
 }
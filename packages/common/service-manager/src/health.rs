@@ -0,0 +1,262 @@
+use std::{
+	collections::HashMap,
+	net::SocketAddr,
+	sync::{
+		Arc, Mutex,
+		atomic::{AtomicBool, Ordering},
+	},
+	time::Duration,
+};
+
+use anyhow::*;
+use axum::{Json, Router, extract::State, http::StatusCode, routing::get};
+use serde::Serialize;
+use universaldb::prelude::*;
+
+/// Max duration for each dependency probe before it is considered unhealthy.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ServiceStatus {
+	/// Service has been spawned and is running.
+	Running,
+	/// Service crashed or exited and is waiting to be restarted.
+	Restarting,
+	/// Singleton service that is waiting for the leader lease. Another node is running it.
+	Standby,
+	/// Oneshot service that completed successfully.
+	Finished,
+	/// Cron service waiting for its next scheduled run.
+	Scheduled,
+	/// Service was stopped because the node is shutting down.
+	Stopped,
+}
+
+impl ServiceStatus {
+	fn is_ready(&self) -> bool {
+		!matches!(self, ServiceStatus::Restarting | ServiceStatus::Stopped)
+	}
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ServiceState {
+	pub kind: &'static str,
+	pub status: ServiceStatus,
+	pub crash_count: u64,
+	pub last_error: Option<String>,
+	pub last_error_ts: Option<i64>,
+	/// Oneshots have to finish before the node is ready.
+	#[serde(skip)]
+	must_finish: bool,
+}
+
+/// Tracks the state of every service run by the service manager.
+#[derive(Clone, Default)]
+pub struct HealthState {
+	services: Arc<Mutex<HashMap<&'static str, ServiceState>>>,
+	shutting_down: Arc<AtomicBool>,
+}
+
+impl HealthState {
+	pub fn register(&self, name: &'static str, kind: &crate::ServiceKind, status: ServiceStatus) {
+		self.services.lock().expect("poisoned").insert(
+			name,
+			ServiceState {
+				kind: kind.as_str(),
+				status,
+				crash_count: 0,
+				last_error: None,
+				last_error_ts: None,
+				must_finish: matches!(kind, crate::ServiceKind::Oneshot),
+			},
+		);
+	}
+
+	pub fn set_status(&self, name: &'static str, status: ServiceStatus) {
+		if let Some(state) = self.services.lock().expect("poisoned").get_mut(name) {
+			state.status = status;
+		}
+	}
+
+	/// Records a crash or unexpected exit and marks the service as restarting.
+	pub fn record_crash(&self, name: &'static str, err: Option<&anyhow::Error>) {
+		if let Some(state) = self.services.lock().expect("poisoned").get_mut(name) {
+			state.status = ServiceStatus::Restarting;
+			state.crash_count += 1;
+			state.last_error = Some(
+				err.map(|err| format!("{err:?}"))
+					.unwrap_or_else(|| "exited unexpectedly".to_string()),
+			);
+			state.last_error_ts = Some(chrono::Utc::now().timestamp_millis());
+		}
+	}
+
+	pub fn snapshot(&self) -> HashMap<&'static str, ServiceState> {
+		self.services.lock().expect("poisoned").clone()
+	}
+
+	/// Marks the node as not ready so traffic is drained before services are stopped.
+	pub fn set_shutting_down(&self) {
+		self.shutting_down.store(true, Ordering::SeqCst);
+	}
+
+	pub fn is_shutting_down(&self) -> bool {
+		self.shutting_down.load(Ordering::SeqCst)
+	}
+}
+
+#[derive(Clone)]
+struct HealthCtx {
+	state: HealthState,
+	pools: rivet_pools::Pools,
+}
+
+#[derive(Serialize)]
+struct HealthResponse {
+	status: &'static str,
+	services: HashMap<&'static str, ServiceState>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	dependencies: Option<HashMap<&'static str, DependencyState>>,
+}
+
+#[derive(Serialize)]
+struct DependencyState {
+	ok: bool,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	error: Option<String>,
+}
+
+/// Serves `/health/live` and `/health/ready`.
+pub async fn serve(
+	config: rivet_config::Config,
+	pools: rivet_pools::Pools,
+	state: HealthState,
+) -> Result<()> {
+	let host = config.health().host();
+	let port = config.health().port();
+	let addr = SocketAddr::from((host, port));
+
+	let router = Router::new()
+		.route("/health/live", get(live))
+		.route("/health/ready", get(ready))
+		.with_state(HealthCtx { state, pools });
+
+	let listener = tokio::net::TcpListener::bind(addr).await?;
+	tracing::info!(?host, ?port, "health server listening");
+
+	axum::serve(listener, router).await?;
+
+	Ok(())
+}
+
+/// The process is responsive. Crashed services are restarted internally, so this does not depend
+/// on service state.
+async fn live(State(ctx): State<HealthCtx>) -> (StatusCode, Json<HealthResponse>) {
+	(
+		StatusCode::OK,
+		Json(HealthResponse {
+			status: "ok",
+			services: ctx.state.snapshot(),
+			dependencies: None,
+		}),
+	)
+}
+
+/// All services are running (and oneshots finished), UDB and UPS are reachable, and the node is not
+/// shutting down.
+async fn ready(State(ctx): State<HealthCtx>) -> (StatusCode, Json<HealthResponse>) {
+	let services = ctx.state.snapshot();
+
+	if ctx.state.is_shutting_down() {
+		return (
+			StatusCode::SERVICE_UNAVAILABLE,
+			Json(HealthResponse {
+				status: "shutting_down",
+				services,
+				dependencies: None,
+			}),
+		);
+	}
+
+	let services_ready = services.values().all(|x| {
+		if x.must_finish {
+			x.status == ServiceStatus::Finished
+		} else {
+			x.status.is_ready()
+		}
+	});
+
+	let (udb_res, ups_res) = tokio::join!(probe_udb(&ctx.pools), probe_ups(&ctx.pools));
+	let dependencies = [("udb", udb_res), ("ups", ups_res)]
+		.into_iter()
+		.map(|(name, res)| {
+			let state = match res {
+				Result::Ok(()) => DependencyState {
+					ok: true,
+					error: None,
+				},
+				Err(err) => {
+					tracing::warn!(dependency = %name, ?err, "health probe failed");
+
+					DependencyState {
+						ok: false,
+						error: Some(err.to_string()),
+					}
+				}
+			};
+
+			(name, state)
+		})
+		.collect::<HashMap<_, _>>();
+
+	let ready = services_ready && dependencies.values().all(|x| x.ok);
+
+	(
+		if ready {
+			StatusCode::OK
+		} else {
+			StatusCode::SERVICE_UNAVAILABLE
+		},
+		Json(HealthResponse {
+			status: if ready { "ok" } else { "unavailable" },
+			services,
+			dependencies: Some(dependencies),
+		}),
+	)
+}
+
+async fn probe_udb(pools: &rivet_pools::Pools) -> Result<()> {
+	let udb = pools.udb()?;
+
+	tokio::time::timeout(
+		PROBE_TIMEOUT,
+		udb.run(|tx| async move {
+			let tx = tx.with_subspace(crate::leader::subspace());
+			tx.exists(&(HEALTH,), Snapshot).await?;
+
+			Result::Ok(())
+		}),
+	)
+	.await
+	.context("udb probe timed out")?
+}
+
+async fn probe_ups(pools: &rivet_pools::Pools) -> Result<()> {
+	let ups = pools.ups()?;
+
+	tokio::time::timeout(PROBE_TIMEOUT, async {
+		// Round trip a message through the driver
+		let subject = format!("rivet.health.{}", uuid::Uuid::new_v4());
+		let mut sub = ups.subscribe(&subject).await?;
+		ups.publish(&subject, &[], universalpubsub::PublishOpts::broadcast())
+			.await?;
+
+		match sub.next().await? {
+			universalpubsub::NextOutput::Message(_) => Result::Ok(()),
+			universalpubsub::NextOutput::Unsubscribed => bail!("ups subscription closed"),
+		}
+	})
+	.await
+	.context("ups probe timed out")?
+}
@@ -0,0 +1,359 @@
+use std::{
+	future::Future,
+	result::Result::Ok,
+	sync::Mutex,
+	time::{Duration, Instant},
+};
+
+use anyhow::*;
+use universaldb::prelude::*;
+use uuid::Uuid;
+
+/// How long a lease is valid for without being renewed. Determines how long it takes for another
+/// node to take over if the leader dies.
+///
+/// Lease expiry is measured with each node's local monotonic clock instead of a timestamp stored in
+/// UDB so clock skew between nodes can't cause two nodes to believe they hold the lease. A follower
+/// only takes over once it has observed the same lease value for this entire duration.
+const LEASE_DURATION: Duration = Duration::from_secs(10);
+/// How often the leader renews its lease.
+const RENEW_INTERVAL: Duration = Duration::from_secs(2);
+/// How often followers attempt to acquire the lease.
+const ACQUIRE_INTERVAL: Duration = Duration::from_secs(2);
+/// The leader steps down if it could not renew its lease within this margin of the lease expiring
+/// to guarantee it is no longer running by the time another node acquires the lease.
+const STEP_DOWN_MARGIN: Duration = Duration::from_secs(3);
+
+pub fn subspace() -> universaldb::utils::Subspace {
+	universaldb::utils::Subspace::new(&(RIVET, SERVICE_MANAGER))
+}
+
+#[derive(Debug)]
+pub struct LeaderLeaseKey {
+	service_name: String,
+}
+
+impl LeaderLeaseKey {
+	pub fn new(service_name: String) -> Self {
+		LeaderLeaseKey { service_name }
+	}
+}
+
+impl FormalKey for LeaderLeaseKey {
+	/// Leader instance id, renew counter. The counter is incremented on every renewal so followers
+	/// can tell whether the lease is still being renewed.
+	type Value = (Uuid, i64);
+
+	fn deserialize(&self, raw: &[u8]) -> Result<Self::Value> {
+		serde_json::from_slice(raw).map_err(Into::into)
+	}
+
+	fn serialize(&self, value: Self::Value) -> Result<Vec<u8>> {
+		serde_json::to_vec(&value).map_err(Into::into)
+	}
+}
+
+impl TuplePack for LeaderLeaseKey {
+	fn pack<W: std::io::Write>(
+		&self,
+		w: &mut W,
+		tuple_depth: TupleDepth,
+	) -> std::io::Result<VersionstampOffset> {
+		let t = (LEADER_LEASE, &self.service_name);
+		t.pack(w, tuple_depth)
+	}
+}
+
+impl<'de> TupleUnpack<'de> for LeaderLeaseKey {
+	fn unpack(input: &[u8], tuple_depth: TupleDepth) -> PackResult<(&[u8], Self)> {
+		let (input, (_, service_name)) = <(usize, String)>::unpack(input, tuple_depth)?;
+		let v = LeaderLeaseKey { service_name };
+
+		Ok((input, v))
+	}
+}
+
+/// Elects a single node to run a singleton service using a lease stored in UDB.
+pub struct LeaderElection {
+	udb: universaldb::Database,
+	service_name: &'static str,
+	instance_id: Uuid,
+	/// Last lease value observed by this instance and when it was first observed.
+	observed: Mutex<Option<Observation>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Observation {
+	lease: (Uuid, i64),
+	since: Instant,
+}
+
+impl LeaderElection {
+	pub fn new(udb: universaldb::Database, service_name: &'static str, instance_id: Uuid) -> Self {
+		LeaderElection {
+			udb,
+			service_name,
+			instance_id,
+			observed: Mutex::new(None),
+		}
+	}
+
+	/// Waits until this instance holds the lease.
+	pub async fn acquire(&self) {
+		let mut interval = tokio::time::interval(ACQUIRE_INTERVAL);
+		interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+		loop {
+			interval.tick().await;
+
+			match self.try_acquire().await {
+				Ok(true) => {
+					tracing::info!(service = %self.service_name, instance_id = %self.instance_id, "acquired leader lease");
+					return;
+				}
+				Ok(false) => {}
+				Err(err) => {
+					tracing::error!(service = %self.service_name, ?err, "failed to acquire leader lease");
+				}
+			}
+		}
+	}
+
+	/// Runs the given future while renewing the lease. Returns `None` if the lease was lost before
+	/// the future completed, in which case the future is dropped.
+	pub async fn run_while_leader<F, T>(&self, fut: F) -> Option<T>
+	where
+		F: Future<Output = T>,
+	{
+		tokio::select! {
+			res = fut => Some(res),
+			_ = self.renew_until_lost() => {
+				tracing::warn!(service = %self.service_name, instance_id = %self.instance_id, "lost leader lease, stopping service");
+				None
+			}
+		}
+	}
+
+	/// Gives up the lease so another node can take over immediately.
+	pub async fn release(&self) -> Result<()> {
+		let service_name = self.service_name.to_string();
+		let instance_id = self.instance_id;
+
+		self.udb
+			.run(|tx| {
+				let service_name = service_name.clone();
+				async move {
+					let tx = tx.with_subspace(subspace());
+
+					let lease_key = LeaderLeaseKey::new(service_name);
+					if let Some((leader_instance_id, _)) =
+						tx.read_opt(&lease_key, Serializable).await?
+					{
+						if leader_instance_id == instance_id {
+							tx.delete(&lease_key);
+						}
+					}
+
+					Ok(())
+				}
+			})
+			.await
+	}
+
+	/// Resolves once the lease can no longer be guaranteed to be held by this instance.
+	async fn renew_until_lost(&self) {
+		let mut last_renew = Instant::now();
+		let mut interval = tokio::time::interval(RENEW_INTERVAL);
+		interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+		// Lease was just acquired
+		interval.tick().await;
+
+		loop {
+			interval.tick().await;
+
+			let deadline = (last_renew + LEASE_DURATION - STEP_DOWN_MARGIN)
+				.saturating_duration_since(Instant::now());
+			match tokio::time::timeout(deadline, self.try_acquire()).await {
+				Ok(Ok(true)) => last_renew = Instant::now(),
+				Ok(Ok(false)) => return,
+				Ok(Err(err)) => {
+					tracing::error!(service = %self.service_name, ?err, "failed to renew leader lease");
+				}
+				Err(_) => {
+					tracing::error!(service = %self.service_name, "timed out renewing leader lease");
+				}
+			}
+
+			if should_step_down(last_renew, Instant::now()) {
+				return;
+			}
+		}
+	}
+
+	/// Acquires or renews the lease. Returns `false` if another instance holds a valid lease.
+	async fn try_acquire(&self) -> Result<bool> {
+		let service_name = self.service_name.to_string();
+		let instance_id = self.instance_id;
+		let observed = *self.observed.lock().expect("poisoned");
+
+		let (acquired, new_observed) = self
+			.udb
+			.run(|tx| {
+				let service_name = service_name.clone();
+				async move {
+					let tx = tx.with_subspace(subspace());
+
+					let lease_key = LeaderLeaseKey::new(service_name);
+					let lease = tx.read_opt(&lease_key, Serializable).await?;
+
+					match evaluate_lease(instance_id, lease, observed, Instant::now()) {
+						LeaseDecision::Write(new_lease) => {
+							tx.write(&lease_key, new_lease)?;
+
+							Ok((true, None))
+						}
+						LeaseDecision::Held(new_observed) => Ok((false, Some(new_observed))),
+					}
+				}
+			})
+			.await?;
+
+		*self.observed.lock().expect("poisoned") = new_observed;
+
+		Ok(acquired)
+	}
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum LeaseDecision {
+	/// Write this lease value, acquiring or renewing the lease.
+	Write((Uuid, i64)),
+	/// Another instance holds the lease.
+	Held(Observation),
+}
+
+/// Decides whether `instance_id` can acquire the given lease. A lease held by another instance is
+/// considered expired once it has been observed unchanged for `LEASE_DURATION`.
+fn evaluate_lease(
+	instance_id: Uuid,
+	lease: Option<(Uuid, i64)>,
+	observed: Option<Observation>,
+	now: Instant,
+) -> LeaseDecision {
+	let Some((leader_instance_id, counter)) = lease else {
+		return LeaseDecision::Write((instance_id, 0));
+	};
+
+	if leader_instance_id == instance_id {
+		return LeaseDecision::Write((instance_id, counter.wrapping_add(1)));
+	}
+
+	match observed {
+		Some(observed) if observed.lease == (leader_instance_id, counter) => {
+			if now.saturating_duration_since(observed.since) >= LEASE_DURATION {
+				LeaseDecision::Write((instance_id, counter.wrapping_add(1)))
+			} else {
+				LeaseDecision::Held(observed)
+			}
+		}
+		// Lease changed since it was last observed, start waiting again
+		_ => LeaseDecision::Held(Observation {
+			lease: (leader_instance_id, counter),
+			since: now,
+		}),
+	}
+}
+
+/// The leader must stop before any follower could consider its lease expired. Followers observe a
+/// renewal at the earliest once it is written, so stepping down `STEP_DOWN_MARGIN` before
+/// `LEASE_DURATION` has passed since the last successful renewal is always early enough.
+fn should_step_down(last_renew: Instant, now: Instant) -> bool {
+	now.saturating_duration_since(last_renew) >= LEASE_DURATION - STEP_DOWN_MARGIN
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn acquire_empty_lease() {
+		let a = Uuid::new_v4();
+
+		assert_eq!(
+			evaluate_lease(a, None, None, Instant::now()),
+			LeaseDecision::Write((a, 0))
+		);
+	}
+
+	#[test]
+	fn renew_own_lease() {
+		let a = Uuid::new_v4();
+
+		assert_eq!(
+			evaluate_lease(a, Some((a, 5)), None, Instant::now()),
+			LeaseDecision::Write((a, 6))
+		);
+	}
+
+	#[test]
+	fn wait_for_lease_expiry() {
+		let a = Uuid::new_v4();
+		let b = Uuid::new_v4();
+		let start = Instant::now();
+
+		// First observation starts the timer
+		let LeaseDecision::Held(observed) = evaluate_lease(b, Some((a, 3)), None, start) else {
+			panic!("should not acquire a lease held by another instance");
+		};
+		assert_eq!(observed.since, start);
+
+		// Unchanged lease within the lease duration is still valid
+		let now = start + LEASE_DURATION - Duration::from_millis(1);
+		assert_eq!(
+			evaluate_lease(b, Some((a, 3)), Some(observed), now),
+			LeaseDecision::Held(observed)
+		);
+
+		// Unchanged lease for the entire lease duration is expired
+		let now = start + LEASE_DURATION;
+		assert_eq!(
+			evaluate_lease(b, Some((a, 3)), Some(observed), now),
+			LeaseDecision::Write((b, 4))
+		);
+	}
+
+	#[test]
+	fn renewed_lease_resets_expiry() {
+		let a = Uuid::new_v4();
+		let b = Uuid::new_v4();
+		let start = Instant::now();
+
+		let LeaseDecision::Held(observed) = evaluate_lease(b, Some((a, 3)), None, start) else {
+			panic!("should not acquire a lease held by another instance");
+		};
+
+		let now = start + LEASE_DURATION;
+		assert_eq!(
+			evaluate_lease(b, Some((a, 4)), Some(observed), now),
+			LeaseDecision::Held(Observation {
+				lease: (a, 4),
+				since: now,
+			})
+		);
+	}
+
+	#[test]
+	fn step_down_before_followers_take_over() {
+		let start = Instant::now();
+
+		assert!(!should_step_down(start, start + RENEW_INTERVAL));
+		assert!(should_step_down(
+			start,
+			start + LEASE_DURATION - STEP_DOWN_MARGIN
+		));
+		assert!(!should_step_down(
+			start,
+			start + LEASE_DURATION - STEP_DOWN_MARGIN - Duration::from_millis(1)
+		));
+	}
+}
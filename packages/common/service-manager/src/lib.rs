@@ -1,15 +1,39 @@
-use std::{future::Future, pin::Pin, sync::Arc, time::Duration};
+use std::{collections::BTreeMap, future::Future, pin::Pin, sync::Arc, time::Duration};
 
 use anyhow::*;
+use rivet_util::{backoff::Backoff, signal::TermSignal};
+use tokio::{sync::watch, task::JoinHandle, time::Instant};
+
+pub mod health;
+mod leader;
+
+use health::{HealthState, ServiceStatus};
+
+/// Services without an explicit shutdown order are stopped last.
+const DEFAULT_SHUTDOWN_ORDER: u32 = 100;
+/// Time to allow a service to exit after signaling shutdown before it is aborted.
+const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
+/// Base delay before restarting a crashed service. Doubles with every consecutive crash.
+const RESTART_BACKOFF_BASE_MS: usize = 1_000;
+/// Caps the restart delay at ~32s.
+const RESTART_BACKOFF_MAX_EXPONENT: usize = 5;
+/// A service that ran for longer than this before crashing resets its restart backoff.
+const RESTART_BACKOFF_RESET: Duration = Duration::from_secs(60);
 
 #[derive(Clone)]
 pub struct Service {
 	pub name: &'static str,
 	pub kind: ServiceKind,
+	/// Services are shut down in ascending order. Services with the same order are shut down
+	/// concurrently.
+	pub shutdown_order: u32,
+	/// Time to allow the service to exit after signaling shutdown before it is aborted.
+	pub shutdown_timeout: Duration,
 	pub run: Arc<
 		dyn Fn(
 				rivet_config::Config,
 				rivet_pools::Pools,
+				watch::Receiver<()>,
 			) -> Pin<Box<dyn Future<Output = Result<()>> + Send>>
 			+ Send
 			+ Sync,
@@ -17,17 +41,55 @@ pub struct Service {
 }
 
 impl Service {
+	/// Creates a service that does not handle shutdown. It is dropped as soon as it is signaled to
+	/// shut down.
 	pub fn new<F, Fut>(name: &'static str, kind: ServiceKind, run: F) -> Self
 	where
 		F: Fn(rivet_config::Config, rivet_pools::Pools) -> Fut + Send + Sync + 'static,
 		Fut: Future<Output = Result<()>> + Send + 'static,
+	{
+		Self::new_graceful(name, kind, move |config, pools, mut shutdown_rx| {
+			let fut = run(config, pools);
+
+			async move {
+				tokio::select! {
+					res = fut => res,
+					_ = shutdown_rx.changed() => Ok(()),
+				}
+			}
+		})
+	}
+
+	/// Creates a service that receives a shutdown signal and is expected to exit on its own within
+	/// its shutdown timeout.
+	pub fn new_graceful<F, Fut>(name: &'static str, kind: ServiceKind, run: F) -> Self
+	where
+		F: Fn(rivet_config::Config, rivet_pools::Pools, watch::Receiver<()>) -> Fut
+			+ Send
+			+ Sync
+			+ 'static,
+		Fut: Future<Output = Result<()>> + Send + 'static,
 	{
 		Self {
 			name,
 			kind,
-			run: Arc::new(move |config, pools| Box::pin(run(config, pools))),
+			shutdown_order: DEFAULT_SHUTDOWN_ORDER,
+			shutdown_timeout: DEFAULT_SHUTDOWN_TIMEOUT,
+			run: Arc::new(move |config, pools, shutdown_rx| {
+				Box::pin(run(config, pools, shutdown_rx))
+			}),
 		}
 	}
+
+	pub fn with_shutdown_order(mut self, shutdown_order: u32) -> Self {
+		self.shutdown_order = shutdown_order;
+		self
+	}
+
+	pub fn with_shutdown_timeout(mut self, shutdown_timeout: Duration) -> Self {
+		self.shutdown_timeout = shutdown_timeout;
+		self
+	}
 }
 
 /// Defines the type of the service. Used for filtering service types to run.
@@ -48,12 +110,27 @@ impl ServiceKind {
 		use ServiceKind::*;
 
 		match self {
-			ApiPublic | ApiPeer | Standalone | Singleton | Core => ServiceBehavior::Service,
+			ApiPublic | ApiPeer | Standalone | Core => ServiceBehavior::Service,
+			Singleton => ServiceBehavior::Singleton,
 			Oneshot => ServiceBehavior::Oneshot,
 			Cron(config) => ServiceBehavior::Cron(config.clone()),
 		}
 	}
 
+	pub fn as_str(&self) -> &'static str {
+		use ServiceKind::*;
+
+		match self {
+			ApiPublic => "api_public",
+			ApiPeer => "api_peer",
+			Standalone => "standalone",
+			Singleton => "singleton",
+			Oneshot => "oneshot",
+			Cron(_) => "cron",
+			Core => "core",
+		}
+	}
+
 	pub fn eq(&self, other: &Self) -> bool {
 		use ServiceKind::*;
 
@@ -77,6 +154,11 @@ enum ServiceBehavior {
 	///
 	/// If crashes or exits, will be restarted.
 	Service,
+	/// Same as `Service`, but only runs on the node currently holding the leader lease for this
+	/// service.
+	///
+	/// If the lease is lost, the service is stopped until the lease is acquired again.
+	Singleton,
 	/// Runs a task that will exit upon completion.
 	///
 	/// If crashes, it will be retried indefinitely.
@@ -116,6 +198,9 @@ impl RunConfigData {
 ///
 /// Useful in order to allow for easily configuring an entrypoint where a custom set of services
 /// run.
+///
+/// On SIGTERM, services are signaled to shut down in ascending `shutdown_order`, each group waiting
+/// for its services to exit (or be aborted after their timeout) before moving on to the next.
 pub async fn start(
 	config: rivet_config::Config,
 	pools: rivet_pools::Pools,
@@ -123,109 +208,245 @@ pub async fn start(
 ) -> Result<()> {
 	// Spawn services
 	tracing::info!(services = ?services.len(), "starting services");
-	let mut join_set = tokio::task::JoinSet::new();
-	let cron_schedule = tokio_cron_scheduler::JobScheduler::new().await?;
+	let mut groups = BTreeMap::<u32, ShutdownGroup>::new();
+	let mut cron_schedule = tokio_cron_scheduler::JobScheduler::new().await?;
 	let mut sleep_indefinitely = false;
+	// Identifies this node when electing leaders for singleton services
+	let instance_id = uuid::Uuid::new_v4();
+
+	// Listen for termination before spawning services so no signal is missed
+	let mut term_signal = TermSignal::new()?;
+
+	// Serve health checks
+	let health = HealthState::default();
+	let health_handle = tokio::spawn({
+		let config = config.clone();
+		let pools = pools.clone();
+		let health = health.clone();
+		async move {
+			if let Err(err) = health::serve(config, pools, health).await {
+				tracing::error!(?err, "health server failed");
+			}
+		}
+	});
+
 	for service in services {
 		tracing::debug!(name = %service.name, kind = ?service.kind, "server starting service");
 
+		health.register(
+			service.name,
+			&service.kind,
+			match service.kind.behavior() {
+				ServiceBehavior::Singleton => ServiceStatus::Standby,
+				ServiceBehavior::Cron(_) => ServiceStatus::Scheduled,
+				_ => ServiceStatus::Running,
+			},
+		);
+
+		let group = groups
+			.entry(service.shutdown_order)
+			.or_insert_with(ShutdownGroup::new);
+		let mut shutdown_rx = group.tx.subscribe();
+
 		match service.kind.behavior() {
 			ServiceBehavior::Service => {
-				join_set
-					.build_task()
+				let handle = tokio::task::Builder::new()
 					.name(&format!("rivet::service::{}", service.name))
 					.spawn({
 						let config = config.clone();
 						let pools = pools.clone();
+						let health = health.clone();
+						let service = service.clone();
 						async move {
 							tracing::debug!(service = %service.name, "starting service");
 
+							let mut backoff = RestartBackoff::new();
 							loop {
-								match (service.run)(config.clone(), pools.clone()).await {
+								let res = (service.run)(
+									config.clone(),
+									pools.clone(),
+									shutdown_rx.clone(),
+								)
+								.await;
+
+								if is_shutting_down(&shutdown_rx) {
+									tracing::info!(service = %service.name, ?res, "service stopped");
+									health.set_status(service.name, ServiceStatus::Stopped);
+									break;
+								}
+
+								match res {
 									Result::Ok(_) => {
 										tracing::error!(service = %service.name, "service exited unexpectedly");
+										health.record_crash(service.name, None);
 									}
 									Err(err) => {
 										tracing::error!(service = %service.name, ?err, "service crashed");
+										health.record_crash(service.name, Some(&err));
 									}
 								}
 
-								tokio::time::sleep(Duration::from_secs(1)).await;
+								if !backoff.wait(&mut shutdown_rx).await {
+									health.set_status(service.name, ServiceStatus::Stopped);
+									break;
+								}
 
 								tracing::info!(service = %service.name, "restarting service");
+								health.set_status(service.name, ServiceStatus::Running);
 							}
 						}
 					})
 					.context("failed to spawn service")?;
+				group.push(&service, handle);
+			}
+			ServiceBehavior::Singleton => {
+				let leader_election =
+					leader::LeaderElection::new((*pools.udb()?).clone(), service.name, instance_id);
+
+				let handle = tokio::task::Builder::new()
+					.name(&format!("rivet::singleton::{}", service.name))
+					.spawn({
+						let config = config.clone();
+						let pools = pools.clone();
+						let health = health.clone();
+						let service = service.clone();
+						async move {
+							tracing::debug!(service = %service.name, "waiting for leader lease");
+
+							let mut backoff = RestartBackoff::new();
+							loop {
+								tokio::select! {
+									_ = leader_election.acquire() => {}
+									_ = shutdown_rx.changed() => {
+										health.set_status(service.name, ServiceStatus::Stopped);
+										break;
+									}
+								}
+
+								tracing::info!(service = %service.name, "starting singleton service");
+								health.set_status(service.name, ServiceStatus::Running);
+
+								let res = leader_election
+									.run_while_leader((service.run)(
+										config.clone(),
+										pools.clone(),
+										shutdown_rx.clone(),
+									))
+									.await;
+
+								// Let another node take over while this one restarts or shuts down
+								if let Err(err) = leader_election.release().await {
+									tracing::error!(service = %service.name, ?err, "failed to release leader lease");
+								}
+
+								if is_shutting_down(&shutdown_rx) {
+									tracing::info!(service = %service.name, ?res, "singleton service stopped");
+									health.set_status(service.name, ServiceStatus::Stopped);
+									break;
+								}
+
+								match res {
+									Some(Result::Ok(_)) => {
+										tracing::error!(service = %service.name, "service exited unexpectedly");
+										health.record_crash(service.name, None);
+									}
+									Some(Err(err)) => {
+										tracing::error!(service = %service.name, ?err, "service crashed");
+										health.record_crash(service.name, Some(&err));
+									}
+									None => {}
+								}
+
+								if !backoff.wait(&mut shutdown_rx).await {
+									health.set_status(service.name, ServiceStatus::Stopped);
+									break;
+								}
+
+								health.set_status(service.name, ServiceStatus::Standby);
+							}
+						}
+					})
+					.context("failed to spawn singleton")?;
+				group.push(&service, handle);
 			}
 			ServiceBehavior::Oneshot => {
-				join_set
-					.build_task()
+				let handle = tokio::task::Builder::new()
 					.name(&format!("rivet::oneoff::{}", service.name))
 					.spawn({
 						let config = config.clone();
 						let pools = pools.clone();
+						let health = health.clone();
+						let service = service.clone();
 						async move {
 							tracing::debug!(oneoff = %service.name, "starting oneoff");
 
+							let mut backoff = RestartBackoff::new();
 							loop {
-								match (service.run)(config.clone(), pools.clone()).await {
-									Result::Ok(_) => {
+								let res = (service.run)(
+									config.clone(),
+									pools.clone(),
+									shutdown_rx.clone(),
+								)
+								.await;
+
+								match res {
+									Result::Ok(_) if !is_shutting_down(&shutdown_rx) => {
 										tracing::debug!(oneoff = %service.name, "oneoff finished");
+										health.set_status(service.name, ServiceStatus::Finished);
+										break;
+									}
+									Result::Ok(_) => {
+										tracing::info!(oneoff = %service.name, "oneoff stopped");
+										health.set_status(service.name, ServiceStatus::Stopped);
 										break;
 									}
 									Err(err) => {
 										tracing::error!(oneoff = %service.name, ?err, "oneoff crashed");
+										health.record_crash(service.name, Some(&err));
 
-										tokio::time::sleep(Duration::from_secs(1)).await;
+										if !backoff.wait(&mut shutdown_rx).await {
+											health.set_status(service.name, ServiceStatus::Stopped);
+											break;
+										}
 
 										tracing::info!(oneoff = %service.name, "restarting oneoff");
+										health.set_status(service.name, ServiceStatus::Running);
 									}
 								}
 							}
 						}
 					})
 					.context("failed to spawn oneoff")?;
+				group.push(&service, handle);
 			}
 			ServiceBehavior::Cron(cron_config) => {
 				sleep_indefinitely = true;
 
 				// Spawn immediate task
 				if cron_config.run_immediately {
-					let service = service.clone();
-					join_set
-						.build_task()
+					let handle = tokio::task::Builder::new()
 						.name(&format!("rivet::cron_immediate::{}", service.name))
 						.spawn({
 							let config = config.clone();
 							let pools = pools.clone();
+							let health = health.clone();
+							let service = service.clone();
+							let shutdown_rx = shutdown_rx.clone();
 							async move {
 								tracing::debug!(cron = %service.name, "starting immediate cron");
 
-								for attempt in 1..=8 {
-									match (service.run)(config.clone(), pools.clone()).await {
-										Result::Ok(_) => {
-											tracing::debug!(cron = %service.name, ?attempt, "cron finished");
-											break;
-										}
-										Err(err) => {
-											tracing::error!(cron = %service.name, ?attempt, ?err, "cron crashed");
-
-											tokio::time::sleep(Duration::from_secs(1)).await;
-
-											tracing::info!(cron = %service.name, ?attempt, "restarting cron");
-										}
-									}
-								}
+								run_cron(&service, config, pools, health, shutdown_rx).await;
 							}
 						})
 						.context("failed to spawn cron")?;
+					group.push(&service, handle);
 				}
 
 				// Spawn cron
 				let config = config.clone();
 				let pools = pools.clone();
 				let service = service.clone();
+				let health = health.clone();
 				cron_schedule
 					.add(tokio_cron_scheduler::Job::new_async_tz(
 						&cron_config.schedule,
@@ -234,24 +455,12 @@ pub async fn start(
 							let config = config.clone();
 							let pools = pools.clone();
 							let service = service.clone();
+							let health = health.clone();
+							let shutdown_rx = shutdown_rx.clone();
 							Box::pin(async move {
 								tracing::debug!(cron = %service.name, ?notification, "running cron");
 
-								for attempt in 1..=8 {
-									match (service.run)(config.clone(), pools.clone()).await {
-										Result::Ok(_) => {
-											tracing::debug!(cron = %service.name, ?attempt, "cron finished");
-											return;
-										}
-										Err(err) => {
-											tracing::error!(cron = %service.name, ?attempt, ?err, "cron crashed");
-
-											tokio::time::sleep(Duration::from_secs(1)).await;
-
-											tracing::info!(cron = %service.name, ?attempt, "restarting cron");
-										}
-									}
-								}
+								run_cron(&service, config, pools, health, shutdown_rx).await;
 							})
 						},
 					)?)
@@ -262,15 +471,318 @@ pub async fn start(
 
 	cron_schedule.start().await?;
 
-	if sleep_indefinitely {
-		std::future::pending().await
-	} else {
-		// Wait for services
-		join_set.join_all().await;
+	let shutdown = tokio::select! {
+		_ = term_signal.recv() => true,
+		_ = wait_for_services(&mut groups), if !sleep_indefinitely => false,
+	};
+
+	if shutdown {
+		tracing::info!("received termination signal, shutting down services");
+
+		// Fail readiness checks so traffic is drained from this node
+		health.set_shutting_down();
+
+		// Stop scheduling new cron runs
+		if let Err(err) = cron_schedule.shutdown().await {
+			tracing::error!(?err, "failed to shut down cron scheduler");
+		}
+
+		tokio::select! {
+			_ = shutdown_services(&mut groups) => {}
+			_ = term_signal.recv() => {
+				tracing::warn!("received second termination signal, aborting services");
+			}
+		}
+
+		// Abort any services that are still running
+		for group in groups.values() {
+			for task in &group.tasks {
+				task.handle.abort();
+			}
+		}
+
+		health_handle.abort();
 
+		if let Err(err) = pools.close().await {
+			tracing::error!(?err, "failed to close pools");
+		}
+
+		tracing::info!("shutdown complete");
+	} else {
 		// Exit
 		tracing::info!("all services finished");
+		health_handle.abort();
+	}
 
-		Ok(())
+	Ok(())
+}
+
+/// Services that share a shutdown order.
+struct ShutdownGroup {
+	tx: watch::Sender<()>,
+	tasks: Vec<ServiceTask>,
+}
+
+impl ShutdownGroup {
+	fn new() -> Self {
+		ShutdownGroup {
+			tx: watch::Sender::new(()),
+			tasks: Vec::new(),
+		}
+	}
+
+	fn push(&mut self, service: &Service, handle: JoinHandle<()>) {
+		self.tasks.push(ServiceTask {
+			name: service.name,
+			shutdown_timeout: service.shutdown_timeout,
+			handle,
+		});
+	}
+}
+
+struct ServiceTask {
+	name: &'static str,
+	shutdown_timeout: Duration,
+	handle: JoinHandle<()>,
+}
+
+/// Waits for all service tasks to exit on their own.
+async fn wait_for_services(groups: &mut BTreeMap<u32, ShutdownGroup>) {
+	for group in groups.values_mut() {
+		for task in &mut group.tasks {
+			if let Err(err) = (&mut task.handle).await {
+				tracing::error!(service = %task.name, ?err, "service task failed");
+			}
+		}
+	}
+}
+
+/// Signals each group to shut down in order, aborting services that do not exit within their
+/// timeout.
+async fn shutdown_services(groups: &mut BTreeMap<u32, ShutdownGroup>) {
+	for (order, group) in groups.iter_mut() {
+		tracing::info!(
+			?order,
+			services = ?group.tasks.iter().map(|x| x.name).collect::<Vec<_>>(),
+			"shutting down services",
+		);
+
+		group.tx.send_replace(());
+		let shutdown_start = Instant::now();
+
+		// Services in the group shut down concurrently, each with its own deadline
+		for task in &mut group.tasks {
+			if task.handle.is_finished() {
+				continue;
+			}
+
+			match tokio::time::timeout_at(shutdown_start + task.shutdown_timeout, &mut task.handle)
+				.await
+			{
+				Result::Ok(_) => {
+					tracing::debug!(service = %task.name, "service shut down");
+				}
+				Err(_) => {
+					tracing::warn!(service = %task.name, timeout = ?task.shutdown_timeout, "service did not shut down in time, aborting");
+					task.handle.abort();
+				}
+			}
+		}
+	}
+}
+
+/// Runs a cron, retrying if it fails.
+async fn run_cron(
+	service: &Service,
+	config: rivet_config::Config,
+	pools: rivet_pools::Pools,
+	health: HealthState,
+	mut shutdown_rx: watch::Receiver<()>,
+) {
+	let mut backoff = RestartBackoff::new();
+	for attempt in 1..=8 {
+		match (service.run)(config.clone(), pools.clone(), shutdown_rx.clone()).await {
+			Result::Ok(_) => {
+				tracing::debug!(cron = %service.name, ?attempt, "cron finished");
+				break;
+			}
+			Err(err) => {
+				tracing::error!(cron = %service.name, ?attempt, ?err, "cron crashed");
+				health.record_crash(service.name, Some(&err));
+
+				if !backoff.wait(&mut shutdown_rx).await {
+					break;
+				}
+
+				tracing::info!(cron = %service.name, ?attempt, "restarting cron");
+			}
+		}
+	}
+
+	health.set_status(service.name, ServiceStatus::Scheduled);
+}
+
+/// Returns true if the service has been signaled to shut down.
+fn is_shutting_down(shutdown_rx: &watch::Receiver<()>) -> bool {
+	// The sender is only dropped once the service manager exits
+	shutdown_rx.has_changed().unwrap_or(true)
+}
+
+/// Exponential backoff between restarts of a crashing service.
+struct RestartBackoff {
+	retry_count: usize,
+	last_start: Instant,
+}
+
+impl RestartBackoff {
+	fn new() -> Self {
+		RestartBackoff {
+			retry_count: 0,
+			last_start: Instant::now(),
+		}
+	}
+
+	/// Waits before the next restart. Returns false if shutdown was signaled while waiting.
+	async fn wait(&mut self, shutdown_rx: &mut watch::Receiver<()>) -> bool {
+		// Service ran long enough to be considered healthy
+		if self.last_start.elapsed() > RESTART_BACKOFF_RESET {
+			self.retry_count = 0;
+		}
+
+		let mut backoff = Backoff::new_at(
+			RESTART_BACKOFF_MAX_EXPONENT,
+			None,
+			RESTART_BACKOFF_BASE_MS,
+			500,
+			self.retry_count,
+		);
+		let next = backoff.step().expect("should not have max retry");
+		self.retry_count += 1;
+
+		tokio::select! {
+			_ = tokio::time::sleep_until(next) => {
+				self.last_start = Instant::now();
+				true
+			}
+			_ = shutdown_rx.changed() => false,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Mutex;
+
+	use super::*;
+
+	#[tokio::test(start_paused = true)]
+	async fn shutdown_in_order() {
+		let mut groups = BTreeMap::new();
+		let stopped = Arc::new(Mutex::new(Vec::new()));
+
+		for (order, drain) in [(2, 0), (0, 5), (1, 1)] {
+			let stopped = stopped.clone();
+			let group = groups.entry(order).or_insert_with(ShutdownGroup::new);
+			let mut shutdown_rx = group.tx.subscribe();
+			group.tasks.push(ServiceTask {
+				name: "test",
+				shutdown_timeout: Duration::from_secs(10),
+				handle: tokio::spawn(async move {
+					let _ = shutdown_rx.changed().await;
+					tokio::time::sleep(Duration::from_secs(drain)).await;
+					stopped.lock().unwrap().push(order);
+				}),
+			});
+		}
+
+		shutdown_services(&mut groups).await;
+
+		assert_eq!(*stopped.lock().unwrap(), vec![0, 1, 2]);
+	}
+
+	#[tokio::test(start_paused = true)]
+	async fn shutdown_aborts_after_timeout() {
+		let mut groups = BTreeMap::new();
+
+		// Ignores the shutdown signal
+		let group = groups.entry(0).or_insert_with(ShutdownGroup::new);
+		group.tasks.push(ServiceTask {
+			name: "stuck",
+			shutdown_timeout: Duration::from_secs(1),
+			handle: tokio::spawn(std::future::pending()),
+		});
+
+		let group = groups.entry(1).or_insert_with(ShutdownGroup::new);
+		let mut shutdown_rx = group.tx.subscribe();
+		let (stopped_tx, stopped_rx) = tokio::sync::oneshot::channel();
+		group.tasks.push(ServiceTask {
+			name: "next",
+			shutdown_timeout: Duration::from_secs(1),
+			handle: tokio::spawn(async move {
+				let _ = shutdown_rx.changed().await;
+				let _ = stopped_tx.send(Instant::now());
+			}),
+		});
+
+		let start = Instant::now();
+		shutdown_services(&mut groups).await;
+
+		// Next group is signaled once the previous group timed out
+		let stopped_ts = stopped_rx.await.unwrap();
+		assert_eq!(stopped_ts - start, Duration::from_secs(1));
+
+		let stuck = &mut groups.get_mut(&0).unwrap().tasks[0].handle;
+		assert!(stuck.await.unwrap_err().is_cancelled());
+	}
+
+	#[tokio::test(start_paused = true)]
+	async fn restart_backoff_grows() {
+		let (_shutdown_tx, mut shutdown_rx) = watch::channel(());
+		let mut backoff = RestartBackoff::new();
+
+		let mut waits = Vec::new();
+		for _ in 0..8 {
+			let start = Instant::now();
+			assert!(backoff.wait(&mut shutdown_rx).await);
+			waits.push(start.elapsed());
+		}
+
+		for (i, wait) in waits.iter().enumerate() {
+			let base = Duration::from_millis(
+				(RESTART_BACKOFF_BASE_MS * 2usize.pow(i.min(RESTART_BACKOFF_MAX_EXPONENT) as u32))
+					as u64,
+			);
+			assert!(
+				*wait >= base && *wait < base + Duration::from_millis(500),
+				"restart {i} waited {wait:?}"
+			);
+		}
+	}
+
+	#[tokio::test(start_paused = true)]
+	async fn restart_backoff_resets_after_healthy_run() {
+		let (_shutdown_tx, mut shutdown_rx) = watch::channel(());
+		let mut backoff = RestartBackoff::new();
+
+		for _ in 0..4 {
+			assert!(backoff.wait(&mut shutdown_rx).await);
+		}
+
+		// Service ran long enough before crashing again
+		tokio::time::advance(RESTART_BACKOFF_RESET + Duration::from_secs(1)).await;
+
+		let start = Instant::now();
+		assert!(backoff.wait(&mut shutdown_rx).await);
+		assert!(start.elapsed() < Duration::from_millis(RESTART_BACKOFF_BASE_MS as u64 + 500));
+	}
+
+	#[tokio::test(start_paused = true)]
+	async fn restart_backoff_interrupted_by_shutdown() {
+		let (shutdown_tx, mut shutdown_rx) = watch::channel(());
+		let mut backoff = RestartBackoff::new();
+
+		shutdown_tx.send_replace(());
+
+		assert!(!backoff.wait(&mut shutdown_rx).await);
 	}
 }
@@ -62,6 +62,7 @@ pub async fn setup_single_datacenter(
 	);
 	// Pick ports for other services
 	let pegboard_port = portpicker::pick_unused_port().context("pegboard_port")?;
+	let health_port = portpicker::pick_unused_port().context("health_port")?;
 
 	tracing::info!(
 		dc = dc.datacenter_label,
@@ -84,6 +85,10 @@ pub async fn setup_single_datacenter(
 		port: Some(pegboard_port),
 		..Default::default()
 	});
+	root.health = Some(rivet_config::config::Health {
+		port: Some(health_port),
+		..Default::default()
+	});
 
 	root.topology = Some(rivet_config::config::topology::Topology {
 		datacenter_label: dc.datacenter_label,
@@ -94,6 +99,7 @@ pub async fn setup_single_datacenter(
 		host: None,
 		port: Some(guard_port),
 		https: None,
+		actor_routing: None,
 	});
 
 	tracing::info!(
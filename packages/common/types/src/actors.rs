@@ -32,6 +32,128 @@ pub enum CrashPolicy {
 	Destroy,
 }
 
+/// Limits how often an actor with `CrashPolicy::Restart` is restarted after crashing, so a crash
+/// looping actor does not keep getting rescheduled forever.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Hash, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RestartPolicy {
+	/// Max amount of consecutive restarts before `limit_action` is taken. Unlimited if not set.
+	pub max_restarts: Option<u32>,
+	/// Restarts count as consecutive if they happen within this duration of the previous restart.
+	/// Defaults to 10 minutes.
+	pub window_ms: Option<i64>,
+	/// Delay before the second consecutive restart. Doubles with every following restart. Defaults
+	/// to 2 seconds. At most 1 hour.
+	pub backoff_base_ms: Option<u64>,
+	/// Max delay between restarts. Defaults to 256 times `backoff_base_ms`. At most 1 hour.
+	pub backoff_max_ms: Option<u64>,
+	/// What to do with the actor once `max_restarts` is exceeded.
+	#[serde(default)]
+	pub limit_action: RestartLimitAction,
+}
+
+#[derive(Debug, Copy, Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RestartLimitAction {
+	Sleep,
+	#[default]
+	Destroy,
+}
+
+impl From<RestartLimitAction> for CrashPolicy {
+	fn from(value: RestartLimitAction) -> Self {
+		match value {
+			RestartLimitAction::Sleep => CrashPolicy::Sleep,
+			RestartLimitAction::Destroy => CrashPolicy::Destroy,
+		}
+	}
+}
+
+/// Order in which pending actors are allocated when there is no runner capacity. Actors with a
+/// higher priority are allocated before actors with a lower priority regardless of how long they
+/// have been pending.
+#[derive(Debug, Copy, Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ActorPriority {
+	Low = 0,
+	#[default]
+	Normal = 1,
+	High = 2,
+}
+
+/// Named alarm registered by an actor. The actor is woken and notified once `ts` is reached.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ActorAlarm {
+	pub name: String,
+	pub ts: i64,
+	/// Arbitrary user-defined binary data, base64 encoded.
+	pub payload: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ActorLogEntry {
+	pub stream: ActorLogStream,
+	pub ts: i64,
+	/// Generation of the actor that wrote this line. Increments every time the actor is
+	/// rescheduled.
+	pub generation: u32,
+	pub message: String,
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ActorLogStream {
+	Stdout,
+	Stderr,
+}
+
+impl From<rivet_data::generated::pegboard_actor_log_entry_v1::Data> for ActorLogEntry {
+	fn from(value: rivet_data::generated::pegboard_actor_log_entry_v1::Data) -> Self {
+		use rivet_data::generated::pegboard_actor_log_entry_v1::Stream;
+
+		ActorLogEntry {
+			stream: match value.stream {
+				Stream::Stdout => ActorLogStream::Stdout,
+				Stream::Stderr => ActorLogStream::Stderr,
+			},
+			ts: value.ts,
+			generation: value.generation,
+			message: value.message,
+		}
+	}
+}
+
+impl From<ActorLogEntry> for rivet_data::generated::pegboard_actor_log_entry_v1::Data {
+	fn from(value: ActorLogEntry) -> Self {
+		use rivet_data::generated::pegboard_actor_log_entry_v1::Stream;
+
+		rivet_data::generated::pegboard_actor_log_entry_v1::Data {
+			stream: match value.stream {
+				ActorLogStream::Stdout => Stream::Stdout,
+				ActorLogStream::Stderr => Stream::Stderr,
+			},
+			ts: value.ts,
+			generation: value.generation,
+			message: value.message,
+		}
+	}
+}
+
+/// Constraints on which runners an actor can be allocated to.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Hash, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ActorPlacement {
+	/// Labels that a runner must have with matching values to be allocated this actor.
+	#[serde(default)]
+	pub required_labels: StringHashableMap,
+	/// Labels that a runner should have. Runners matching more of these labels are preferred.
+	#[serde(default)]
+	pub preferred_labels: StringHashableMap,
+	/// Prefer runners that are not already running an actor with the same name.
+	#[serde(default)]
+	pub anti_affinity: bool,
+}
+
 #[derive(Debug, Deserialize, Serialize, Hash, ToSchema)]
 pub struct ActorName {
 	pub metadata: serde_json::Map<String, serde_json::Value>,
@@ -39,7 +161,7 @@ pub struct ActorName {
 
 // HACK: We can't define ToSchema on HashableMap directly, so we have to define concrete types that
 // we want to be supported in OpenAPI
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, Hash)]
 pub struct StringHashableMap(pub util::serde::HashableMap<String, String>);
 
 impl From<util::serde::HashableMap<String, String>> for StringHashableMap {
@@ -122,4 +122,24 @@ define_keys! {
 	(94, SERVERLESS, "serverless"),
 	(95, DESIRED_SLOTS, "desired_slots"),
 	(96, BY_VARIANT, "by_variant"),
+	(97, SERVICE_MANAGER, "service_manager"),
+	(98, LEADER_LEASE, "leader_lease"),
+	(99, HEALTH, "health"),
+	(100, COMMITTED_PREFIX, "committed_prefix"),
+	(101, SNAPSHOT_WATERMARK, "snapshot_watermark"),
+	(102, COMMAND_ERROR, "command_error"),
+	(103, LABEL, "label"),
+	(104, PLACEMENT, "placement"),
+	(105, ANTI_AFFINITY, "anti_affinity"),
+	(106, SLOTS, "slots"),
+	(107, ROLLOUT, "rollout"),
+	(108, ALLOCATIONS, "allocations"),
+	(109, CRASHES, "crashes"),
+	(110, ROLLED_BACK_TS, "rolled_back_ts"),
+	(111, PENDING_ACTOR_BY_PRIORITY, "pending_actor_by_priority"),
+	(112, ALARM, "alarm"),
+	(113, PENDING_ACTOR_COUNT, "pending_actor_count"),
+	(114, PREEMPTIBLE_ACTOR, "preemptible_actor"),
+	(115, EXCLUDE_RUNNER_ID, "exclude_runner_id"),
+	(116, PROTOCOL_VERSION, "protocol_version"),
 }
@@ -45,6 +45,22 @@ pub fn end_of_key_range(key: &[u8]) -> Vec<u8> {
 	end_key
 }
 
+/// Returns the first key that does not start with the given prefix.
+///
+/// Trailing 0xFF bytes are stripped and the last remaining byte is incremented. Panics if the
+/// prefix is empty or consists only of 0xFF bytes.
+pub fn strinc(prefix: &[u8]) -> Vec<u8> {
+	let len = prefix
+		.iter()
+		.rposition(|b| *b != 0xFF)
+		.expect("key must contain a byte that is not 0xFF")
+		+ 1;
+
+	let mut end_key = prefix[..len].to_vec();
+	end_key[len - 1] += 1;
+	end_key
+}
+
 // Copied from foundationdb crate
 #[inline]
 pub fn parse_bytes(input: &[u8], num: usize) -> PackResult<(&[u8], &[u8])> {
@@ -32,7 +32,13 @@ pub enum IdError {
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Id {
+	/// Random UUIDv4 + label.
 	V1([u8; 18]),
+	/// Time-ordered UUIDv7 + label.
+	///
+	/// IDs created later sort after earlier IDs when packed in a tuple, so inserts into indexes
+	/// keyed by this ID are append-only.
+	V2([u8; 18]),
 }
 
 impl Id {
@@ -51,19 +57,66 @@ impl Id {
 		Id::V1(data)
 	}
 
+	pub fn new_v2(label: u16) -> Self {
+		Self::v2(Uuid::now_v7(), label)
+	}
+
+	/// Construct V2 from components.
+	pub fn v2(uuid: Uuid, label: u16) -> Self {
+		let mut data = [0u8; 18];
+		data[..16].copy_from_slice(uuid.as_bytes());
+		data[16..].copy_from_slice(&label.to_be_bytes());
+		Id::V2(data)
+	}
+
+	/// Construct a new V2 ID if `time_ordered` is true, otherwise a new V1 ID.
+	pub fn new_with_ordering(time_ordered: bool, label: u16) -> Self {
+		if time_ordered {
+			Self::new_v2(label)
+		} else {
+			Self::new_v1(label)
+		}
+	}
+
 	pub fn nil() -> Self {
 		Id::V1([0u8; 18])
 	}
 
+	pub fn version(&self) -> u8 {
+		match self {
+			Id::V1(_) => 1,
+			Id::V2(_) => 2,
+		}
+	}
+
+	pub fn uuid(&self) -> Uuid {
+		let mut b = [0u8; 16];
+		b.copy_from_slice(&self.data()[..16]);
+		Uuid::from_bytes(b)
+	}
+
 	pub fn label(&self) -> u16 {
+		let mut b = [0u8; 2];
+		b.copy_from_slice(&self.data()[16..]);
+		u16::from_be_bytes(b)
+	}
+
+	/// Unix timestamp in milliseconds of when this ID was created. Only V2 IDs are time-ordered.
+	pub fn timestamp_ms(&self) -> Option<u64> {
 		match self {
-			Id::V1(data) => {
-				let mut b = [0u8; 2];
-				b.copy_from_slice(&data[16..]);
-				u16::from_be_bytes(b)
+			Id::V1(_) => None,
+			Id::V2(_) => {
+				let (secs, nanos) = self.uuid().get_timestamp()?.to_unix();
+				Some(secs * 1000 + (nanos / 1_000_000) as u64)
 			}
 		}
 	}
+
+	fn data(&self) -> &[u8; 18] {
+		match self {
+			Id::V1(data) | Id::V2(data) => data,
+		}
+	}
 }
 
 impl Id {
@@ -73,15 +126,11 @@ impl Id {
 
 	/// Convert the ID to its byte representation.
 	pub fn as_bytes(&self) -> Vec<u8> {
-		match self {
-			Id::V1(data) => {
-				let mut bytes = [0; 19];
-				bytes[0] = 1; // Version byte
-				bytes[1..].copy_from_slice(data);
+		let mut bytes = [0; 19];
+		bytes[0] = self.version(); // Version byte
+		bytes[1..].copy_from_slice(self.data());
 
-				bytes.to_vec()
-			}
-		}
+		bytes.to_vec()
 	}
 
 	/// Construct an ID from its byte representation.
@@ -91,7 +140,7 @@ impl Id {
 		}
 
 		match bytes[0] {
-			1 => {
+			v @ (1 | 2) => {
 				if bytes.len() != 19 {
 					return Err(IdError::InvalidLength {
 						expected: 19,
@@ -101,7 +150,11 @@ impl Id {
 
 				let mut data = [0u8; 18];
 				data.copy_from_slice(&bytes[1..]);
-				Ok(Id::V1(data))
+				if v == 1 {
+					Ok(Id::V1(data))
+				} else {
+					Ok(Id::V2(data))
+				}
 			}
 			v => Err(IdError::UnsupportedVersion(v)),
 		}
@@ -124,8 +177,8 @@ impl FromStr for Id {
 		);
 
 		match version {
-			1 => {
-				// v1 uses 19 bytes → 30 chars base36
+			1 | 2 => {
+				// v1 and v2 use 19 bytes → 30 chars base36
 				let expected_len = 30;
 				let got = s.len();
 				if got != expected_len {
@@ -141,7 +194,11 @@ impl FromStr for Id {
 				let mut data = [0u8; 18];
 				data.copy_from_slice(&buf[1..]);
 
-				Ok(Id::V1(data))
+				if version == 1 {
+					Ok(Id::V1(data))
+				} else {
+					Ok(Id::V2(data))
+				}
 			}
 			v => Err(IdError::UnsupportedVersion(v)),
 		}
@@ -150,32 +207,28 @@ impl FromStr for Id {
 
 impl fmt::Display for Id {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-		match self {
-			Id::V1(data) => {
-				// pack version + data into 19-byte array
-				let mut temp = [0u8; 19];
-				temp[0] = 1;
-				temp[1..].copy_from_slice(data);
-				// encode to 30-char base36
-				let mut buf = [b'0'; 30];
-				for i in 0..buf.len() {
-					let mut rem = 0u32;
-					for byte in temp.iter_mut().rev() {
-						let v = (rem << 8) | (*byte as u32);
-						*byte = (v / 36) as u8;
-						rem = v % 36;
-					}
-					buf[i] = if rem < 10 {
-						b'0' + (rem as u8)
-					} else {
-						b'a' + ((rem - 10) as u8)
-					};
-				}
-				// safe as ASCII
-				let s = unsafe { String::from_utf8_unchecked(buf.to_vec()) };
-				write!(f, "{}", s)
+		// pack version + data into 19-byte array
+		let mut temp = [0u8; 19];
+		temp[0] = self.version();
+		temp[1..].copy_from_slice(self.data());
+		// encode to 30-char base36
+		let mut buf = [b'0'; 30];
+		for i in 0..buf.len() {
+			let mut rem = 0u32;
+			for byte in temp.iter_mut().rev() {
+				let v = (rem << 8) | (*byte as u32);
+				*byte = (v / 36) as u8;
+				rem = v % 36;
 			}
+			buf[i] = if rem < 10 {
+				b'0' + (rem as u8)
+			} else {
+				b'a' + ((rem - 10) as u8)
+			};
 		}
+		// safe as ASCII
+		let s = unsafe { String::from_utf8_unchecked(buf.to_vec()) };
+		write!(f, "{}", s)
 	}
 }
 
@@ -245,7 +298,7 @@ impl<'de> TupleUnpack<'de> for Id {
 		let input = universaldb::utils::parse_code(input, universaldb::utils::codes::ID)?;
 		let (_, version) = universaldb::utils::parse_byte(input)?;
 
-		let (input, slice) = if version == 1 {
+		let (input, slice) = if version == 1 || version == 2 {
 			// Parse 19 bytes including version
 			universaldb::utils::parse_bytes(input, 19)?
 		} else {
@@ -313,4 +366,39 @@ mod tests {
 		let parsed = Id::from_str(&s).unwrap();
 		assert_eq!(parsed, id);
 	}
+
+	#[test]
+	fn test_v2_roundtrip() {
+		let label = 0xABCD;
+		let id = Id::new_v2(label);
+		let s = id.to_string();
+		assert_eq!(s.len(), 30);
+		let parsed = Id::from_str(&s).unwrap();
+		assert_eq!(parsed, id);
+		assert_eq!(parsed.label(), label);
+		assert_eq!(Id::from_slice(&id.as_bytes()).unwrap(), id);
+	}
+
+	#[test]
+	fn test_v2_packed_order() {
+		let ids = (0..10)
+			.map(|_| {
+				std::thread::sleep(std::time::Duration::from_millis(2));
+				Id::new_v2(1)
+			})
+			.collect::<Vec<_>>();
+
+		let packed = ids
+			.iter()
+			.map(|id| universaldb::tuple::pack(&(id,)))
+			.collect::<Vec<_>>();
+		assert!(packed.windows(2).all(|w| w[0] < w[1]));
+
+		let unpacked = packed
+			.iter()
+			.map(|x| universaldb::tuple::unpack::<(Id,)>(x).unwrap().0)
+			.collect::<Vec<_>>();
+		assert_eq!(unpacked, ids);
+		assert!(ids[0].timestamp_ms().is_some());
+	}
 }
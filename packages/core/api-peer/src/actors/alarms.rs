@@ -0,0 +1,39 @@
+use anyhow::Result;
+use rivet_api_builder::ApiCtx;
+use rivet_api_types::actors::{
+	alarms::{AlarmsQuery, AlarmsResponse},
+	get::GetQuery,
+};
+use rivet_util::Id;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AlarmsPath {
+	pub actor_id: Id,
+}
+
+/// Returns the pending named alarms of the actor.
+pub async fn alarms(ctx: ApiCtx, path: AlarmsPath, query: AlarmsQuery) -> Result<AlarmsResponse> {
+	// Validates the actor exists in the given namespace
+	crate::actors::get::get(
+		ctx.clone(),
+		crate::actors::get::GetPath {
+			actor_id: path.actor_id,
+		},
+		GetQuery {
+			namespace: query.namespace,
+		},
+	)
+	.await?;
+
+	let alarms_res = ctx
+		.op(pegboard::ops::actor::list_alarms::Input {
+			actor_id: path.actor_id,
+		})
+		.await?;
+
+	Ok(AlarmsResponse {
+		alarms: alarms_res.alarms,
+	})
+}
@@ -0,0 +1,198 @@
+use anyhow::{Result, ensure};
+use futures_util::StreamExt;
+use gas::prelude::*;
+use rivet_api_builder::ApiCtx;
+use rivet_api_types::actors::{
+	bulk::{
+		BulkCreateRequest, BulkCreateResponse, BulkCreateResult, BulkDestroyQuery,
+		BulkDestroyResponse, BulkError,
+	},
+	create::CreateQuery,
+};
+use rivet_error::RivetError;
+
+/// Max actors per bulk create request.
+pub const MAX_BULK_CREATE: usize = 1000;
+/// Max actors created or destroyed concurrently.
+const BULK_PARALLELISM: usize = 32;
+/// Number of actors to read per page when listing actors to destroy.
+const DESTROY_PAGE_SIZE: usize = 1000;
+
+/// Creates multiple actors in this datacenter. Failures are reported per actor instead of failing
+/// the entire request.
+///
+/// The `datacenter` of each actor is ignored, api-public groups actors by datacenter before
+/// calling this.
+pub async fn bulk_create(
+	ctx: ApiCtx,
+	_path: (),
+	query: CreateQuery,
+	body: BulkCreateRequest,
+) -> Result<BulkCreateResponse> {
+	ensure!(
+		body.actors.len() <= MAX_BULK_CREATE,
+		"too many actors, max {MAX_BULK_CREATE}"
+	);
+
+	let namespace = ctx
+		.op(namespace::ops::resolve_for_name_global::Input {
+			name: query.namespace.clone(),
+		})
+		.await?
+		.ok_or_else(|| namespace::errors::Namespace::NotFound.build())?;
+	let namespace_id = namespace.namespace_id;
+
+	let results = futures_util::stream::iter(body.actors)
+		.map(|bulk_actor| {
+			let ctx = ctx.clone();
+			let actor = bulk_actor.actor;
+
+			async move {
+				let actor_id = Id::new_with_ordering(
+					ctx.config().ids().time_ordered_actor_ids(),
+					ctx.config().dc_label(),
+				);
+
+				let res = ctx
+					.op(pegboard::ops::actor::create::Input {
+						actor_id,
+						namespace_id,
+						name: actor.name,
+						key: actor.key,
+						runner_name_selector: actor.runner_name_selector,
+						input: actor.input,
+						crash_policy: actor.crash_policy,
+						placement: actor.placement,
+						slots: actor.slots,
+						max_pending_duration_ms: actor.max_pending_duration_ms,
+						priority: actor.priority,
+						restart_policy: actor.restart_policy,
+						ttl_ms: actor.ttl_ms,
+						destroy_at_ts: actor.destroy_at_ts,
+						idle_timeout_ms: actor.idle_timeout_ms,
+						forward_request: true,
+						datacenter_name: None,
+					})
+					.await;
+
+				match res {
+					Ok(res) => BulkCreateResult {
+						actor: Some(res.actor),
+						error: None,
+					},
+					Err(err) => {
+						let err = RivetError::extract(&err);
+
+						BulkCreateResult {
+							actor: None,
+							error: Some(BulkError {
+								group: err.group().to_string(),
+								code: err.code().to_string(),
+								message: err.message().to_string(),
+							}),
+						}
+					}
+				}
+			}
+		})
+		// Preserves order of results
+		.buffered(BULK_PARALLELISM)
+		.collect::<Vec<_>>()
+		.await;
+
+	Ok(BulkCreateResponse { results })
+}
+
+/// Destroys all active actors in this datacenter matching the given filter.
+pub async fn bulk_destroy(
+	ctx: ApiCtx,
+	_path: (),
+	query: BulkDestroyQuery,
+) -> Result<BulkDestroyResponse> {
+	let namespace = ctx
+		.op(namespace::ops::resolve_for_name_global::Input {
+			name: query.namespace.clone(),
+		})
+		.await?
+		.ok_or_else(|| namespace::errors::Namespace::NotFound.build())?;
+
+	// Actors are listed newest first. Pages continue after the last listed actor so actors with
+	// the same create timestamp are not skipped or read twice.
+	let mut matched = Vec::new();
+	let mut cursor = None;
+	loop {
+		let list_res = ctx
+			.op(pegboard::ops::actor::list_for_ns::Input {
+				namespace_id: namespace.namespace_id,
+				name: query.name.clone(),
+				key: None,
+				include_destroyed: false,
+				created_before: query.created_before,
+				cursor,
+				limit: DESTROY_PAGE_SIZE,
+			})
+			.await?;
+
+		let page_len = list_res.actors.len();
+		let mut reached_end = false;
+
+		for actor in list_res.actors {
+			if query
+				.created_after
+				.is_some_and(|created_after| actor.create_ts < created_after)
+			{
+				reached_end = true;
+				break;
+			}
+
+			cursor = Some((actor.create_ts, actor.actor_id));
+
+			if let Some(key_prefix) = &query.key_prefix {
+				if !actor
+					.key
+					.as_ref()
+					.is_some_and(|key| key.starts_with(key_prefix))
+				{
+					continue;
+				}
+			}
+
+			matched.push(actor.actor_id);
+		}
+
+		// Pages can be shorter than the limit if actors are skipped while listing, so read until
+		// an empty page
+		if reached_end || page_len == 0 {
+			break;
+		}
+	}
+
+	let results = futures_util::stream::iter(matched)
+		.map(|actor_id| {
+			let ctx = ctx.clone();
+
+			async move {
+				let res = ctx
+					.signal(pegboard::workflows::actor::Destroy {})
+					.to_workflow::<pegboard::workflows::actor::Workflow>()
+					.tag("actor_id", actor_id)
+					.send()
+					.await;
+
+				(actor_id, res)
+			}
+		})
+		.buffer_unordered(BULK_PARALLELISM)
+		.collect::<Vec<_>>()
+		.await;
+
+	let mut actor_ids = Vec::with_capacity(results.len());
+	for (actor_id, res) in results {
+		match res {
+			Ok(_) => actor_ids.push(actor_id),
+			Err(err) => tracing::warn!(?err, ?actor_id, "failed to destroy actor"),
+		}
+	}
+
+	Ok(BulkDestroyResponse { actor_ids })
+}
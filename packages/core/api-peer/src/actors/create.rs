@@ -16,7 +16,10 @@ pub async fn create(
 		.await?
 		.ok_or_else(|| namespace::errors::Namespace::NotFound.build())?;
 
-	let actor_id = Id::new_v1(ctx.config().dc_label());
+	let actor_id = Id::new_with_ordering(
+		ctx.config().ids().time_ordered_actor_ids(),
+		ctx.config().dc_label(),
+	);
 
 	let res = ctx
 		.op(pegboard::ops::actor::create::Input {
@@ -27,6 +30,14 @@ pub async fn create(
 			runner_name_selector: body.runner_name_selector,
 			input: body.input.clone(),
 			crash_policy: body.crash_policy,
+			placement: body.placement,
+			slots: body.slots,
+			max_pending_duration_ms: body.max_pending_duration_ms,
+			priority: body.priority,
+			restart_policy: body.restart_policy,
+			ttl_ms: body.ttl_ms,
+			destroy_at_ts: body.destroy_at_ts,
+			idle_timeout_ms: body.idle_timeout_ms,
 			// NOTE: This can forward if the user attempts to create an actor with a target dc and this dc
 			// ends up forwarding to another.
 			forward_request: true,
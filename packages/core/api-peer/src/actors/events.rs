@@ -0,0 +1,242 @@
+use std::{convert::Infallible, future::ready};
+
+use anyhow::Result;
+use axum::{
+	extract::{Extension, Path, Query},
+	response::{
+		IntoResponse, Response,
+		sse::{Event, KeepAlive, Sse},
+	},
+};
+use futures_util::{Stream, StreamExt, stream::BoxStream};
+use gas::prelude::MessageTrait;
+use pegboard::workflows::actor as actor_wf;
+use rivet_api_builder::{ApiCtx, ApiError};
+use rivet_api_types::actors::{
+	events::{EventsQuery, LifecycleEvent, LifecycleEventKind, ListEventsQuery},
+	get::GetQuery,
+};
+use rivet_error::RivetError;
+use rivet_util::Id;
+use serde::Deserialize;
+use serde_json::json;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EventsPath {
+	pub actor_id: Id,
+}
+
+pub async fn events(
+	Extension(ctx): Extension<ApiCtx>,
+	Path(path): Path<EventsPath>,
+	Query(query): Query<EventsQuery>,
+) -> Response {
+	match events_inner(ctx, path, query).await {
+		Ok(response) => response,
+		Err(err) => ApiError::from(err).into_response(),
+	}
+}
+
+/// Streams lifecycle events of an actor as server-sent events until the actor is destroyed.
+pub async fn events_inner(ctx: ApiCtx, path: EventsPath, query: EventsQuery) -> Result<Response> {
+	// Subscribe before reading the actor so no transitions are missed in between
+	let events = subscribe(&ctx, None, json!({ "actor_id": path.actor_id })).await?;
+
+	let actor = crate::actors::get::get(
+		ctx.clone(),
+		crate::actors::get::GetPath {
+			actor_id: path.actor_id,
+		},
+		GetQuery {
+			namespace: query.namespace,
+		},
+	)
+	.await?
+	.actor;
+
+	// Nothing left to stream
+	if actor.destroy_ts.is_some() {
+		return Ok(into_sse(futures_util::stream::empty()));
+	}
+
+	// End the stream after the final event
+	let events = events.scan(false, |done, event| {
+		if *done {
+			return ready(None);
+		}
+
+		*done = matches!(
+			event,
+			Ok(LifecycleEvent {
+				event: LifecycleEventKind::DestroyComplete,
+				..
+			})
+		);
+
+		ready(Some(event))
+	});
+
+	Ok(into_sse(events))
+}
+
+pub async fn list_events(
+	Extension(ctx): Extension<ApiCtx>,
+	Query(query): Query<ListEventsQuery>,
+) -> Response {
+	match list_events_inner(ctx, query).await {
+		Ok(response) => response,
+		Err(err) => ApiError::from(err).into_response(),
+	}
+}
+
+/// Streams lifecycle events of all actors in a namespace in this datacenter as server-sent
+/// events, optionally filtered by actor name.
+pub async fn list_events_inner(ctx: ApiCtx, query: ListEventsQuery) -> Result<Response> {
+	let namespace = ctx
+		.op(namespace::ops::resolve_for_name_global::Input {
+			name: query.namespace.clone(),
+		})
+		.await?
+		.ok_or_else(|| namespace::errors::Namespace::NotFound.build())?;
+
+	let mut tags = json!({ "namespace_id": namespace.namespace_id });
+	if let Some(name) = query.name {
+		tags["name"] = json!(name);
+	}
+
+	// Lifecycle messages are also published per namespace so this only receives messages of
+	// this namespace
+	let events = subscribe(&ctx, Some(namespace.namespace_id), tags).await?;
+
+	Ok(into_sse(events))
+}
+
+/// Converts lifecycle events to server-sent events. If the subscription fails, an `error` event is
+/// sent and the stream ends.
+fn into_sse(events: impl Stream<Item = Result<LifecycleEvent>> + Send + 'static) -> Response {
+	let events = events
+		.scan(false, |failed, event| {
+			if *failed {
+				return ready(None);
+			}
+
+			let event = match event {
+				Ok(event) => Event::default().event(event.event.name()).json_data(&event),
+				Err(err) => {
+					tracing::warn!(?err, "lifecycle event subscription failed");
+					*failed = true;
+
+					let err = RivetError::extract(&err);
+					Event::default().event("error").json_data(json!({
+						"group": err.group(),
+						"code": err.code(),
+						"message": err.message(),
+					}))
+				}
+			};
+
+			ready(Some(event))
+		})
+		.filter_map(|event| {
+			ready(match event {
+				Ok(event) => Some(Ok::<_, Infallible>(event)),
+				Err(err) => {
+					tracing::warn!(?err, "failed to serialize lifecycle event");
+					None
+				}
+			})
+		});
+
+	Sse::new(events)
+		.keep_alive(KeepAlive::default())
+		.into_response()
+}
+
+/// Subscribes to all actor lifecycle messages matching the given tags. If a namespace is given,
+/// only messages of that namespace are received.
+async fn subscribe(
+	ctx: &ApiCtx,
+	namespace_id: Option<Id>,
+	tags: serde_json::Value,
+) -> Result<impl Stream<Item = Result<LifecycleEvent>> + Send + 'static> {
+	let streams = vec![
+		subscribe_msg::<actor_wf::CreateComplete>(ctx, namespace_id, &tags, |_| {
+			LifecycleEventKind::CreateComplete
+		})
+		.await?,
+		subscribe_msg::<actor_wf::Ready>(ctx, namespace_id, &tags, |msg| {
+			LifecycleEventKind::Ready {
+				runner_id: msg.runner_id,
+			}
+		})
+		.await?,
+		subscribe_msg::<actor_wf::Failed>(ctx, namespace_id, &tags, |msg| {
+			let err = RivetError::extract(&msg.error.clone().build());
+
+			LifecycleEventKind::Failed {
+				group: err.group().to_string(),
+				code: err.code().to_string(),
+				message: err.message().to_string(),
+			}
+		})
+		.await?,
+		subscribe_msg::<actor_wf::Crashed>(ctx, namespace_id, &tags, |msg| {
+			LifecycleEventKind::Crashed { lost: msg.lost }
+		})
+		.await?,
+		subscribe_msg::<actor_wf::Sleeping>(ctx, namespace_id, &tags, |_| {
+			LifecycleEventKind::Sleeping
+		})
+		.await?,
+		subscribe_msg::<actor_wf::DestroyStarted>(ctx, namespace_id, &tags, |_| {
+			LifecycleEventKind::DestroyStarted
+		})
+		.await?,
+		subscribe_msg::<actor_wf::DestroyComplete>(ctx, namespace_id, &tags, |_| {
+			LifecycleEventKind::DestroyComplete
+		})
+		.await?,
+	];
+
+	Ok(futures_util::stream::select_all(streams))
+}
+
+async fn subscribe_msg<M: MessageTrait>(
+	ctx: &ApiCtx,
+	namespace_id: Option<Id>,
+	tags: &serde_json::Value,
+	kind: fn(&M) -> LifecycleEventKind,
+) -> Result<BoxStream<'static, Result<LifecycleEvent>>> {
+	let sub = if let Some(namespace_id) = namespace_id {
+		ctx.subscribe_topic::<M>(namespace_id, tags).await?
+	} else {
+		ctx.subscribe::<M>(tags).await?
+	};
+
+	Ok(sub
+		.into_stream()
+		.filter_map(move |res| {
+			ready(match res {
+				Ok(msg) => {
+					let actor_id = msg
+						.tags()
+						.get("actor_id")
+						.and_then(|x| serde_json::from_value::<Id>(x.clone()).ok());
+
+					if let Some(actor_id) = actor_id {
+						Some(Ok(LifecycleEvent {
+							actor_id,
+							ts: msg.msg_ts(),
+							event: kind(msg.body()),
+						}))
+					} else {
+						tracing::warn!(message = M::NAME, "lifecycle message missing actor id");
+						None
+					}
+				}
+				Err(err) => Some(Err(err.into())),
+			})
+		})
+		.boxed())
+}
@@ -86,6 +86,7 @@ pub async fn list(ctx: ApiCtx, _path: (), query: ListQuery) -> Result<ListRespon
 					.as_deref()
 					.map(|c| c.parse::<i64>())
 					.transpose()?,
+				cursor: None,
 				limit: query.limit.unwrap_or(100),
 			})
 			.await?;
@@ -0,0 +1,167 @@
+use std::{collections::VecDeque, convert::Infallible, time::Duration};
+
+use anyhow::Result;
+use axum::{
+	extract::{Extension, Path, Query},
+	response::{
+		IntoResponse, Json, Response,
+		sse::{Event, KeepAlive, Sse},
+	},
+};
+use futures_util::Stream;
+use rivet_api_builder::{ApiCtx, ApiError};
+use rivet_api_types::actors::{
+	get::GetQuery,
+	logs::{LogsQuery, LogsResponse},
+};
+use rivet_types::actors::ActorLogEntry;
+use rivet_util::Id;
+use serde::Deserialize;
+
+const DEFAULT_TAIL: usize = 100;
+const MAX_TAIL: usize = 1000;
+/// How often to check for new lines when following logs.
+const FOLLOW_POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// Max number of lines to read per poll when following logs.
+const FOLLOW_READ_LIMIT: usize = 1000;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LogsPath {
+	pub actor_id: Id,
+}
+
+pub async fn logs(
+	Extension(ctx): Extension<ApiCtx>,
+	Path(path): Path<LogsPath>,
+	Query(query): Query<LogsQuery>,
+) -> Response {
+	match logs_inner(ctx, path, query).await {
+		Ok(response) => response,
+		Err(err) => ApiError::from(err).into_response(),
+	}
+}
+
+/// Returns the most recent log lines of an actor as JSON, or streams them as server-sent events
+/// if `follow` is set.
+pub async fn logs_inner(ctx: ApiCtx, path: LogsPath, query: LogsQuery) -> Result<Response> {
+	// Validates the actor exists in the given namespace
+	crate::actors::get::get(
+		ctx.clone(),
+		crate::actors::get::GetPath {
+			actor_id: path.actor_id,
+		},
+		GetQuery {
+			namespace: query.namespace,
+		},
+	)
+	.await?;
+
+	let logs_res = ctx
+		.op(pegboard::ops::actor::list_logs::Input {
+			actor_id: path.actor_id,
+			after_idx: None,
+			limit: query.tail.unwrap_or(DEFAULT_TAIL).min(MAX_TAIL),
+		})
+		.await?;
+
+	if query.follow.unwrap_or_default() {
+		let stream = follow_logs(
+			ctx,
+			path.actor_id,
+			logs_res.entries,
+			logs_res.last_idx.unwrap_or_default(),
+		);
+
+		Ok(Sse::new(stream)
+			.keep_alive(KeepAlive::default())
+			.into_response())
+	} else {
+		Ok(Json(LogsResponse {
+			entries: logs_res.entries,
+		})
+		.into_response())
+	}
+}
+
+struct FollowState {
+	ctx: ApiCtx,
+	actor_id: Id,
+	pending: VecDeque<ActorLogEntry>,
+	last_idx: u64,
+	/// Set once the actor is destroyed. The stream ends after the remaining lines are sent.
+	destroyed: bool,
+}
+
+fn follow_logs(
+	ctx: ApiCtx,
+	actor_id: Id,
+	entries: Vec<ActorLogEntry>,
+	last_idx: u64,
+) -> impl Stream<Item = Result<Event, Infallible>> {
+	let state = FollowState {
+		ctx,
+		actor_id,
+		pending: entries.into(),
+		last_idx,
+		destroyed: false,
+	};
+
+	futures_util::stream::unfold(state, |mut state| async move {
+		loop {
+			if let Some(entry) = state.pending.pop_front() {
+				match Event::default().event("log").json_data(&entry) {
+					Ok(event) => return Some((Ok(event), state)),
+					Err(err) => {
+						tracing::warn!(?err, "failed to serialize log entry");
+						continue;
+					}
+				}
+			}
+
+			if state.destroyed {
+				return None;
+			}
+
+			tokio::time::sleep(FOLLOW_POLL_INTERVAL).await;
+
+			if let Err(err) = poll_logs(&mut state).await {
+				tracing::warn!(?err, actor_id=?state.actor_id, "failed to poll actor logs");
+				return None;
+			}
+		}
+	})
+}
+
+async fn poll_logs(state: &mut FollowState) -> Result<()> {
+	// Check if destroyed before reading so that lines written before the destroy are not missed
+	let actors_res = state
+		.ctx
+		.op(pegboard::ops::actor::get::Input {
+			actor_ids: vec![state.actor_id],
+		})
+		.await?;
+	state.destroyed = actors_res
+		.actors
+		.first()
+		.map_or(true, |actor| actor.destroy_ts.is_some());
+
+	let logs_res = state
+		.ctx
+		.op(pegboard::ops::actor::list_logs::Input {
+			actor_id: state.actor_id,
+			after_idx: Some(state.last_idx),
+			limit: FOLLOW_READ_LIMIT,
+		})
+		.await?;
+
+	// More lines may be pending, keep reading even if destroyed
+	if logs_res.entries.len() == FOLLOW_READ_LIMIT {
+		state.destroyed = false;
+	}
+
+	state.pending.extend(logs_res.entries);
+	state.last_idx = logs_res.last_idx.unwrap_or(state.last_idx);
+
+	Ok(())
+}
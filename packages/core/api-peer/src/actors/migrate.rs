@@ -0,0 +1,82 @@
+use anyhow::Result;
+use rivet_api_builder::ApiCtx;
+use rivet_api_types::actors::{
+	get::GetQuery,
+	migrate::{MigrateQuery, MigrateRequest, MigrateResponse},
+};
+use rivet_util::Id;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MigratePath {
+	pub actor_id: Id,
+}
+
+/// Stops the actor and reallocates it to a different runner. Used for draining and rebalancing
+/// runners.
+pub async fn migrate(
+	ctx: ApiCtx,
+	path: MigratePath,
+	query: MigrateQuery,
+	body: MigrateRequest,
+) -> Result<MigrateResponse> {
+	let actor = crate::actors::get::get(
+		ctx.clone(),
+		crate::actors::get::GetPath {
+			actor_id: path.actor_id,
+		},
+		GetQuery {
+			namespace: query.namespace,
+		},
+	)
+	.await?
+	.actor;
+
+	if actor.destroy_ts.is_some() || actor.connectable_ts.is_none() {
+		return Err(pegboard::errors::Actor::NotRunning.build());
+	}
+
+	if let Some(runner_id) = body.runner_id {
+		let runner = ctx
+			.op(pegboard::ops::runner::get::Input {
+				runner_ids: vec![runner_id],
+			})
+			.await?
+			.runners
+			.into_iter()
+			.next()
+			.ok_or_else(|| pegboard::errors::Runner::NotFound.build())?;
+
+		if runner.namespace_id != actor.namespace_id
+			|| runner.name != actor.runner_name_selector
+			|| runner.drain_ts.is_some()
+			|| runner.stop_ts.is_some()
+		{
+			return Err(pegboard::errors::Actor::InvalidMigrationTarget.build());
+		}
+
+		let current_runner_id = ctx
+			.op(pegboard::ops::actor::get_runner::Input {
+				actor_ids: vec![path.actor_id],
+			})
+			.await?
+			.actors
+			.into_iter()
+			.next()
+			.map(|actor| actor.runner_id);
+		if current_runner_id == Some(runner_id) {
+			return Err(pegboard::errors::Actor::InvalidMigrationTarget.build());
+		}
+	}
+
+	ctx.signal(pegboard::workflows::actor::Migrate {
+		runner_id: body.runner_id,
+	})
+	.to_workflow::<pegboard::workflows::actor::Workflow>()
+	.tag("actor_id", path.actor_id)
+	.send()
+	.await?;
+
+	Ok(MigrateResponse {})
+}
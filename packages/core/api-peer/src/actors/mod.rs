@@ -1,5 +1,11 @@
+pub mod alarms;
+pub mod bulk;
 pub mod create;
 pub mod delete;
+pub mod events;
 pub mod get;
 pub mod list;
 pub mod list_names;
+pub mod logs;
+pub mod migrate;
+pub mod queue;
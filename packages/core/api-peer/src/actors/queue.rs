@@ -0,0 +1,47 @@
+use anyhow::Result;
+use rivet_api_builder::ApiCtx;
+use rivet_api_types::actors::{
+	get::GetQuery,
+	queue::{QueueQuery, QueueResponse},
+};
+use rivet_util::Id;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct QueuePath {
+	pub actor_id: Id,
+}
+
+/// Returns the actor's position in the allocation queue of its runner name.
+pub async fn queue(ctx: ApiCtx, path: QueuePath, query: QueueQuery) -> Result<QueueResponse> {
+	let actor = crate::actors::get::get(
+		ctx.clone(),
+		crate::actors::get::GetPath {
+			actor_id: path.actor_id,
+		},
+		GetQuery {
+			namespace: query.namespace,
+		},
+	)
+	.await?
+	.actor;
+
+	let queue = ctx
+		.op(pegboard::ops::runner::get_queue::Input {
+			namespace_id: actor.namespace_id,
+			runner_name_selector: actor.runner_name_selector.clone(),
+			actor_id: actor
+				.pending_allocation_ts
+				.is_some()
+				.then_some(actor.actor_id),
+		})
+		.await?;
+
+	Ok(QueueResponse {
+		runner_name_selector: actor.runner_name_selector,
+		pending_allocation_ts: actor.pending_allocation_ts,
+		position: queue.position,
+		depth: queue.depth,
+	})
+}
@@ -0,0 +1,85 @@
+use anyhow::Result;
+use epoxy::protocol::ReplicaId;
+use gas::prelude::*;
+use rivet_api_builder::ApiCtx;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+pub struct StatusResponse {
+	#[serde(flatten)]
+	pub status: epoxy::ops::cluster_status::Output,
+}
+
+pub async fn status(ctx: ApiCtx, _path: (), _query: ()) -> Result<StatusResponse> {
+	let status = ctx.op(epoxy::ops::cluster_status::Input {}).await?;
+
+	Ok(StatusResponse { status })
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ReconfigureResponse {}
+
+pub async fn reconfigure(
+	ctx: ApiCtx,
+	_path: (),
+	_query: (),
+	_body: (),
+) -> Result<ReconfigureResponse> {
+	if !ctx.config().is_leader() {
+		return Err(epoxy::errors::Coordinator::NotLeader.build());
+	}
+
+	ctx.signal(epoxy::workflows::coordinator::Reconfigure {})
+		.to_workflow::<epoxy::workflows::coordinator::Workflow>()
+		.tag("replica", ctx.config().epoxy_replica_id())
+		.send()
+		.await?;
+
+	Ok(ReconfigureResponse {})
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RemoveReplicaPath {
+	pub replica_id: ReplicaId,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RemoveReplicaResponse {}
+
+pub async fn remove_replica(
+	ctx: ApiCtx,
+	path: RemoveReplicaPath,
+	_query: (),
+) -> Result<RemoveReplicaResponse> {
+	if !ctx.config().is_leader() {
+		return Err(epoxy::errors::Coordinator::NotLeader.build());
+	}
+
+	let replica_id = ctx.config().epoxy_replica_id();
+	if path.replica_id == replica_id {
+		return Err(epoxy::errors::Coordinator::CannotRemoveCoordinator.build());
+	}
+
+	let config = ctx
+		.op(epoxy::ops::read_cluster_config::Input { replica_id })
+		.await?
+		.config;
+	if !config
+		.replicas
+		.iter()
+		.any(|r| r.replica_id == path.replica_id)
+	{
+		return Err(epoxy::errors::Coordinator::ReplicaNotFound.build());
+	}
+
+	ctx.signal(epoxy::workflows::coordinator::RemoveReplica {
+		replica_id: path.replica_id,
+	})
+	.to_workflow::<epoxy::workflows::coordinator::Workflow>()
+	.tag("replica", replica_id)
+	.send()
+	.await?;
+
+	Ok(RemoveReplicaResponse {})
+}
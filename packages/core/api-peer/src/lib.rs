@@ -1,8 +1,10 @@
 use std::net::SocketAddr;
 
 use anyhow::*;
+use tokio::sync::watch;
 
 pub mod actors;
+pub mod epoxy;
 pub mod internal;
 pub mod namespaces;
 pub mod router;
@@ -11,7 +13,11 @@ pub mod runners;
 
 pub use router::router as create_router;
 
-pub async fn start(config: rivet_config::Config, pools: rivet_pools::Pools) -> Result<()> {
+pub async fn start(
+	config: rivet_config::Config,
+	pools: rivet_pools::Pools,
+	mut shutdown_rx: watch::Receiver<()>,
+) -> Result<()> {
 	let host = config.api_peer().host();
 	let port = config.api_peer().port();
 	let addr = SocketAddr::from((host, port));
@@ -21,7 +27,12 @@ pub async fn start(config: rivet_config::Config, pools: rivet_pools::Pools) -> R
 	let listener = tokio::net::TcpListener::bind(addr).await?;
 	tracing::info!(?host, ?port, "api-peer server listening");
 
-	axum::serve(listener, router).await?;
+	// Stop accepting connections and let in-flight requests finish on shutdown
+	axum::serve(listener, router)
+		.with_graceful_shutdown(async move {
+			let _ = shutdown_rx.changed().await;
+		})
+		.await?;
 
 	Ok(())
 }
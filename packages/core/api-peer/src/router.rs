@@ -28,13 +28,38 @@ pub async fn router(
 			// MARK: Actors
 			.route("/actors", get(actors::list::list))
 			.route("/actors", post(actors::create::create))
+			.route("/actors/bulk", post(actors::bulk::bulk_create))
+			.route("/actors/bulk", delete(actors::bulk::bulk_destroy))
 			.route("/actors/{actor_id}", get(actors::get::get))
 			.route("/actors/{actor_id}", delete(actors::delete::delete))
 			.route("/actors/names", get(actors::list_names::list_names))
+			.route(
+				"/actors/events",
+				axum::routing::get(actors::events::list_events),
+			)
+			.route(
+				"/actors/{actor_id}/logs",
+				axum::routing::get(actors::logs::logs),
+			)
+			.route(
+				"/actors/{actor_id}/events",
+				axum::routing::get(actors::events::events),
+			)
+			.route("/actors/{actor_id}/queue", get(actors::queue::queue))
+			.route("/actors/{actor_id}/migrate", post(actors::migrate::migrate))
+			.route("/actors/{actor_id}/alarms", get(actors::alarms::alarms))
 			// MARK: Runners
 			.route("/runners", get(runners::list))
 			.route("/runners/{runner_id}", get(runners::get))
 			.route("/runners/names", get(runners::list_names))
+			.route("/runners/queue", get(runners::queue))
+			// MARK: Epoxy
+			.route("/epoxy/status", get(crate::epoxy::status))
+			.route("/epoxy/reconfigure", post(crate::epoxy::reconfigure))
+			.route(
+				"/epoxy/replicas/{replica_id}",
+				delete(crate::epoxy::remove_replica),
+			)
 			// MARK: Internal
 			.route("/cache/purge", post(internal::cache_purge))
 			.route(
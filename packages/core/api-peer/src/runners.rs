@@ -2,7 +2,7 @@ use anyhow::Result;
 use rivet_api_builder::ApiCtx;
 use rivet_api_types::{
 	pagination::Pagination,
-	runners::{get::*, list::*},
+	runners::{get::*, list::*, queue::*},
 };
 use rivet_util::Id;
 use serde::{Deserialize, Serialize};
@@ -129,3 +129,26 @@ pub async fn list_names(
 		pagination: Pagination { cursor },
 	})
 }
+
+/// Returns the allocation queue of a runner name in this datacenter.
+pub async fn queue(ctx: ApiCtx, _path: (), query: QueueQuery) -> Result<QueueResponse> {
+	let namespace = ctx
+		.op(namespace::ops::resolve_for_name_global::Input {
+			name: query.namespace.clone(),
+		})
+		.await?
+		.ok_or_else(|| namespace::errors::Namespace::NotFound.build())?;
+
+	let queue = ctx
+		.op(pegboard::ops::runner::get_queue::Input {
+			namespace_id: namespace.namespace_id,
+			runner_name_selector: query.runner_name,
+			actor_id: None,
+		})
+		.await?;
+
+	Ok(QueueResponse {
+		depth: queue.depth,
+		oldest_pending_allocation_ts: queue.oldest_pending_allocation_ts,
+	})
+}
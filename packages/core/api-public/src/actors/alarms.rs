@@ -0,0 +1,74 @@
+use anyhow::Result;
+use axum::{
+	extract::{Extension, Path, Query},
+	http::HeaderMap,
+	response::{IntoResponse, Json, Response},
+};
+use rivet_api_builder::{ApiCtx, ApiError};
+use rivet_api_types::actors::alarms::*;
+use rivet_api_util::request_remote_datacenter_raw;
+use rivet_util::Id;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AlarmsPath {
+	pub actor_id: Id,
+}
+
+/// Returns the named alarms the actor registered that have not fired yet.
+///
+/// ## Datacenter Round Trips
+///
+/// 2 round trips:
+/// - GET /actors/{}/alarms
+/// - [api-peer] namespace::ops::resolve_for_name_global
+#[utoipa::path(
+	get,
+	operation_id = "actors_alarms",
+	path = "/actors/{actor_id}/alarms",
+	params(
+		("actor_id" = Id, Path),
+		AlarmsQuery,
+	),
+	responses(
+		(status = 200, body = AlarmsResponse),
+	),
+)]
+pub async fn alarms(
+	Extension(ctx): Extension<ApiCtx>,
+	headers: HeaderMap,
+	Path(path): Path<AlarmsPath>,
+	Query(query): Query<AlarmsQuery>,
+) -> Response {
+	match alarms_inner(ctx, headers, path, query).await {
+		Ok(response) => response,
+		Err(err) => ApiError::from(err).into_response(),
+	}
+}
+
+async fn alarms_inner(
+	ctx: ApiCtx,
+	headers: HeaderMap,
+	path: AlarmsPath,
+	query: AlarmsQuery,
+) -> Result<Response> {
+	if path.actor_id.label() == ctx.config().dc_label() {
+		let peer_path = rivet_api_peer::actors::alarms::AlarmsPath {
+			actor_id: path.actor_id,
+		};
+		let res = rivet_api_peer::actors::alarms::alarms(ctx, peer_path, query).await?;
+		Ok(Json(res).into_response())
+	} else {
+		request_remote_datacenter_raw(
+			&ctx,
+			path.actor_id.label(),
+			&format!("/actors/{}/alarms", path.actor_id),
+			axum::http::Method::GET,
+			headers,
+			Some(&query),
+			Option::<&()>::None,
+		)
+		.await
+	}
+}
@@ -0,0 +1,233 @@
+use std::collections::HashMap;
+
+use anyhow::{Context, Result, ensure};
+use axum::{
+	extract::{Extension, Query},
+	http::HeaderMap,
+	response::{IntoResponse, Json, Response},
+};
+use rivet_api_builder::{ApiCtx, ApiError};
+use rivet_api_peer::actors::bulk::MAX_BULK_CREATE;
+use rivet_api_types::actors::bulk::*;
+use rivet_api_util::{fanout_to_datacenters_with_method, request_remote_datacenter};
+use rivet_error::RivetError;
+
+use crate::{actors::create::CreateQuery, errors};
+
+/// Creates multiple actors, each in the datacenter given by its `datacenter` field or the
+/// `datacenter` query parameter.
+///
+/// Each actor is created independently. Failures are reported per actor in `results`, which has
+/// the same order as the request. Actors are grouped by datacenter and each datacenter is
+/// requested in parallel.
+///
+/// ## Datacenter Round Trips
+///
+/// **For actors created in the current datacenter:**
+///
+/// 2 round trips:
+/// - namespace::ops::resolve_for_name_global
+/// - [pegboard::workflows::actor] Create actor workflows (includes Epoxy key allocation)
+///
+/// **For actors created in a different datacenter:**
+///
+/// 3 round trips:
+/// - namespace::ops::resolve_for_name_global
+/// - POST /actors/bulk to remote datacenter
+/// - [pegboard::workflows::actor] Create actor workflows (includes Epoxy key allocation)
+#[utoipa::path(
+	post,
+	operation_id = "actors_bulk_create",
+	path = "/actors/bulk",
+	params(CreateQuery),
+	request_body(content = BulkCreateRequest, content_type = "application/json"),
+	responses(
+		(status = 200, body = BulkCreateResponse),
+	),
+)]
+pub async fn bulk_create(
+	Extension(ctx): Extension<ApiCtx>,
+	headers: HeaderMap,
+	Query(query): Query<CreateQuery>,
+	Json(body): Json<BulkCreateRequest>,
+) -> Response {
+	match bulk_create_inner(ctx, headers, query, body).await {
+		Ok(response) => Json(response).into_response(),
+		Err(err) => ApiError::from(err).into_response(),
+	}
+}
+
+async fn bulk_create_inner(
+	ctx: ApiCtx,
+	headers: HeaderMap,
+	query: CreateQuery,
+	body: BulkCreateRequest,
+) -> Result<BulkCreateResponse> {
+	if body.actors.len() > MAX_BULK_CREATE {
+		return Err(errors::Validation::InvalidInput {
+			message: format!(
+				"Too many actors. Maximum is {MAX_BULK_CREATE}, got {}.",
+				body.actors.len()
+			),
+		}
+		.build());
+	}
+
+	// Default datacenter for actors that don't specify one
+	let default_dc_label = if let Some(dc_name) = &query.datacenter {
+		ctx.config()
+			.dc_for_name(dc_name)
+			.ok_or_else(|| errors::Datacenter::NotFound.build())?
+			.datacenter_label
+	} else {
+		ctx.config().dc_label()
+	};
+
+	let mut results = (0..body.actors.len()).map(|_| None).collect::<Vec<_>>();
+
+	// Group actors by target datacenter, keeping their index in the request
+	let mut groups = HashMap::<u16, Vec<(usize, BulkCreateActor)>>::new();
+	for (idx, actor) in body.actors.into_iter().enumerate() {
+		let dc_label = if let Some(dc_name) = &actor.datacenter {
+			match ctx.config().dc_for_name(dc_name) {
+				Some(dc) => dc.datacenter_label,
+				None => {
+					results[idx] = Some(error_result(&errors::Datacenter::NotFound.build()));
+					continue;
+				}
+			}
+		} else {
+			default_dc_label
+		};
+
+		groups.entry(dc_label).or_default().push((idx, actor));
+	}
+
+	let query = rivet_api_types::actors::create::CreateQuery {
+		namespace: query.namespace,
+	};
+
+	let group_results =
+		futures_util::future::join_all(groups.into_iter().map(|(dc_label, group)| {
+			let ctx = ctx.clone();
+			let headers = headers.clone();
+			let query = query.clone();
+
+			async move {
+				let (idxs, actors): (Vec<_>, Vec<_>) = group.into_iter().unzip();
+				let body = BulkCreateRequest { actors };
+
+				let res = if dc_label == ctx.config().dc_label() {
+					rivet_api_peer::actors::bulk::bulk_create(ctx, (), query, body).await
+				} else {
+					request_remote_datacenter::<BulkCreateResponse>(
+						ctx.config(),
+						dc_label,
+						"/actors/bulk",
+						axum::http::Method::POST,
+						headers,
+						Some(&query),
+						Some(&body),
+					)
+					.await
+				};
+
+				(idxs, res)
+			}
+		}))
+		.await;
+
+	for (idxs, res) in group_results {
+		match res {
+			Ok(res) => {
+				ensure!(
+					res.results.len() == idxs.len(),
+					"datacenter returned {} results for {} actors",
+					res.results.len(),
+					idxs.len()
+				);
+
+				for (idx, result) in idxs.into_iter().zip(res.results) {
+					results[idx] = Some(result);
+				}
+			}
+			// A failed datacenter fails only the actors that were sent to it
+			Err(err) => {
+				tracing::warn!(?err, "bulk create request to datacenter failed");
+
+				for idx in idxs {
+					results[idx] = Some(error_result(&err));
+				}
+			}
+		}
+	}
+
+	Ok(BulkCreateResponse {
+		results: results
+			.into_iter()
+			.map(|x| x.context("missing bulk create result"))
+			.collect::<Result<_>>()?,
+	})
+}
+
+fn error_result(err: &anyhow::Error) -> BulkCreateResult {
+	let err = RivetError::extract(err);
+
+	BulkCreateResult {
+		actor: None,
+		error: Some(BulkError {
+			group: err.group().to_string(),
+			code: err.code().to_string(),
+			message: err.message().to_string(),
+		}),
+	}
+}
+
+/// Destroys all actors with the given name matching the filter across all datacenters.
+///
+/// Datacenters that fail to respond are skipped. The request can be retried safely.
+///
+/// ## Datacenter Round Trips
+///
+/// 2 round trips:
+/// - DELETE /actors/bulk (fanout)
+/// - [api-peer] namespace::ops::resolve_for_name_global
+#[utoipa::path(
+	delete,
+	operation_id = "actors_bulk_destroy",
+	path = "/actors/bulk",
+	params(BulkDestroyQuery),
+	responses(
+		(status = 200, body = BulkDestroyResponse),
+	),
+)]
+pub async fn bulk_destroy(
+	Extension(ctx): Extension<ApiCtx>,
+	headers: HeaderMap,
+	Query(query): Query<BulkDestroyQuery>,
+) -> Response {
+	match bulk_destroy_inner(ctx, headers, query).await {
+		Ok(response) => Json(response).into_response(),
+		Err(err) => ApiError::from(err).into_response(),
+	}
+}
+
+async fn bulk_destroy_inner(
+	ctx: ApiCtx,
+	headers: HeaderMap,
+	query: BulkDestroyQuery,
+) -> Result<BulkDestroyResponse> {
+	let actor_ids =
+		fanout_to_datacenters_with_method::<BulkDestroyResponse, _, _, _, _, Vec<rivet_util::Id>>(
+			ctx,
+			headers,
+			axum::http::Method::DELETE,
+			"/actors/bulk",
+			query,
+			|ctx, query| async move { rivet_api_peer::actors::bulk::bulk_destroy(ctx, (), query).await },
+			|res, agg| agg.extend(res.actor_ids),
+		)
+		.await?;
+
+	Ok(BulkDestroyResponse { actor_ids })
+}
@@ -0,0 +1,185 @@
+use std::convert::Infallible;
+
+use anyhow::Result;
+use axum::{
+	body::{Body, Bytes},
+	extract::{Extension, Path, Query},
+	http::{HeaderMap, HeaderValue, header},
+	response::{IntoResponse, Response},
+};
+use futures_util::{Stream, StreamExt};
+use rivet_api_builder::{ApiCtx, ApiError};
+use rivet_api_types::actors::events::*;
+use rivet_api_util::request_remote_datacenter_stream;
+use rivet_util::Id;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EventsPath {
+	pub actor_id: Id,
+}
+
+/// Streams lifecycle events of an actor as server-sent events until the actor is destroyed.
+///
+/// The event name is the event type (e.g. `ready`, `crashed`, `sleeping`).
+///
+/// ## Datacenter Round Trips
+///
+/// 2 round trips:
+/// - GET /actors/{}/events
+/// - [api-peer] namespace::ops::resolve_for_name_global
+#[utoipa::path(
+	get,
+	operation_id = "actors_events",
+	path = "/actors/{actor_id}/events",
+	params(
+		("actor_id" = Id, Path),
+		EventsQuery,
+	),
+	responses(
+		(status = 200, content_type = "text/event-stream", body = LifecycleEvent),
+	),
+)]
+pub async fn events(
+	Extension(ctx): Extension<ApiCtx>,
+	headers: HeaderMap,
+	Path(path): Path<EventsPath>,
+	Query(query): Query<EventsQuery>,
+) -> Response {
+	match events_inner(ctx, headers, path, query).await {
+		Ok(response) => response,
+		Err(err) => ApiError::from(err).into_response(),
+	}
+}
+
+async fn events_inner(
+	ctx: ApiCtx,
+	headers: HeaderMap,
+	path: EventsPath,
+	query: EventsQuery,
+) -> Result<Response> {
+	if path.actor_id.label() == ctx.config().dc_label() {
+		let peer_path = rivet_api_peer::actors::events::EventsPath {
+			actor_id: path.actor_id,
+		};
+		rivet_api_peer::actors::events::events_inner(ctx, peer_path, query).await
+	} else {
+		request_remote_datacenter_stream(
+			&ctx,
+			path.actor_id.label(),
+			&format!("/actors/{}/events", path.actor_id),
+			axum::http::Method::GET,
+			headers,
+			Some(&query),
+		)
+		.await
+	}
+}
+
+/// Streams lifecycle events of all actors in a namespace across all datacenters as server-sent
+/// events, optionally filtered by actor name.
+///
+/// ## Datacenter Round Trips
+///
+/// 1 round trip per datacenter:
+/// - GET /actors/events (fanout)
+#[utoipa::path(
+	get,
+	operation_id = "actors_list_events",
+	path = "/actors/events",
+	params(ListEventsQuery),
+	responses(
+		(status = 200, content_type = "text/event-stream", body = LifecycleEvent),
+	),
+)]
+pub async fn list_events(
+	Extension(ctx): Extension<ApiCtx>,
+	headers: HeaderMap,
+	Query(query): Query<ListEventsQuery>,
+) -> Response {
+	match list_events_inner(ctx, headers, query).await {
+		Ok(response) => response,
+		Err(err) => ApiError::from(err).into_response(),
+	}
+}
+
+async fn list_events_inner(
+	ctx: ApiCtx,
+	headers: HeaderMap,
+	query: ListEventsQuery,
+) -> Result<Response> {
+	let dcs = ctx.config().topology().datacenters.clone();
+
+	let mut bodies = Vec::with_capacity(dcs.len());
+	for dc in dcs {
+		let response = if dc.datacenter_label == ctx.config().dc_label() {
+			rivet_api_peer::actors::events::list_events_inner(ctx.clone(), query.clone()).await?
+		} else {
+			request_remote_datacenter_stream(
+				&ctx,
+				dc.datacenter_label,
+				"/actors/events",
+				axum::http::Method::GET,
+				headers.clone(),
+				Some(&query),
+			)
+			.await?
+		};
+
+		// Forward errors (e.g. namespace not found) as is
+		if !response.status().is_success() {
+			return Ok(response);
+		}
+
+		bodies.push(sse_frames(response.into_body()).boxed());
+	}
+
+	let mut response = Body::from_stream(futures_util::stream::select_all(bodies)).into_response();
+	response.headers_mut().insert(
+		header::CONTENT_TYPE,
+		HeaderValue::from_static("text/event-stream"),
+	);
+	response
+		.headers_mut()
+		.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+
+	Ok(response)
+}
+
+/// Splits a server-sent event body into whole events so that events from multiple datacenters
+/// can be interleaved without corrupting each other. Sends an `error` event if the body fails.
+fn sse_frames(body: Body) -> impl Stream<Item = Result<Bytes, Infallible>> + Send {
+	futures_util::stream::unfold(
+		(Some(body.into_data_stream()), Vec::new()),
+		|(stream, mut buf)| async move {
+			let mut stream = stream?;
+
+			loop {
+				if let Some(pos) = buf.windows(2).position(|w| w == b"\n\n") {
+					let frame = buf.drain(..pos + 2).collect::<Vec<_>>();
+					return Some((Ok(Bytes::from(frame)), (Some(stream), buf)));
+				}
+
+				match stream.next().await {
+					Some(Ok(chunk)) => buf.extend_from_slice(&chunk),
+					Some(Err(err)) => {
+						tracing::warn!(?err, "datacenter event stream failed");
+
+						// Let the client know events from this datacenter stopped
+						let frame = format!(
+							"event: error\ndata: {}\n\n",
+							serde_json::json!({
+								"group": "api",
+								"code": "datacenter_stream_failed",
+								"message": "Event stream from a datacenter failed.",
+							})
+						);
+						return Some((Ok(Bytes::from(frame)), (None, Vec::new())));
+					}
+					None => return None,
+				}
+			}
+		},
+	)
+}
@@ -64,6 +64,8 @@ async fn get_by_id_inner(ctx: ApiCtx, query: GetByIdQuery) -> Result<GetByIdResp
 			namespace_id: namespace.namespace_id,
 			name: query.name,
 			key: query.key,
+			// Not found is returned to the client as is
+			linearizable: true,
 		})
 		.await?
 		.actor;
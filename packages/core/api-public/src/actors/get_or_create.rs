@@ -5,7 +5,7 @@ use axum::{
 	response::{IntoResponse, Json, Response},
 };
 use rivet_api_builder::{ApiCtx, ApiError};
-use rivet_types::actors::CrashPolicy;
+use rivet_types::actors::{ActorPlacement, ActorPriority, CrashPolicy, RestartPolicy};
 use rivet_util::Id;
 use serde::{Deserialize, Serialize};
 use utoipa::{IntoParams, ToSchema};
@@ -30,6 +30,24 @@ pub struct GetOrCreateRequest {
 	pub input: Option<String>,
 	pub runner_name_selector: String,
 	pub crash_policy: CrashPolicy,
+	/// Constraints on which runners the actor can be allocated to.
+	pub placement: Option<ActorPlacement>,
+	/// Amount of runner slots the actor consumes. Defaults to 1.
+	pub slots: Option<u32>,
+	/// Destroy the actor if it waits longer than this for a runner with capacity. Defaults to the
+	/// runner config's `max_pending_duration_ms`.
+	pub max_pending_duration_ms: Option<i64>,
+	/// Actors with a higher priority are allocated first when there is no runner capacity.
+	pub priority: Option<ActorPriority>,
+	/// Limits restarts when `crash_policy` is `restart`.
+	pub restart_policy: Option<RestartPolicy>,
+	/// Destroy the actor this long after it was created.
+	pub ttl_ms: Option<i64>,
+	/// Timestamp (in ms) at which to destroy the actor. If `ttl_ms` is also set, the actor is
+	/// destroyed at whichever is earlier.
+	pub destroy_at_ts: Option<i64>,
+	/// Destroy the actor once it has been sleeping for this long.
+	pub idle_timeout_ms: Option<i64>,
 }
 
 #[derive(Serialize, ToSchema)]
@@ -109,6 +127,8 @@ async fn get_or_create_inner(
 			namespace_id: namespace.namespace_id,
 			name: body.name.clone(),
 			key: body.key.clone(),
+			// Conflicting creates are resolved when reserving the key
+			linearizable: false,
 		})
 		.await?;
 
@@ -131,7 +151,8 @@ async fn get_or_create_inner(
 		ctx.config().dc_label()
 	};
 
-	let actor_id = Id::new_v1(target_dc_label);
+	let actor_id =
+		Id::new_with_ordering(ctx.config().ids().time_ordered_actor_ids(), target_dc_label);
 
 	match ctx
 		.op(pegboard::ops::actor::create::Input {
@@ -142,6 +163,14 @@ async fn get_or_create_inner(
 			runner_name_selector: body.runner_name_selector,
 			input: body.input.clone(),
 			crash_policy: body.crash_policy,
+			placement: body.placement.clone(),
+			slots: body.slots,
+			max_pending_duration_ms: body.max_pending_duration_ms,
+			priority: body.priority,
+			restart_policy: body.restart_policy.clone(),
+			ttl_ms: body.ttl_ms,
+			destroy_at_ts: body.destroy_at_ts,
+			idle_timeout_ms: body.idle_timeout_ms,
 			forward_request: true,
 			datacenter_name: query.datacenter.clone(),
 		})
@@ -164,6 +164,8 @@ async fn list_inner(ctx: ApiCtx, headers: HeaderMap, query: ListQuery) -> Result
 				namespace_id: namespace.namespace_id,
 				name: name.clone(),
 				key: key.clone(),
+				// Not found is returned to the client as is
+				linearizable: true,
 			})
 			.await?
 			.actor
@@ -0,0 +1,86 @@
+use anyhow::Result;
+use axum::{
+	extract::{Extension, Path, Query},
+	http::HeaderMap,
+	response::{IntoResponse, Response},
+};
+use rivet_api_builder::{ApiCtx, ApiError};
+use rivet_api_types::actors::logs::*;
+use rivet_api_util::{request_remote_datacenter_raw, request_remote_datacenter_stream};
+use rivet_util::Id;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LogsPath {
+	pub actor_id: Id,
+}
+
+/// Returns the most recent log lines of an actor.
+///
+/// If `follow` is set, streams log lines as server-sent events with the `log` event name until
+/// the actor is destroyed.
+///
+/// ## Datacenter Round Trips
+///
+/// 2 round trips:
+/// - GET /actors/{}/logs
+/// - [api-peer] namespace::ops::resolve_for_name_global
+#[utoipa::path(
+	get,
+	operation_id = "actors_logs",
+	path = "/actors/{actor_id}/logs",
+	params(
+		("actor_id" = Id, Path),
+		LogsQuery,
+	),
+	responses(
+		(status = 200, body = LogsResponse),
+	),
+)]
+pub async fn logs(
+	Extension(ctx): Extension<ApiCtx>,
+	headers: HeaderMap,
+	Path(path): Path<LogsPath>,
+	Query(query): Query<LogsQuery>,
+) -> Response {
+	match logs_inner(ctx, headers, path, query).await {
+		Ok(response) => response,
+		Err(err) => ApiError::from(err).into_response(),
+	}
+}
+
+async fn logs_inner(
+	ctx: ApiCtx,
+	headers: HeaderMap,
+	path: LogsPath,
+	query: LogsQuery,
+) -> Result<Response> {
+	if path.actor_id.label() == ctx.config().dc_label() {
+		let peer_path = rivet_api_peer::actors::logs::LogsPath {
+			actor_id: path.actor_id,
+		};
+		rivet_api_peer::actors::logs::logs_inner(ctx, peer_path, query).await
+	} else if query.follow.unwrap_or_default() {
+		request_remote_datacenter_stream(
+			&ctx,
+			path.actor_id.label(),
+			&format!("/actors/{}/logs", path.actor_id),
+			axum::http::Method::GET,
+			headers,
+			Some(&query),
+		)
+		.await
+	} else {
+		request_remote_datacenter_raw(
+			&ctx,
+			path.actor_id.label(),
+			&format!("/actors/{}/logs", path.actor_id),
+			axum::http::Method::GET,
+			headers,
+			Some(&query),
+			Option::<&()>::None,
+		)
+		.await
+	}
+}
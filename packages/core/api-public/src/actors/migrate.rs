@@ -0,0 +1,81 @@
+use anyhow::Result;
+use axum::{
+	extract::{Extension, Path, Query},
+	http::HeaderMap,
+	response::{IntoResponse, Json, Response},
+};
+use rivet_api_builder::{ApiCtx, ApiError};
+use rivet_api_types::actors::migrate::*;
+use rivet_api_util::request_remote_datacenter_raw;
+use rivet_util::Id;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MigratePath {
+	pub actor_id: Id,
+}
+
+/// Stops the actor and reallocates it to a different runner with a new generation. The actor keeps
+/// its id, key and KV data.
+///
+/// ## Datacenter Round Trips
+///
+/// 2 round trips:
+/// - POST /actors/{}/migrate
+/// - [api-peer] namespace::ops::resolve_for_name_global (when looking up the actor)
+///
+/// Validating the target runner and signaling the actor workflow happen in the actor's datacenter
+/// without additional round trips.
+#[utoipa::path(
+	post,
+	operation_id = "actors_migrate",
+	path = "/actors/{actor_id}/migrate",
+	params(
+		("actor_id" = Id, Path),
+		MigrateQuery,
+	),
+	request_body(content = MigrateRequest, content_type = "application/json"),
+	responses(
+		(status = 200, body = MigrateResponse),
+	),
+)]
+pub async fn migrate(
+	Extension(ctx): Extension<ApiCtx>,
+	headers: HeaderMap,
+	Path(path): Path<MigratePath>,
+	Query(query): Query<MigrateQuery>,
+	Json(body): Json<MigrateRequest>,
+) -> Response {
+	match migrate_inner(ctx, headers, path, query, body).await {
+		Ok(response) => response,
+		Err(err) => ApiError::from(err).into_response(),
+	}
+}
+
+async fn migrate_inner(
+	ctx: ApiCtx,
+	headers: HeaderMap,
+	path: MigratePath,
+	query: MigrateQuery,
+	body: MigrateRequest,
+) -> Result<Response> {
+	if path.actor_id.label() == ctx.config().dc_label() {
+		let peer_path = rivet_api_peer::actors::migrate::MigratePath {
+			actor_id: path.actor_id,
+		};
+		let res = rivet_api_peer::actors::migrate::migrate(ctx, peer_path, query, body).await?;
+		Ok(Json(res).into_response())
+	} else {
+		request_remote_datacenter_raw(
+			&ctx,
+			path.actor_id.label(),
+			&format!("/actors/{}/migrate", path.actor_id),
+			axum::http::Method::POST,
+			headers,
+			Some(&query),
+			Some(&body),
+		)
+		.await
+	}
+}
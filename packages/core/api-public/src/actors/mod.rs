@@ -1,9 +1,15 @@
+pub mod alarms;
+pub mod bulk;
 pub mod create;
 pub mod delete;
+pub mod events;
 pub mod get;
 pub mod get_by_id;
 pub mod get_or_create;
 pub mod get_or_create_by_id;
 pub mod list;
 pub mod list_names;
+pub mod logs;
+pub mod migrate;
+pub mod queue;
 pub mod utils;
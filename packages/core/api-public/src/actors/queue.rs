@@ -0,0 +1,75 @@
+use anyhow::Result;
+use axum::{
+	extract::{Extension, Path, Query},
+	http::HeaderMap,
+	response::{IntoResponse, Json, Response},
+};
+use rivet_api_builder::{ApiCtx, ApiError};
+use rivet_api_types::actors::queue::*;
+use rivet_api_util::request_remote_datacenter_raw;
+use rivet_util::Id;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct QueuePath {
+	pub actor_id: Id,
+}
+
+/// Returns the actor's position in the allocation queue. Actors wait in the queue when there are
+/// no runners with enough capacity.
+///
+/// ## Datacenter Round Trips
+///
+/// 2 round trips:
+/// - GET /actors/{}/queue
+/// - [api-peer] namespace::ops::resolve_for_name_global
+#[utoipa::path(
+	get,
+	operation_id = "actors_queue",
+	path = "/actors/{actor_id}/queue",
+	params(
+		("actor_id" = Id, Path),
+		QueueQuery,
+	),
+	responses(
+		(status = 200, body = QueueResponse),
+	),
+)]
+pub async fn queue(
+	Extension(ctx): Extension<ApiCtx>,
+	headers: HeaderMap,
+	Path(path): Path<QueuePath>,
+	Query(query): Query<QueueQuery>,
+) -> Response {
+	match queue_inner(ctx, headers, path, query).await {
+		Ok(response) => response,
+		Err(err) => ApiError::from(err).into_response(),
+	}
+}
+
+async fn queue_inner(
+	ctx: ApiCtx,
+	headers: HeaderMap,
+	path: QueuePath,
+	query: QueueQuery,
+) -> Result<Response> {
+	if path.actor_id.label() == ctx.config().dc_label() {
+		let peer_path = rivet_api_peer::actors::queue::QueuePath {
+			actor_id: path.actor_id,
+		};
+		let res = rivet_api_peer::actors::queue::queue(ctx, peer_path, query).await?;
+		Ok(Json(res).into_response())
+	} else {
+		request_remote_datacenter_raw(
+			&ctx,
+			path.actor_id.label(),
+			&format!("/actors/{}/queue", path.actor_id),
+			axum::http::Method::GET,
+			headers,
+			Some(&query),
+			Option::<&()>::None,
+		)
+		.await
+	}
+}
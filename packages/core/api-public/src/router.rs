@@ -1,6 +1,6 @@
 use axum::response::Redirect;
 use rivet_api_builder::{
-	create_router,
+	AuditLogger, audit_log_middleware, create_cors_layer, create_router_with_cors,
 	wrappers::{get, post},
 };
 use utoipa::OpenApi;
@@ -12,14 +12,23 @@ use crate::{actors, datacenters, namespaces, runner_configs, runners, ui};
 	actors::list::list,
 	actors::get::get,
 	actors::create::create,
+	actors::bulk::bulk_create,
+	actors::bulk::bulk_destroy,
 	actors::delete::delete,
 	actors::list_names::list_names,
 	actors::get_or_create::get_or_create,
 	actors::get_by_id::get_by_id,
 	actors::get_or_create_by_id::get_or_create_by_id,
+	actors::logs::logs,
+	actors::events::events,
+	actors::events::list_events,
+	actors::queue::queue,
+	actors::migrate::migrate,
+	actors::alarms::alarms,
 	runners::list,
 	runners::get,
 	runners::list_names,
+	runners::queue,
 	namespaces::list,
 	namespaces::get,
 	namespaces::create,
@@ -36,7 +45,10 @@ pub async fn router(
 	config: rivet_config::Config,
 	pools: rivet_pools::Pools,
 ) -> anyhow::Result<axum::Router> {
-	create_router(name, config, pools, |router| {
+	let audit_logger = AuditLogger::from_config(&config, &pools).await?;
+	let cors_layer = create_cors_layer(config.api_public().cors.as_ref())?;
+
+	let router = create_router_with_cors(name, config, pools, cors_layer, |router| {
 		router
 			// Root redirect
 			.route(
@@ -62,6 +74,14 @@ pub async fn router(
 			// MARK: Actors
 			.route("/actors", axum::routing::get(actors::list::list))
 			.route("/actors", axum::routing::post(actors::create::create))
+			.route(
+				"/actors/bulk",
+				axum::routing::post(actors::bulk::bulk_create),
+			)
+			.route(
+				"/actors/bulk",
+				axum::routing::delete(actors::bulk::bulk_destroy),
+			)
 			.route(
 				"/actors",
 				axum::routing::put(actors::get_or_create::get_or_create),
@@ -74,6 +94,10 @@ pub async fn router(
 				"/actors/names",
 				axum::routing::get(actors::list_names::list_names),
 			)
+			.route(
+				"/actors/events",
+				axum::routing::get(actors::events::list_events),
+			)
 			.route(
 				"/actors/by-id",
 				axum::routing::get(actors::get_by_id::get_by_id),
@@ -83,10 +107,31 @@ pub async fn router(
 				axum::routing::put(actors::get_or_create_by_id::get_or_create_by_id),
 			)
 			.route("/actors/{actor_id}", axum::routing::get(actors::get::get))
+			.route(
+				"/actors/{actor_id}/logs",
+				axum::routing::get(actors::logs::logs),
+			)
+			.route(
+				"/actors/{actor_id}/events",
+				axum::routing::get(actors::events::events),
+			)
+			.route(
+				"/actors/{actor_id}/queue",
+				axum::routing::get(actors::queue::queue),
+			)
+			.route(
+				"/actors/{actor_id}/migrate",
+				axum::routing::post(actors::migrate::migrate),
+			)
+			.route(
+				"/actors/{actor_id}/alarms",
+				axum::routing::get(actors::alarms::alarms),
+			)
 			// MARK: Runners
 			.route("/runners", axum::routing::get(runners::list))
 			.route("/runners/{runner_id}", axum::routing::get(runners::get))
 			.route("/runners/names", axum::routing::get(runners::list_names))
+			.route("/runners/queue", axum::routing::get(runners::queue))
 			// MARK: Datacenters
 			.route("/datacenters", get(datacenters::list))
 			// MARK: UI
@@ -94,5 +139,15 @@ pub async fn router(
 			.route("/ui/", axum::routing::get(ui::serve_index))
 			.route("/ui/{*path}", axum::routing::get(ui::serve_ui))
 	})
-	.await
+	.await?;
+
+	// Installed outside of the standard middleware so the response already has a ray id
+	if let Some(audit_logger) = audit_logger {
+		Ok(router.layer(axum::middleware::from_fn_with_state(
+			audit_logger,
+			audit_log_middleware,
+		)))
+	} else {
+		Ok(router)
+	}
 }
@@ -8,7 +8,11 @@ use rivet_api_builder::{ApiCtx, ApiError};
 use rivet_util::Id;
 
 use rivet_api_peer::runner_configs::*;
-use rivet_api_util::request_remote_datacenter;
+use rivet_api_types::runners::list as runners_list;
+use rivet_api_util::{fanout_to_datacenters, request_remote_datacenter};
+
+/// Max number of runners per datacenter checked for the pinned version of a runner config.
+const MAX_PINNED_VERSION_CHECK_RUNNERS: usize = 1024;
 
 #[utoipa::path(
 	get,
@@ -89,6 +93,17 @@ async fn upsert_inner(
 	query: UpsertQuery,
 	body: UpsertRequest,
 ) -> Result<UpsertResponse> {
+	if let Some(pinned_version) = body.0.rollout.pinned_version {
+		validate_pinned_version(
+			ctx.clone(),
+			headers.clone(),
+			&query.namespace,
+			&path.runner_name,
+			pinned_version,
+		)
+		.await?;
+	}
+
 	if ctx.config().is_leader() {
 		rivet_api_peer::runner_configs::upsert(ctx, path, query, body).await
 	} else {
@@ -106,6 +121,42 @@ async fn upsert_inner(
 	}
 }
 
+/// Runners of every datacenter are checked since the config applies globally.
+async fn validate_pinned_version(
+	ctx: ApiCtx,
+	headers: HeaderMap,
+	namespace: &str,
+	runner_name: &str,
+	pinned_version: u32,
+) -> Result<()> {
+	let versions = fanout_to_datacenters::<runners_list::ListResponse, _, _, _, _, Vec<u32>>(
+		ctx,
+		headers,
+		"/runners",
+		runners_list::ListQuery {
+			namespace: namespace.to_string(),
+			name: Some(runner_name.to_string()),
+			include_stopped: Some(false),
+			limit: Some(MAX_PINNED_VERSION_CHECK_RUNNERS),
+			cursor: None,
+		},
+		|ctx, query| async move { rivet_api_peer::runners::list(ctx, (), query).await },
+		|res, agg| agg.extend(res.runners.into_iter().map(|runner| runner.version)),
+	)
+	.await?;
+
+	if !versions.contains(&pinned_version) {
+		return Err(namespace::errors::RunnerConfig::Invalid {
+			reason: format!(
+				"`rollout.pinned_version` {pinned_version} has no running runners named {runner_name}"
+			),
+		}
+		.build());
+	}
+
+	Ok(())
+}
+
 #[utoipa::path(
 	delete,
 	operation_id = "runner_configs_delete",
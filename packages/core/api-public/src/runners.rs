@@ -7,7 +7,7 @@ use axum::{
 use rivet_api_builder::{ApiCtx, ApiError};
 use rivet_api_types::{
 	pagination::Pagination,
-	runners::{get::*, list::*},
+	runners::{get::*, list::*, queue::*},
 };
 use rivet_api_util::{fanout_to_datacenters, request_remote_datacenter_raw};
 use rivet_util::Id;
@@ -194,3 +194,49 @@ async fn list_names_inner(
 		pagination: Pagination { cursor },
 	})
 }
+
+/// Returns the amount of actors waiting for a runner with the given name across all datacenters.
+///
+/// ## Datacenter Round Trips
+///
+/// 2 round trips:
+/// - GET /runners/queue (fanout)
+/// - [api-peer] namespace::ops::resolve_for_name_global
+#[utoipa::path(
+	get,
+	operation_id = "runners_queue",
+	path = "/runners/queue",
+	params(QueueQuery),
+	responses(
+		(status = 200, body = QueueResponse),
+	),
+)]
+pub async fn queue(
+	Extension(ctx): Extension<ApiCtx>,
+	headers: HeaderMap,
+	Query(query): Query<QueueQuery>,
+) -> Response {
+	match queue_inner(ctx, headers, query).await {
+		Ok(response) => Json(response).into_response(),
+		Err(err) => ApiError::from(err).into_response(),
+	}
+}
+
+async fn queue_inner(ctx: ApiCtx, headers: HeaderMap, query: QueueQuery) -> Result<QueueResponse> {
+	fanout_to_datacenters::<QueueResponse, _, _, _, _, QueueResponse>(
+		ctx,
+		headers,
+		"/runners/queue",
+		query,
+		|ctx, query| async move { rivet_api_peer::runners::queue(ctx, (), query).await },
+		|res, agg| {
+			agg.depth += res.depth;
+			agg.oldest_pending_allocation_ts = agg
+				.oldest_pending_allocation_ts
+				.into_iter()
+				.chain(res.oldest_pending_allocation_ts)
+				.min();
+		},
+	)
+	.await
+}
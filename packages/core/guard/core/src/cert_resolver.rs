@@ -1,6 +1,13 @@
+use anyhow::{Context, Result, anyhow, ensure};
+use rustls::crypto::ring::sign::any_supported_type;
 use rustls::server::{ClientHello, ResolvesServerCert};
 use rustls::{ServerConfig, sign::CertifiedKey};
-use std::sync::Arc;
+use rustls_pemfile::{certs, private_key};
+use std::{
+	path::{Path, PathBuf},
+	sync::{Arc, RwLock},
+	time::Duration,
+};
 
 /// Type signature for a function that resolves a TLS certificate based on the server name
 pub type CertResolverFn = Arc<
@@ -59,3 +66,159 @@ pub fn create_tls_config(resolver_fn: CertResolverFn) -> ServerConfig {
 		.with_no_client_auth()
 		.with_cert_resolver(Arc::new(CertResolver::new(resolver_fn)))
 }
+
+/// Loads a PEM encoded certificate chain and private key into a `CertifiedKey`.
+pub fn load_certified_key(cert_pem: &[u8], key_pem: &[u8]) -> Result<CertifiedKey> {
+	let cert_chain = certs(&mut &cert_pem[..])
+		.collect::<Result<Vec<_>, _>>()
+		.context("failed to parse certificate")?;
+	ensure!(!cert_chain.is_empty(), "no certificates found in certificate file");
+
+	let key_der = private_key(&mut &key_pem[..])
+		.context("failed to parse private key")?
+		.context("no private key found in key file")?;
+	let signing_key =
+		any_supported_type(&key_der).map_err(|e| anyhow!("failed to load signing key: {e}"))?;
+
+	Ok(CertifiedKey::new(cert_chain, signing_key))
+}
+
+/// A certificate loaded from a cert/key file pair on disk that can be reloaded when the files
+/// change.
+pub struct ReloadableCert {
+	name: String,
+	cert_path: PathBuf,
+	key_path: PathBuf,
+	state: RwLock<ReloadableCertState>,
+}
+
+struct ReloadableCertState {
+	cert_pem: Vec<u8>,
+	key_pem: Vec<u8>,
+	certified_key: Arc<CertifiedKey>,
+}
+
+impl std::fmt::Debug for ReloadableCert {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("ReloadableCert")
+			.field("name", &self.name)
+			.field("cert_path", &self.cert_path)
+			.field("key_path", &self.key_path)
+			.finish()
+	}
+}
+
+impl ReloadableCert {
+	/// Loads the certificate for the first time. Fails if the files are missing or invalid.
+	pub fn load(
+		name: impl Into<String>,
+		cert_path: impl AsRef<Path>,
+		key_path: impl AsRef<Path>,
+	) -> Result<Self> {
+		let name = name.into();
+		let cert_path = cert_path.as_ref().to_path_buf();
+		let key_path = key_path.as_ref().to_path_buf();
+
+		let (cert_pem, key_pem) = read_pair(&cert_path, &key_path)
+			.with_context(|| format!("failed to read {name} certificate"))?;
+		let certified_key = load_certified_key(&cert_pem, &key_pem)
+			.with_context(|| format!("failed to load {name} certificate"))?;
+
+		tracing::info!(%name, ?cert_path, ?key_path, "certificate loaded");
+
+		Ok(Self {
+			name,
+			cert_path,
+			key_path,
+			state: RwLock::new(ReloadableCertState {
+				cert_pem,
+				key_pem,
+				certified_key: Arc::new(certified_key),
+			}),
+		})
+	}
+
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	/// Returns the currently loaded certificate.
+	pub fn get(&self) -> Arc<CertifiedKey> {
+		self.state
+			.read()
+			.expect("cert state poisoned")
+			.certified_key
+			.clone()
+	}
+
+	/// Re-reads the files from disk and swaps in the new certificate if the contents changed.
+	///
+	/// Returns `true` if the certificate was replaced. If the new files are invalid, the previous
+	/// certificate stays in use and an error is returned.
+	pub fn reload_if_changed(&self) -> Result<bool> {
+		// Compare contents instead of modification times since mounted secrets are usually
+		// swapped via symlinks and mtime granularity is not reliable
+		let (cert_pem, key_pem) = read_pair(&self.cert_path, &self.key_path)?;
+
+		{
+			let state = self.state.read().expect("cert state poisoned");
+			if state.cert_pem == cert_pem && state.key_pem == key_pem {
+				return Ok(false);
+			}
+		}
+
+		let certified_key = load_certified_key(&cert_pem, &key_pem)?;
+
+		let mut state = self.state.write().expect("cert state poisoned");
+		*state = ReloadableCertState {
+			cert_pem,
+			key_pem,
+			certified_key: Arc::new(certified_key),
+		};
+
+		Ok(true)
+	}
+}
+
+fn read_pair(cert_path: &Path, key_path: &Path) -> Result<(Vec<u8>, Vec<u8>)> {
+	let cert_pem = std::fs::read(cert_path)
+		.with_context(|| format!("failed to read certificate file {}", cert_path.display()))?;
+	let key_pem = std::fs::read(key_path)
+		.with_context(|| format!("failed to read key file {}", key_path.display()))?;
+
+	Ok((cert_pem, key_pem))
+}
+
+/// Periodically reloads the given certificates when their files change on disk. Runs forever.
+pub async fn watch_certs(certs: Vec<Arc<ReloadableCert>>, interval: Duration) {
+	let mut interval = tokio::time::interval(interval);
+	interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+	// Skip first immediate tick, certs were just loaded
+	interval.tick().await;
+
+	loop {
+		interval.tick().await;
+
+		for cert in &certs {
+			let cert = cert.clone();
+			let res = tokio::task::spawn_blocking(move || {
+				let res = cert.reload_if_changed();
+				(cert, res)
+			})
+			.await;
+
+			match res {
+				Ok((cert, Result::Ok(true))) => {
+					tracing::info!(name=%cert.name(), "certificate reloaded");
+				}
+				Ok((_, Result::Ok(false))) => {}
+				Ok((cert, Err(err))) => {
+					tracing::error!(name=%cert.name(), ?err, "failed to reload certificate, keeping previous certificate");
+				}
+				Err(err) => {
+					tracing::error!(?err, "certificate reload task failed");
+				}
+			}
+		}
+	}
+}
@@ -29,6 +29,7 @@ use url::Url;
 use crate::{custom_serve::CustomServeTrait, errors, metrics, request_context::RequestContext};
 
 pub const X_FORWARDED_FOR: HeaderName = HeaderName::from_static("x-forwarded-for");
+pub const X_FORWARDED_HOST: HeaderName = HeaderName::from_static("x-forwarded-host");
 pub const X_RIVET_ERROR: HeaderName = HeaderName::from_static("x-rivet-error");
 const ROUTE_CACHE_TTL: Duration = Duration::from_secs(60 * 10); // 10 minutes
 const PROXY_STATE_CACHE_TTL: Duration = Duration::from_secs(60 * 60); // 1 hour
@@ -2162,6 +2163,13 @@ fn add_proxy_headers_with_addr(
 		}
 	}
 
+	// Add X-Forwarded-Host header so the original host is not lost when re-routing
+	if !original_headers.contains_key(X_FORWARDED_HOST) {
+		if let Some(host) = original_headers.get(hyper::header::HOST) {
+			headers.insert(X_FORWARDED_HOST, host.clone());
+		}
+	}
+
 	// Add X-Forwarded-For header
 	if let Some(existing) = original_headers.get(X_FORWARDED_FOR) {
 		if let Result::Ok(forwarded) = existing.to_str() {
@@ -10,10 +10,14 @@ use crate::proxy_service::{CacheKeyFn, MiddlewareFn, ProxyServiceFactory, Routin
 use anyhow::*;
 use hyper::service::service_fn;
 use rivet_util::signal::TermSignal;
+use tokio::sync::watch;
 use tokio_rustls::TlsAcceptor;
 use tracing::Instrument;
 
 // Start the server
+//
+// If `shutdown_rx` is provided, the caller is responsible for triggering shutdown and termination
+// signals are ignored.
 #[tracing::instrument(skip_all)]
 pub async fn run_server(
 	config: rivet_config::Config,
@@ -22,6 +26,7 @@ pub async fn run_server(
 	middleware_fn: MiddlewareFn,
 	cert_resolver_fn: Option<CertResolverFn>,
 	clickhouse_inserter: Option<clickhouse_inserter::ClickHouseInserterHandle>,
+	mut shutdown_rx: Option<watch::Receiver<()>>,
 ) -> Result<()> {
 	// Set up HTTP server
 	let http_addr: std::net::SocketAddr = (config.guard().host(), config.guard().port()).into();
@@ -129,6 +134,15 @@ pub async fn run_server(
 
 	// Accept connections until we receive a shutdown signal
 	loop {
+		let external_shutdown = shutdown_rx.is_some();
+		let shutdown_fut = async {
+			if let Some(shutdown_rx) = &mut shutdown_rx {
+				shutdown_rx.changed().await
+			} else {
+				std::future::pending().await
+			}
+		};
+
 		let result: Result<()> = tokio::select! {
 			conn = http_listener.accept() => {
 				match conn {
@@ -167,6 +181,7 @@ pub async fn run_server(
 									// Handle TLS connection
 									let https_factory_clone = factory.clone();
 									let acceptor_clone = acceptor.clone();
+									let watcher = graceful.watcher();
 
 									// Accept TLS connection in a separate task to avoid ownership issues
 									tokio::spawn(async move {
@@ -198,8 +213,9 @@ pub async fn run_server(
 												// Create a new server for each connection
 												let conn_server = hyper_util::server::conn::auto::Builder::new(hyper_util::rt::TokioExecutor::new());
 
-												// Serve the connection (no graceful shutdown in spawned task)
-												if let Err(err) = conn_server.serve_connection_with_upgrades(io, service).await {
+												// Serve the connection with graceful shutdown support
+												let conn = conn_server.serve_connection_with_upgrades(io, service);
+												if let Err(err) = watcher.watch(conn.into_owned()).await {
 													tracing::debug!(?err, "HTTPS connection error");
 												}
 
@@ -238,7 +254,17 @@ pub async fn run_server(
 				Ok(())
 			},
 
-			_ = term_signal.recv() => {
+			res = shutdown_fut => {
+				if res.is_err() {
+					tracing::debug!("shutdown channel dropped, ignoring");
+					shutdown_rx = None;
+					Ok(())
+				} else {
+					tracing::info!("Shutdown requested, starting shutdown");
+					break;
+				}
+			}
+			_ = term_signal.recv(), if !external_shutdown => {
 				tracing::info!("Termination signal received, starting shutdown");
 				break;
 			}
@@ -249,6 +275,10 @@ pub async fn run_server(
 		}
 	}
 
+	// Stop accepting new connections while in-flight requests drain
+	drop(http_listener);
+	drop(https_listener);
+
 	// Start graceful shutdown with timeout
 	tokio::select! {
 		_ = graceful.shutdown() => {
@@ -0,0 +1,50 @@
+use std::path::Path;
+
+use rivet_guard_core::cert_resolver::ReloadableCert;
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/tls");
+
+fn copy_pair(dir: &Path, name: &str) {
+	std::fs::copy(
+		format!("{FIXTURES}/{name}_cert.pem"),
+		dir.join("cert.pem"),
+	)
+	.unwrap();
+	std::fs::copy(format!("{FIXTURES}/{name}_key.pem"), dir.join("key.pem")).unwrap();
+}
+
+#[test]
+fn test_cert_reload_on_change() {
+	let dir = tempfile::tempdir().unwrap();
+	copy_pair(dir.path(), "api");
+
+	let cert =
+		ReloadableCert::load("test", dir.path().join("cert.pem"), dir.path().join("key.pem"))
+			.unwrap();
+	let original = cert.get();
+
+	// Unchanged files do not trigger a reload
+	assert!(!cert.reload_if_changed().unwrap());
+	assert_eq!(original.cert, cert.get().cert);
+
+	// Swapping the files replaces the certificate
+	copy_pair(dir.path(), "job");
+	assert!(cert.reload_if_changed().unwrap());
+	assert_ne!(original.cert, cert.get().cert);
+}
+
+#[test]
+fn test_cert_reload_keeps_previous_on_invalid() {
+	let dir = tempfile::tempdir().unwrap();
+	copy_pair(dir.path(), "api");
+
+	let cert =
+		ReloadableCert::load("test", dir.path().join("cert.pem"), dir.path().join("key.pem"))
+			.unwrap();
+	let original = cert.get();
+
+	// Partially written files fail to parse and the previous certificate stays in use
+	std::fs::write(dir.path().join("key.pem"), "not a key").unwrap();
+	assert!(cert.reload_if_changed().is_err());
+	assert_eq!(original.cert, cert.get().cert);
+}
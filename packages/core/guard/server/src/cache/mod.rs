@@ -6,7 +6,7 @@ use std::{
 
 use anyhow::Result;
 use gas::prelude::*;
-use rivet_guard_core::CacheKeyFn;
+use rivet_guard_core::{CacheKeyFn, proxy_service::X_FORWARDED_HOST};
 
 pub mod actor;
 
@@ -23,7 +23,7 @@ pub fn create_cache_key_function(_ctx: StandaloneCtx) -> CacheKeyFn {
 			Err(err) => {
 				tracing::debug!(?err, "failed parsing target for cache key");
 
-				return Ok(host_path_cache_key(hostname, path));
+				return Ok(host_path_cache_key(hostname, path, headers));
 			}
 		};
 
@@ -40,7 +40,7 @@ pub fn create_cache_key_function(_ctx: StandaloneCtx) -> CacheKeyFn {
 		if let Some(cache_key) = cache_key {
 			Ok(cache_key)
 		} else {
-			Ok(host_path_cache_key(hostname, path))
+			Ok(host_path_cache_key(hostname, path, headers))
 		}
 	})
 }
@@ -57,12 +57,18 @@ fn read_target(headers: &hyper::HeaderMap) -> Result<&str> {
 	Ok(target.to_str()?)
 }
 
-fn host_path_cache_key(hostname: &str, path: &str) -> u64 {
+fn host_path_cache_key(hostname: &str, path: &str, headers: &hyper::HeaderMap) -> u64 {
 	// Extract just the hostname, stripping the port if present
 	let hostname_only = hostname.split(':').next().unwrap_or(hostname);
 
+	// Actors routed by hostname from a peer dc are only identified by the forwarded host
+	let forwarded_host = headers
+		.get(X_FORWARDED_HOST)
+		.and_then(|x| x.to_str().ok());
+
 	let mut hasher = DefaultHasher::new();
 	hostname_only.hash(&mut hasher);
+	forwarded_host.hash(&mut hasher);
 	path.hash(&mut hasher);
 	hasher.finish()
 }
@@ -1,5 +1,6 @@
 use anyhow::*;
 use gas::prelude::*;
+use tokio::sync::watch;
 
 pub mod cache;
 pub mod errors;
@@ -9,7 +10,11 @@ pub mod shared_state;
 pub mod tls;
 
 #[tracing::instrument(skip_all)]
-pub async fn start(config: rivet_config::Config, pools: rivet_pools::Pools) -> Result<()> {
+pub async fn start(
+	config: rivet_config::Config,
+	pools: rivet_pools::Pools,
+	shutdown_rx: watch::Receiver<()>,
+) -> Result<()> {
 	let cache = rivet_cache::CacheInner::from_env(&config, pools.clone())?;
 	let ctx = StandaloneCtx::new(
 		db::DatabaseKv::from_pools(pools.clone()).await?,
@@ -53,6 +58,7 @@ pub async fn start(config: rivet_config::Config, pools: rivet_pools::Pools) -> R
 		middleware_fn,
 		cert_resolver,
 		clickhouse_inserter,
+		Some(shutdown_rx),
 	)
 	.await
 }
@@ -66,7 +66,20 @@ pub fn create_routing_function(ctx: StandaloneCtx, shared_state: SharedState) ->
 							return Ok(routing_output);
 						}
 					} else {
-						// No x-rivet-target header, try routing to api-public by default
+						// No x-rivet-target header, try routing to actors by hostname or path
+						if let Some(routing_output) = pegboard_gateway::route_request_implicit(
+							&ctx,
+							&shared_state,
+							host,
+							path,
+							headers,
+						)
+						.await?
+						{
+							return Ok(routing_output);
+						}
+
+						// Otherwise route to api-public by default
 						if let Some(routing_output) =
 							api_public::route_request(&ctx, "api-public", host, path).await?
 						{
@@ -3,7 +3,9 @@ use std::time::Duration;
 use anyhow::Result;
 use gas::prelude::*;
 use hyper::header::HeaderName;
-use rivet_guard_core::proxy_service::{RouteConfig, RouteTarget, RoutingOutput, RoutingTimeout};
+use rivet_guard_core::proxy_service::{
+	RouteConfig, RouteTarget, RoutingOutput, RoutingTimeout, X_FORWARDED_HOST,
+};
 use universaldb::utils::IsolationLevel::*;
 
 use crate::{errors, shared_state::SharedState};
@@ -11,7 +13,7 @@ use crate::{errors, shared_state::SharedState};
 const ACTOR_READY_TIMEOUT: Duration = Duration::from_secs(10);
 pub const X_RIVET_ACTOR: HeaderName = HeaderName::from_static("x-rivet-actor");
 
-/// Route requests to actor services based on hostname and path
+/// Route requests to actor services based on the `x-rivet-actor` header
 #[tracing::instrument(skip_all)]
 pub async fn route_request(
 	ctx: &StandaloneCtx,
@@ -33,8 +35,62 @@ pub async fn route_request(
 		}
 		.build()
 	})?;
-	let actor_id = Id::parse(actor_id_str.to_str()?)?;
+	let Some(actor_id) = actor_id_str.to_str().ok().and_then(|x| Id::parse(x).ok()) else {
+		tracing::debug!(?actor_id_str, "invalid actor id header");
+		return Ok(None);
+	};
+
+	route_to_actor(ctx, shared_state, actor_id, path, path).await
+}
+
+/// Route requests to actor services based on `{actor_id}.actor.{domain}` hostnames or
+/// `{path_prefix}/{actor_id}/...` paths, if configured
+#[tracing::instrument(skip_all)]
+pub async fn route_request_implicit(
+	ctx: &StandaloneCtx,
+	shared_state: &SharedState,
+	host: &str,
+	path: &str,
+	headers: &hyper::HeaderMap,
+) -> Result<Option<RoutingOutput>> {
+	let Some(actor_routing) = &ctx.config().guard().actor_routing else {
+		return Ok(None);
+	};
 
+	if let Some(domain) = &actor_routing.domain {
+		// Requests re-routed from a peer dc only carry the original host in x-forwarded-host
+		let forwarded_host = headers
+			.get(X_FORWARDED_HOST)
+			.and_then(|x| x.to_str().ok())
+			.map(|x| x.split(':').next().unwrap_or(x));
+
+		for host in std::iter::once(host).chain(forwarded_host) {
+			if let Some(actor_id) = parse_actor_hostname(host, domain) {
+				return route_to_actor(ctx, shared_state, actor_id, path, path).await;
+			}
+		}
+	}
+
+	if let Some(path_prefix) = &actor_routing.path_prefix {
+		if let Some((actor_id, actor_path)) = parse_actor_path(path, path_prefix) {
+			// Forward the original path to peer dcs so they can parse it again
+			return route_to_actor(ctx, shared_state, actor_id, path, &actor_path).await;
+		}
+	}
+
+	Ok(None)
+}
+
+/// Routes to the actor's datacenter or to the actor itself if it lives in this datacenter.
+///
+/// `path` is the path of the incoming request, `actor_path` is the path forwarded to the actor.
+async fn route_to_actor(
+	ctx: &StandaloneCtx,
+	shared_state: &SharedState,
+	actor_id: Id,
+	path: &str,
+	actor_path: &str,
+) -> Result<Option<RoutingOutput>> {
 	// Route to peer dc where the actor lives
 	if actor_id.label() != ctx.config().dc_label() {
 		tracing::debug!(peer_dc_label=?actor_id.label(), "re-routing actor to peer dc");
@@ -65,7 +121,43 @@ pub async fn route_request(
 	}
 
 	// Lookup actor
-	find_actor(ctx, shared_state, actor_id, path).await
+	find_actor(ctx, shared_state, actor_id, actor_path).await
+}
+
+/// Parses the actor ID from a `{actor_id}.actor.{domain}` hostname. Returns `None` if the hostname
+/// does not match or the subdomain is not a valid actor ID.
+fn parse_actor_hostname(host: &str, domain: &str) -> Option<Id> {
+	let host = host.to_ascii_lowercase();
+	let actor_id = host
+		.strip_suffix(&domain.to_ascii_lowercase())?
+		.strip_suffix(".actor.")?;
+
+	if actor_id.contains('.') {
+		return None;
+	}
+
+	Id::parse(actor_id).ok()
+}
+
+/// Parses a `{path_prefix}/{actor_id}/...` path into the actor ID and the remaining path that
+/// is forwarded to the actor (including the query). Returns `None` if the path does not match or
+/// the segment after the prefix is not a valid actor ID.
+fn parse_actor_path(path: &str, path_prefix: &str) -> Option<(Id, String)> {
+	let rest = path
+		.strip_prefix(path_prefix.trim_end_matches('/'))?
+		.strip_prefix('/')?;
+
+	let (actor_id, actor_path) = rest.split_at(rest.find(['/', '?']).unwrap_or(rest.len()));
+	let actor_id = Id::parse(actor_id).ok()?;
+
+	// `{path_prefix}/{actor_id}` and `{path_prefix}/{actor_id}?...` route to the actor's root
+	let actor_path = if actor_path.starts_with('/') {
+		actor_path.to_string()
+	} else {
+		format!("/{actor_path}")
+	};
+
+	Some((actor_id, actor_path))
 }
 
 struct FoundActor {
@@ -194,8 +286,102 @@ async fn find_actor(
 		shared_state.pegboard_gateway.clone(),
 		actor_id,
 		runner_key,
+		path.to_string(),
 	);
 	Ok(Some(RoutingOutput::CustomServe(std::sync::Arc::new(
 		gateway,
 	))))
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn actor_hostname() {
+		let actor_id = Id::new_v1(1);
+
+		assert_eq!(
+			parse_actor_hostname(&format!("{actor_id}.actor.rivet.test"), "rivet.test"),
+			Some(actor_id)
+		);
+		assert_eq!(
+			parse_actor_hostname(
+				&format!("{}.ACTOR.Rivet.Test", actor_id.to_string().to_uppercase()),
+				"rivet.test"
+			),
+			Some(actor_id)
+		);
+
+		// Wrong domain
+		assert_eq!(
+			parse_actor_hostname(&format!("{actor_id}.actor.other.test"), "rivet.test"),
+			None
+		);
+		// Missing actor subdomain
+		assert_eq!(
+			parse_actor_hostname(&format!("{actor_id}.rivet.test"), "rivet.test"),
+			None
+		);
+		// Nested subdomain
+		assert_eq!(
+			parse_actor_hostname(&format!("foo.{actor_id}.actor.rivet.test"), "rivet.test"),
+			None
+		);
+		// Not an actor ID
+		assert_eq!(
+			parse_actor_hostname("api.actor.rivet.test", "rivet.test"),
+			None
+		);
+		assert_eq!(
+			parse_actor_hostname(".actor.rivet.test", "rivet.test"),
+			None
+		);
+	}
+
+	#[test]
+	fn actor_path() {
+		let actor_id = Id::new_v1(1);
+		let prefix = "/gateway/actors";
+
+		assert_eq!(
+			parse_actor_path(&format!("/gateway/actors/{actor_id}/foo/bar?x=1"), prefix),
+			Some((actor_id, "/foo/bar?x=1".to_string()))
+		);
+		assert_eq!(
+			parse_actor_path(&format!("/gateway/actors/{actor_id}/"), prefix),
+			Some((actor_id, "/".to_string()))
+		);
+		assert_eq!(
+			parse_actor_path(&format!("/gateway/actors/{actor_id}"), prefix),
+			Some((actor_id, "/".to_string()))
+		);
+		assert_eq!(
+			parse_actor_path(&format!("/gateway/actors/{actor_id}?x=1"), prefix),
+			Some((actor_id, "/?x=1".to_string()))
+		);
+		// Trailing slash in the configured prefix
+		assert_eq!(
+			parse_actor_path(
+				&format!("/gateway/actors/{actor_id}/foo"),
+				"/gateway/actors/"
+			),
+			Some((actor_id, "/foo".to_string()))
+		);
+
+		// Different prefix
+		assert_eq!(
+			parse_actor_path(&format!("/actors/{actor_id}/logs"), prefix),
+			None
+		);
+		// Prefix must end at a path segment boundary
+		assert_eq!(
+			parse_actor_path(&format!("/gateway/actorsx/{actor_id}"), prefix),
+			None
+		);
+		// Not an actor ID
+		assert_eq!(parse_actor_path("/gateway/actors/names", prefix), None);
+		assert_eq!(parse_actor_path("/gateway/actors/", prefix), None);
+		assert_eq!(parse_actor_path("/gateway/actors", prefix), None);
+	}
+}
@@ -39,6 +39,9 @@ pub struct PegboardGateway {
 	shared_state: SharedState,
 	actor_id: Id,
 	runner_key: String,
+	/// Path (including query) forwarded to the actor. May differ from the request path if a
+	/// routing prefix was stripped.
+	path: String,
 }
 
 impl PegboardGateway {
@@ -47,12 +50,14 @@ impl PegboardGateway {
 		shared_state: SharedState,
 		actor_id: Id,
 		runner_key: String,
+		path: String,
 	) -> Self {
 		Self {
 			ctx,
 			shared_state,
 			actor_id,
 			runner_key,
+			path,
 		}
 	}
 }
@@ -85,11 +90,11 @@ impl CustomServeTrait for PegboardGateway {
 		&self,
 		client_ws: HyperWebsocket,
 		headers: &hyper::HeaderMap,
-		path: &str,
+		_path: &str,
 		_request_context: &mut RequestContext,
 	) -> std::result::Result<(), (HyperWebsocket, anyhow::Error)> {
 		match self
-			.handle_websocket_inner(client_ws, headers, path, _request_context)
+			.handle_websocket_inner(client_ws, headers, _request_context)
 			.await
 		{
 			Result::Ok(()) => std::result::Result::<(), (HyperWebsocket, anyhow::Error)>::Ok(()),
@@ -113,14 +118,7 @@ impl PegboardGateway {
 		req: Request<Full<Bytes>>,
 		_request_context: &mut RequestContext,
 	) -> Result<Response<ResponseBody>> {
-		// Extract actor ID for the message
-		let actor_id = req
-			.headers()
-			.get("x-rivet-actor")
-			.context("missing x-rivet-actor header")?
-			.to_str()
-			.context("invalid x-rivet-actor header")?
-			.to_string();
+		let actor_id = self.actor_id.to_string();
 
 		// Extract request parts
 		let mut headers = HashableMap::new();
@@ -130,12 +128,9 @@ impl PegboardGateway {
 			}
 		}
 
-		// Extract method and path before consuming the request
+		// Extract method before consuming the request
 		let method = req.method().to_string();
-		let path = req
-			.uri()
-			.path_and_query()
-			.map_or_else(|| "/".to_string(), |x| x.to_string());
+		let path = self.path.clone();
 
 		let body_bytes = req
 			.into_body()
@@ -215,18 +210,9 @@ impl PegboardGateway {
 		&self,
 		client_ws: HyperWebsocket,
 		headers: &hyper::HeaderMap,
-		path: &str,
 		_request_context: &mut RequestContext,
 	) -> std::result::Result<(), (HyperWebsocket, anyhow::Error)> {
-		// Extract actor ID for the message
-		let actor_id = match headers
-			.get("x-rivet-actor")
-			.context("missing x-rivet-actor header")
-			.and_then(|v| v.to_str().context("invalid x-rivet-actor header"))
-		{
-			Result::Ok(v) => v.to_string(),
-			Err(err) => return Err((client_ws, err)),
-		};
+		let actor_id = self.actor_id.to_string();
 
 		// Extract headers
 		let mut request_headers = HashableMap::new();
@@ -250,7 +236,7 @@ impl PegboardGateway {
 		// Send WebSocket open message
 		let open_message = MessageKind::ToServerWebSocketOpen(ToServerWebSocketOpen {
 			actor_id: actor_id.clone(),
-			path: path.to_string(),
+			path: self.path.clone(),
 			headers: request_headers,
 		});
 
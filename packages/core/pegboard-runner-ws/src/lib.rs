@@ -5,7 +5,7 @@ use std::{
 		Arc,
 		atomic::{AtomicU32, Ordering},
 	},
-	time::Duration,
+	time::{Duration, Instant},
 };
 
 use futures_util::{
@@ -21,7 +21,7 @@ use rivet_runner_protocol::*;
 use serde_json::json;
 use tokio::{
 	net::{TcpListener, TcpStream},
-	sync::{Mutex, RwLock},
+	sync::{Mutex, RwLock, watch},
 };
 use tokio_tungstenite::{
 	WebSocketStream,
@@ -33,6 +33,8 @@ use tokio_tungstenite::{
 use versioned_data_util::OwnedVersionedData;
 
 const UPDATE_PING_INTERVAL: Duration = Duration::from_secs(3);
+/// How long to wait for runner connections to close on shutdown.
+const SHUTDOWN_CLOSE_TIMEOUT: Duration = Duration::from_secs(5);
 
 #[derive(RivetError, Debug)]
 #[error("ws")]
@@ -44,6 +46,11 @@ enum WsError {
 	NewRunnerConnected,
 	#[error("connection_closed", "Normal connection close.")]
 	ConnectionClosed,
+	#[error(
+		"going_away",
+		"The server is shutting down, reconnect to another node."
+	)]
+	GoingAway,
 	#[error(
 		"eviction",
 		"The websocket has been evicted and should not attempt to reconnect."
@@ -80,7 +87,11 @@ struct Connection {
 type Connections = HashMap<Id, Arc<Connection>>;
 
 #[tracing::instrument(skip_all)]
-pub async fn start(config: rivet_config::Config, pools: rivet_pools::Pools) -> Result<()> {
+pub async fn start(
+	config: rivet_config::Config,
+	pools: rivet_pools::Pools,
+	mut shutdown_rx: watch::Receiver<()>,
+) -> Result<()> {
 	let cache = rivet_cache::CacheInner::from_env(&config, pools.clone())?;
 	let ctx = StandaloneCtx::new(
 		db::DatabaseKv::from_pools(pools.clone()).await?,
@@ -106,15 +117,57 @@ pub async fn start(config: rivet_config::Config, pools: rivet_pools::Pools) -> R
 	// If these do exit, then the `handle_connection` task will run indefinitely and never
 	// send/receive anything to runners. Runner workflows will then expire because of their ping,
 	// their workflow will complete, and runners will be unusable unless they reconnect.
-	tokio::join!(
-		socket_thread(&ctx, conns.clone(), listener),
-		msg_thread(&ctx, conns.clone()),
-		update_ping_thread(&ctx, conns.clone()),
-	);
+	tokio::select! {
+		_ = async {
+			tokio::join!(
+				socket_thread(&ctx, conns.clone(), listener),
+				msg_thread(&ctx, conns.clone()),
+				update_ping_thread(&ctx, conns.clone()),
+			)
+		} => {}
+		// Stops accepting new connections since the listener is dropped
+		_ = shutdown_rx.changed() => {
+			close_connections(conns.clone()).await;
+		}
+	}
 
 	Ok(())
 }
 
+/// Closes all runner connections so runners reconnect to another node.
+#[tracing::instrument(skip_all)]
+async fn close_connections(conns: Arc<RwLock<Connections>>) {
+	let conns_list = conns
+		.read()
+		.await
+		.iter()
+		.map(|(runner_id, conn)| (*runner_id, conn.clone()))
+		.collect::<Vec<_>>();
+	tracing::info!(count = conns_list.len(), "closing runner connections");
+
+	let close_frame = err_to_close_frame(WsError::GoingAway.build());
+	for (runner_id, conn) in conns_list {
+		let mut tx = conn.tx.lock().await;
+		if let Err(err) = tx.send(Message::Close(Some(close_frame.clone()))).await {
+			tracing::warn!(?runner_id, ?err, "failed closing runner connection");
+		}
+	}
+
+	// Wait for connection tasks to clean up
+	let start = Instant::now();
+	while !conns.read().await.is_empty() {
+		if start.elapsed() > SHUTDOWN_CLOSE_TIMEOUT {
+			tracing::warn!(
+				remaining = conns.read().await.len(),
+				"timed out closing runner connections"
+			);
+			break;
+		}
+
+		tokio::time::sleep(Duration::from_millis(100)).await;
+	}
+}
+
 #[tracing::instrument(skip_all)]
 async fn socket_thread(
 	ctx: &StandaloneCtx,
@@ -295,7 +348,7 @@ async fn build_connection(
 			}
 		};
 
-		let packet = versioned::ToServer::deserialize(&buf, protocol_version)
+		let mut packet = versioned::ToServer::deserialize(&buf, protocol_version)
 			.map_err(|err| WsError::InvalidPacket(err.to_string()).build())?
 			.try_into()
 			.map_err(|err: anyhow::Error| WsError::InvalidPacket(err.to_string()).build())?;
@@ -304,9 +357,12 @@ async fn build_connection(
 			name,
 			version,
 			total_slots,
+			protocol_version: init_protocol_version,
 			..
-		} = &packet
+		} = &mut packet
 		{
+			*init_protocol_version = protocol_version;
+
 			// Look up existing runner by key
 			let existing_runner = ctx
 				.op(pegboard::ops::runner::get_by_key::Input {
@@ -615,6 +671,12 @@ async fn handle_messages(
 					}
 				}
 			}
+			ToServer::ToServerActorLogs(logs) => {
+				// Logs are best effort, don't close the connection if they fail to write
+				if let Err(err) = handle_actor_logs(ctx, runner_id, logs).await {
+					tracing::warn!(?err, ?runner_id, "failed to handle actor logs");
+				}
+			}
 			// Forward to runner wf
 			_ => {
 				ctx.signal(protocol::ToServer::try_from(packet)?)
@@ -628,6 +690,47 @@ async fn handle_messages(
 	bail!("stream closed {runner_id}");
 }
 
+async fn handle_actor_logs(
+	ctx: &StandaloneCtx,
+	runner_id: Id,
+	logs: ToServerActorLogs,
+) -> Result<()> {
+	let actor_id = Id::parse(&logs.actor_id)?;
+
+	// Verify actor belongs to this runner
+	let actors_res = ctx
+		.op(pegboard::ops::actor::get_runner::Input {
+			actor_ids: vec![actor_id],
+		})
+		.await?;
+	let actor_belongs = actors_res
+		.actors
+		.first()
+		.map(|x| x.runner_id == runner_id)
+		.unwrap_or_default();
+	if !actor_belongs {
+		bail!("given actor does not belong to runner");
+	}
+
+	ctx.op(pegboard::ops::actor::append_logs::Input {
+		actor_id,
+		entries: logs
+			.entries
+			.into_iter()
+			.map(|entry| rivet_types::actors::ActorLogEntry {
+				stream: match entry.stream {
+					ActorLogStream::Stdout => rivet_types::actors::ActorLogStream::Stdout,
+					ActorLogStream::Stderr => rivet_types::actors::ActorLogStream::Stderr,
+				},
+				ts: entry.ts,
+				generation: logs.generation,
+				message: entry.message,
+			})
+			.collect(),
+	})
+	.await
+}
+
 #[tracing::instrument(skip_all)]
 async fn update_ping_thread(ctx: &StandaloneCtx, conns: Arc<RwLock<Connections>>) {
 	loop {
@@ -838,6 +941,7 @@ fn err_to_close_frame(err: anyhow::Error) -> CloseFrame {
 
 	let code = match (rivet_err.group(), rivet_err.code()) {
 		("ws", "connection_closed") => CloseCode::Normal,
+		("ws", "going_away") => CloseCode::Away,
 		_ => CloseCode::Error,
 	};
 
@@ -9,7 +9,7 @@ use std::{
 use anyhow::Result;
 use futures_util::{StreamExt, TryStreamExt};
 use gas::prelude::*;
-use namespace::types::RunnerConfig;
+use namespace::types::RunnerConfigKind;
 use pegboard::keys;
 use reqwest_eventsource as sse;
 use rivet_runner_protocol::protocol;
@@ -100,14 +100,14 @@ async fn tick(
 			.find(|rc| rc.namespace_id == *ns_id)
 			.context("runner config not found")?;
 
-		let RunnerConfig::Serverless {
+		let RunnerConfigKind::Serverless {
 			url,
 			request_lifespan,
 			slots_per_runner,
 			min_runners,
 			max_runners,
 			runners_margin,
-		} = &runner_config.config
+		} = &runner_config.config.kind
 		else {
 			tracing::warn!(
 				?ns_id,
@@ -2,7 +2,11 @@ use anyhow::Result;
 use gas::prelude::*;
 
 #[tracing::instrument(skip_all)]
-pub async fn start(config: rivet_config::Config, pools: rivet_pools::Pools) -> Result<()> {
+pub async fn start(
+	config: rivet_config::Config,
+	pools: rivet_pools::Pools,
+	shutdown_rx: tokio::sync::watch::Receiver<()>,
+) -> Result<()> {
 	let reg = pegboard::registry()?
 		.merge(namespace::registry()?)?
 		.merge(epoxy::registry()?)?;
@@ -11,5 +15,5 @@ pub async fn start(config: rivet_config::Config, pools: rivet_pools::Pools) -> R
 	let worker = Worker::new(reg.handle(), db, config, pools);
 
 	// Start worker
-	worker.start(None).await
+	worker.start(Some(shutdown_rx)).await
 }
@@ -0,0 +1,45 @@
+use anyhow::*;
+use clap::Parser;
+use rivet_api_types::actors::migrate::{MigrateRequest, MigrateResponse};
+use rivet_api_util::{HeaderMap, Method};
+use rivet_util::Id;
+
+#[derive(Parser)]
+pub enum SubCommand {
+	/// Stops an actor and reallocates it to a different runner. Used to drain or rebalance
+	/// runners.
+	Migrate {
+		#[clap(index = 1)]
+		actor_id: Id,
+		/// Runner to move the actor to. If not set, any other runner is chosen.
+		#[clap(long)]
+		runner_id: Option<Id>,
+	},
+}
+
+impl SubCommand {
+	pub async fn execute(self, config: rivet_config::Config) -> Result<()> {
+		match self {
+			Self::Migrate {
+				actor_id,
+				runner_id,
+			} => {
+				// Actors are managed by the datacenter they were created in
+				rivet_api_util::request_remote_datacenter::<MigrateResponse>(
+					&config,
+					actor_id.label(),
+					&format!("/actors/{actor_id}/migrate"),
+					Method::POST,
+					HeaderMap::new(),
+					Option::<&()>::None,
+					Some(&MigrateRequest { runner_id }),
+				)
+				.await?;
+
+				println!("migration of actor {actor_id} requested");
+
+				Ok(())
+			}
+		}
+	}
+}
@@ -0,0 +1,80 @@
+use anyhow::*;
+use clap::Parser;
+use rivet_api_peer::epoxy::{ReconfigureResponse, RemoveReplicaResponse, StatusResponse};
+use rivet_api_util::{HeaderMap, Method};
+
+use crate::util::{self, format::colored_json};
+
+#[derive(Parser)]
+pub enum SubCommand {
+	/// Prints the status of every replica in the cluster.
+	Status {
+		/// Prints the raw JSON response.
+		#[clap(long, short = 'j')]
+		json: bool,
+	},
+	/// Adds any new datacenters in the topology to the cluster.
+	Reconfigure,
+	/// Removes a dead replica from the cluster config.
+	RemoveReplica { replica_id: u64 },
+}
+
+impl SubCommand {
+	pub async fn execute(self, config: rivet_config::Config) -> Result<()> {
+		match self {
+			Self::Status { json } => {
+				let res = rivet_api_util::request_remote_datacenter::<StatusResponse>(
+					&config,
+					config.dc_label(),
+					"/epoxy/status",
+					Method::GET,
+					HeaderMap::new(),
+					Option::<&()>::None,
+					Option::<&()>::None,
+				)
+				.await?;
+
+				if json {
+					println!("{}", colored_json(&serde_json::to_value(&res)?)?);
+				} else {
+					util::epoxy::print_status(res.status);
+				}
+
+				Ok(())
+			}
+			Self::Reconfigure => {
+				// The coordinator only runs in the leader datacenter
+				rivet_api_util::request_remote_datacenter::<ReconfigureResponse>(
+					&config,
+					config.leader_dc()?.datacenter_label,
+					"/epoxy/reconfigure",
+					Method::POST,
+					HeaderMap::new(),
+					Option::<&()>::None,
+					Option::<&()>::None,
+				)
+				.await?;
+
+				println!("reconfigure requested");
+
+				Ok(())
+			}
+			Self::RemoveReplica { replica_id } => {
+				rivet_api_util::request_remote_datacenter::<RemoveReplicaResponse>(
+					&config,
+					config.leader_dc()?.datacenter_label,
+					&format!("/epoxy/replicas/{replica_id}"),
+					Method::DELETE,
+					HeaderMap::new(),
+					Option::<&()>::None,
+					Option::<&()>::None,
+				)
+				.await?;
+
+				println!("removal of replica {replica_id} requested");
+
+				Ok(())
+			}
+		}
+	}
+}
@@ -1,5 +1,7 @@
+pub mod actor;
 pub mod config;
 pub mod db;
+pub mod epoxy;
 pub mod start;
 pub mod udb;
 pub mod wf;
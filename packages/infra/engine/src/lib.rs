@@ -23,6 +23,16 @@ pub enum SubCommand {
 		#[clap(subcommand)]
 		command: wf::SubCommand,
 	},
+	/// Manages actors
+	Actor {
+		#[clap(subcommand)]
+		command: actor::SubCommand,
+	},
+	/// Inspects and manages the epoxy cluster
+	Epoxy {
+		#[clap(subcommand)]
+		command: epoxy::SubCommand,
+	},
 	/// Manage the Rivet config
 	Config {
 		#[clap(subcommand)]
@@ -38,6 +48,8 @@ impl SubCommand {
 			SubCommand::Start(opts) => opts.execute(config, &run_config).await,
 			SubCommand::Database { command } => command.execute(config).await,
 			SubCommand::Workflow { command } => command.execute(config).await,
+			SubCommand::Actor { command } => command.execute(config).await,
+			SubCommand::Epoxy { command } => command.execute(config).await,
 			SubCommand::Config { command } => command.execute(config).await,
 			SubCommand::Udb(opts) => opts.execute(config).await,
 		}
@@ -1,32 +1,64 @@
+use std::time::Duration;
+
 use anyhow::*;
 use rivet_service_manager::{RunConfigData, Service, ServiceKind};
 
 pub fn config(_rivet_config: rivet_config::Config) -> Result<RunConfigData> {
 	let services = vec![
-		Service::new("api_peer", ServiceKind::ApiPeer, |config, pools| {
-			Box::pin(rivet_api_peer::start(config, pools))
-		}),
-		Service::new("guard", ServiceKind::Standalone, |config, pools| {
-			Box::pin(rivet_guard::start(config, pools))
-		}),
-		Service::new(
-			"pegboard_runner_ws",
-			ServiceKind::ApiPublic,
-			|config, pools| Box::pin(pegboard_runner_ws::start(config, pools)),
-		),
-		Service::new(
-			"workflow_worker",
+		// Stop accepting traffic and drain in-flight requests first. Guard waits up to 30s for
+		// connections to drain internally, so it is given extra time to exit on its own before
+		// being aborted.
+		Service::new_graceful(
+			"guard",
 			ServiceKind::Standalone,
-			|config, pools| Box::pin(rivet_workflow_worker::start(config, pools)),
-		),
+			|config, pools, shutdown_rx| Box::pin(rivet_guard::start(config, pools, shutdown_rx)),
+		)
+		.with_shutdown_order(0)
+		.with_shutdown_timeout(Duration::from_secs(35)),
+		// Nothing left to do once shutdown starts
 		Service::new("bootstrap", ServiceKind::Oneshot, |config, pools| {
 			Box::pin(rivet_bootstrap::start(config, pools))
-		}),
+		})
+		.with_shutdown_order(0),
+		// Requests proxied by guard have drained by now
+		Service::new_graceful(
+			"api_peer",
+			ServiceKind::ApiPeer,
+			|config, pools, shutdown_rx| {
+				Box::pin(rivet_api_peer::start(config, pools, shutdown_rx))
+			},
+		)
+		.with_shutdown_order(1)
+		.with_shutdown_timeout(Duration::from_secs(10)),
+		// Stop scaling serverless runners before their connections are closed. Outbound
+		// connections drain on their own.
 		Service::new(
 			"pegboard_serverless",
-			ServiceKind::Standalone,
+			ServiceKind::Singleton,
 			|config, pools| Box::pin(pegboard_serverless::start(config, pools)),
-		),
+		)
+		.with_shutdown_order(1),
+		// Close runner connections once no more requests are tunneled to them
+		Service::new_graceful(
+			"pegboard_runner_ws",
+			ServiceKind::ApiPublic,
+			|config, pools, shutdown_rx| {
+				Box::pin(pegboard_runner_ws::start(config, pools, shutdown_rx))
+			},
+		)
+		.with_shutdown_order(1)
+		.with_shutdown_timeout(Duration::from_secs(10)),
+		// Let running workflows finish last. The worker waits up to 30s for workflows internally, so
+		// it is given a generous margin to write their state before being aborted.
+		Service::new_graceful(
+			"workflow_worker",
+			ServiceKind::Standalone,
+			|config, pools, shutdown_rx| {
+				Box::pin(rivet_workflow_worker::start(config, pools, shutdown_rx))
+			},
+		)
+		.with_shutdown_order(2)
+		.with_shutdown_timeout(Duration::from_secs(45)),
 	];
 
 	Ok(RunConfigData { services })
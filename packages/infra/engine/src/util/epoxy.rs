@@ -0,0 +1,58 @@
+use epoxy::{ops::cluster_status::Output, types::ReplicaStatus};
+use rivet_term::console::style;
+use tabled::Tabled;
+
+#[derive(Tabled)]
+struct ReplicaTableRow {
+	pub replica_id: u64,
+	#[tabled(display_with = "display_status")]
+	pub status: ReplicaStatus,
+	#[tabled(display_with = "display_option")]
+	pub epoch: Option<u64>,
+	#[tabled(display_with = "display_option")]
+	pub log_length: Option<u64>,
+	#[tabled(display_with = "display_option")]
+	pub lag: Option<u64>,
+	pub api_peer_url: String,
+}
+
+pub fn print_status(status: Output) {
+	println!(
+		"{} {}  {} {}",
+		style("coordinator:").bold(),
+		status.config.coordinator_replica_id,
+		style("epoch:").bold(),
+		status.config.epoch
+	);
+
+	let rows = status
+		.replicas
+		.into_iter()
+		.map(|replica| ReplicaTableRow {
+			replica_id: replica.replica_id,
+			status: replica.status,
+			epoch: replica.details.as_ref().map(|x| x.epoch),
+			log_length: replica.details.as_ref().map(|x| x.log_length),
+			lag: replica.details.as_ref().map(|x| x.lag),
+			api_peer_url: replica.api_peer_url,
+		})
+		.collect::<Vec<_>>();
+
+	rivet_term::format::table(rows);
+}
+
+fn display_status(status: &ReplicaStatus) -> String {
+	match status {
+		ReplicaStatus::Joining => style("joining").yellow().to_string(),
+		ReplicaStatus::Learning => style("learning").blue().to_string(),
+		ReplicaStatus::Active => style("active").green().to_string(),
+	}
+}
+
+/// Replicas that could not be reached have no details.
+fn display_option(value: &Option<u64>) -> String {
+	match value {
+		Some(value) => value.to_string(),
+		None => style("unreachable").red().to_string(),
+	}
+}
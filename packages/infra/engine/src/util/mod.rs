@@ -1,4 +1,5 @@
 pub mod db;
+pub mod epoxy;
 pub mod format;
 pub mod udb;
 pub mod wf;
@@ -0,0 +1,130 @@
+mod common;
+
+use std::time::Duration;
+
+async fn create_alarm_actor(namespace: &str, name: &str, guard_port: u16) -> String {
+	common::create_actor_with_options(
+		common::CreateActorOptions {
+			namespace: namespace.to_string(),
+			name: name.to_string(),
+			..Default::default()
+		},
+		guard_port,
+	)
+	.await
+}
+
+async fn wait_for_fired_alarms(runner: &common::runner::TestRunner, actor_id: &str, count: usize) {
+	tokio::time::timeout(Duration::from_secs(10), async {
+		while runner.fired_alarms(actor_id).len() < count {
+			tokio::time::sleep(Duration::from_millis(100)).await;
+		}
+	})
+	.await
+	.expect("timed out waiting for alarms to fire");
+}
+
+/// Puts the actor to sleep and wakes it again with a request.
+async fn restart_actor(
+	runner: &common::runner::TestRunner,
+	actor_id: &str,
+	namespace: &str,
+	guard_port: u16,
+) {
+	let starts = runner.start_attempts(actor_id).len();
+	runner.sleep_actor(actor_id);
+
+	tokio::time::timeout(Duration::from_secs(10), async {
+		loop {
+			let response = common::get_actor(actor_id, Some(namespace), guard_port).await;
+			common::assert_success_response(&response);
+			let body: serde_json::Value = response.json().await.expect("failed to parse response");
+
+			if !body["actor"]["sleep_ts"].is_null() {
+				break;
+			}
+			tokio::time::sleep(Duration::from_millis(100)).await;
+		}
+	})
+	.await
+	.expect("timed out waiting for actor to sleep");
+
+	common::ping_actor_via_guard(guard_port, actor_id).await;
+	assert_eq!(runner.start_attempts(actor_id).len(), starts + 1);
+}
+
+#[test]
+fn alarm_fires_for_running_actor() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, _, runner) = common::setup_test_namespace_with_runner(dc).await;
+
+		let actor_id = create_alarm_actor(
+			&namespace,
+			common::runner::ALARM_ACTOR_NAME,
+			dc.guard_port(),
+		)
+		.await;
+
+		wait_for_fired_alarms(&runner, &actor_id, 1).await;
+		assert_eq!(
+			runner.fired_alarms(&actor_id),
+			vec![common::runner::TEST_ALARM_NAME.to_string()]
+		);
+
+		// The alarm was acknowledged, so it is not delivered again on the next start
+		restart_actor(&runner, &actor_id, &namespace, dc.guard_port()).await;
+		tokio::time::sleep(Duration::from_secs(2)).await;
+		assert_eq!(runner.fired_alarms(&actor_id).len(), 1);
+
+		runner.shutdown().await;
+	});
+}
+
+#[test]
+fn unacknowledged_alarm_fires_again_on_start() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, _, runner) = common::setup_test_namespace_with_runner(dc).await;
+
+		// The first delivery fails, so the alarm is not acknowledged
+		let actor_id = create_alarm_actor(
+			&namespace,
+			common::runner::FAILING_ALARM_ACTOR_NAME,
+			dc.guard_port(),
+		)
+		.await;
+		wait_for_fired_alarms(&runner, &actor_id, 1).await;
+
+		restart_actor(&runner, &actor_id, &namespace, dc.guard_port()).await;
+		wait_for_fired_alarms(&runner, &actor_id, 2).await;
+
+		// Acknowledged on the second delivery
+		restart_actor(&runner, &actor_id, &namespace, dc.guard_port()).await;
+		tokio::time::sleep(Duration::from_secs(2)).await;
+		assert_eq!(runner.fired_alarms(&actor_id).len(), 2);
+
+		runner.shutdown().await;
+	});
+}
+
+#[test]
+fn named_alarm_fires_after_wake_alarm_while_running() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, _, runner) = common::setup_test_namespace_with_runner(dc).await;
+
+		// The wake alarm is due first but does nothing while the actor is running
+		let actor_id = create_alarm_actor(
+			&namespace,
+			common::runner::WAKE_ALARM_ACTOR_NAME,
+			dc.guard_port(),
+		)
+		.await;
+
+		wait_for_fired_alarms(&runner, &actor_id, 1).await;
+		assert_eq!(runner.start_attempts(&actor_id).len(), 1);
+
+		runner.shutdown().await;
+	});
+}
@@ -0,0 +1,231 @@
+mod common;
+
+use std::collections::HashSet;
+
+use gas::prelude::*;
+use serde_json::json;
+
+fn actor_body(name: &str, key: &str) -> serde_json::Value {
+	json!({
+		"name": name,
+		"key": key,
+		"runner_name_selector": "test-runner",
+		"crash_policy": "destroy",
+	})
+}
+
+async fn bulk_create(
+	namespace: &str,
+	actors: Vec<serde_json::Value>,
+	guard_port: u16,
+) -> Vec<serde_json::Value> {
+	let response = reqwest::Client::new()
+		.post(format!(
+			"http://127.0.0.1:{guard_port}/actors/bulk?namespace={namespace}"
+		))
+		.json(&json!({ "actors": actors }))
+		.send()
+		.await
+		.expect("failed to send bulk create request");
+	common::assert_success_response(&response);
+
+	let body: serde_json::Value = response.json().await.expect("failed to parse response");
+	body["results"].as_array().expect("missing results").clone()
+}
+
+async fn bulk_destroy(namespace: &str, query: &str, guard_port: u16) -> HashSet<String> {
+	let response = reqwest::Client::new()
+		.delete(format!(
+			"http://127.0.0.1:{guard_port}/actors/bulk?namespace={namespace}&{query}"
+		))
+		.send()
+		.await
+		.expect("failed to send bulk destroy request");
+	common::assert_success_response(&response);
+
+	let body: serde_json::Value = response.json().await.expect("failed to parse response");
+	body["actor_ids"]
+		.as_array()
+		.expect("missing actor ids")
+		.iter()
+		.map(|x| x.as_str().expect("invalid actor id").to_string())
+		.collect()
+}
+
+fn result_actor_id(result: &serde_json::Value) -> String {
+	result["actor"]["actor_id"]
+		.as_str()
+		.unwrap_or_else(|| panic!("actor was not created: {result}"))
+		.to_string()
+}
+
+#[test]
+fn bulk_create_reports_failures_in_order() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, _, runner) = common::setup_test_namespace_with_runner(dc).await;
+
+		let existing_key = common::generate_unique_key();
+		common::create_actor_with_options(
+			common::CreateActorOptions {
+				namespace: namespace.clone(),
+				key: Some(existing_key.clone()),
+				..Default::default()
+			},
+			dc.guard_port(),
+		)
+		.await;
+
+		let mut unknown_dc = actor_body("test-actor", &common::generate_unique_key());
+		unknown_dc["datacenter"] = json!("does-not-exist");
+
+		let results = bulk_create(
+			&namespace,
+			vec![
+				actor_body("test-actor", &common::generate_unique_key()),
+				actor_body("test-actor", &existing_key),
+				unknown_dc,
+				actor_body("test-actor", &common::generate_unique_key()),
+			],
+			dc.guard_port(),
+		)
+		.await;
+		assert_eq!(results.len(), 4);
+
+		result_actor_id(&results[0]);
+		assert_eq!(results[1]["error"]["code"], "duplicate_key");
+		assert_eq!(results[2]["error"]["group"], "datacenter");
+		assert_eq!(results[2]["error"]["code"], "not_found");
+		result_actor_id(&results[3]);
+
+		runner.shutdown().await;
+	});
+}
+
+#[test]
+fn bulk_create_per_actor_datacenter() {
+	common::run(common::TestOpts::new(2), |ctx| async move {
+		let (namespace, _, _runner) =
+			common::setup_test_namespace_with_runner(ctx.leader_dc()).await;
+
+		let mut remote = actor_body("test-actor", &common::generate_unique_key());
+		remote["datacenter"] = json!("dc-2");
+
+		let results = bulk_create(
+			&namespace,
+			vec![
+				remote,
+				actor_body("test-actor", &common::generate_unique_key()),
+			],
+			ctx.leader_dc().guard_port(),
+		)
+		.await;
+		assert_eq!(results.len(), 2);
+
+		// Results keep the request order even though each datacenter is requested separately
+		common::assert_actor_in_dc(&result_actor_id(&results[0]), 2).await;
+		common::assert_actor_in_dc(&result_actor_id(&results[1]), 1).await;
+	});
+}
+
+#[test]
+fn bulk_destroy_filters() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, _, runner) = common::setup_test_namespace_with_runner(dc).await;
+
+		let results = bulk_create(
+			&namespace,
+			vec![
+				actor_body("bulk-actor", "match-1"),
+				actor_body("bulk-actor", "match-2"),
+				actor_body("bulk-actor", "other-1"),
+				actor_body("other-actor", "match-3"),
+			],
+			dc.guard_port(),
+		)
+		.await;
+		let actor_ids = results.iter().map(result_actor_id).collect::<Vec<_>>();
+
+		// Only actors with the name and key prefix are destroyed
+		let destroyed = bulk_destroy(
+			&namespace,
+			"name=bulk-actor&key_prefix=match-",
+			dc.guard_port(),
+		)
+		.await;
+		assert_eq!(
+			destroyed,
+			HashSet::from([actor_ids[0].clone(), actor_ids[1].clone()])
+		);
+
+		// Actors created before `created_after` are not destroyed
+		let created_after = rivet_util::timestamp::now() + 60_000;
+		let destroyed = bulk_destroy(
+			&namespace,
+			&format!("name=bulk-actor&created_after={created_after}"),
+			dc.guard_port(),
+		)
+		.await;
+		assert!(destroyed.is_empty());
+
+		let destroyed = bulk_destroy(&namespace, "name=bulk-actor", dc.guard_port()).await;
+		assert!(destroyed.contains(&actor_ids[2]));
+		assert!(!destroyed.contains(&actor_ids[3]));
+
+		runner.shutdown().await;
+	});
+}
+
+#[test]
+fn list_for_ns_cursor_pages() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, namespace_id, runner) = common::setup_test_namespace_with_runner(dc).await;
+
+		let results = bulk_create(
+			&namespace,
+			(0..5)
+				.map(|_| actor_body("paged-actor", &common::generate_unique_key()))
+				.collect(),
+			dc.guard_port(),
+		)
+		.await;
+		let actor_ids = results
+			.iter()
+			.map(|x| result_actor_id(x).parse::<Id>().expect("invalid actor id"))
+			.collect::<HashSet<_>>();
+
+		// Every actor is listed exactly once when reading pages smaller than the total
+		let mut listed = Vec::new();
+		let mut cursor = None;
+		loop {
+			let res = dc
+				.workflow_ctx
+				.op(pegboard::ops::actor::list_for_ns::Input {
+					namespace_id,
+					name: "paged-actor".to_string(),
+					key: None,
+					include_destroyed: false,
+					created_before: None,
+					cursor,
+					limit: 2,
+				})
+				.await
+				.expect("failed to list actors");
+			if res.actors.is_empty() {
+				break;
+			}
+
+			for actor in res.actors {
+				cursor = Some((actor.create_ts, actor.actor_id));
+				listed.push(actor.actor_id);
+			}
+		}
+
+		assert_eq!(listed.len(), actor_ids.len());
+		assert_eq!(listed.into_iter().collect::<HashSet<_>>(), actor_ids);
+
+		runner.shutdown().await;
+	});
+}
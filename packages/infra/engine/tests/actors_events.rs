@@ -0,0 +1,182 @@
+mod common;
+
+use futures_util::{Stream, StreamExt};
+
+struct SseReader<S> {
+	stream: S,
+	buf: String,
+}
+
+impl<S, B> SseReader<S>
+where
+	S: Stream<Item = reqwest::Result<B>> + Unpin,
+	B: AsRef<[u8]>,
+{
+	fn new(stream: S) -> Self {
+		SseReader {
+			stream,
+			buf: String::new(),
+		}
+	}
+
+	/// Returns the next event name and data. Returns `None` once the stream ends.
+	async fn next(&mut self) -> Option<(String, serde_json::Value)> {
+		loop {
+			if let Some(pos) = self.buf.find("\n\n") {
+				let frame = self.buf[..pos].to_string();
+				self.buf.drain(..pos + 2);
+
+				let mut event = None;
+				let mut data = None;
+				for line in frame.lines() {
+					if let Some(x) = line.strip_prefix("event:") {
+						event = Some(x.trim().to_string());
+					} else if let Some(x) = line.strip_prefix("data:") {
+						data = Some(serde_json::from_str(x.trim()).expect("invalid event data"));
+					}
+				}
+
+				// Skip keep alive comments
+				if let (Some(event), Some(data)) = (event, data) {
+					return Some((event, data));
+				}
+
+				continue;
+			}
+
+			let chunk = self.stream.next().await?.expect("failed to read stream");
+			self.buf.push_str(&String::from_utf8_lossy(chunk.as_ref()));
+		}
+	}
+}
+
+async fn open_stream(url: String) -> reqwest::Response {
+	let response = reqwest::Client::new()
+		.get(url)
+		.send()
+		.await
+		.expect("failed to send events request");
+	assert!(
+		response.status().is_success(),
+		"failed to open event stream: {}",
+		response.status()
+	);
+
+	response
+}
+
+#[test]
+fn actor_events_end_after_destroy() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, _, runner) = common::setup_test_namespace_with_runner(dc).await;
+
+		let actor_id = common::create_actor(&namespace, dc.guard_port()).await;
+
+		let response = open_stream(format!(
+			"http://127.0.0.1:{}/actors/{}/events?namespace={}",
+			dc.guard_port(),
+			actor_id,
+			namespace
+		))
+		.await;
+		let mut events = SseReader::new(response.bytes_stream());
+
+		common::destroy_actor(&actor_id, &namespace, dc.guard_port()).await;
+
+		let mut names = Vec::new();
+		while let Some((name, data)) = events.next().await {
+			assert_eq!(data["actor_id"], actor_id);
+			names.push(name);
+		}
+		assert_eq!(names, vec!["destroy_started", "destroy_complete"]);
+
+		runner.shutdown().await;
+	});
+}
+
+#[test]
+fn namespace_events_only_include_namespace() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, _, runner) = common::setup_test_namespace_with_runner(dc).await;
+		let (other_namespace, _, other_runner) = common::setup_test_namespace_with_runner(dc).await;
+
+		let response = open_stream(format!(
+			"http://127.0.0.1:{}/actors/events?namespace={}",
+			dc.guard_port(),
+			namespace
+		))
+		.await;
+		let mut events = SseReader::new(response.bytes_stream());
+
+		// Events of other namespaces are not received
+		let other_actor_id = common::create_actor(&other_namespace, dc.guard_port()).await;
+		let actor_id = common::create_actor(&namespace, dc.guard_port()).await;
+
+		loop {
+			let (name, data) = events.next().await.expect("stream ended early");
+			assert_eq!(
+				data["actor_id"], actor_id,
+				"received event for {other_actor_id} from another namespace"
+			);
+
+			if name == "ready" {
+				break;
+			}
+		}
+
+		runner.shutdown().await;
+		other_runner.shutdown().await;
+	});
+}
+
+#[test]
+fn namespace_events_filter_by_name() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, _, runner) = common::setup_test_namespace_with_runner(dc).await;
+
+		let response = open_stream(format!(
+			"http://127.0.0.1:{}/actors/events?namespace={}&name=filtered-actor",
+			dc.guard_port(),
+			namespace
+		))
+		.await;
+		let mut events = SseReader::new(response.bytes_stream());
+
+		common::create_actor(&namespace, dc.guard_port()).await;
+		let actor_id = common::create_actor_with_options(
+			common::CreateActorOptions {
+				namespace: namespace.clone(),
+				name: "filtered-actor".to_string(),
+				..Default::default()
+			},
+			dc.guard_port(),
+		)
+		.await;
+
+		let (name, data) = events.next().await.expect("stream ended early");
+		assert_eq!(name, "create_complete");
+		assert_eq!(data["actor_id"], actor_id);
+
+		runner.shutdown().await;
+	});
+}
+
+#[test]
+fn namespace_events_unknown_namespace() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+
+		let response = reqwest::Client::new()
+			.get(format!(
+				"http://127.0.0.1:{}/actors/events?namespace=does-not-exist",
+				dc.guard_port()
+			))
+			.send()
+			.await
+			.expect("failed to send events request");
+		common::assert_error_response(response, "namespace_not_found").await;
+	});
+}
@@ -0,0 +1,202 @@
+mod common;
+
+use std::time::Duration;
+
+use futures_util::StreamExt;
+use gas::prelude::*;
+use rivet_types::actors::{ActorLogEntry, ActorLogStream};
+
+fn log_entry(message: &str) -> ActorLogEntry {
+	ActorLogEntry {
+		stream: ActorLogStream::Stdout,
+		ts: rivet_util::timestamp::now(),
+		generation: 0,
+		message: message.to_string(),
+	}
+}
+
+async fn list_messages(ctx: &StandaloneCtx, actor_id: Id, after_idx: Option<u64>) -> Vec<String> {
+	ctx.op(pegboard::ops::actor::list_logs::Input {
+		actor_id,
+		after_idx,
+		limit: 100,
+	})
+	.await
+	.expect("failed to list logs")
+	.entries
+	.into_iter()
+	.map(|entry| entry.message)
+	.collect()
+}
+
+#[test]
+fn actor_logs_append_and_list() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, _, runner) = common::setup_test_namespace_with_runner(dc).await;
+
+		let actor_id = common::create_actor(&namespace, dc.guard_port()).await;
+		let actor_id_parsed = actor_id.parse::<Id>().expect("invalid actor id");
+
+		// Wait for the line logged by the runner when the actor starts
+		loop {
+			let messages = list_messages(&dc.workflow_ctx, actor_id_parsed, Some(0)).await;
+			if messages.iter().any(|x| x == "actor started") {
+				break;
+			}
+			tokio::time::sleep(Duration::from_millis(100)).await;
+		}
+
+		dc.workflow_ctx
+			.op(pegboard::ops::actor::append_logs::Input {
+				actor_id: actor_id_parsed,
+				entries: (0..5).map(|i| log_entry(&format!("line {i}"))).collect(),
+			})
+			.await
+			.expect("failed to append logs");
+
+		// Tail returns the most recent lines in order
+		let res = dc
+			.workflow_ctx
+			.op(pegboard::ops::actor::list_logs::Input {
+				actor_id: actor_id_parsed,
+				after_idx: None,
+				limit: 2,
+			})
+			.await
+			.expect("failed to list logs");
+		let messages = res
+			.entries
+			.iter()
+			.map(|entry| entry.message.as_str())
+			.collect::<Vec<_>>();
+		assert_eq!(messages, vec!["line 3", "line 4"]);
+
+		// Reading after the last index returns nothing new
+		let last_idx = res.last_idx.expect("missing last idx");
+		assert!(
+			list_messages(&dc.workflow_ctx, actor_id_parsed, Some(last_idx))
+				.await
+				.is_empty()
+		);
+
+		// Long lines are truncated
+		dc.workflow_ctx
+			.op(pegboard::ops::actor::append_logs::Input {
+				actor_id: actor_id_parsed,
+				entries: vec![log_entry(&"a".repeat(10_000))],
+			})
+			.await
+			.expect("failed to append logs");
+		let messages = list_messages(&dc.workflow_ctx, actor_id_parsed, Some(last_idx)).await;
+		assert_eq!(messages.len(), 1);
+		assert_eq!(messages[0].len(), 4096);
+
+		// Read the tail through the api
+		let response = reqwest::Client::new()
+			.get(format!(
+				"http://127.0.0.1:{}/actors/{}/logs?namespace={}&tail=2",
+				dc.guard_port(),
+				actor_id,
+				namespace
+			))
+			.send()
+			.await
+			.expect("failed to send logs request");
+		assert!(response.status().is_success());
+		let body: serde_json::Value = response.json().await.expect("failed to parse response");
+		let entries = body["entries"].as_array().expect("missing entries");
+		assert_eq!(entries.len(), 2);
+		assert_eq!(entries[0]["message"], "line 4");
+
+		// Extra lines in a single append are dropped
+		dc.workflow_ctx
+			.op(pegboard::ops::actor::append_logs::Input {
+				actor_id: actor_id_parsed,
+				entries: (0..1000)
+					.map(|i| log_entry(&format!("batch {i}")))
+					.collect(),
+			})
+			.await
+			.expect("failed to append logs");
+		let res = dc
+			.workflow_ctx
+			.op(pegboard::ops::actor::list_logs::Input {
+				actor_id: actor_id_parsed,
+				after_idx: None,
+				limit: 1,
+			})
+			.await
+			.expect("failed to list logs");
+		assert_eq!(res.entries[0].message, "batch 255");
+
+		// Logs are kept after the actor is destroyed
+		common::destroy_actor(&actor_id, &namespace, dc.guard_port()).await;
+		common::wait_for_actor_propagation(&actor_id, 1).await;
+		let messages = list_messages(&dc.workflow_ctx, actor_id_parsed, Some(0)).await;
+		assert!(messages.iter().any(|x| x == "actor started"));
+
+		runner.shutdown().await;
+	});
+}
+
+#[test]
+fn actor_logs_follow() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, _, runner) = common::setup_test_namespace_with_runner(dc).await;
+
+		let actor_id = common::create_actor(&namespace, dc.guard_port()).await;
+		let actor_id_parsed = actor_id.parse::<Id>().expect("invalid actor id");
+
+		dc.workflow_ctx
+			.op(pegboard::ops::actor::append_logs::Input {
+				actor_id: actor_id_parsed,
+				entries: vec![log_entry("before follow")],
+			})
+			.await
+			.expect("failed to append logs");
+
+		let response = reqwest::Client::new()
+			.get(format!(
+				"http://127.0.0.1:{}/actors/{}/logs?namespace={}&follow=true",
+				dc.guard_port(),
+				actor_id,
+				namespace
+			))
+			.send()
+			.await
+			.expect("failed to send logs request");
+		assert!(response.status().is_success());
+		let mut stream = response.bytes_stream();
+
+		// Lines written while following are streamed
+		dc.workflow_ctx
+			.op(pegboard::ops::actor::append_logs::Input {
+				actor_id: actor_id_parsed,
+				entries: vec![log_entry("after follow")],
+			})
+			.await
+			.expect("failed to append logs");
+
+		let mut body = String::new();
+		while !body.contains("after follow") {
+			let chunk = stream
+				.next()
+				.await
+				.expect("stream ended early")
+				.expect("failed to read stream");
+			body.push_str(&String::from_utf8_lossy(&chunk));
+		}
+		assert!(body.contains("event: log"));
+		assert!(body.contains("before follow"));
+
+		// The stream ends once the actor is destroyed
+		common::destroy_actor(&actor_id, &namespace, dc.guard_port()).await;
+		while let Some(chunk) = stream.next().await {
+			chunk.expect("failed to read stream");
+		}
+
+		runner.shutdown().await;
+	});
+}
@@ -0,0 +1,178 @@
+mod common;
+
+use std::time::Duration;
+
+use serde_json::json;
+
+async fn migrate_actor(
+	actor_id: &str,
+	namespace: &str,
+	runner_id: Option<String>,
+	guard_port: u16,
+) -> reqwest::Response {
+	reqwest::Client::new()
+		.post(format!(
+			"http://127.0.0.1:{guard_port}/actors/{actor_id}/migrate?namespace={namespace}"
+		))
+		.json(&json!({ "runner_id": runner_id }))
+		.send()
+		.await
+		.expect("failed to send actor migrate request")
+}
+
+async fn wait_for_actor_in_runner(runner: &common::runner::TestRunner, actor_id: &str) {
+	tokio::time::timeout(Duration::from_secs(10), async {
+		while !runner.has_actor(actor_id).await {
+			tokio::time::sleep(Duration::from_millis(100)).await;
+		}
+	})
+	.await
+	.expect("timed out waiting for actor to start on runner");
+}
+
+#[test]
+fn migrate_to_target_runner() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, _) = common::setup_test_namespace(dc.guard_port()).await;
+
+		let runner_a = common::setup_runner(dc, &namespace, "runner-a", 1, 20).await;
+		let runner_b = common::setup_runner(dc, &namespace, "runner-b", 1, 20).await;
+
+		let actor_id = common::create_actor(&namespace, dc.guard_port()).await;
+		common::wait_for_actor_propagation(&actor_id, 1).await;
+
+		let (from, to) = if runner_a.has_actor(&actor_id).await {
+			(&runner_a, &runner_b)
+		} else {
+			(&runner_b, &runner_a)
+		};
+
+		let response = migrate_actor(
+			&actor_id,
+			&namespace,
+			Some(to.runner_id.to_string()),
+			dc.guard_port(),
+		)
+		.await;
+		common::assert_success_response(&response);
+
+		wait_for_actor_in_runner(to, &actor_id).await;
+		assert!(!from.has_actor(&actor_id).await);
+		common::assert_actor_in_runner(dc, &actor_id, &to.runner_id.to_string()).await;
+
+		runner_a.shutdown().await;
+		runner_b.shutdown().await;
+	});
+}
+
+#[test]
+fn migrate_pending_excludes_old_runner() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, _) = common::setup_test_namespace(dc.guard_port()).await;
+
+		let runner_a = common::setup_runner(dc, &namespace, "runner-a", 1, 1).await;
+		let actor_id = common::create_actor(&namespace, dc.guard_port()).await;
+		wait_for_actor_in_runner(&runner_a, &actor_id).await;
+
+		let runner_b = common::setup_runner(dc, &namespace, "runner-b", 1, 1).await;
+		let blocking_actor_id = common::create_actor(&namespace, dc.guard_port()).await;
+		wait_for_actor_in_runner(&runner_b, &blocking_actor_id).await;
+
+		// Every other runner is full, so the actor waits in the queue. The slot it frees on its old
+		// runner must not be given back to it.
+		let response = migrate_actor(&actor_id, &namespace, None, dc.guard_port()).await;
+		common::assert_success_response(&response);
+		tokio::time::sleep(Duration::from_secs(2)).await;
+		assert!(!runner_a.has_actor(&actor_id).await);
+
+		common::destroy_actor(&blocking_actor_id, &namespace, dc.guard_port()).await;
+		wait_for_actor_in_runner(&runner_b, &actor_id).await;
+		assert!(!runner_a.has_actor(&actor_id).await);
+
+		runner_a.shutdown().await;
+		runner_b.shutdown().await;
+	});
+}
+
+#[test]
+fn migrate_invalid_target() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, _, runner) = common::setup_test_namespace_with_runner(dc).await;
+		let (other_namespace, _) = common::setup_test_namespace(dc.guard_port()).await;
+		let other_runner = common::setup_runner(dc, &other_namespace, "other-runner", 1, 20).await;
+
+		let actor_id = common::create_actor(&namespace, dc.guard_port()).await;
+		wait_for_actor_in_runner(&runner, &actor_id).await;
+
+		let response = migrate_actor(
+			&actor_id,
+			&namespace,
+			Some(other_runner.runner_id.to_string()),
+			dc.guard_port(),
+		)
+		.await;
+		common::assert_error_response(response, "invalid_migration_target").await;
+
+		// The actor's current runner is not a valid target
+		let response = migrate_actor(
+			&actor_id,
+			&namespace,
+			Some(runner.runner_id.to_string()),
+			dc.guard_port(),
+		)
+		.await;
+		common::assert_error_response(response, "invalid_migration_target").await;
+
+		runner.shutdown().await;
+		other_runner.shutdown().await;
+	});
+}
+
+#[test]
+fn migrate_to_full_target_ignored() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, _) = common::setup_test_namespace(dc.guard_port()).await;
+
+		let runner_a = common::setup_runner(dc, &namespace, "runner-a", 1, 1).await;
+		let actor_id = common::create_actor(&namespace, dc.guard_port()).await;
+		wait_for_actor_in_runner(&runner_a, &actor_id).await;
+
+		let runner_b = common::setup_runner(dc, &namespace, "runner-b", 1, 1).await;
+		let blocking_actor_id = common::create_actor(&namespace, dc.guard_port()).await;
+		wait_for_actor_in_runner(&runner_b, &blocking_actor_id).await;
+
+		// The target has no free slots, so the actor keeps running on its current runner
+		let response = migrate_actor(
+			&actor_id,
+			&namespace,
+			Some(runner_b.runner_id.to_string()),
+			dc.guard_port(),
+		)
+		.await;
+		common::assert_success_response(&response);
+		tokio::time::sleep(Duration::from_secs(2)).await;
+		assert!(runner_a.has_actor(&actor_id).await);
+		common::assert_actor_in_runner(dc, &actor_id, &runner_a.runner_id.to_string()).await;
+
+		runner_a.shutdown().await;
+		runner_b.shutdown().await;
+	});
+}
+
+#[test]
+fn migrate_pending_actor_fails() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, _) = common::setup_test_namespace(dc.guard_port()).await;
+
+		// No runners are connected so the actor stays pending
+		let actor_id = common::create_actor(&namespace, dc.guard_port()).await;
+
+		let response = migrate_actor(&actor_id, &namespace, None, dc.guard_port()).await;
+		common::assert_error_response(response, "not_running").await;
+	});
+}
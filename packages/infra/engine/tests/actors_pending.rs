@@ -0,0 +1,202 @@
+mod common;
+
+use std::time::Duration;
+
+use serde_json::json;
+
+async fn actor_queue(actor_id: &str, namespace: &str, guard_port: u16) -> serde_json::Value {
+	let response = reqwest::Client::new()
+		.get(format!(
+			"http://127.0.0.1:{guard_port}/actors/{actor_id}/queue?namespace={namespace}"
+		))
+		.send()
+		.await
+		.expect("failed to send actor queue request");
+	common::assert_success_response(&response);
+
+	response.json().await.expect("failed to parse response")
+}
+
+async fn runner_queue(namespace: &str, guard_port: u16) -> serde_json::Value {
+	let response = reqwest::Client::new()
+		.get(format!(
+			"http://127.0.0.1:{guard_port}/runners/queue?namespace={namespace}&runner_name=test-runner"
+		))
+		.send()
+		.await
+		.expect("failed to send runner queue request");
+	common::assert_success_response(&response);
+
+	response.json().await.expect("failed to parse response")
+}
+
+async fn wait_for_destroy(actor_id: &str, namespace: &str, guard_port: u16) {
+	loop {
+		let response = common::get_actor(actor_id, Some(namespace), guard_port).await;
+		common::assert_success_response(&response);
+		let body: serde_json::Value = response.json().await.expect("failed to parse response");
+
+		if !body["actor"]["destroy_ts"].is_null() {
+			break;
+		}
+		tokio::time::sleep(Duration::from_millis(100)).await;
+	}
+}
+
+#[test]
+fn pending_queue_depth_and_position() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, _) = common::setup_test_namespace(dc.guard_port()).await;
+
+		// No runners are connected so every actor stays pending
+		let mut actor_ids = Vec::new();
+		for _ in 0..3 {
+			actor_ids.push(common::create_actor(&namespace, dc.guard_port()).await);
+		}
+
+		for (i, actor_id) in actor_ids.iter().enumerate() {
+			let queue = actor_queue(actor_id, &namespace, dc.guard_port()).await;
+			assert_eq!(queue["position"], i);
+			assert_eq!(queue["depth"], 3);
+		}
+
+		let queue = runner_queue(&namespace, dc.guard_port()).await;
+		assert_eq!(queue["depth"], 3);
+		assert!(!queue["oldest_pending_allocation_ts"].is_null());
+
+		// Destroyed actors leave the queue
+		common::destroy_actor(&actor_ids[0], &namespace, dc.guard_port()).await;
+		loop {
+			if runner_queue(&namespace, dc.guard_port()).await["depth"] == 2 {
+				break;
+			}
+			tokio::time::sleep(Duration::from_millis(100)).await;
+		}
+		let queue = actor_queue(&actor_ids[1], &namespace, dc.guard_port()).await;
+		assert_eq!(queue["position"], 0);
+
+		// Allocated actors leave the queue
+		let runner = common::setup_runner(dc, &namespace, "runner", 1, 20).await;
+		loop {
+			if runner_queue(&namespace, dc.guard_port()).await["depth"] == 0 {
+				break;
+			}
+			tokio::time::sleep(Duration::from_millis(100)).await;
+		}
+		let queue = actor_queue(&actor_ids[1], &namespace, dc.guard_port()).await;
+		assert!(queue["position"].is_null());
+
+		runner.shutdown().await;
+	});
+}
+
+#[test]
+fn pending_timeout() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, _) = common::setup_test_namespace(dc.guard_port()).await;
+
+		let actor_id = common::create_actor_with_options(
+			common::actor_options_with_extra(
+				&namespace,
+				json!({ "max_pending_duration_ms": 1000 }),
+			),
+			dc.guard_port(),
+		)
+		.await;
+
+		wait_for_destroy(&actor_id, &namespace, dc.guard_port()).await;
+		assert_eq!(runner_queue(&namespace, dc.guard_port()).await["depth"], 0);
+	});
+}
+
+#[test]
+fn pending_timeout_runner_config_default() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, _) = common::setup_test_namespace(dc.guard_port()).await;
+
+		common::upsert_runner_config(
+			&namespace,
+			"test-runner",
+			json!({
+				"normal": {},
+				"max_pending_duration_ms": 1000,
+			}),
+			dc.guard_port(),
+		)
+		.await;
+
+		let actor_id = common::create_actor(&namespace, dc.guard_port()).await;
+		wait_for_destroy(&actor_id, &namespace, dc.guard_port()).await;
+
+		// The actor's own value takes precedence over the default
+		let actor_id = common::create_actor_with_options(
+			common::actor_options_with_extra(
+				&namespace,
+				json!({ "max_pending_duration_ms": 60_000 }),
+			),
+			dc.guard_port(),
+		)
+		.await;
+		tokio::time::sleep(Duration::from_secs(3)).await;
+
+		let response = common::get_actor(&actor_id, Some(&namespace), dc.guard_port()).await;
+		let body: serde_json::Value = response.json().await.expect("failed to parse response");
+		assert!(body["actor"]["destroy_ts"].is_null());
+	});
+}
+
+#[test]
+fn pending_timeout_runner_config_invalid() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, _) = common::setup_test_namespace(dc.guard_port()).await;
+
+		let response = common::upsert_runner_config_response(
+			&namespace,
+			"test-runner",
+			json!({
+				"normal": {},
+				"max_pending_duration_ms": 0,
+			}),
+			dc.guard_port(),
+		)
+		.await;
+		common::assert_error_response(response, "invalid").await;
+
+		// Would overflow the pending timeout timestamp
+		let response = common::upsert_runner_config_response(
+			&namespace,
+			"test-runner",
+			json!({
+				"normal": {},
+				"max_pending_duration_ms": i64::MAX,
+			}),
+			dc.guard_port(),
+		)
+		.await;
+		common::assert_error_response(response, "invalid").await;
+	});
+}
+
+#[test]
+fn pending_timeout_invalid() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, _) = common::setup_test_namespace(dc.guard_port()).await;
+
+		for max_pending_duration_ms in [0, i64::MAX] {
+			let response = common::create_actor_response(
+				common::actor_options_with_extra(
+					&namespace,
+					json!({ "max_pending_duration_ms": max_pending_duration_ms }),
+				),
+				dc.guard_port(),
+			)
+			.await;
+			common::assert_error_response(response, "invalid_max_pending_duration").await;
+		}
+	});
+}
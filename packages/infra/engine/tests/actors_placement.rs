@@ -0,0 +1,188 @@
+mod common;
+
+use std::time::Duration;
+
+use gas::prelude::*;
+use serde_json::json;
+use universaldb::utils::IsolationLevel::*;
+
+async fn anti_affinity_count(ctx: &StandaloneCtx, runner_id: Id, group: &str) -> Option<i64> {
+	ctx.udb()
+		.expect("failed to get udb")
+		.run(|tx| {
+			let group = group.to_string();
+
+			async move {
+				let tx = tx.with_subspace(pegboard::keys::subspace());
+				tx.read_opt(
+					&pegboard::keys::runner::AntiAffinityCountKey::new(runner_id, group),
+					Serializable,
+				)
+				.await
+			}
+		})
+		.await
+		.expect("failed to read anti affinity count")
+}
+
+async fn create_placed_actor(
+	namespace: &str,
+	name: &str,
+	placement: serde_json::Value,
+	guard_port: u16,
+) -> String {
+	common::create_actor_with_options(
+		common::CreateActorOptions {
+			name: name.to_string(),
+			..common::actor_options_with_extra(namespace, json!({ "placement": placement }))
+		},
+		guard_port,
+	)
+	.await
+}
+
+#[test]
+fn placement_required_labels() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, _) = common::setup_test_namespace(dc.guard_port()).await;
+
+		let gpu_runner = common::runner::TestRunner::new_with_labels(
+			dc.guard_port(),
+			&namespace,
+			"gpu-runner",
+			1,
+			20,
+			&[("gpu", "true")],
+		)
+		.await;
+		let cpu_runner = common::runner::TestRunner::new_with_labels(
+			dc.guard_port(),
+			&namespace,
+			"cpu-runner",
+			1,
+			20,
+			&[("gpu", "false")],
+		)
+		.await;
+
+		for _ in 0..3 {
+			let actor_id = create_placed_actor(
+				&namespace,
+				"gpu-actor",
+				json!({ "required_labels": { "gpu": "true" } }),
+				dc.guard_port(),
+			)
+			.await;
+			common::wait_for_actor_propagation(&actor_id, 1).await;
+			common::assert_actor_in_runner(dc, &actor_id, &gpu_runner.runner_id.to_string()).await;
+		}
+
+		gpu_runner.shutdown().await;
+		cpu_runner.shutdown().await;
+	});
+}
+
+#[test]
+fn placement_required_labels_unmatched() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, _, runner) = common::setup_test_namespace_with_runner(dc).await;
+
+		let actor_id = create_placed_actor(
+			&namespace,
+			"gpu-actor",
+			json!({ "required_labels": { "gpu": "true" } }),
+			dc.guard_port(),
+		)
+		.await;
+
+		// No runner has the label so the actor stays pending
+		tokio::time::sleep(Duration::from_secs(2)).await;
+		assert!(!runner.has_actor(&actor_id).await);
+
+		// Runners advertising the label after the actor was created pick it up
+		let gpu_runner = common::runner::TestRunner::new_with_labels(
+			dc.guard_port(),
+			&namespace,
+			"gpu-runner",
+			1,
+			20,
+			&[("gpu", "true")],
+		)
+		.await;
+		loop {
+			if gpu_runner.has_actor(&actor_id).await {
+				break;
+			}
+			tokio::time::sleep(Duration::from_millis(100)).await;
+		}
+
+		runner.shutdown().await;
+		gpu_runner.shutdown().await;
+	});
+}
+
+#[test]
+fn placement_anti_affinity() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, _) = common::setup_test_namespace(dc.guard_port()).await;
+
+		let runner_a = common::setup_runner(dc, &namespace, "runner-a", 1, 20).await;
+		let runner_b = common::setup_runner(dc, &namespace, "runner-b", 1, 20).await;
+
+		let mut actor_ids = Vec::new();
+		for _ in 0..2 {
+			let actor_id = create_placed_actor(
+				&namespace,
+				"replica",
+				json!({ "anti_affinity": true }),
+				dc.guard_port(),
+			)
+			.await;
+			common::wait_for_actor_propagation(&actor_id, 1).await;
+			actor_ids.push(actor_id);
+		}
+
+		// Replicas are spread across runners
+		let runners = dc
+			.workflow_ctx
+			.op(pegboard::ops::actor::get_runner::Input {
+				actor_ids: actor_ids
+					.iter()
+					.map(|x| x.parse().expect("invalid actor id"))
+					.collect(),
+			})
+			.await
+			.expect("failed to get actor runners");
+		assert_eq!(runners.actors.len(), 2);
+		assert_ne!(runners.actors[0].runner_id, runners.actors[1].runner_id);
+
+		for runner_id in [runner_a.runner_id, runner_b.runner_id] {
+			assert_eq!(
+				anti_affinity_count(&dc.workflow_ctx, runner_id, "replica").await,
+				Some(1)
+			);
+		}
+
+		// Counts are removed once the runner has no actors of the group left
+		for actor_id in &actor_ids {
+			common::destroy_actor(actor_id, &namespace, dc.guard_port()).await;
+		}
+		for runner_id in [runner_a.runner_id, runner_b.runner_id] {
+			loop {
+				if anti_affinity_count(&dc.workflow_ctx, runner_id, "replica")
+					.await
+					.is_none()
+				{
+					break;
+				}
+				tokio::time::sleep(Duration::from_millis(100)).await;
+			}
+		}
+
+		runner_a.shutdown().await;
+		runner_b.shutdown().await;
+	});
+}
@@ -0,0 +1,201 @@
+mod common;
+
+use std::time::Duration;
+
+use serde_json::json;
+
+async fn create_actor_with_priority(
+	namespace: &str,
+	priority: &str,
+	crash_policy: &str,
+	guard_port: u16,
+) -> String {
+	common::create_actor_with_options(
+		common::actor_options_with_extra(
+			namespace,
+			json!({
+				"priority": priority,
+				"crash_policy": crash_policy,
+			}),
+		),
+		guard_port,
+	)
+	.await
+}
+
+async fn queue_position(actor_id: &str, namespace: &str, guard_port: u16) -> serde_json::Value {
+	let response = reqwest::Client::new()
+		.get(format!(
+			"http://127.0.0.1:{guard_port}/actors/{actor_id}/queue?namespace={namespace}"
+		))
+		.send()
+		.await
+		.expect("failed to send actor queue request");
+	common::assert_success_response(&response);
+
+	let body: serde_json::Value = response.json().await.expect("failed to parse response");
+	body["position"].clone()
+}
+
+async fn actor(actor_id: &str, namespace: &str, guard_port: u16) -> serde_json::Value {
+	let response = common::get_actor(actor_id, Some(namespace), guard_port).await;
+	common::assert_success_response(&response);
+
+	let body: serde_json::Value = response.json().await.expect("failed to parse response");
+	body["actor"].clone()
+}
+
+#[test]
+fn priority_allocation_order() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, _) = common::setup_test_namespace(dc.guard_port()).await;
+
+		// No runners are connected so every actor stays pending
+		let low_id =
+			create_actor_with_priority(&namespace, "low", "destroy", dc.guard_port()).await;
+		let normal_id =
+			create_actor_with_priority(&namespace, "normal", "destroy", dc.guard_port()).await;
+		let high_id =
+			create_actor_with_priority(&namespace, "high", "destroy", dc.guard_port()).await;
+
+		// Higher priorities are ahead in the queue regardless of creation order
+		assert_eq!(
+			queue_position(&high_id, &namespace, dc.guard_port()).await,
+			0
+		);
+		assert_eq!(
+			queue_position(&normal_id, &namespace, dc.guard_port()).await,
+			1
+		);
+		assert_eq!(
+			queue_position(&low_id, &namespace, dc.guard_port()).await,
+			2
+		);
+
+		// Only the high priority actor fits on the runner
+		let runner = common::setup_runner(dc, &namespace, "runner", 1, 1).await;
+		common::wait_for_actor_propagation(&high_id, 1).await;
+		common::assert_actor_in_runner(dc, &high_id, &runner.runner_id.to_string()).await;
+
+		assert_eq!(
+			queue_position(&normal_id, &namespace, dc.guard_port()).await,
+			0
+		);
+		assert_eq!(
+			queue_position(&low_id, &namespace, dc.guard_port()).await,
+			1
+		);
+
+		runner.shutdown().await;
+	});
+}
+
+#[test]
+fn priority_preempts_sleepable_low_priority() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, _) = common::setup_test_namespace(dc.guard_port()).await;
+
+		common::upsert_runner_config(
+			&namespace,
+			"test-runner",
+			json!({
+				"normal": {},
+				"preempt_low_priority": true,
+			}),
+			dc.guard_port(),
+		)
+		.await;
+
+		let runner = common::setup_runner(dc, &namespace, "runner", 1, 1).await;
+
+		let low_id = create_actor_with_priority(&namespace, "low", "sleep", dc.guard_port()).await;
+		common::wait_for_actor_propagation(&low_id, 1).await;
+		common::assert_actor_in_runner(dc, &low_id, &runner.runner_id.to_string()).await;
+
+		// The runner is full, so the low priority actor is put to sleep to make room
+		let high_id =
+			create_actor_with_priority(&namespace, "high", "destroy", dc.guard_port()).await;
+		loop {
+			if !actor(&low_id, &namespace, dc.guard_port()).await["sleep_ts"].is_null() {
+				break;
+			}
+			tokio::time::sleep(Duration::from_millis(100)).await;
+		}
+
+		common::wait_for_actor_propagation(&high_id, 1).await;
+		common::assert_actor_in_runner(dc, &high_id, &runner.runner_id.to_string()).await;
+
+		runner.shutdown().await;
+	});
+}
+
+#[test]
+fn priority_does_not_preempt_by_default() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, _) = common::setup_test_namespace(dc.guard_port()).await;
+
+		let runner = common::setup_runner(dc, &namespace, "runner", 1, 1).await;
+
+		let low_id = create_actor_with_priority(&namespace, "low", "sleep", dc.guard_port()).await;
+		common::wait_for_actor_propagation(&low_id, 1).await;
+
+		// Preemption is opt-in, so the high priority actor waits for capacity instead
+		let high_id =
+			create_actor_with_priority(&namespace, "high", "destroy", dc.guard_port()).await;
+		tokio::time::sleep(Duration::from_secs(3)).await;
+
+		assert!(actor(&low_id, &namespace, dc.guard_port()).await["sleep_ts"].is_null());
+		assert_eq!(
+			queue_position(&high_id, &namespace, dc.guard_port()).await,
+			0
+		);
+
+		runner.shutdown().await;
+	});
+}
+
+#[test]
+fn priority_waiting_for_capacity_blocks_lower_priority() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, _) = common::setup_test_namespace(dc.guard_port()).await;
+
+		let runner = common::setup_runner(dc, &namespace, "runner", 1, 2).await;
+
+		let mut normal_ids = Vec::new();
+		for _ in 0..2 {
+			let actor_id =
+				create_actor_with_priority(&namespace, "normal", "destroy", dc.guard_port()).await;
+			common::wait_for_actor_propagation(&actor_id, 1).await;
+			normal_ids.push(actor_id);
+		}
+
+		// Needs the whole runner
+		let high_id = common::create_actor_with_options(
+			common::actor_options_with_extra(&namespace, json!({ "priority": "high", "slots": 2 })),
+			dc.guard_port(),
+		)
+		.await;
+		let low_id =
+			create_actor_with_priority(&namespace, "low", "destroy", dc.guard_port()).await;
+
+		// The freed slot is kept for the high priority actor instead of going to the low priority one
+		common::destroy_actor(&normal_ids[0], &namespace, dc.guard_port()).await;
+		tokio::time::sleep(Duration::from_secs(2)).await;
+		assert!(!runner.has_actor(&low_id).await);
+		assert!(!runner.has_actor(&high_id).await);
+
+		common::destroy_actor(&normal_ids[1], &namespace, dc.guard_port()).await;
+		common::wait_for_actor_propagation(&high_id, 1).await;
+		common::assert_actor_in_runner(dc, &high_id, &runner.runner_id.to_string()).await;
+		assert_eq!(
+			queue_position(&low_id, &namespace, dc.guard_port()).await,
+			0
+		);
+
+		runner.shutdown().await;
+	});
+}
@@ -0,0 +1,240 @@
+mod common;
+
+use std::time::Duration;
+
+use serde_json::json;
+
+async fn create_crash_actor(
+	namespace: &str,
+	restart_policy: serde_json::Value,
+	guard_port: u16,
+) -> String {
+	common::create_actor_with_options(
+		common::CreateActorOptions {
+			name: common::runner::CRASH_ACTOR_NAME.to_string(),
+			durable: true,
+			..common::actor_options_with_extra(
+				namespace,
+				json!({ "restart_policy": restart_policy }),
+			)
+		},
+		guard_port,
+	)
+	.await
+}
+
+async fn wait_for_actor(
+	actor_id: &str,
+	namespace: &str,
+	guard_port: u16,
+	f: impl Fn(&serde_json::Value) -> bool,
+) -> serde_json::Value {
+	tokio::time::timeout(Duration::from_secs(30), async {
+		loop {
+			let response = common::get_actor(actor_id, Some(namespace), guard_port).await;
+			common::assert_success_response(&response);
+			let body: serde_json::Value = response.json().await.expect("failed to parse response");
+
+			if f(&body["actor"]) {
+				break body["actor"].clone();
+			}
+			tokio::time::sleep(Duration::from_millis(100)).await;
+		}
+	})
+	.await
+	.expect("timed out waiting for actor")
+}
+
+#[test]
+fn restart_limit_destroys_actor() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, _, runner) = common::setup_test_namespace_with_runner(dc).await;
+
+		let actor_id = create_crash_actor(
+			&namespace,
+			json!({
+				"max_restarts": 2,
+				"backoff_base_ms": 100,
+				"limit_action": "destroy",
+			}),
+			dc.guard_port(),
+		)
+		.await;
+
+		wait_for_actor(&actor_id, &namespace, dc.guard_port(), |actor| {
+			!actor["destroy_ts"].is_null()
+		})
+		.await;
+
+		// The first start and 2 restarts
+		assert_eq!(runner.start_attempts(&actor_id).len(), 3);
+
+		runner.shutdown().await;
+	});
+}
+
+#[test]
+fn restart_limit_sleeps_actor() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, _, runner) = common::setup_test_namespace_with_runner(dc).await;
+
+		let actor_id = create_crash_actor(
+			&namespace,
+			json!({
+				"max_restarts": 2,
+				"backoff_base_ms": 100,
+				"limit_action": "sleep",
+			}),
+			dc.guard_port(),
+		)
+		.await;
+
+		let actor = wait_for_actor(&actor_id, &namespace, dc.guard_port(), |actor| {
+			!actor["sleep_ts"].is_null()
+		})
+		.await;
+		assert!(actor["destroy_ts"].is_null());
+
+		// The actor is not restarted again while sleeping
+		tokio::time::sleep(Duration::from_secs(2)).await;
+		assert_eq!(runner.start_attempts(&actor_id).len(), 3);
+
+		runner.shutdown().await;
+	});
+}
+
+#[test]
+fn restart_backoff_is_capped() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, _, runner) = common::setup_test_namespace_with_runner(dc).await;
+
+		// Without the cap, the delays would be 500ms, 1s, 2s, 4s and 8s
+		let actor_id = create_crash_actor(
+			&namespace,
+			json!({
+				"backoff_base_ms": 500,
+				"backoff_max_ms": 1000,
+			}),
+			dc.guard_port(),
+		)
+		.await;
+
+		let attempts = tokio::time::timeout(Duration::from_secs(20), async {
+			loop {
+				let attempts = runner.start_attempts(&actor_id);
+				if attempts.len() >= 6 {
+					break attempts;
+				}
+				tokio::time::sleep(Duration::from_millis(100)).await;
+			}
+		})
+		.await
+		.expect("timed out waiting for restarts");
+
+		// Allows for backoff jitter and allocation time
+		for gap in attempts.windows(2).map(|x| x[1] - x[0]) {
+			assert!(gap < 2500, "restart delay of {gap}ms exceeds backoff cap");
+		}
+
+		common::destroy_actor(&actor_id, &namespace, dc.guard_port()).await;
+		runner.shutdown().await;
+	});
+}
+
+#[test]
+fn restart_backoff_reaches_max() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, _, runner) = common::setup_test_namespace_with_runner(dc).await;
+
+		// The max is not a power of two multiple of the base, the delays are 1s, 2s, 3s and 3s
+		let actor_id = create_crash_actor(
+			&namespace,
+			json!({
+				"backoff_base_ms": 1000,
+				"backoff_max_ms": 3000,
+			}),
+			dc.guard_port(),
+		)
+		.await;
+
+		let attempts = tokio::time::timeout(Duration::from_secs(30), async {
+			loop {
+				let attempts = runner.start_attempts(&actor_id);
+				if attempts.len() >= 6 {
+					break attempts;
+				}
+				tokio::time::sleep(Duration::from_millis(100)).await;
+			}
+		})
+		.await
+		.expect("timed out waiting for restarts");
+
+		let gaps = attempts.windows(2).map(|x| x[1] - x[0]).collect::<Vec<_>>();
+		let last_gap = gaps[4];
+		assert!(
+			last_gap >= 3000,
+			"restart delay of {last_gap}ms is below backoff max"
+		);
+		for gap in gaps {
+			assert!(gap < 4000, "restart delay of {gap}ms exceeds backoff max");
+		}
+
+		common::destroy_actor(&actor_id, &namespace, dc.guard_port()).await;
+		runner.shutdown().await;
+	});
+}
+
+#[test]
+fn restart_policy_invalid() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, _) = common::setup_test_namespace(dc.guard_port()).await;
+
+		for restart_policy in [
+			json!({ "backoff_base_ms": 0 }),
+			json!({ "backoff_base_ms": 1000, "backoff_max_ms": 500 }),
+			// Would overflow the backoff
+			json!({ "backoff_base_ms": u64::MAX }),
+			json!({ "backoff_max_ms": u64::MAX }),
+		] {
+			let response = common::create_actor_response(
+				common::actor_options_with_extra(
+					&namespace,
+					json!({ "restart_policy": restart_policy }),
+				),
+				dc.guard_port(),
+			)
+			.await;
+			common::assert_error_response(response, "invalid_restart_policy").await;
+		}
+	});
+}
+
+#[test]
+fn restart_without_limit_keeps_restarting() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, _, runner) = common::setup_test_namespace_with_runner(dc).await;
+
+		let actor_id = create_crash_actor(
+			&namespace,
+			json!({ "backoff_base_ms": 100, "backoff_max_ms": 100 }),
+			dc.guard_port(),
+		)
+		.await;
+
+		tokio::time::sleep(Duration::from_secs(3)).await;
+		assert!(runner.start_attempts(&actor_id).len() > 3);
+
+		let actor = wait_for_actor(&actor_id, &namespace, dc.guard_port(), |_| true).await;
+		assert!(actor["destroy_ts"].is_null());
+		assert!(actor["sleep_ts"].is_null());
+
+		common::destroy_actor(&actor_id, &namespace, dc.guard_port()).await;
+		runner.shutdown().await;
+	});
+}
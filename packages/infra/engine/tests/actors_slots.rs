@@ -0,0 +1,147 @@
+mod common;
+
+use std::time::Duration;
+
+use serde_json::json;
+
+#[test]
+fn slots_above_runner_capacity_rejected() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, _) = common::setup_test_namespace(dc.guard_port()).await;
+		let runner = common::setup_runner(dc, &namespace, "small-runner", 1, 4).await;
+
+		let response = common::create_actor_response(
+			common::actor_options_with_extra(&namespace, json!({ "slots": 5 })),
+			dc.guard_port(),
+		)
+		.await;
+		common::assert_error_response(response, "slots_exceed_runner_capacity").await;
+
+		let response = common::create_actor_response(
+			common::actor_options_with_extra(&namespace, json!({ "slots": 0 })),
+			dc.guard_port(),
+		)
+		.await;
+		common::assert_error_response(response, "invalid_slots").await;
+
+		// Exactly the runner's capacity fits
+		let actor_id = common::create_actor_with_options(
+			common::actor_options_with_extra(&namespace, json!({ "slots": 4 })),
+			dc.guard_port(),
+		)
+		.await;
+		common::wait_for_actor_propagation(&actor_id, 1).await;
+		common::assert_actor_in_runner(dc, &actor_id, &runner.runner_id.to_string()).await;
+
+		runner.shutdown().await;
+	});
+}
+
+#[test]
+fn slots_weighted_allocation() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, _) = common::setup_test_namespace(dc.guard_port()).await;
+		let runner = common::setup_runner(dc, &namespace, "weighted-runner", 1, 4).await;
+
+		let heavy_actor_id = common::create_actor_with_options(
+			common::actor_options_with_extra(&namespace, json!({ "slots": 3 })),
+			dc.guard_port(),
+		)
+		.await;
+		common::wait_for_actor_propagation(&heavy_actor_id, 1).await;
+		assert!(runner.has_actor(&heavy_actor_id).await);
+
+		// Only 1 slot is left so this actor stays pending
+		let pending_actor_id = common::create_actor_with_options(
+			common::actor_options_with_extra(&namespace, json!({ "slots": 2 })),
+			dc.guard_port(),
+		)
+		.await;
+		tokio::time::sleep(Duration::from_secs(2)).await;
+		assert!(!runner.has_actor(&pending_actor_id).await);
+
+		// Freeing the heavy actor's slots allocates the pending actor
+		common::destroy_actor(&heavy_actor_id, &namespace, dc.guard_port()).await;
+		loop {
+			if runner.has_actor(&pending_actor_id).await {
+				break;
+			}
+			tokio::time::sleep(Duration::from_millis(100)).await;
+		}
+
+		runner.shutdown().await;
+	});
+}
+
+#[test]
+fn slots_heavy_actor_not_starved_by_light_actors() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, _) = common::setup_test_namespace(dc.guard_port()).await;
+		let runner = common::setup_runner(dc, &namespace, "weighted-runner", 1, 4).await;
+
+		let mut running_ids = Vec::new();
+		for _ in 0..4 {
+			let actor_id = common::create_actor_with_options(
+				common::actor_options_with_extra(&namespace, json!({ "slots": 1 })),
+				dc.guard_port(),
+			)
+			.await;
+			common::wait_for_actor_propagation(&actor_id, 1).await;
+			running_ids.push(actor_id);
+		}
+
+		// Queued ahead of the light actors
+		let heavy_actor_id = common::create_actor_with_options(
+			common::actor_options_with_extra(&namespace, json!({ "slots": 4 })),
+			dc.guard_port(),
+		)
+		.await;
+		let mut light_actor_ids = Vec::new();
+		for _ in 0..3 {
+			light_actor_ids.push(
+				common::create_actor_with_options(
+					common::actor_options_with_extra(&namespace, json!({ "slots": 1 })),
+					dc.guard_port(),
+				)
+				.await,
+			);
+		}
+
+		// Slots free up one at a time and are kept for the heavy actor
+		for actor_id in &running_ids[..3] {
+			common::destroy_actor(actor_id, &namespace, dc.guard_port()).await;
+			tokio::time::sleep(Duration::from_secs(1)).await;
+
+			for light_actor_id in &light_actor_ids {
+				assert!(!runner.has_actor(light_actor_id).await);
+			}
+		}
+
+		common::destroy_actor(&running_ids[3], &namespace, dc.guard_port()).await;
+		loop {
+			if runner.has_actor(&heavy_actor_id).await {
+				break;
+			}
+			tokio::time::sleep(Duration::from_millis(100)).await;
+		}
+		for light_actor_id in &light_actor_ids {
+			assert!(!runner.has_actor(light_actor_id).await);
+		}
+
+		// The light actors are allocated once the heavy actor is gone
+		common::destroy_actor(&heavy_actor_id, &namespace, dc.guard_port()).await;
+		for light_actor_id in &light_actor_ids {
+			loop {
+				if runner.has_actor(light_actor_id).await {
+					break;
+				}
+				tokio::time::sleep(Duration::from_millis(100)).await;
+			}
+		}
+
+		runner.shutdown().await;
+	});
+}
@@ -0,0 +1,213 @@
+mod common;
+
+use std::time::Duration;
+
+use serde_json::json;
+
+/// Waits for the actor to be destroyed and returns it.
+async fn wait_for_destroy(actor_id: &str, namespace: &str, guard_port: u16) -> serde_json::Value {
+	tokio::time::timeout(Duration::from_secs(15), async {
+		loop {
+			let response = common::get_actor(actor_id, Some(namespace), guard_port).await;
+			common::assert_success_response(&response);
+			let body: serde_json::Value = response.json().await.expect("failed to parse response");
+
+			if !body["actor"]["destroy_ts"].is_null() {
+				break body["actor"].clone();
+			}
+			tokio::time::sleep(Duration::from_millis(100)).await;
+		}
+	})
+	.await
+	.expect("timed out waiting for actor to be destroyed")
+}
+
+#[test]
+fn ttl_destroys_running_actor() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, _, runner) = common::setup_test_namespace_with_runner(dc).await;
+
+		let actor_id = common::create_actor_with_options(
+			common::actor_options_with_extra(&namespace, json!({ "ttl_ms": 2000 })),
+			dc.guard_port(),
+		)
+		.await;
+		common::wait_for_actor_propagation(&actor_id, 1).await;
+		assert!(runner.has_actor(&actor_id).await);
+
+		let actor = wait_for_destroy(&actor_id, &namespace, dc.guard_port()).await;
+		let lifetime = actor["destroy_ts"].as_i64().unwrap() - actor["create_ts"].as_i64().unwrap();
+		assert!(lifetime >= 2000, "actor destroyed after {lifetime}ms");
+
+		runner.shutdown().await;
+	});
+}
+
+#[test]
+fn ttl_destroys_pending_actor() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, _) = common::setup_test_namespace(dc.guard_port()).await;
+
+		// No runners are connected so the actor expires while waiting for allocation
+		let actor_id = common::create_actor_with_options(
+			common::actor_options_with_extra(&namespace, json!({ "ttl_ms": 1000 })),
+			dc.guard_port(),
+		)
+		.await;
+
+		wait_for_destroy(&actor_id, &namespace, dc.guard_port()).await;
+	});
+}
+
+#[test]
+fn ttl_destroys_actor_during_restart_backoff() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, _, runner) = common::setup_test_namespace_with_runner(dc).await;
+
+		// The second restart would only happen after 60s
+		let actor_id = common::create_actor_with_options(
+			common::CreateActorOptions {
+				name: common::runner::CRASH_ACTOR_NAME.to_string(),
+				durable: true,
+				..common::actor_options_with_extra(
+					&namespace,
+					json!({
+						"ttl_ms": 3000,
+						"restart_policy": { "backoff_base_ms": 60_000 },
+					}),
+				)
+			},
+			dc.guard_port(),
+		)
+		.await;
+
+		let actor = wait_for_destroy(&actor_id, &namespace, dc.guard_port()).await;
+		let lifetime = actor["destroy_ts"].as_i64().unwrap() - actor["create_ts"].as_i64().unwrap();
+		assert!(lifetime < 10_000, "actor destroyed after {lifetime}ms");
+
+		runner.shutdown().await;
+	});
+}
+
+#[test]
+fn destroy_at_ts_destroys_actor() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, _, runner) = common::setup_test_namespace_with_runner(dc).await;
+
+		let destroy_at_ts = rivet_util::timestamp::now() + 2000;
+		let actor_id = common::create_actor_with_options(
+			common::actor_options_with_extra(
+				&namespace,
+				json!({
+					"destroy_at_ts": destroy_at_ts,
+					// The earlier of the two is used
+					"ttl_ms": 60_000,
+				}),
+			),
+			dc.guard_port(),
+		)
+		.await;
+
+		let actor = wait_for_destroy(&actor_id, &namespace, dc.guard_port()).await;
+		assert!(actor["destroy_ts"].as_i64().unwrap() >= destroy_at_ts);
+
+		runner.shutdown().await;
+	});
+}
+
+#[test]
+fn invalid_ttl_rejected() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, _) = common::setup_test_namespace(dc.guard_port()).await;
+
+		let response = common::create_actor_response(
+			common::actor_options_with_extra(
+				&namespace,
+				json!({ "destroy_at_ts": rivet_util::timestamp::now() - 1000 }),
+			),
+			dc.guard_port(),
+		)
+		.await;
+		common::assert_error_response(response, "invalid_ttl").await;
+
+		let response = common::create_actor_response(
+			common::actor_options_with_extra(&namespace, json!({ "ttl_ms": 0 })),
+			dc.guard_port(),
+		)
+		.await;
+		common::assert_error_response(response, "invalid_ttl").await;
+
+		// Would overflow the expiry timestamp
+		let response = common::create_actor_response(
+			common::actor_options_with_extra(&namespace, json!({ "ttl_ms": i64::MAX })),
+			dc.guard_port(),
+		)
+		.await;
+		common::assert_error_response(response, "invalid_ttl").await;
+
+		let response = common::create_actor_response(
+			common::actor_options_with_extra(&namespace, json!({ "idle_timeout_ms": i64::MAX })),
+			dc.guard_port(),
+		)
+		.await;
+		common::assert_error_response(response, "invalid_ttl").await;
+	});
+}
+
+#[test]
+fn idle_timeout_destroys_sleeping_actor() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, _, runner) = common::setup_test_namespace_with_runner(dc).await;
+
+		// Crashing with the sleep crash policy puts the actor to sleep
+		let actor_id = common::create_actor_with_options(
+			common::CreateActorOptions {
+				name: common::runner::CRASH_ACTOR_NAME.to_string(),
+				..common::actor_options_with_extra(
+					&namespace,
+					json!({
+						"crash_policy": "sleep",
+						"idle_timeout_ms": 1000,
+					}),
+				)
+			},
+			dc.guard_port(),
+		)
+		.await;
+
+		let actor = wait_for_destroy(&actor_id, &namespace, dc.guard_port()).await;
+		assert!(!actor["sleep_ts"].is_null());
+		let idle = actor["destroy_ts"].as_i64().unwrap() - actor["sleep_ts"].as_i64().unwrap();
+		assert!(idle >= 1000, "actor destroyed after sleeping for {idle}ms");
+
+		runner.shutdown().await;
+	});
+}
+
+#[test]
+fn idle_timeout_does_not_destroy_running_actor() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, _, runner) = common::setup_test_namespace_with_runner(dc).await;
+
+		let actor_id = common::create_actor_with_options(
+			common::actor_options_with_extra(&namespace, json!({ "idle_timeout_ms": 1000 })),
+			dc.guard_port(),
+		)
+		.await;
+		tokio::time::sleep(Duration::from_secs(3)).await;
+
+		let response = common::get_actor(&actor_id, Some(&namespace), dc.guard_port()).await;
+		let body: serde_json::Value = response.json().await.expect("failed to parse response");
+		assert!(body["actor"]["destroy_ts"].is_null());
+		assert!(runner.has_actor(&actor_id).await);
+
+		runner.shutdown().await;
+	});
+}
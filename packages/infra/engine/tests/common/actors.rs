@@ -9,6 +9,8 @@ pub struct CreateActorOptions {
 	pub runner_name_selector: Option<String>,
 	pub durable: bool,
 	pub datacenter: Option<String>,
+	/// Additional fields merged into the request body, e.g. `placement`.
+	pub extra: Option<serde_json::Value>,
 }
 
 impl Default for CreateActorOptions {
@@ -24,11 +26,46 @@ impl Default for CreateActorOptions {
 			runner_name_selector: Some("test-runner".to_string()),
 			durable: false,
 			datacenter: None,
+			extra: None,
 		}
 	}
 }
 
+/// Default options in the given namespace with additional fields merged into the request body.
+pub fn actor_options_with_extra(namespace: &str, extra: serde_json::Value) -> CreateActorOptions {
+	CreateActorOptions {
+		namespace: namespace.to_string(),
+		extra: Some(extra),
+		..Default::default()
+	}
+}
+
 pub async fn create_actor_with_options(options: CreateActorOptions, guard_port: u16) -> String {
+	let response = create_actor_response(options, guard_port).await;
+
+	if !response.status().is_success() {
+		let text = response.text().await.expect("Failed to read response text");
+		panic!("Failed to create actor: {}", text);
+	}
+
+	let body: serde_json::Value = response
+		.json()
+		.await
+		.expect("Failed to parse JSON response");
+	let actor_id = body["actor"]["actor_id"]
+		.as_str()
+		.expect("Missing actor_id in response");
+
+	tracing::info!(?actor_id, "actor created");
+
+	actor_id.to_string()
+}
+
+/// Sends an actor creation request without checking the response.
+pub async fn create_actor_response(
+	options: CreateActorOptions,
+	guard_port: u16,
+) -> reqwest::Response {
 	tracing::info!(?options.namespace, ?options.name, "creating actor");
 
 	let mut body = json!({
@@ -47,6 +84,11 @@ pub async fn create_actor_with_options(options: CreateActorOptions, guard_port:
 	if let Some(runner_name_selector) = options.runner_name_selector {
 		body["runner_name_selector"] = json!(runner_name_selector);
 	}
+	if let Some(serde_json::Value::Object(extra)) = options.extra {
+		for (k, v) in extra {
+			body[k] = v;
+		}
+	}
 	let mut url = format!(
 		"http://127.0.0.1:{}/actors?namespace={}",
 		guard_port, options.namespace
@@ -56,30 +98,12 @@ pub async fn create_actor_with_options(options: CreateActorOptions, guard_port:
 		url.push_str(&format!("&datacenter={}", datacenter));
 	}
 
-	let client = reqwest::Client::new();
-	let response = client
+	reqwest::Client::new()
 		.post(url)
 		.json(&body)
 		.send()
 		.await
-		.expect("Failed to send actor creation request");
-
-	if !response.status().is_success() {
-		let text = response.text().await.expect("Failed to read response text");
-		panic!("Failed to create actor: {}", text);
-	}
-
-	let body: serde_json::Value = response
-		.json()
-		.await
-		.expect("Failed to parse JSON response");
-	let actor_id = body["actor"]["actor_id"]
-		.as_str()
-		.expect("Missing actor_id in response");
-
-	tracing::info!(?actor_id, "actor created");
-
-	actor_id.to_string()
+		.expect("Failed to send actor creation request")
 }
 
 pub async fn create_actor(namespace_name: &str, guard_port: u16) -> String {
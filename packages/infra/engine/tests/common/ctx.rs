@@ -78,22 +78,38 @@ impl TestCtx {
 			let pools = pools.clone();
 			async move {
 				let services = vec![
-					Service::new("api-peer", ServiceKind::ApiPeer, |config, pools| {
-						Box::pin(rivet_api_peer::start(config, pools))
-					}),
-					Service::new("guard", ServiceKind::Standalone, |config, pools| {
-						Box::pin(rivet_guard::start(config, pools))
-					}),
-					Service::new(
+					Service::new_graceful(
+						"api-peer",
+						ServiceKind::ApiPeer,
+						|config, pools, shutdown_rx| {
+							Box::pin(rivet_api_peer::start(config, pools, shutdown_rx))
+						},
+					)
+					.with_shutdown_order(1),
+					Service::new_graceful(
+						"guard",
+						ServiceKind::Standalone,
+						|config, pools, shutdown_rx| {
+							Box::pin(rivet_guard::start(config, pools, shutdown_rx))
+						},
+					)
+					.with_shutdown_order(0),
+					Service::new_graceful(
 						"pegboard-runner-ws",
 						ServiceKind::ApiPublic,
-						|config, pools| Box::pin(pegboard_runner_ws::start(config, pools)),
-					),
-					Service::new(
+						|config, pools, shutdown_rx| {
+							Box::pin(pegboard_runner_ws::start(config, pools, shutdown_rx))
+						},
+					)
+					.with_shutdown_order(1),
+					Service::new_graceful(
 						"workflow-worker",
 						ServiceKind::Standalone,
-						|config, pools| Box::pin(rivet_workflow_worker::start(config, pools)),
-					),
+						|config, pools, shutdown_rx| {
+							Box::pin(rivet_workflow_worker::start(config, pools, shutdown_rx))
+						},
+					)
+					.with_shutdown_order(2),
 					Service::new("bootstrap", ServiceKind::Oneshot, |config, pools| {
 						Box::pin(rivet_bootstrap::start(config, pools))
 					}),
@@ -34,3 +34,38 @@ pub async fn create_namespace(name: &str, guard_port: u16) -> rivet_util::Id {
 
 	namespace_id
 }
+
+/// Sends a runner config upsert request without checking the response.
+pub async fn upsert_runner_config_response(
+	namespace_name: &str,
+	runner_name: &str,
+	config: serde_json::Value,
+	guard_port: u16,
+) -> reqwest::Response {
+	tracing::info!(?namespace_name, ?runner_name, "upserting runner config");
+
+	reqwest::Client::new()
+		.put(format!(
+			"http://127.0.0.1:{}/runner-configs/{}?namespace={}",
+			guard_port, runner_name, namespace_name
+		))
+		.json(&config)
+		.send()
+		.await
+		.expect("Failed to send runner config upsert request")
+}
+
+pub async fn upsert_runner_config(
+	namespace_name: &str,
+	runner_name: &str,
+	config: serde_json::Value,
+	guard_port: u16,
+) {
+	let response =
+		upsert_runner_config_response(namespace_name, runner_name, config, guard_port).await;
+
+	if !response.status().is_success() {
+		let text = response.text().await.expect("Failed to read response text");
+		panic!("Failed to upsert runner config: {}", text);
+	}
+}
@@ -1,15 +1,46 @@
-use std::{path::Path, time::Duration};
-
+use std::{
+	collections::HashMap,
+	sync::{Arc, Mutex},
+	time::Duration,
+};
+
+use anyhow::*;
+use async_trait::async_trait;
+use rivet_runner::{
+	Actor, ActorAlarm, ActorHandler, Runner, RunnerConfig, TunnelWebSocket, WebSocketMessage,
+	http::{Request, Response},
+};
 use rivet_util::Id;
-use tokio::process::{Child, Command};
+
+const RUNNER_NAME: &str = "test-runner";
+
+/// Actors with this name fail to start, which the engine treats as a crash.
+pub const CRASH_ACTOR_NAME: &str = "crash-actor";
+
+/// Actors with this name set a named alarm due 500ms after their first start.
+pub const ALARM_ACTOR_NAME: &str = "alarm-actor";
+
+/// Like `ALARM_ACTOR_NAME`, but the first delivery of the alarm fails.
+pub const FAILING_ALARM_ACTOR_NAME: &str = "failing-alarm-actor";
+
+/// Like `ALARM_ACTOR_NAME`, but also sets a wake alarm that is due before the named alarm.
+pub const WAKE_ALARM_ACTOR_NAME: &str = "wake-alarm-actor";
+
+pub const TEST_ALARM_NAME: &str = "test-alarm";
 
 pub struct TestRunner {
 	pub runner_id: Id,
-	internal_port: u16,
-	pub port: u16,
-	handle: Child,
+	runner: Runner,
+	start_attempts: StartAttempts,
+	fired_alarms: FiredAlarms,
 }
 
+/// Timestamps of every start of each actor on a runner, including starts that crashed.
+type StartAttempts = Arc<Mutex<HashMap<String, Vec<i64>>>>;
+
+/// Names of every alarm delivered to each actor on a runner, including failed deliveries.
+type FiredAlarms = Arc<Mutex<HashMap<String, Vec<String>>>>;
+
 impl TestRunner {
 	pub async fn new(
 		port: u16,
@@ -18,117 +49,200 @@ impl TestRunner {
 		version: u32,
 		total_slots: u32,
 	) -> Self {
-		let internal_server_port = portpicker::pick_unused_port().expect("runner http server port");
-		let http_server_port = portpicker::pick_unused_port().expect("runner http server port");
+		Self::new_with_labels(port, namespace_name, key, version, total_slots, &[]).await
+	}
 
-		tracing::info!(?internal_server_port, ?http_server_port, "starting runner");
+	pub async fn new_with_labels(
+		port: u16,
+		namespace_name: &str,
+		key: &str,
+		version: u32,
+		total_slots: u32,
+		labels: &[(&str, &str)],
+	) -> Self {
+		tracing::info!(?port, %key, ?labels, "starting runner");
+
+		let mut config = RunnerConfig::new(
+			format!("http://127.0.0.1:{port}"),
+			namespace_name,
+			RUNNER_NAME,
+			key,
+		);
+		config.version = version;
+		config.total_slots = total_slots;
+		config.labels = labels
+			.iter()
+			.map(|(k, v)| (k.to_string(), v.to_string()))
+			.collect();
+
+		let start_attempts = StartAttempts::default();
+		let fired_alarms = FiredAlarms::default();
+		let runner = Runner::start(
+			config,
+			TestActorHandler {
+				start_attempts: start_attempts.clone(),
+				fired_alarms: fired_alarms.clone(),
+			},
+		)
+		.await
+		.expect("failed to start runner");
+
+		tokio::time::timeout(Duration::from_secs(10), runner.wait_connected())
+			.await
+			.expect("timed out waiting for runner to connect")
+			.expect("failed to wait for runner to connect");
 
-		let manifest_dir = env!("CARGO_MANIFEST_DIR");
-		let runner_script_path =
-			Path::new(manifest_dir).join("../../../sdks/typescript/test-runner/dist/index.js");
+		let runner_id = runner
+			.runner_id()
+			.expect("runner id not set")
+			.parse()
+			.expect("invalid runner id");
 
-		if !runner_script_path.exists() {
-			panic!(
-				"Runner script not found at '{}'. Build it first with `pnpm install && pnpm build -F @rivetkit/engine-test-runner`.",
-				runner_script_path.display(),
-			);
+		TestRunner {
+			runner_id,
+			runner,
+			start_attempts,
+			fired_alarms,
 		}
+	}
 
-		let handle = Command::new("node")
-			.arg(runner_script_path)
-			.env("INTERNAL_SERVER_PORT", internal_server_port.to_string())
-			.env("RIVET_NAMESPACE", namespace_name)
-			.env("RIVET_RUNNER_KEY", key.to_string())
-			.env("RIVET_RUNNER_VERSION", version.to_string())
-			.env("RIVET_RUNNER_TOTAL_SLOTS", total_slots.to_string())
-			.env("RIVET_ENDPOINT", format!("http://127.0.0.1:{port}"))
-			.kill_on_drop(true)
-			.spawn()
-			.expect("Failed to execute runner js file, node not installed");
+	pub async fn has_actor(&self, actor_id: &str) -> bool {
+		self.runner.has_actor(actor_id, None)
+	}
 
-		let runner_id = Self::wait_ready(internal_server_port).await;
+	/// Timestamps of every time the actor was started on this runner.
+	pub fn start_attempts(&self, actor_id: &str) -> Vec<i64> {
+		self.start_attempts
+			.lock()
+			.unwrap()
+			.get(actor_id)
+			.cloned()
+			.unwrap_or_default()
+	}
 
-		TestRunner {
-			runner_id,
-			internal_port: internal_server_port,
-			port: http_server_port,
-			handle,
-		}
+	/// Names of every alarm delivered to the actor on this runner.
+	pub fn fired_alarms(&self, actor_id: &str) -> Vec<String> {
+		self.fired_alarms
+			.lock()
+			.unwrap()
+			.get(actor_id)
+			.cloned()
+			.unwrap_or_default()
 	}
 
-	async fn wait_ready(port: u16) -> Id {
-		let client = reqwest::Client::new();
-		let mut attempts = 0;
-
-		loop {
-			let res = client
-				.get(format!("http://127.0.0.1:{port}/wait-ready"))
-				.send()
-				.await;
-
-			let response = match res {
-				Ok(x) => x,
-				Err(err) => {
-					if attempts < 10 {
-						attempts += 1;
-						tokio::time::sleep(Duration::from_millis(150)).await;
-						continue;
-					} else {
-						Err(err).expect("Failed to send wait ready request to runner")
-					}
-				}
-			};
+	/// Asks the engine to put the actor to sleep.
+	pub fn sleep_actor(&self, actor_id: &str) {
+		self.runner
+			.actor(actor_id)
+			.expect("actor not running on runner")
+			.sleep();
+	}
 
-			if !response.status().is_success() {
-				if attempts < 10 {
-					attempts += 1;
-					tokio::time::sleep(Duration::from_millis(150)).await;
-					continue;
-				}
+	pub async fn shutdown(&self) {
+		self.runner.shutdown(true).await;
+	}
+}
 
-				let text = response.text().await.expect("Failed to read response text");
-				panic!("Failed to wait ready for runner: {text}");
-			}
+struct TestActorHandler {
+	start_attempts: StartAttempts,
+	fired_alarms: FiredAlarms,
+}
 
-			return response
-				.json()
-				.await
-				.expect("Failed to parse JSON response");
+#[async_trait]
+impl ActorHandler for TestActorHandler {
+	async fn on_actor_start(&self, actor: Actor) -> Result<()> {
+		let first_start = {
+			let mut start_attempts = self.start_attempts.lock().unwrap();
+			let attempts = start_attempts.entry(actor.actor_id.clone()).or_default();
+			attempts.push(rivet_util::timestamp::now());
+			attempts.len() == 1
+		};
+
+		if actor.config.name == CRASH_ACTOR_NAME {
+			bail!("test actor crashed");
 		}
-	}
 
-	pub async fn has_actor(&self, actor_id: &str) -> bool {
-		let client = reqwest::Client::new();
-		let response = client
-			.get(format!("http://127.0.0.1:{}/has-actor", self.internal_port))
-			.query(&[("actor", actor_id)])
-			.send()
-			.await
-			.expect("Failed to send request has-actor to runner");
+		if first_start
+			&& (actor.config.name == ALARM_ACTOR_NAME
+				|| actor.config.name == FAILING_ALARM_ACTOR_NAME
+				|| actor.config.name == WAKE_ALARM_ACTOR_NAME)
+		{
+			let now = rivet_util::timestamp::now();
 
-		if response.status() == reqwest::StatusCode::NOT_FOUND {
-			return false;
+			if actor.config.name == WAKE_ALARM_ACTOR_NAME {
+				actor.set_alarm(Some(now + 100));
+			}
+			actor.set_named_alarm(TEST_ALARM_NAME, now + 500, None);
 		}
 
-		if response.status().is_success() {
-			return true;
+		tracing::info!(actor_id = %actor.actor_id, generation = actor.generation, "actor started");
+		actor.log(
+			rivet_runner_protocol::ActorLogStream::Stdout,
+			"actor started",
+		);
+		Ok(())
+	}
+
+	async fn on_actor_stop(&self, actor: Actor) -> Result<()> {
+		tracing::info!(actor_id = %actor.actor_id, generation = actor.generation, "actor stopped");
+		Ok(())
+	}
+
+	async fn on_alarms_fired(&self, actor: Actor, alarms: Vec<ActorAlarm>) -> Result<()> {
+		let deliveries = {
+			let mut fired_alarms = self.fired_alarms.lock().unwrap();
+			let fired = fired_alarms.entry(actor.actor_id.clone()).or_default();
+			fired.extend(alarms.into_iter().map(|alarm| alarm.name));
+			fired.len()
+		};
+
+		if actor.config.name == FAILING_ALARM_ACTOR_NAME && deliveries == 1 {
+			bail!("test alarm failed");
 		}
 
-		let text = response.text().await.expect("Failed to fetch has-actor");
-		panic!("Failed to fetch has-actor: {text}");
+		Ok(())
 	}
 
-	pub async fn shutdown(&self) {
-		let client = reqwest::Client::new();
-		let response = client
-			.get(format!("http://127.0.0.1:{}/shutdown", self.internal_port))
-			.send()
-			.await
-			.expect("Failed to send shutdown request to runner");
+	async fn fetch(&self, actor: Actor, req: Request<Vec<u8>>) -> Result<Response<Vec<u8>>> {
+		tracing::info!(actor_id = %actor.actor_id, uri = %req.uri(), "fetch called");
 
-		if !response.status().is_success() {
-			let text = response.text().await.expect("Failed to read response text");
-			panic!("Failed to shutdown runner: {text}");
+		if req.uri().path() == "/ping" {
+			let body = serde_json::json!({
+				"actorId": actor.actor_id,
+				"status": "ok",
+				"timestamp": rivet_util::timestamp::now(),
+			});
+
+			return Ok(Response::builder()
+				.status(200)
+				.header("content-type", "application/json")
+				.body(serde_json::to_vec(&body)?)?);
 		}
+
+		Ok(Response::builder().status(200).body(b"ok".to_vec())?)
+	}
+
+	async fn websocket(
+		&self,
+		actor: Actor,
+		_req: Request<()>,
+		mut ws: TunnelWebSocket,
+	) -> Result<()> {
+		tracing::info!(actor_id = %actor.actor_id, "websocket opened");
+
+		// Echo server
+		while let Some(msg) = ws.recv().await {
+			match msg {
+				WebSocketMessage::Text(text) => ws.send_text(format!("Echo: {text}"))?,
+				WebSocketMessage::Binary(data) => {
+					ws.send_text(format!("Echo: {}", String::from_utf8_lossy(&data)))?
+				}
+				WebSocketMessage::Close { .. } => break,
+			}
+		}
+
+		tracing::info!(actor_id = %actor.actor_id, "websocket closed");
+
+		Ok(())
 	}
 }
@@ -0,0 +1,89 @@
+mod common;
+
+use serde_json::json;
+
+/// Fills `runner` with actors, then starts an empty runner and returns the runner the next actor
+/// is allocated to.
+async fn allocate_after_partial_fill(
+	dc: &common::TestDatacenter,
+	namespace: &str,
+) -> (
+	common::runner::TestRunner,
+	common::runner::TestRunner,
+	String,
+) {
+	let busy_runner = common::setup_runner(dc, namespace, "busy-runner", 1, 20).await;
+	for _ in 0..3 {
+		let actor_id = common::create_actor(namespace, dc.guard_port()).await;
+		common::wait_for_actor_propagation(&actor_id, 1).await;
+		common::assert_actor_in_runner(dc, &actor_id, &busy_runner.runner_id.to_string()).await;
+	}
+
+	let empty_runner = common::setup_runner(dc, namespace, "empty-runner", 1, 20).await;
+
+	let actor_id = common::create_actor(namespace, dc.guard_port()).await;
+	common::wait_for_actor_propagation(&actor_id, 1).await;
+
+	(busy_runner, empty_runner, actor_id)
+}
+
+#[test]
+fn allocation_default_most_free_slots() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, _) = common::setup_test_namespace(dc.guard_port()).await;
+
+		let (busy_runner, empty_runner, actor_id) =
+			allocate_after_partial_fill(dc, &namespace).await;
+		common::assert_actor_in_runner(dc, &actor_id, &empty_runner.runner_id.to_string()).await;
+
+		busy_runner.shutdown().await;
+		empty_runner.shutdown().await;
+	});
+}
+
+#[test]
+fn allocation_bin_pack() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, _) = common::setup_test_namespace(dc.guard_port()).await;
+
+		common::upsert_runner_config(
+			&namespace,
+			"test-runner",
+			json!({
+				"normal": {},
+				"allocation_strategy": "bin_pack",
+			}),
+			dc.guard_port(),
+		)
+		.await;
+
+		let (busy_runner, empty_runner, actor_id) =
+			allocate_after_partial_fill(dc, &namespace).await;
+		common::assert_actor_in_runner(dc, &actor_id, &busy_runner.runner_id.to_string()).await;
+
+		busy_runner.shutdown().await;
+		empty_runner.shutdown().await;
+	});
+}
+
+#[test]
+fn allocation_random_top_n_validation() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, _) = common::setup_test_namespace(dc.guard_port()).await;
+
+		let response = common::upsert_runner_config_response(
+			&namespace,
+			"test-runner",
+			json!({
+				"normal": {},
+				"allocation_strategy": { "random_top_n": { "n": 0 } },
+			}),
+			dc.guard_port(),
+		)
+		.await;
+		common::assert_error_response(response, "invalid").await;
+	});
+}
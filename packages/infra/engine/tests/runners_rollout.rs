@@ -0,0 +1,221 @@
+mod common;
+
+use std::time::Duration;
+
+use futures_util::TryStreamExt;
+use gas::prelude::*;
+use serde_json::json;
+use universaldb::options::StreamingMode;
+use universaldb::utils::IsolationLevel::*;
+
+async fn is_rolled_back(ctx: &StandaloneCtx, namespace_id: Id, version: u32) -> bool {
+	ctx.udb()
+		.expect("failed to get udb")
+		.run(|tx| async move {
+			let tx = tx.with_subspace(pegboard::keys::subspace());
+			tx.exists(
+				&pegboard::keys::ns::RolloutRolledBackTsKey::new(
+					namespace_id,
+					"test-runner".to_string(),
+					version,
+				),
+				Serializable,
+			)
+			.await
+		})
+		.await
+		.expect("failed to read rolled back ts")
+}
+
+async fn crash_count(ctx: &StandaloneCtx, namespace_id: Id, version: u32) -> i64 {
+	ctx.udb()
+		.expect("failed to get udb")
+		.run(|tx| async move {
+			let tx = tx.with_subspace(pegboard::keys::subspace());
+			let subspace = pegboard::keys::subspace().subspace(
+				&pegboard::keys::ns::RolloutCrashesKey::subspace(
+					namespace_id,
+					"test-runner".to_string(),
+					version,
+				),
+			);
+
+			let mut stream = tx.get_ranges_keyvalues(
+				universaldb::RangeOption {
+					mode: StreamingMode::WantAll,
+					..(&subspace).into()
+				},
+				Serializable,
+			);
+
+			let mut count = 0;
+			while let Some(entry) = stream.try_next().await? {
+				let (_, crashes) =
+					tx.read_entry::<pegboard::keys::ns::RolloutCrashesKey>(&entry)?;
+				count += crashes;
+			}
+
+			Ok(count)
+		})
+		.await
+		.expect("failed to read crash count")
+}
+
+async fn crash_actor(namespace: &str, guard_port: u16) {
+	common::create_actor_with_options(
+		common::CreateActorOptions {
+			namespace: namespace.to_string(),
+			name: common::runner::CRASH_ACTOR_NAME.to_string(),
+			..Default::default()
+		},
+		guard_port,
+	)
+	.await;
+}
+
+#[test]
+fn rollout_pinned_version_must_exist() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, _, runner) = common::setup_test_namespace_with_runner(dc).await;
+
+		let response = common::upsert_runner_config_response(
+			&namespace,
+			"test-runner",
+			json!({
+				"normal": {},
+				"rollout": { "pinned_version": 5 },
+			}),
+			dc.guard_port(),
+		)
+		.await;
+		common::assert_error_response(response, "invalid").await;
+
+		// The connected runner's version can be pinned
+		common::upsert_runner_config(
+			&namespace,
+			"test-runner",
+			json!({
+				"normal": {},
+				"rollout": { "pinned_version": 1 },
+			}),
+			dc.guard_port(),
+		)
+		.await;
+
+		runner.shutdown().await;
+	});
+}
+
+#[test]
+fn rollout_canary_falls_back_when_full() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, _) = common::setup_test_namespace(dc.guard_port()).await;
+
+		let old_runner = common::setup_runner(dc, &namespace, "old-runner", 1, 20).await;
+		let new_runner = common::setup_runner(dc, &namespace, "new-runner", 2, 1).await;
+
+		common::upsert_runner_config(
+			&namespace,
+			"test-runner",
+			json!({
+				"normal": {},
+				"rollout": { "canary_percent": 100 },
+			}),
+			dc.guard_port(),
+		)
+		.await;
+
+		let actor_id = common::create_actor(&namespace, dc.guard_port()).await;
+		common::wait_for_actor_propagation(&actor_id, 1).await;
+		common::assert_actor_in_runner(dc, &actor_id, &new_runner.runner_id.to_string()).await;
+
+		// The canary version is full, so the actor is allocated to the previous version instead of
+		// staying pending
+		let actor_id = common::create_actor(&namespace, dc.guard_port()).await;
+		common::wait_for_actor_propagation(&actor_id, 1).await;
+		common::assert_actor_in_runner(dc, &actor_id, &old_runner.runner_id.to_string()).await;
+
+		old_runner.shutdown().await;
+		new_runner.shutdown().await;
+	});
+}
+
+#[test]
+fn rollout_rollback_crashing_version() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, namespace_id) = common::setup_test_namespace(dc.guard_port()).await;
+
+		let old_runner = common::setup_runner(dc, &namespace, "old-runner", 1, 20).await;
+		let new_runner = common::setup_runner(dc, &namespace, "new-runner", 2, 20).await;
+
+		common::upsert_runner_config(
+			&namespace,
+			"test-runner",
+			json!({
+				"normal": {},
+				"rollout": {
+					"rollback": { "max_crash_rate_percent": 50, "min_allocations": 1 },
+				},
+			}),
+			dc.guard_port(),
+		)
+		.await;
+
+		crash_actor(&namespace, dc.guard_port()).await;
+		loop {
+			if is_rolled_back(&dc.workflow_ctx, namespace_id, 2).await {
+				break;
+			}
+			tokio::time::sleep(Duration::from_millis(100)).await;
+		}
+
+		// New actors skip the rolled back version
+		let actor_id = common::create_actor(&namespace, dc.guard_port()).await;
+		common::wait_for_actor_propagation(&actor_id, 1).await;
+		common::assert_actor_in_runner(dc, &actor_id, &old_runner.runner_id.to_string()).await;
+
+		old_runner.shutdown().await;
+		new_runner.shutdown().await;
+	});
+}
+
+#[test]
+fn rollout_rollback_keeps_last_version() {
+	common::run(common::TestOpts::new(1), |ctx| async move {
+		let dc = ctx.leader_dc();
+		let (namespace, namespace_id, runner) = common::setup_test_namespace_with_runner(dc).await;
+
+		common::upsert_runner_config(
+			&namespace,
+			"test-runner",
+			json!({
+				"normal": {},
+				"rollout": {
+					"rollback": { "max_crash_rate_percent": 50, "min_allocations": 1 },
+				},
+			}),
+			dc.guard_port(),
+		)
+		.await;
+
+		crash_actor(&namespace, dc.guard_port()).await;
+		loop {
+			if crash_count(&dc.workflow_ctx, namespace_id, 1).await > 0 {
+				break;
+			}
+			tokio::time::sleep(Duration::from_millis(100)).await;
+		}
+
+		// The only version is not rolled back even though its crash rate is over the threshold
+		assert!(!is_rolled_back(&dc.workflow_ctx, namespace_id, 1).await);
+
+		let actor_id = common::create_actor(&namespace, dc.guard_port()).await;
+		common::wait_for_actor_propagation(&actor_id, 1).await;
+		common::assert_actor_in_runner(dc, &actor_id, &runner.runner_id.to_string()).await;
+
+		runner.shutdown().await;
+	});
+}
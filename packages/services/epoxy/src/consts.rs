@@ -40,3 +40,27 @@ pub const DOWNLOAD_INSTANCE_COUNT: u64 = 50_000;
 ///
 /// This number is relatively small since we have to do a lot of operations per key.
 pub const RECOVER_KEY_CHUNK_SIZE: u64 = 500;
+
+/// Number of KV entries to download in a single chunk when installing a snapshot.
+pub const DOWNLOAD_SNAPSHOT_COUNT: u64 = 10_000;
+
+/// How often each replica advances its committed prefix and compacts its log.
+pub const COMPACTION_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Max number of log entries to scan in a single transaction when advancing the committed prefix.
+pub const COMMITTED_PREFIX_SCAN_COUNT: u64 = 10_000;
+
+/// Max number of log entries to remove in a single transaction when compacting the log.
+///
+/// Each entry also clears its key instance and ballot keys, so this is lower than the scan count.
+pub const COMPACT_INSTANCE_CHUNK_SIZE: u64 = 1_000;
+
+/// How often to check if the dependencies of a read barrier have been committed on this replica.
+pub const LINEARIZABLE_READ_POLL_INTERVAL: Duration = Duration::from_millis(25);
+
+/// How long to wait for a dependency of a read barrier to be committed on this replica before
+/// running explicit prepare for it.
+///
+/// Commits are broadcast in the background by the leader of each instance, so this is usually
+/// only reached if the leader failed before broadcasting the commit.
+pub const LINEARIZABLE_READ_DEP_TIMEOUT: Duration = Duration::from_secs(2);
@@ -19,3 +19,22 @@ pub enum Kv {
 	#[error("not_leader", "Current node is not the leader.")]
 	NotLeader,
 }
+
+#[derive(RivetError, Debug, Deserialize, Serialize)]
+#[error("epoxy_coordinator")]
+pub enum Coordinator {
+	#[error(
+		"not_leader",
+		"The epoxy coordinator only runs in the leader datacenter."
+	)]
+	NotLeader,
+
+	#[error("replica_not_found", "Replica not found in the cluster config.")]
+	ReplicaNotFound,
+
+	#[error(
+		"cannot_remove_coordinator",
+		"Cannot remove the replica that runs the coordinator."
+	)]
+	CannotRemoveCoordinator,
+}
@@ -12,7 +12,12 @@ pub struct VersionedPath {
 pub fn mount_routes(
 	router: axum::Router<rivet_api_builder::GlobalApiCtx>,
 ) -> axum::Router<rivet_api_builder::GlobalApiCtx> {
-	router.route("/v{version}/epoxy/message", bin::post(message))
+	router
+		.route("/v{version}/epoxy/message", bin::post(message))
+		.route(
+			"/v{version}/epoxy/kv/get-linearizable",
+			bin::post(kv_get_linearizable),
+		)
 }
 
 pub async fn message(ctx: ApiCtx, path: VersionedPath, _query: (), body: Bytes) -> Result<Vec<u8>> {
@@ -33,3 +38,29 @@ pub async fn message(ctx: ApiCtx, path: VersionedPath, _query: (), body: Bytes)
 
 	versioned::Response::latest(response).serialize(path.version)
 }
+
+/// Reads a key with `get_linearizable`. The body is a `KvGetLinearizableRequest` and the response a
+/// `KvGetLinearizableResponse`, both serialized with the given protocol version.
+pub async fn kv_get_linearizable(
+	ctx: ApiCtx,
+	path: VersionedPath,
+	_query: (),
+	body: Bytes,
+) -> Result<Vec<u8>> {
+	ensure!(
+		path.version >= 2,
+		"linearizable reads are not supported by protocol v{}",
+		path.version
+	);
+
+	let request = serde_bare::from_slice::<protocol::KvGetLinearizableRequest>(&body)?;
+
+	let output = ctx
+		.op(crate::ops::kv::get_linearizable::Input { key: request.key })
+		.await?;
+
+	serde_bare::to_vec(&protocol::KvGetLinearizableResponse {
+		value: output.value,
+	})
+	.map_err(Into::into)
+}
@@ -281,3 +281,178 @@ impl TuplePack for InstanceBallotKey {
 		t.pack(w, tuple_depth)
 	}
 }
+
+/// Highest slot of an instance replica where this slot and all slots before it are committed on
+/// this replica.
+#[derive(Debug)]
+pub struct CommittedPrefixKey {
+	pub instance_replica_id: ReplicaId,
+}
+
+impl CommittedPrefixKey {
+	pub fn new(instance_replica_id: ReplicaId) -> Self {
+		Self {
+			instance_replica_id,
+		}
+	}
+
+	pub fn subspace() -> CommittedPrefixSubspaceKey {
+		CommittedPrefixSubspaceKey
+	}
+}
+
+impl FormalKey for CommittedPrefixKey {
+	type Value = SlotId;
+
+	fn deserialize(&self, raw: &[u8]) -> Result<Self::Value> {
+		Ok(SlotId::from_be_bytes(raw.try_into()?))
+	}
+
+	fn serialize(&self, value: Self::Value) -> Result<Vec<u8>> {
+		Ok(value.to_be_bytes().to_vec())
+	}
+}
+
+impl TuplePack for CommittedPrefixKey {
+	fn pack<W: std::io::Write>(
+		&self,
+		w: &mut W,
+		tuple_depth: TupleDepth,
+	) -> std::io::Result<VersionstampOffset> {
+		let t = (COMMITTED_PREFIX, self.instance_replica_id);
+		t.pack(w, tuple_depth)
+	}
+}
+
+impl<'de> TupleUnpack<'de> for CommittedPrefixKey {
+	fn unpack(input: &[u8], tuple_depth: TupleDepth) -> PackResult<(&[u8], Self)> {
+		let (input, (_, instance_replica_id)) = <(usize, ReplicaId)>::unpack(input, tuple_depth)?;
+
+		let v = CommittedPrefixKey {
+			instance_replica_id,
+		};
+
+		Result::Ok((input, v))
+	}
+}
+
+pub struct CommittedPrefixSubspaceKey;
+
+impl TuplePack for CommittedPrefixSubspaceKey {
+	fn pack<W: std::io::Write>(
+		&self,
+		w: &mut W,
+		tuple_depth: TupleDepth,
+	) -> std::io::Result<VersionstampOffset> {
+		let t = (COMMITTED_PREFIX,);
+		t.pack(w, tuple_depth)
+	}
+}
+
+/// Highest compacted slot of an instance replica. Log entries up to and including this slot have
+/// been applied to the KV state and removed from the log.
+#[derive(Debug)]
+pub struct SnapshotWatermarkKey {
+	pub instance_replica_id: ReplicaId,
+}
+
+impl SnapshotWatermarkKey {
+	pub fn new(instance_replica_id: ReplicaId) -> Self {
+		Self {
+			instance_replica_id,
+		}
+	}
+
+	pub fn subspace() -> SnapshotWatermarkSubspaceKey {
+		SnapshotWatermarkSubspaceKey
+	}
+}
+
+impl FormalKey for SnapshotWatermarkKey {
+	type Value = SlotId;
+
+	fn deserialize(&self, raw: &[u8]) -> Result<Self::Value> {
+		Ok(SlotId::from_be_bytes(raw.try_into()?))
+	}
+
+	fn serialize(&self, value: Self::Value) -> Result<Vec<u8>> {
+		Ok(value.to_be_bytes().to_vec())
+	}
+}
+
+impl TuplePack for SnapshotWatermarkKey {
+	fn pack<W: std::io::Write>(
+		&self,
+		w: &mut W,
+		tuple_depth: TupleDepth,
+	) -> std::io::Result<VersionstampOffset> {
+		let t = (SNAPSHOT_WATERMARK, self.instance_replica_id);
+		t.pack(w, tuple_depth)
+	}
+}
+
+impl<'de> TupleUnpack<'de> for SnapshotWatermarkKey {
+	fn unpack(input: &[u8], tuple_depth: TupleDepth) -> PackResult<(&[u8], Self)> {
+		let (input, (_, instance_replica_id)) = <(usize, ReplicaId)>::unpack(input, tuple_depth)?;
+
+		let v = SnapshotWatermarkKey {
+			instance_replica_id,
+		};
+
+		Result::Ok((input, v))
+	}
+}
+
+pub struct SnapshotWatermarkSubspaceKey;
+
+impl TuplePack for SnapshotWatermarkSubspaceKey {
+	fn pack<W: std::io::Write>(
+		&self,
+		w: &mut W,
+		tuple_depth: TupleDepth,
+	) -> std::io::Result<VersionstampOffset> {
+		let t = (SNAPSHOT_WATERMARK,);
+		t.pack(w, tuple_depth)
+	}
+}
+
+/// Marks an instance whose commands were not applied because a check failed when committing.
+///
+/// Used to recover proposals with commands on multiple keys, since each key is recovered separately.
+#[derive(Debug)]
+pub struct CommandErrorKey {
+	instance_replica_id: ReplicaId,
+	instance_slot_id: SlotId,
+}
+
+impl CommandErrorKey {
+	pub fn new(instance_replica_id: ReplicaId, instance_slot_id: SlotId) -> Self {
+		Self {
+			instance_replica_id,
+			instance_slot_id,
+		}
+	}
+}
+
+impl FormalKey for CommandErrorKey {
+	type Value = ();
+
+	fn deserialize(&self, _raw: &[u8]) -> Result<Self::Value> {
+		Ok(())
+	}
+
+	fn serialize(&self, _value: Self::Value) -> Result<Vec<u8>> {
+		Ok(Vec::new())
+	}
+}
+
+impl TuplePack for CommandErrorKey {
+	fn pack<W: std::io::Write>(
+		&self,
+		w: &mut W,
+		tuple_depth: TupleDepth,
+	) -> std::io::Result<VersionstampOffset> {
+		let t = (COMMAND_ERROR, self.instance_replica_id, self.instance_slot_id);
+		t.pack(w, tuple_depth)
+	}
+}
@@ -0,0 +1,176 @@
+use anyhow::*;
+use epoxy_protocol::protocol::{self, ReplicaId};
+use gas::prelude::*;
+use rivet_api_builder::prelude::*;
+use std::collections::HashMap;
+
+use crate::{consts, http_client, replica, types, utils};
+
+#[derive(Debug)]
+pub struct Input {}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Output {
+	/// Config as seen by this replica.
+	pub config: types::ClusterConfig,
+	pub replicas: Vec<ReplicaStatus>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplicaStatus {
+	pub replica_id: ReplicaId,
+	pub status: types::ReplicaStatus,
+	pub api_peer_url: String,
+	/// None if the replica could not be reached.
+	pub details: Option<ReplicaDetails>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplicaDetails {
+	/// Config epoch as seen by the replica.
+	pub epoch: u64,
+	/// Number of log entries that have not been compacted yet.
+	pub log_length: u64,
+	/// Number of instances committed on another replica that are not yet committed on this
+	/// replica.
+	pub lag: u64,
+	pub committed_prefixes: Vec<types::Instance>,
+	pub watermark: Vec<types::Instance>,
+}
+
+/// Reads the status of every replica in the cluster config of this replica.
+#[operation]
+pub async fn epoxy_cluster_status(ctx: &OperationCtx, _input: &Input) -> Result<Output> {
+	let replica_id = ctx.config().epoxy_replica_id();
+
+	let config = ctx
+		.udb()?
+		.run(move |tx| async move { utils::read_config(&tx, replica_id).await })
+		.await?;
+
+	// Read status from every replica, including ourselves
+	let responses = futures_util::future::join_all(config.replicas.iter().map(|replica| {
+		let config = config.clone();
+		let to_replica_id = replica.replica_id;
+		async move {
+			let res = if to_replica_id == replica_id {
+				get_local_replica_status(ctx, replica_id).await
+			} else {
+				send_get_replica_status(ctx, &config, replica_id, to_replica_id).await
+			};
+
+			match res {
+				Result::Ok(res) => Some(res),
+				Err(err) => {
+					tracing::warn!(?err, ?to_replica_id, "failed to get replica status");
+					None
+				}
+			}
+		}
+	}))
+	.await;
+
+	// Highest committed prefix per instance replica across all reachable replicas
+	let mut max_prefixes = HashMap::<ReplicaId, u64>::new();
+	for res in responses.iter().flatten() {
+		for prefix in &res.committed_prefixes {
+			let max = max_prefixes.entry(prefix.replica_id).or_default();
+			*max = (*max).max(prefix.slot_id);
+		}
+	}
+
+	let replicas = config
+		.replicas
+		.iter()
+		.zip(responses)
+		.map(|(replica, res)| ReplicaStatus {
+			replica_id: replica.replica_id,
+			status: replica.status.clone().into(),
+			api_peer_url: replica.api_peer_url.clone(),
+			details: res.map(|res| build_details(res, &max_prefixes)),
+		})
+		.collect();
+
+	Ok(Output {
+		config: config.into(),
+		replicas,
+	})
+}
+
+fn build_details(
+	res: protocol::GetReplicaStatusResponse,
+	max_prefixes: &HashMap<ReplicaId, u64>,
+) -> ReplicaDetails {
+	let slot_for = |instances: &[protocol::Instance], instance_replica_id: ReplicaId| {
+		instances
+			.iter()
+			.find(|x| x.replica_id == instance_replica_id)
+			.map_or(0, |x| x.slot_id)
+	};
+
+	// Gaps in the log are counted as entries, so this is an upper bound
+	let log_length = res
+		.last_slots
+		.iter()
+		.map(|last_slot| {
+			last_slot
+				.slot_id
+				.saturating_sub(slot_for(&res.watermark, last_slot.replica_id))
+		})
+		.sum();
+
+	let lag = max_prefixes
+		.iter()
+		.map(|(instance_replica_id, max_prefix)| {
+			max_prefix.saturating_sub(slot_for(&res.committed_prefixes, *instance_replica_id))
+		})
+		.sum();
+
+	ReplicaDetails {
+		epoch: res.config.epoch,
+		log_length,
+		lag,
+		committed_prefixes: res.committed_prefixes.into_iter().map(Into::into).collect(),
+		watermark: res.watermark.into_iter().map(Into::into).collect(),
+	}
+}
+
+async fn get_local_replica_status(
+	ctx: &OperationCtx,
+	replica_id: ReplicaId,
+) -> Result<protocol::GetReplicaStatusResponse> {
+	ctx.udb()?
+		.run(move |tx| async move { replica::messages::get_replica_status(&*tx, replica_id).await })
+		.await
+}
+
+async fn send_get_replica_status(
+	ctx: &OperationCtx,
+	config: &protocol::ClusterConfig,
+	from_replica_id: ReplicaId,
+	to_replica_id: ReplicaId,
+) -> Result<protocol::GetReplicaStatusResponse> {
+	let response = tokio::time::timeout(
+		consts::REQUEST_TIMEOUT,
+		http_client::send_message(
+			&ApiCtx::new_from_operation(&ctx)?,
+			config,
+			protocol::Request {
+				from_replica_id,
+				to_replica_id,
+				kind: protocol::RequestKind::GetReplicaStatusRequest,
+			},
+		),
+	)
+	.await
+	.context("get replica status request timed out")??;
+
+	let protocol::Response {
+		kind: protocol::ResponseKind::GetReplicaStatusResponse(response),
+	} = response
+	else {
+		bail!("wrong response type");
+	};
+
+	Ok(response)
+}
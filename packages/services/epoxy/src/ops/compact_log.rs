@@ -0,0 +1,172 @@
+use anyhow::*;
+use epoxy_protocol::protocol::{self, ReplicaId, SlotId};
+use gas::prelude::*;
+use rivet_api_builder::prelude::*;
+
+use crate::{consts, http_client, replica::compaction, utils};
+
+#[derive(Debug)]
+pub struct Input {}
+
+#[derive(Debug)]
+pub struct Output {
+	/// Snapshot watermark of this replica after compaction.
+	pub watermark: Vec<protocol::Instance>,
+}
+
+/// Advances this replica's committed prefix and removes log entries that are committed on every
+/// replica in the cluster.
+#[operation]
+pub async fn epoxy_compact_log(ctx: &OperationCtx, _input: &Input) -> Result<Output> {
+	let replica_id = ctx.config().epoxy_replica_id();
+
+	// Read config
+	let config = ctx
+		.udb()?
+		.run(move |tx| async move { utils::read_config(&tx, replica_id).await })
+		.await?;
+	let all_replicas = utils::get_all_replicas(&config);
+
+	// Advance the local committed prefix for every instance replica
+	for &instance_replica_id in &all_replicas {
+		loop {
+			let (prev_prefix, new_prefix) = ctx
+				.udb()?
+				.run(move |tx| async move {
+					compaction::advance_committed_prefix(
+						&tx,
+						replica_id,
+						instance_replica_id,
+						consts::COMMITTED_PREFIX_SCAN_COUNT,
+					)
+					.await
+				})
+				.await?;
+
+			tracing::debug!(
+				?instance_replica_id,
+				prev_prefix,
+				new_prefix,
+				"advanced committed prefix"
+			);
+
+			if new_prefix - prev_prefix < consts::COMMITTED_PREFIX_SCAN_COUNT {
+				break;
+			}
+		}
+	}
+
+	// Collect committed prefixes from every replica, including joining and learning replicas.
+	// Replicas that are still catching up have no prefix yet, which prevents compacting instances
+	// they have not downloaded.
+	let local_prefixes = ctx
+		.udb()?
+		.run(move |tx| async move { compaction::read_committed_prefixes(&tx, replica_id).await })
+		.await?;
+	let mut all_prefixes =
+		send_get_committed_prefixes(ctx, &config, replica_id, &all_replicas).await?;
+
+	// Every replica must respond, otherwise we cannot know if the instances are committed there
+	if all_prefixes.len() + 1 < all_replicas.len() {
+		tracing::info!(
+			responses = all_prefixes.len(),
+			replicas = all_replicas.len(),
+			"not all replicas responded, skipping compaction"
+		);
+	} else {
+		all_prefixes.push(local_prefixes);
+
+		for &instance_replica_id in &all_replicas {
+			let up_to_slot = cluster_committed_prefix(&all_prefixes, instance_replica_id);
+			if up_to_slot == 0 {
+				continue;
+			}
+
+			loop {
+				let watermark = ctx
+					.udb()?
+					.run(move |tx| async move {
+						compaction::compact_instances(
+							&tx,
+							replica_id,
+							instance_replica_id,
+							up_to_slot,
+							consts::COMPACT_INSTANCE_CHUNK_SIZE,
+						)
+						.await
+					})
+					.await?;
+
+				if watermark >= up_to_slot {
+					break;
+				}
+			}
+
+			tracing::debug!(?instance_replica_id, up_to_slot, "compacted log");
+		}
+	}
+
+	let watermark = ctx
+		.udb()?
+		.run(move |tx| async move { compaction::read_snapshot_watermark(&tx, replica_id).await })
+		.await?;
+
+	Ok(Output { watermark })
+}
+
+/// Lowest committed prefix of the given instance replica across all replicas.
+fn cluster_committed_prefix(
+	all_prefixes: &[Vec<protocol::Instance>],
+	instance_replica_id: ReplicaId,
+) -> SlotId {
+	all_prefixes
+		.iter()
+		.map(|prefixes| {
+			prefixes
+				.iter()
+				.find(|x| x.replica_id == instance_replica_id)
+				.map_or(0, |x| x.slot_id)
+		})
+		.min()
+		.unwrap_or(0)
+}
+
+async fn send_get_committed_prefixes(
+	ctx: &OperationCtx,
+	config: &protocol::ClusterConfig,
+	from_replica_id: ReplicaId,
+	replica_ids: &[ReplicaId],
+) -> Result<Vec<Vec<protocol::Instance>>> {
+	let responses = http_client::fanout_to_replicas(
+		from_replica_id,
+		replica_ids,
+		utils::QuorumType::All,
+		|to_replica_id| {
+			let config = config.clone();
+			async move {
+				let response = http_client::send_message(
+					&ApiCtx::new_from_operation(&ctx)?,
+					&config,
+					protocol::Request {
+						from_replica_id,
+						to_replica_id,
+						kind: protocol::RequestKind::GetCommittedPrefixRequest,
+					},
+				)
+				.await?;
+
+				let protocol::Response {
+					kind: protocol::ResponseKind::GetCommittedPrefixResponse(response),
+				} = response
+				else {
+					bail!("wrong response type");
+				};
+
+				Ok(response.prefixes)
+			}
+		},
+	)
+	.await?;
+
+	Ok(responses)
+}
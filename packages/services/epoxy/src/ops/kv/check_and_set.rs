@@ -0,0 +1,55 @@
+use std::collections::HashSet;
+
+use anyhow::*;
+use epoxy_protocol::protocol;
+use gas::prelude::*;
+use rivet_api_builder::prelude::*;
+
+use crate::ops::propose::ProposalResult;
+
+#[derive(Debug)]
+pub struct Input {
+	pub entries: Vec<Entry>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Entry {
+	pub key: Vec<u8>,
+	/// The current value must match one of these. `None` matches a key that does not exist.
+	pub expect_one_of: Vec<Option<Vec<u8>>>,
+	/// `None` deletes the key.
+	pub new_value: Option<Vec<u8>>,
+}
+
+/// Atomically sets multiple keys if every key matches its expected value.
+///
+/// All entries are proposed as a single instance. If any check fails, none of the keys are
+/// updated and the first failing check is returned as a command error.
+#[operation]
+pub async fn epoxy_kv_check_and_set(ctx: &OperationCtx, input: &Input) -> Result<ProposalResult> {
+	ensure!(!input.entries.is_empty(), "no entries to set");
+
+	// Every command is validated against the state before the proposal, so a key listed twice
+	// would be checked against a stale value
+	let mut seen = HashSet::new();
+	for entry in &input.entries {
+		ensure!(seen.insert(&entry.key), "duplicate key in check and set");
+	}
+
+	let commands = input
+		.entries
+		.iter()
+		.map(|entry| protocol::Command {
+			kind: protocol::CommandKind::CheckAndSetCommand(protocol::CheckAndSetCommand {
+				key: entry.key.clone(),
+				expect_one_of: entry.expect_one_of.clone(),
+				new_value: entry.new_value.clone(),
+			}),
+		})
+		.collect();
+
+	ctx.op(crate::ops::propose::Input {
+		proposal: protocol::Proposal { commands },
+	})
+	.await
+}
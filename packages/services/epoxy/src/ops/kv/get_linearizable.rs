@@ -0,0 +1,162 @@
+use anyhow::*;
+use epoxy_protocol::protocol::{self, ReplicaId};
+use gas::prelude::*;
+use rivet_api_builder::prelude::*;
+use std::time::Instant;
+use universaldb::utils::{FormalKey, IsolationLevel::*};
+
+use crate::{
+	consts, keys,
+	ops::{
+		explicit_prepare::ExplicitPrepareResult,
+		propose::{self, ProposalResult},
+	},
+	replica::compaction,
+};
+
+#[derive(Debug)]
+pub struct Input {
+	pub key: Vec<u8>,
+}
+
+#[derive(Debug)]
+pub struct Output {
+	pub value: Option<Vec<u8>>,
+}
+
+/// Reads the latest committed value of a key.
+///
+/// Unlike `get_local` and `get_optimistic`, this is guaranteed to observe every write that
+/// committed before this read started, regardless of which replica proposed it.
+///
+/// This works by:
+/// 1. Proposing a read barrier for the key through the normal consensus path. Since the barrier
+///    interferes with all writes to the key, its dependencies include every prior write.
+/// 2. Waiting until every dependency of the barrier is committed on this replica, running explicit
+///    prepare for dependencies whose leader never broadcast a commit.
+/// 3. Reading the value locally.
+///
+/// The read barrier is always proposed by this replica, so the value is read from this replica too.
+///
+/// This requires a full consensus round, so only use this in rare cases where reading a stale
+/// value is not acceptable.
+#[operation]
+pub async fn epoxy_kv_get_linearizable(ctx: &OperationCtx, input: &Input) -> Result<Output> {
+	let replica_id = ctx.config().epoxy_replica_id();
+
+	let (result, payload) = propose::propose_inner(
+		ctx,
+		protocol::Proposal {
+			commands: vec![protocol::Command {
+				kind: protocol::CommandKind::ReadCommand(protocol::ReadCommand {
+					key: input.key.clone(),
+				}),
+			}],
+		},
+	)
+	.await?;
+
+	match result {
+		ProposalResult::Committed => {}
+		ProposalResult::ConsensusFailed => bail!("consensus failed for read barrier"),
+		ProposalResult::CommandError(err) => {
+			bail!("unexpected command error for read barrier: {err:?}")
+		}
+	}
+
+	for dep in &payload.deps {
+		wait_for_commit(ctx, replica_id, dep).await?;
+	}
+
+	// Read from local KV store now that all prior writes have been applied
+	let kv_key = keys::keys::KvValueKey::new(input.key.clone());
+	let subspace = keys::subspace(replica_id);
+	let packed_key = subspace.pack(&kv_key);
+
+	let value = ctx
+		.udb()?
+		.run(|tx| {
+			let packed_key = packed_key.clone();
+			let kv_key = kv_key.clone();
+			async move {
+				let value = tx.get(&packed_key, Serializable).await?;
+				if let Some(v) = value {
+					Ok(Some(kv_key.deserialize(&v)?))
+				} else {
+					Ok(None)
+				}
+			}
+		})
+		.await?;
+
+	Ok(Output { value })
+}
+
+/// Waits until the given instance is committed on this replica.
+async fn wait_for_commit(
+	ctx: &OperationCtx,
+	replica_id: ReplicaId,
+	instance: &protocol::Instance,
+) -> Result<()> {
+	let start = Instant::now();
+
+	loop {
+		let committed = ctx
+			.udb()?
+			.run(|tx| {
+				let instance = instance.clone();
+				async move { is_committed(&tx, replica_id, &instance).await }
+			})
+			.await?;
+		if committed {
+			return Ok(());
+		}
+
+		if start.elapsed() > consts::LINEARIZABLE_READ_DEP_TIMEOUT {
+			break;
+		}
+
+		tokio::time::sleep(consts::LINEARIZABLE_READ_POLL_INTERVAL).await;
+	}
+
+	// The leader of this instance likely failed before broadcasting the commit
+	tracing::warn!(
+		?instance,
+		"dependency of read barrier not committed, running explicit prepare"
+	);
+
+	let result = ctx
+		.op(crate::ops::explicit_prepare::Input {
+			instance: instance.clone(),
+		})
+		.await?;
+
+	match result {
+		ExplicitPrepareResult::Committed | ExplicitPrepareResult::CommandError(_) => Ok(()),
+		ExplicitPrepareResult::Failed => {
+			bail!("failed to commit dependency {instance:?} of read barrier")
+		}
+	}
+}
+
+async fn is_committed(
+	tx: &universaldb::Transaction,
+	replica_id: ReplicaId,
+	instance: &protocol::Instance,
+) -> Result<bool> {
+	// Compacted instances are committed on every replica
+	if compaction::is_compacted(tx, replica_id, instance).await? {
+		return Ok(true);
+	}
+
+	let log_key = keys::replica::LogEntryKey::new(instance.replica_id, instance.slot_id);
+	let packed_key = keys::subspace(replica_id).pack(&log_key);
+
+	match tx.get(&packed_key, Serializable).await? {
+		Some(bytes) => Ok(matches!(
+			log_key.deserialize(&bytes)?.state,
+			protocol::State::Committed
+		)),
+		None => Ok(false),
+	}
+}
@@ -0,0 +1,113 @@
+use anyhow::*;
+use epoxy_protocol::protocol::ReplicaId;
+use futures_util::TryStreamExt;
+use gas::prelude::*;
+use rivet_api_builder::prelude::*;
+use universaldb::prelude::*;
+use universaldb::{KeySelector, RangeOption, options::StreamingMode};
+
+use crate::keys;
+
+#[derive(Debug)]
+pub struct Input {
+	pub replica_id: ReplicaId,
+	/// Only keys starting with this prefix are returned.
+	pub prefix: Vec<u8>,
+	/// Last key of the previous page.
+	pub after_key: Option<Vec<u8>>,
+	pub limit: usize,
+}
+
+#[derive(Debug)]
+pub struct Output {
+	pub entries: Vec<Entry>,
+	/// Last key of this page. None if there are no more keys.
+	pub last_key: Option<Vec<u8>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+	pub key: Vec<u8>,
+	pub value: Vec<u8>,
+}
+
+/// Lists committed keys with the given prefix in key order from the local KV store.
+///
+/// Like `get_local`, this may not include writes that are committed on other replicas but not yet
+/// on this replica. Each page is read in a single transaction, but pages may observe different
+/// states.
+#[operation]
+pub async fn epoxy_kv_list_local(ctx: &OperationCtx, input: &Input) -> Result<Output> {
+	let subspace = keys::subspace(input.replica_id);
+
+	// Strip the byte string terminator so the packed key matches every key with this prefix
+	let mut prefix_begin = subspace.pack(&(KV, &input.prefix));
+	ensure!(
+		prefix_begin.pop() == Some(0x00),
+		"invalid packed key prefix"
+	);
+	// First key that does not start with the prefix. Appending 0xFF is not enough since keys may
+	// contain raw 0xFF bytes after the prefix.
+	let prefix_end = universaldb::utils::strinc(&prefix_begin);
+
+	let begin = if let Some(after_key) = &input.after_key {
+		// Skip past all entries for the last key. Entries of a key are nested tuples, so they all
+		// sort before the packed key followed by 0xFF.
+		let mut key_after = subspace.pack(&(KV, after_key));
+		key_after.push(0xFF);
+		key_after.max(prefix_begin)
+	} else {
+		prefix_begin
+	};
+
+	let limit = input.limit;
+	let (entries, scanned, last_key) = ctx
+		.udb()?
+		.run(|tx| {
+			let subspace = subspace.clone();
+			let begin = begin.clone();
+			let prefix_end = prefix_end.clone();
+			async move {
+				let mut stream = tx.get_ranges_keyvalues(
+					RangeOption {
+						begin: KeySelector::first_greater_or_equal(begin),
+						end: KeySelector::first_greater_or_equal(prefix_end),
+						limit: Some(limit),
+						mode: StreamingMode::WantAll,
+						..Default::default()
+					},
+					Serializable,
+				);
+
+				let mut entries = Vec::new();
+				let mut scanned = 0;
+				let mut last_key = None;
+				while let Some(kv) = stream.try_next().await? {
+					scanned += 1;
+
+					let (_, key, value_type) =
+						subspace.unpack::<(usize, Vec<u8>, usize)>(kv.key())?;
+
+					// Optimistic cache entries are not part of the committed state
+					if value_type == COMMITTED_VALUE {
+						let kv_key = keys::keys::KvValueKey::new(key.clone());
+						entries.push(Entry {
+							key: key.clone(),
+							value: kv_key.deserialize(kv.value())?,
+						});
+					}
+
+					last_key = Some(key);
+				}
+
+				Ok((entries, scanned, last_key))
+			}
+		})
+		.await?;
+
+	Ok(Output {
+		entries,
+		// Reached the end of the prefix
+		last_key: if scanned < limit { None } else { last_key },
+	})
+}
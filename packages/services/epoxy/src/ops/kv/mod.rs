@@ -1,2 +1,5 @@
+pub mod check_and_set;
+pub mod get_linearizable;
 pub mod get_local;
 pub mod get_optimistic;
+pub mod list_local;
@@ -1,3 +1,5 @@
+pub mod cluster_status;
+pub mod compact_log;
 pub mod explicit_prepare;
 pub mod kv;
 pub mod propose;
@@ -28,6 +28,18 @@ pub struct Input {
 
 #[operation]
 pub async fn epoxy_propose(ctx: &OperationCtx, input: &Input) -> Result<ProposalResult> {
+	let (result, _) = propose_inner(ctx, input.proposal.clone()).await?;
+	Ok(result)
+}
+
+/// Runs a proposal through consensus on this replica.
+///
+/// Returns the result along with the payload that was committed, which includes the final
+/// dependencies of the instance.
+pub async fn propose_inner(
+	ctx: &OperationCtx,
+	proposal: protocol::Proposal,
+) -> Result<(ProposalResult, Payload)> {
 	let replica_id = ctx.config().epoxy_replica_id();
 
 	// Read config
@@ -40,7 +52,7 @@ pub async fn epoxy_propose(ctx: &OperationCtx, input: &Input) -> Result<Proposal
 	let payload = ctx
 		.udb()?
 		.run(move |tx| {
-			let proposal = input.proposal.clone();
+			let proposal = proposal.clone();
 			async move { replica::lead_consensus::lead_consensus(&*tx, replica_id, proposal).await }
 		})
 		.await?;
@@ -64,10 +76,14 @@ pub async fn epoxy_propose(ctx: &OperationCtx, input: &Input) -> Result<Proposal
 
 	match path {
 		Path::PathFast(protocol::PathFast { payload }) => {
-			commit(ctx, &config, replica_id, &quorum_members, payload).await
+			let result = commit(ctx, &config, replica_id, &quorum_members, payload.clone()).await?;
+			Ok((result, payload))
 		}
 		Path::PathSlow(protocol::PathSlow { payload }) => {
-			run_paxos_accept(ctx, &config, replica_id, &quorum_members, payload).await
+			let result =
+				run_paxos_accept(ctx, &config, replica_id, &quorum_members, payload.clone())
+					.await?;
+			Ok((result, payload))
 		}
 	}
 }
@@ -6,6 +6,9 @@ use universaldb::prelude::*;
 use crate::{keys, ops::propose::CommandError, replica::utils};
 
 /// Commits a proposal to KV store.
+///
+/// All commands are validated before any are applied, so a proposal with commands on multiple keys
+/// is applied atomically.
 pub async fn commit_kv(
 	tx: &Transaction,
 	replica_id: ReplicaId,
@@ -36,6 +39,9 @@ pub async fn commit_kv(
 					}));
 				}
 			}
+			protocol::CommandKind::ReadCommand(_) => {
+				// Read barrier does not modify the KV
+			}
 			protocol::CommandKind::NoopCommand => {
 				// No-op command does nothing
 			}
@@ -56,7 +62,7 @@ pub async fn commit_kv(
 		let new_value = match &command.kind {
 			protocol::CommandKind::SetCommand(cmd) => &cmd.value,
 			protocol::CommandKind::CheckAndSetCommand(cmd) => &cmd.new_value,
-			protocol::CommandKind::NoopCommand => {
+			protocol::CommandKind::ReadCommand(_) | protocol::CommandKind::NoopCommand => {
 				continue;
 			}
 		};
@@ -77,3 +83,25 @@ pub async fn commit_kv(
 
 	Result::Ok(None)
 }
+
+/// Records that the commands of an instance were not applied.
+pub fn record_command_error(
+	tx: &Transaction,
+	replica_id: ReplicaId,
+	instance: &protocol::Instance,
+) {
+	let subspace = keys::subspace(replica_id);
+	let key = keys::replica::CommandErrorKey::new(instance.replica_id, instance.slot_id);
+	tx.set(&subspace.pack(&key), &[]);
+}
+
+/// Returns true if the commands of an instance were not applied when it was committed.
+pub async fn has_command_error(
+	tx: &Transaction,
+	replica_id: ReplicaId,
+	instance: &protocol::Instance,
+) -> Result<bool> {
+	let subspace = keys::subspace(replica_id);
+	let key = keys::replica::CommandErrorKey::new(instance.replica_id, instance.slot_id);
+	Ok(tx.get(&subspace.pack(&key), Serializable).await?.is_some())
+}
@@ -0,0 +1,357 @@
+use anyhow::*;
+use epoxy_protocol::protocol::{self, ReplicaId, SlotId};
+use futures_util::TryStreamExt;
+use universaldb::prelude::*;
+use universaldb::{KeySelector, RangeOption, Transaction, options::StreamingMode};
+
+use crate::{keys, replica::utils};
+
+/// Reads the committed prefix of every instance replica on this replica.
+pub async fn read_committed_prefixes(
+	tx: &Transaction,
+	replica_id: ReplicaId,
+) -> Result<Vec<protocol::Instance>> {
+	let subspace = keys::subspace(replica_id);
+	let prefix_subspace = subspace.subspace(&keys::replica::CommittedPrefixKey::subspace());
+
+	let mut stream = tx.get_ranges_keyvalues(
+		RangeOption {
+			mode: StreamingMode::WantAll,
+			..(&prefix_subspace).into()
+		},
+		Serializable,
+	);
+
+	let mut prefixes = Vec::new();
+	while let Some(kv) = stream.try_next().await? {
+		let key = subspace.unpack::<keys::replica::CommittedPrefixKey>(kv.key())?;
+		let slot_id = key.deserialize(kv.value())?;
+
+		prefixes.push(protocol::Instance {
+			replica_id: key.instance_replica_id,
+			slot_id,
+		});
+	}
+
+	Ok(prefixes)
+}
+
+/// Reads the snapshot watermark of every instance replica on this replica.
+pub async fn read_snapshot_watermark(
+	tx: &Transaction,
+	replica_id: ReplicaId,
+) -> Result<Vec<protocol::Instance>> {
+	let subspace = keys::subspace(replica_id);
+	let watermark_subspace = subspace.subspace(&keys::replica::SnapshotWatermarkKey::subspace());
+
+	let mut stream = tx.get_ranges_keyvalues(
+		RangeOption {
+			mode: StreamingMode::WantAll,
+			..(&watermark_subspace).into()
+		},
+		Serializable,
+	);
+
+	let mut watermark = Vec::new();
+	while let Some(kv) = stream.try_next().await? {
+		let key = subspace.unpack::<keys::replica::SnapshotWatermarkKey>(kv.key())?;
+		let slot_id = key.deserialize(kv.value())?;
+
+		watermark.push(protocol::Instance {
+			replica_id: key.instance_replica_id,
+			slot_id,
+		});
+	}
+
+	Ok(watermark)
+}
+
+/// Returns the highest compacted slot for the given instance replica, or 0 if nothing has been
+/// compacted.
+pub async fn get_snapshot_watermark(
+	tx: &Transaction,
+	replica_id: ReplicaId,
+	instance_replica_id: ReplicaId,
+) -> Result<SlotId> {
+	let watermark_key = keys::replica::SnapshotWatermarkKey::new(instance_replica_id);
+	let packed_key = keys::subspace(replica_id).pack(&watermark_key);
+
+	match tx.get(&packed_key, Serializable).await? {
+		Some(bytes) => watermark_key.deserialize(&bytes),
+		None => Ok(0),
+	}
+}
+
+/// Returns true if the instance has been compacted and its log entry no longer exists.
+pub async fn is_compacted(
+	tx: &Transaction,
+	replica_id: ReplicaId,
+	instance: &protocol::Instance,
+) -> Result<bool> {
+	let watermark = get_snapshot_watermark(tx, replica_id, instance.replica_id).await?;
+	Ok(instance.slot_id <= watermark)
+}
+
+/// Sets the snapshot watermark and committed prefix after installing a snapshot from another
+/// replica. Never moves either value backwards.
+pub async fn install_snapshot_watermark(
+	tx: &Transaction,
+	replica_id: ReplicaId,
+	watermark: &[protocol::Instance],
+) -> Result<()> {
+	let subspace = keys::subspace(replica_id);
+
+	for instance in watermark {
+		let current_watermark = get_snapshot_watermark(tx, replica_id, instance.replica_id).await?;
+		if instance.slot_id > current_watermark {
+			let watermark_key = keys::replica::SnapshotWatermarkKey::new(instance.replica_id);
+			tx.set(
+				&subspace.pack(&watermark_key),
+				&watermark_key.serialize(instance.slot_id)?,
+			);
+		}
+
+		let prefix_key = keys::replica::CommittedPrefixKey::new(instance.replica_id);
+		let packed_prefix_key = subspace.pack(&prefix_key);
+		let current_prefix = match tx.get(&packed_prefix_key, Serializable).await? {
+			Some(bytes) => prefix_key.deserialize(&bytes)?,
+			None => 0,
+		};
+		if instance.slot_id > current_prefix {
+			tx.set(&packed_prefix_key, &prefix_key.serialize(instance.slot_id)?);
+		}
+	}
+
+	Ok(())
+}
+
+/// Advances the committed prefix of an instance replica by scanning the log for consecutive
+/// committed entries. Scans at most `limit` entries.
+///
+/// The prefix stops at the first slot that is missing or not committed on this replica.
+///
+/// Returns the previous and the new committed prefix.
+pub async fn advance_committed_prefix(
+	tx: &Transaction,
+	replica_id: ReplicaId,
+	instance_replica_id: ReplicaId,
+	limit: u64,
+) -> Result<(SlotId, SlotId)> {
+	let subspace = keys::subspace(replica_id);
+
+	let prefix_key = keys::replica::CommittedPrefixKey::new(instance_replica_id);
+	let packed_prefix_key = subspace.pack(&prefix_key);
+	let stored_prefix = match tx.get(&packed_prefix_key, Serializable).await? {
+		Some(bytes) => prefix_key.deserialize(&bytes)?,
+		None => 0,
+	};
+
+	// Compacted slots are committed by definition
+	let watermark = get_snapshot_watermark(tx, replica_id, instance_replica_id).await?;
+	let mut prefix = stored_prefix.max(watermark);
+
+	let (_, end) = subspace.subspace(&(LOG, instance_replica_id)).range();
+	let begin = subspace.pack(&keys::replica::LogEntryKey::new(
+		instance_replica_id,
+		prefix + 1,
+	));
+
+	// Snapshot reads are safe here since log entries only ever progress towards committed. Reading
+	// a stale entry can only stop the prefix early.
+	let mut stream = tx.get_ranges_keyvalues(
+		RangeOption {
+			begin: KeySelector::first_greater_or_equal(begin),
+			end: KeySelector::first_greater_or_equal(end),
+			limit: Some(limit as usize),
+			mode: StreamingMode::WantAll,
+			..Default::default()
+		},
+		Snapshot,
+	);
+
+	while let Some(kv) = stream.try_next().await? {
+		let log_key = subspace.unpack::<keys::replica::LogEntryKey>(kv.key())?;
+
+		// Gap in the log, this replica has not seen this instance yet
+		if log_key.instance_slot_id != prefix + 1 {
+			break;
+		}
+
+		let log_entry = log_key.deserialize(kv.value())?;
+		if !matches!(log_entry.state, protocol::State::Committed) {
+			break;
+		}
+
+		prefix = log_key.instance_slot_id;
+	}
+
+	if prefix != stored_prefix {
+		tx.set(&packed_prefix_key, &prefix_key.serialize(prefix)?);
+	}
+
+	Ok((stored_prefix, prefix))
+}
+
+/// Removes log entries of an instance replica up to and including `up_to_slot` and moves the
+/// snapshot watermark forward. Removes at most `limit` entries.
+///
+/// The caller must guarantee that all instances up to `up_to_slot` are committed on every replica
+/// in the cluster. The KV state already reflects these instances since commands are applied to KV
+/// when committed, so the KV state itself acts as the snapshot.
+///
+/// Returns the new snapshot watermark.
+pub async fn compact_instances(
+	tx: &Transaction,
+	replica_id: ReplicaId,
+	instance_replica_id: ReplicaId,
+	up_to_slot: SlotId,
+	limit: u64,
+) -> Result<SlotId> {
+	let subspace = keys::subspace(replica_id);
+
+	let watermark = get_snapshot_watermark(tx, replica_id, instance_replica_id).await?;
+	if up_to_slot <= watermark {
+		return Ok(watermark);
+	}
+
+	let begin = subspace.pack(&keys::replica::LogEntryKey::new(
+		instance_replica_id,
+		watermark + 1,
+	));
+	let end = subspace.pack(&keys::replica::LogEntryKey::new(
+		instance_replica_id,
+		up_to_slot + 1,
+	));
+
+	let mut stream = tx.get_ranges_keyvalues(
+		RangeOption {
+			begin: KeySelector::first_greater_or_equal(begin),
+			end: KeySelector::first_greater_or_equal(end),
+			limit: Some(limit as usize),
+			mode: StreamingMode::WantAll,
+			..Default::default()
+		},
+		Serializable,
+	);
+
+	let mut removed = 0;
+	let mut last_slot = watermark;
+	while let Some(kv) = stream.try_next().await? {
+		let log_key = subspace.unpack::<keys::replica::LogEntryKey>(kv.key())?;
+		let log_entry = log_key.deserialize(kv.value())?;
+
+		ensure!(
+			matches!(log_entry.state, protocol::State::Committed),
+			"cannot compact uncommitted instance ({}, {})",
+			log_key.instance_replica_id,
+			log_key.instance_slot_id,
+		);
+
+		// Remove the instance from the interference index
+		for command in &log_entry.commands {
+			if let Some(key) = utils::extract_key_from_command(command) {
+				tx.clear(&subspace.pack(&keys::replica::KeyInstanceKey::new(
+					key,
+					log_key.instance_replica_id,
+					log_key.instance_slot_id,
+				)));
+			}
+		}
+
+		tx.clear(&subspace.pack(&keys::replica::InstanceBallotKey::new(
+			log_key.instance_replica_id,
+			log_key.instance_slot_id,
+		)));
+		tx.clear(&subspace.pack(&keys::replica::CommandErrorKey::new(
+			log_key.instance_replica_id,
+			log_key.instance_slot_id,
+		)));
+		tx.clear(kv.key());
+
+		removed += 1;
+		last_slot = log_key.instance_slot_id;
+	}
+
+	// Fewer entries than the limit means everything up to the target slot was removed
+	let new_watermark = if removed < limit {
+		up_to_slot
+	} else {
+		last_slot
+	};
+
+	let watermark_key = keys::replica::SnapshotWatermarkKey::new(instance_replica_id);
+	tx.set(
+		&subspace.pack(&watermark_key),
+		&watermark_key.serialize(new_watermark)?,
+	);
+
+	tracing::debug!(
+		?replica_id,
+		?instance_replica_id,
+		removed,
+		new_watermark,
+		"compacted instances"
+	);
+
+	Ok(new_watermark)
+}
+
+/// Reads a chunk of committed KV values after the given key.
+///
+/// Returns the entries and the last key in the chunk, or `None` if there are no more entries.
+pub async fn read_snapshot_chunk(
+	tx: &Transaction,
+	replica_id: ReplicaId,
+	after_key: Option<Vec<u8>>,
+	count: u64,
+) -> Result<(Vec<protocol::SnapshotEntry>, Option<Vec<u8>>)> {
+	let subspace = keys::subspace(replica_id);
+	let kv_subspace = subspace.subspace(&(KV,));
+	let (kv_begin, kv_end) = kv_subspace.range();
+
+	let begin = if let Some(after_key) = &after_key {
+		// Skip past all entries for the last key
+		let mut key_after = subspace.pack(&(KV, after_key));
+		key_after.push(0xFF);
+		key_after
+	} else {
+		kv_begin
+	};
+
+	let mut stream = tx.get_ranges_keyvalues(
+		RangeOption {
+			begin: KeySelector::first_greater_or_equal(begin),
+			end: KeySelector::first_greater_or_equal(kv_end),
+			limit: Some(count as usize),
+			mode: StreamingMode::WantAll,
+			..Default::default()
+		},
+		Serializable,
+	);
+
+	let mut entries = Vec::new();
+	let mut scanned = 0;
+	let mut last_key = None;
+	while let Some(kv) = stream.try_next().await? {
+		scanned += 1;
+
+		let (_, key, value_type) = subspace.unpack::<(usize, Vec<u8>, usize)>(kv.key())?;
+
+		// Optimistic cache entries are not part of the committed state
+		if value_type == COMMITTED_VALUE {
+			entries.push(protocol::SnapshotEntry {
+				key: key.clone(),
+				value: kv.value().to_vec(),
+			});
+		}
+
+		last_key = Some(key);
+	}
+
+	// Reached the end of the KV subspace
+	if scanned < count {
+		last_key = None;
+	}
+
+	Ok((entries, last_key))
+}
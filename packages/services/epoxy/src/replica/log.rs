@@ -3,7 +3,10 @@ use epoxy_protocol::protocol::{self, ReplicaId};
 use universaldb::Transaction;
 use universaldb::utils::{FormalKey, IsolationLevel::*};
 
-use crate::{keys, replica::utils};
+use crate::{
+	keys,
+	replica::{compaction, utils},
+};
 
 /// Returns the numeric order of a state for comparison.
 /// NONE < PREACCEPTED < ACCEPTED < COMMITTED
@@ -23,6 +26,14 @@ pub async fn update_log(
 ) -> Result<()> {
 	tracing::debug!(?replica_id, ?instance, ?log_entry.state, "updating log");
 
+	// Compacted instances are committed on every replica, so any write to them is stale
+	ensure!(
+		!compaction::is_compacted(tx, replica_id, instance).await?,
+		"instance ({}, {}) has already been compacted",
+		instance.replica_id,
+		instance.slot_id,
+	);
+
 	let subspace = keys::subspace(replica_id);
 	let log_key = keys::replica::LogEntryKey::new(instance.replica_id, instance.slot_id);
 	let packed_key = subspace.pack(&log_key);
@@ -86,6 +86,17 @@ pub async fn message_request(
 				instances,
 			})
 		}
+		protocol::RequestKind::GetCommandErrorsRequest(req) => {
+			let response = ctx
+				.udb()?
+				.run(move |tx| {
+					let req = req.clone();
+					async move { replica::messages::get_command_errors(&*tx, replica_id, req).await }
+				})
+				.await?;
+
+			protocol::ResponseKind::GetCommandErrorsResponse(response)
+		}
 		protocol::RequestKind::HealthCheckRequest => {
 			// Simple health check - just return success
 			tracing::debug!("received health check request");
@@ -141,6 +152,49 @@ pub async fn message_request(
 				value: result.value,
 			})
 		}
+		protocol::RequestKind::KvGetLinearizableRequest(req) => {
+			// Run the read barrier from this replica on behalf of the sender
+			let result = ctx
+				.op(ops::kv::get_linearizable::Input {
+					key: req.key.clone(),
+				})
+				.await?;
+
+			protocol::ResponseKind::KvGetLinearizableResponse(protocol::KvGetLinearizableResponse {
+				value: result.value,
+			})
+		}
+		protocol::RequestKind::GetCommittedPrefixRequest => {
+			let response = ctx
+				.udb()?
+				.run(move |tx| async move {
+					replica::messages::get_committed_prefix(&*tx, replica_id).await
+				})
+				.await?;
+
+			protocol::ResponseKind::GetCommittedPrefixResponse(response)
+		}
+		protocol::RequestKind::GetReplicaStatusRequest => {
+			let response = ctx
+				.udb()?
+				.run(move |tx| async move {
+					replica::messages::get_replica_status(&*tx, replica_id).await
+				})
+				.await?;
+
+			protocol::ResponseKind::GetReplicaStatusResponse(response)
+		}
+		protocol::RequestKind::DownloadSnapshotRequest(req) => {
+			let response = ctx
+				.udb()?
+				.run(move |tx| {
+					let req = req.clone();
+					async move { replica::messages::download_snapshot(&*tx, replica_id, req).await }
+				})
+				.await?;
+
+			protocol::ResponseKind::DownloadSnapshotResponse(response)
+		}
 	};
 
 	Ok(protocol::Response { kind })
@@ -33,7 +33,12 @@ pub async fn commit(
 
 	// Commit commands if requested
 	let cmd_err = if commit_to_kv {
-		crate::replica::commit_kv::commit_kv(&*tx, replica_id, &proposal.commands).await?
+		let cmd_err =
+			crate::replica::commit_kv::commit_kv(&*tx, replica_id, &proposal.commands).await?;
+		if cmd_err.is_some() {
+			crate::replica::commit_kv::record_command_error(tx, replica_id, &instance);
+		}
+		cmd_err
 	} else {
 		tracing::debug!(?replica_id, ?instance, "skipping kv commit");
 		None
@@ -33,6 +33,9 @@ pub async fn committed(
 	// Commit commands
 	let cmd_err =
 		crate::replica::commit_kv::commit_kv(&*tx, replica_id, &payload.proposal.commands).await?;
+	if cmd_err.is_some() {
+		crate::replica::commit_kv::record_command_error(tx, replica_id, instance);
+	}
 
 	tracing::debug!(?replica_id, ?instance, ?cmd_err, "committed");
 
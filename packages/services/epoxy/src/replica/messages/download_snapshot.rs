@@ -0,0 +1,24 @@
+use anyhow::Result;
+use epoxy_protocol::protocol::{self, ReplicaId};
+use universaldb::Transaction;
+
+use crate::replica::compaction;
+
+pub async fn download_snapshot(
+	tx: &Transaction,
+	replica_id: ReplicaId,
+	req: protocol::DownloadSnapshotRequest,
+) -> Result<protocol::DownloadSnapshotResponse> {
+	tracing::info!(?replica_id, "handling download snapshot message");
+
+	// Read in the same transaction as the KV values so the watermark matches the snapshot
+	let watermark = compaction::read_snapshot_watermark(tx, replica_id).await?;
+	let (entries, last_key) =
+		compaction::read_snapshot_chunk(tx, replica_id, req.after_key, req.count).await?;
+
+	Ok(protocol::DownloadSnapshotResponse {
+		watermark,
+		entries,
+		last_key,
+	})
+}
@@ -0,0 +1,26 @@
+use anyhow::Result;
+use epoxy_protocol::protocol::{self, ReplicaId};
+use universaldb::Transaction;
+
+use crate::replica::commit_kv;
+
+pub async fn get_command_errors(
+	tx: &Transaction,
+	replica_id: ReplicaId,
+	req: protocol::GetCommandErrorsRequest,
+) -> Result<protocol::GetCommandErrorsResponse> {
+	tracing::debug!(
+		?replica_id,
+		count = req.instances.len(),
+		"handling get command errors message"
+	);
+
+	let mut instances = Vec::new();
+	for instance in req.instances {
+		if commit_kv::has_command_error(tx, replica_id, &instance).await? {
+			instances.push(instance);
+		}
+	}
+
+	Ok(protocol::GetCommandErrorsResponse { instances })
+}
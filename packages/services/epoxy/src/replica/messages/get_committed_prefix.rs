@@ -0,0 +1,16 @@
+use anyhow::Result;
+use epoxy_protocol::protocol::{self, ReplicaId};
+use universaldb::Transaction;
+
+use crate::replica::compaction;
+
+pub async fn get_committed_prefix(
+	tx: &Transaction,
+	replica_id: ReplicaId,
+) -> Result<protocol::GetCommittedPrefixResponse> {
+	tracing::debug!(?replica_id, "handling get committed prefix message");
+
+	let prefixes = compaction::read_committed_prefixes(tx, replica_id).await?;
+
+	Ok(protocol::GetCommittedPrefixResponse { prefixes })
+}
@@ -0,0 +1,50 @@
+use anyhow::Result;
+use epoxy_protocol::protocol::{self, ReplicaId};
+use futures_util::TryStreamExt;
+use universaldb::prelude::*;
+use universaldb::{RangeOption, Transaction, options::StreamingMode};
+
+use crate::{keys, replica::compaction, utils};
+
+pub async fn get_replica_status(
+	tx: &Transaction,
+	replica_id: ReplicaId,
+) -> Result<protocol::GetReplicaStatusResponse> {
+	tracing::debug!(?replica_id, "handling get replica status message");
+
+	let config = utils::read_config(tx, replica_id).await?;
+	let committed_prefixes = compaction::read_committed_prefixes(tx, replica_id).await?;
+	let watermark = compaction::read_snapshot_watermark(tx, replica_id).await?;
+
+	// Read the highest slot in the log for each instance replica
+	let subspace = keys::subspace(replica_id);
+	let mut last_slots = Vec::new();
+	for instance_replica_id in utils::get_all_replicas(&config) {
+		let log_subspace = subspace.subspace(&(LOG, instance_replica_id));
+
+		let mut stream = tx.get_ranges_keyvalues(
+			RangeOption {
+				limit: Some(1),
+				reverse: true,
+				mode: StreamingMode::WantAll,
+				..(&log_subspace).into()
+			},
+			Snapshot,
+		);
+
+		if let Some(kv) = stream.try_next().await? {
+			let log_key = subspace.unpack::<keys::replica::LogEntryKey>(kv.key())?;
+			last_slots.push(protocol::Instance {
+				replica_id: instance_replica_id,
+				slot_id: log_key.instance_slot_id,
+			});
+		}
+	}
+
+	Ok(protocol::GetReplicaStatusResponse {
+		config,
+		last_slots,
+		committed_prefixes,
+		watermark,
+	})
+}
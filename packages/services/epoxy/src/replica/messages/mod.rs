@@ -3,6 +3,10 @@ pub mod accepted;
 pub mod commit;
 pub mod committed;
 pub mod download_instances;
+pub mod download_snapshot;
+pub mod get_command_errors;
+pub mod get_committed_prefix;
+pub mod get_replica_status;
 pub mod pre_accept;
 pub mod prepare;
 
@@ -11,5 +15,9 @@ pub use accepted::accepted;
 pub use commit::commit;
 pub use committed::committed;
 pub use download_instances::download_instances;
+pub use download_snapshot::download_snapshot;
+pub use get_command_errors::get_command_errors;
+pub use get_committed_prefix::get_committed_prefix;
+pub use get_replica_status::get_replica_status;
 pub use pre_accept::pre_accept;
 pub use prepare::prepare;
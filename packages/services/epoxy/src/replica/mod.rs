@@ -1,5 +1,6 @@
 pub mod ballot;
 pub mod commit_kv;
+pub mod compaction;
 pub mod decide_path;
 pub mod lead_consensus;
 pub mod log;
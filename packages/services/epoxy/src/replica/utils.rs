@@ -83,6 +83,8 @@ pub fn extract_key_from_command(command: &protocol::Command) -> Option<Vec<u8>>
 	match &command.kind {
 		protocol::CommandKind::SetCommand(cmd) => Some(cmd.key.clone()),
 		protocol::CommandKind::CheckAndSetCommand(cmd) => Some(cmd.key.clone()),
+		// Read commands must interfere with writes to the same key to act as a read barrier
+		protocol::CommandKind::ReadCommand(cmd) => Some(cmd.key.clone()),
 		protocol::CommandKind::NoopCommand => {
 			// Noop command has no effects on the KV and does not cause interference
 			None
@@ -7,6 +7,7 @@ use serde::{Deserialize, Serialize};
 use crate::types;
 
 pub mod reconfigure;
+pub mod remove_replica;
 pub mod replica_status_change;
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -37,6 +38,9 @@ pub async fn epoxy_coordinator(ctx: &mut WorkflowCtx, _input: &Input) -> Result<
 				Main::ReplicaStatusChange(sig) => {
 					replica_status_change::replica_status_change(ctx, sig).await?;
 				}
+				Main::RemoveReplica(sig) => {
+					remove_replica::remove_replica(ctx, sig).await?;
+				}
 			}
 
 			Ok(Loop::<()>::Continue)
@@ -81,7 +85,14 @@ pub struct ReplicaStatusChange {
 	pub status: types::ReplicaStatus,
 }
 
+/// Removes a replica from the cluster config, for example if its datacenter is permanently gone.
+#[signal("epoxy_coordinator_remove_replica")]
+pub struct RemoveReplica {
+	pub replica_id: protocol::ReplicaId,
+}
+
 join_signal!(Main {
 	Reconfigure,
 	ReplicaStatusChange,
+	RemoveReplica,
 });
@@ -0,0 +1,95 @@
+use anyhow::*;
+use epoxy_protocol::protocol;
+use gas::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+	State,
+	replica_status_change::{IncrementEpochInput, NotifyAllReplicasInput},
+};
+use crate::types;
+
+pub async fn remove_replica(ctx: &mut WorkflowCtx, signal: super::RemoveReplica) -> Result<()> {
+	let output = ctx
+		.activity(RemoveReplicaFromConfigInput {
+			replica_id: signal.replica_id,
+		})
+		.await?;
+
+	let RemoveReplicaFromConfigOutput::Removed { was_active } = output else {
+		return Ok(());
+	};
+
+	// Quorum size changes when an active replica is removed
+	if was_active {
+		ctx.activity(IncrementEpochInput {}).await?;
+	}
+
+	let notify_out = ctx.activity(NotifyAllReplicasInput {}).await?;
+
+	let replica_id = ctx.config().epoxy_replica_id();
+	ctx.msg(super::ConfigChangeMessage {
+		config: notify_out.config,
+	})
+	.tag("replica", replica_id)
+	.send()
+	.await?;
+
+	Ok(())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Hash)]
+pub struct RemoveReplicaFromConfigInput {
+	pub replica_id: protocol::ReplicaId,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum RemoveReplicaFromConfigOutput {
+	Removed { was_active: bool },
+	NotFound,
+	IsCoordinator,
+}
+
+#[activity(RemoveReplicaFromConfig)]
+pub async fn remove_replica_from_config(
+	ctx: &ActivityCtx,
+	input: &RemoveReplicaFromConfigInput,
+) -> Result<RemoveReplicaFromConfigOutput> {
+	let mut state = ctx.state::<State>()?;
+
+	if input.replica_id == state.config.coordinator_replica_id {
+		tracing::warn!(replica_id = ?input.replica_id, "cannot remove coordinator replica");
+		return Ok(RemoveReplicaFromConfigOutput::IsCoordinator);
+	}
+
+	let Some(idx) = state
+		.config
+		.replicas
+		.iter()
+		.position(|r| r.replica_id == input.replica_id)
+	else {
+		tracing::warn!(replica_id = ?input.replica_id, "replica to remove not found");
+		return Ok(RemoveReplicaFromConfigOutput::NotFound);
+	};
+
+	let replica = state.config.replicas.remove(idx);
+
+	// The replica will be added again on the next reconfigure if it is still in the topology
+	if ctx
+		.config()
+		.topology()
+		.dc_for_label(input.replica_id as u16)
+		.is_some()
+	{
+		tracing::warn!(
+			replica_id = ?input.replica_id,
+			"removed replica is still in the topology and will rejoin on the next reconfigure"
+		);
+	}
+
+	tracing::info!(replica_id = ?input.replica_id, status = ?replica.status, "removed replica");
+
+	Ok(RemoveReplicaFromConfigOutput::Removed {
+		was_active: matches!(replica.status, types::ReplicaStatus::Active),
+	})
+}
@@ -95,7 +95,7 @@ pub struct NotifyAllReplicasInput {}
 
 #[derive(Debug, Clone, Serialize, Deserialize, Hash)]
 pub struct NotifyAllReplicasOutput {
-	config: types::ClusterConfig,
+	pub config: types::ClusterConfig,
 }
 
 #[activity(NotifyAllReplicas)]
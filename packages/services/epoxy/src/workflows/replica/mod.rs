@@ -20,8 +20,11 @@ pub async fn epoxy_replica(ctx: &mut WorkflowCtx, input: &Input) -> Result<()> {
 	// Main loop
 	ctx.repeat(|ctx| {
 		async move {
-			// Noop for now
-			ctx.listen::<Main>().await?;
+			ctx.listen_with_timeout::<Main>(crate::consts::COMPACTION_INTERVAL)
+				.await?;
+
+			ctx.activity(CompactLogInput {}).await?;
+
 			Ok(Loop::<()>::Continue)
 		}
 		.boxed()
@@ -31,6 +34,18 @@ pub async fn epoxy_replica(ctx: &mut WorkflowCtx, input: &Input) -> Result<()> {
 	Ok(())
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, Hash)]
+pub struct CompactLogInput {}
+
+#[activity(CompactLog)]
+pub async fn compact_log(ctx: &ActivityCtx, _input: &CompactLogInput) -> Result<()> {
+	let res = ctx.op(crate::ops::compact_log::Input {}).await?;
+
+	tracing::debug!(watermark = ?res.watermark, "compacted replica log");
+
+	Ok(())
+}
+
 #[signal("epoxy_replica_begin_learning")]
 pub struct BeginLearning {
 	pub config: types::ClusterConfig,
@@ -18,6 +18,79 @@ pub async fn setup_replica(ctx: &mut WorkflowCtx, _input: &super::Input) -> Resu
 	// Wait for cooridinator to send begin learning signal
 	let begin_learning = ctx.listen::<super::BeginLearning>().await?;
 
+	// Install a snapshot of the KV state from an active replica if its log has been compacted. Log
+	// entries below the snapshot watermark no longer exist on any replica.
+	let snapshot_replica = begin_learning.config.replicas.iter().find(|r| {
+		r.replica_id != ctx.config().epoxy_replica_id()
+			&& matches!(r.status, types::ReplicaStatus::Active)
+	});
+	if let Some(snapshot_replica) = snapshot_replica.cloned() {
+		#[derive(Serialize, Deserialize)]
+		struct SnapshotState {
+			after_key: Option<Vec<u8>>,
+			/// Watermark of the first chunk. Every following chunk must have the same watermark.
+			watermark: Option<Vec<types::Instance>>,
+			/// Total downloaded entries so far.
+			total_downloaded_entries: u64,
+		}
+
+		ctx.loope(
+			SnapshotState {
+				after_key: None,
+				watermark: None,
+				total_downloaded_entries: 0,
+			},
+			|ctx, state| {
+				let learning_config = begin_learning.config.clone();
+				let snapshot_replica = snapshot_replica.clone();
+				async move {
+					let output = ctx
+						.activity(DownloadSnapshotChunkInput {
+							learning_config,
+							from_replica_id: snapshot_replica.replica_id,
+							after_key: state.after_key.clone(),
+							watermark: state.watermark.clone(),
+							count: crate::consts::DOWNLOAD_SNAPSHOT_COUNT,
+							total_downloaded_entries: state.total_downloaded_entries,
+						})
+						.await?;
+
+					// Replica compacted its log while downloading, earlier chunks may be missing
+					// instances that no longer exist in its log
+					if output.restart {
+						tracing::info!("snapshot watermark changed, restarting snapshot download");
+						state.after_key = None;
+						state.watermark = None;
+						state.total_downloaded_entries = 0;
+						return Ok(Loop::Continue);
+					}
+
+					// Nothing has been compacted, the full log will be downloaded instead
+					if output.watermark.is_empty() {
+						return Ok(Loop::Break(()));
+					}
+
+					state.watermark = Some(output.watermark);
+					state.total_downloaded_entries += output.entry_count;
+
+					if let Some(last_key) = output.last_key {
+						state.after_key = Some(last_key);
+					} else {
+						tracing::info!(
+							total_downloaded_entries = state.total_downloaded_entries,
+							"finished downloading snapshot"
+						);
+						return Ok(Loop::Break(()));
+					}
+
+					Ok(Loop::<()>::Continue)
+				}
+				.boxed()
+			},
+		)
+		.await?;
+	}
+
 	// TODO: Paralellize replicas
 	let total_replicas = begin_learning.config.replicas.len();
 	let mut replica_index = 0;
@@ -133,6 +206,170 @@ pub async fn setup_replica(ctx: &mut WorkflowCtx, _input: &super::Input) -> Resu
 	Ok(())
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, Hash)]
+pub struct DownloadSnapshotChunkInput {
+	/// Config received from BeginLearning
+	pub learning_config: types::ClusterConfig,
+	pub from_replica_id: protocol::ReplicaId,
+	pub after_key: Option<Vec<u8>>,
+	/// Watermark of the first chunk. None if this is the first chunk.
+	pub watermark: Option<Vec<types::Instance>>,
+	pub count: u64,
+	pub total_downloaded_entries: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadSnapshotChunkOutput {
+	/// The last key in the downloaded chunk.
+	///
+	/// Used for pagination.
+	///
+	/// If none, will assume there are no more chunks and stop downloading.
+	pub last_key: Option<Vec<u8>>,
+	/// Watermark that was installed. Empty if the replica has not compacted its log.
+	pub watermark: Vec<types::Instance>,
+	pub entry_count: u64,
+	/// The watermark changed since the first chunk. The local KV was cleared and the snapshot has to
+	/// be downloaded again from the start.
+	#[serde(default)]
+	pub restart: bool,
+}
+
+/// Downloads a chunk of committed KV values from a replica and installs them along with the
+/// snapshot watermark.
+///
+/// Unlike `apply_log_entry`, values are written to KV directly. The snapshot reflects every
+/// instance up to the watermark, and instances above the watermark are replayed on top of it in
+/// the `recover_keys_chunk` phase.
+#[activity(DownloadSnapshotChunk)]
+pub async fn download_snapshot_chunk(
+	ctx: &ActivityCtx,
+	input: &DownloadSnapshotChunkInput,
+) -> Result<DownloadSnapshotChunkOutput> {
+	let replica_id = ctx.config().epoxy_replica_id();
+	let config = &input.learning_config;
+	let proto_config: protocol::ClusterConfig = input.learning_config.clone().into();
+
+	tracing::info!(
+		from_replica_id = ?input.from_replica_id,
+		total_downloaded_entries = input.total_downloaded_entries,
+		after_key_len = input.after_key.as_ref().map(|k| k.len()),
+		count = input.count,
+		"downloading snapshot chunk"
+	);
+
+	// Send download request to replica
+	let request = protocol::Request {
+		from_replica_id: config.coordinator_replica_id,
+		to_replica_id: input.from_replica_id,
+		kind: protocol::RequestKind::DownloadSnapshotRequest(protocol::DownloadSnapshotRequest {
+			after_key: input.after_key.clone(),
+			count: input.count,
+		}),
+	};
+
+	let response =
+		crate::http_client::send_message(&ApiCtx::new_from_activity(&ctx)?, &proto_config, request)
+			.await?;
+
+	let protocol::ResponseKind::DownloadSnapshotResponse(download_response) = response.kind else {
+		bail!("unexpected response type for download snapshot request");
+	};
+
+	let watermark = download_response
+		.watermark
+		.into_iter()
+		.map(Into::into)
+		.collect::<Vec<types::Instance>>();
+
+	// Each chunk is read in a separate transaction. If the replica compacted its log in between,
+	// values in earlier chunks may not reflect instances that were removed from its log since, so
+	// the snapshot is no longer consistent with the instances that will be downloaded after it.
+	if let Some(first_watermark) = &input.watermark {
+		if !same_watermark(first_watermark, &watermark) {
+			tracing::info!(
+				?first_watermark,
+				?watermark,
+				"snapshot watermark changed between chunks"
+			);
+
+			ctx.udb()?
+				.run(|tx| async move {
+					let subspace = crate::keys::subspace(replica_id);
+					tx.clear_subspace_range(&subspace.subspace(&(KV,)));
+
+					Result::Ok(())
+				})
+				.await?;
+
+			return Ok(DownloadSnapshotChunkOutput {
+				last_key: None,
+				watermark: Vec::new(),
+				entry_count: 0,
+				restart: true,
+			});
+		}
+	}
+
+	if watermark.is_empty() {
+		tracing::info!("replica has not compacted its log, skipping snapshot");
+
+		return Ok(DownloadSnapshotChunkOutput {
+			last_key: None,
+			watermark,
+			entry_count: 0,
+			restart: false,
+		});
+	}
+
+	let entries = download_response.entries;
+	let entry_count = entries.len() as u64;
+
+	ctx.udb()?
+		.run(|tx| {
+			let entries = entries.clone();
+			let watermark = watermark
+				.iter()
+				.cloned()
+				.map(Into::into)
+				.collect::<Vec<protocol::Instance>>();
+
+			async move {
+				let subspace = crate::keys::subspace(replica_id);
+
+				for entry in entries {
+					let value_key = crate::keys::keys::KvValueKey::new(entry.key);
+					tx.set(
+						&subspace.pack(&value_key),
+						&value_key.serialize(entry.value)?,
+					);
+				}
+
+				crate::replica::compaction::install_snapshot_watermark(
+					&*tx, replica_id, &watermark,
+				)
+				.await?;
+
+				Result::Ok(())
+			}
+		})
+		.await?;
+
+	tracing::info!(entry_count, "installed snapshot chunk");
+
+	Ok(DownloadSnapshotChunkOutput {
+		last_key: download_response.last_key,
+		watermark,
+		entry_count,
+		restart: false,
+	})
+}
+
+/// Compares watermarks regardless of the order of instance replicas.
+fn same_watermark(a: &[types::Instance], b: &[types::Instance]) -> bool {
+	a.len() == b.len() && a.iter().all(|x| b.contains(x))
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Hash)]
 pub struct DownloadInstancesChunkInput {
 	/// Config received from BeginLearning
@@ -208,12 +445,78 @@ pub async fn download_instances_chunk(
 		apply_log_entry(ctx, &entry.log_entry, &entry.instance).await?;
 	}
 
+	// Keys are recovered separately, so fetch which multi-key proposals failed their checks in
+	// order to keep them atomic during recovery
+	let multi_key_instances = instances
+		.iter()
+		.filter(|entry| {
+			matches!(entry.log_entry.state, protocol::State::Committed)
+				&& is_multi_key(&entry.log_entry.commands)
+		})
+		.map(|entry| entry.instance.clone())
+		.collect::<Vec<_>>();
+	if !multi_key_instances.is_empty() {
+		download_command_errors(ctx, input, &proto_config, multi_key_instances).await?;
+	}
+
 	// Return whether we should continue downloading chunks and the last instance
 	Ok(DownloadInstancesChunkOutput {
 		last_instance: instances.last().map(|entry| entry.instance.clone().into()),
 	})
 }
 
+async fn download_command_errors(
+	ctx: &ActivityCtx,
+	input: &DownloadInstancesChunkInput,
+	proto_config: &protocol::ClusterConfig,
+	instances: Vec<protocol::Instance>,
+) -> Result<()> {
+	let replica_id = ctx.config().epoxy_replica_id();
+
+	let request = protocol::Request {
+		from_replica_id: input.learning_config.coordinator_replica_id,
+		to_replica_id: input.from_replica_id,
+		kind: protocol::RequestKind::GetCommandErrorsRequest(protocol::GetCommandErrorsRequest {
+			instances,
+		}),
+	};
+
+	let response =
+		crate::http_client::send_message(&ApiCtx::new_from_activity(&ctx)?, proto_config, request)
+			.await?;
+
+	let protocol::ResponseKind::GetCommandErrorsResponse(response) = response.kind else {
+		bail!("unexpected response type for get command errors request");
+	};
+
+	tracing::debug!(count = response.instances.len(), "received command errors");
+
+	ctx.udb()?
+		.run(move |tx| {
+			let instances = response.instances.clone();
+			async move {
+				for instance in &instances {
+					crate::replica::commit_kv::record_command_error(&*tx, replica_id, instance);
+				}
+				Result::Ok(())
+			}
+		})
+		.await?;
+
+	Ok(())
+}
+
+/// Returns true if the commands touch more than one key.
+fn is_multi_key(commands: &[protocol::Command]) -> bool {
+	let mut keys = commands
+		.iter()
+		.filter_map(crate::replica::utils::extract_key_from_command);
+	let Some(first) = keys.next() else {
+		return false;
+	};
+	keys.any(|key| key != first)
+}
+
 /// Save log entry to UDB & call the appropriate message handler.
 ///
 /// This function is idempotent since message handlers are executed in the same UDB transaction
@@ -252,6 +555,12 @@ async fn apply_log_entry(
 			let instance = instance.clone();
 
 			async move {
+				// Already included in the installed snapshot
+				if crate::replica::compaction::is_compacted(&*tx, replica_id, &instance).await? {
+					tracing::debug!(?instance, "instance already compacted, skipping");
+					return Result::Ok(());
+				}
+
 				let subspace = crate::keys::subspace(replica_id);
 				let log_key =
 					crate::keys::replica::LogEntryKey::new(instance.replica_id, instance.slot_id);
@@ -705,16 +1014,34 @@ async fn recover_key_value_with_instances(
 			.cloned()
 			.collect::<Vec<_>>();
 
-		if !commands_for_key.is_empty() {
-			tracing::trace!(
-				instance = ?entry.instance,
-				command_count = commands_for_key.len(),
-				"applying commands from entry"
-			);
+		if commands_for_key.is_empty() {
+			continue;
+		}
 
-			// Execute commands
-			crate::replica::commit_kv::commit_kv(&*tx, replica_id, &commands_for_key).await?;
+		// Commands on other keys are applied when those keys are recovered, so the checks of the
+		// whole proposal cannot be validated here. Use the result recorded when it was committed.
+		if is_multi_key(&entry.entry.commands) {
+			let instance = protocol::Instance {
+				replica_id: entry.instance.0,
+				slot_id: entry.instance.1,
+			};
+			if crate::replica::commit_kv::has_command_error(tx, replica_id, &instance).await? {
+				tracing::trace!(
+					instance = ?entry.instance,
+					"skipping multi-key entry with command error"
+				);
+				continue;
+			}
 		}
+
+		tracing::trace!(
+			instance = ?entry.instance,
+			command_count = commands_for_key.len(),
+			"applying commands from entry"
+		);
+
+		// Execute commands
+		crate::replica::commit_kv::commit_kv(&*tx, replica_id, &commands_for_key).await?;
 	}
 
 	Result::Ok(())
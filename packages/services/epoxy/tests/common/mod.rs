@@ -116,6 +116,13 @@ impl TestCtx {
 			.wf_ctx
 	}
 
+	pub fn api_peer_port(&self, replica_id: ReplicaId) -> u16 {
+		self.replica_metadata
+			.get(&replica_id)
+			.expect("replica not added")
+			.api_peer_port
+	}
+
 	pub async fn add_replica(&mut self, replica_id: ReplicaId) -> anyhow::Result<()> {
 		tracing::info!(?replica_id, "adding replica");
 
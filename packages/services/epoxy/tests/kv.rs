@@ -185,3 +185,190 @@ async fn test_check_and_set_operations() {
 	// Note: Direct workflow state checking is no longer available through ops API
 	// Verify through KV get operation instead
 }
+
+fn cas_entry(
+	key: &[u8],
+	expected: Option<&[u8]>,
+	new_value: &[u8],
+) -> epoxy::ops::kv::check_and_set::Entry {
+	epoxy::ops::kv::check_and_set::Entry {
+		key: key.to_vec(),
+		expect_one_of: vec![expected.map(|x| x.to_vec())],
+		new_value: Some(new_value.to_vec()),
+	}
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_multi_key_check_and_set() {
+	let test_ctx = TestCtx::new_with(THREE_REPLICAS).await.unwrap();
+	let replica_id = THREE_REPLICAS[0];
+	let ctx = test_ctx.get_ctx(replica_id);
+
+	// Create both keys in one proposal
+	let result = ctx
+		.op(epoxy::ops::kv::check_and_set::Input {
+			entries: vec![
+				cas_entry(b"multi_a", None, b"a1"),
+				cas_entry(b"multi_b", None, b"b1"),
+			],
+		})
+		.await
+		.unwrap();
+	assert!(matches!(result, ProposalResult::Committed));
+
+	// Second check fails, so neither key should be updated
+	let result = ctx
+		.op(epoxy::ops::kv::check_and_set::Input {
+			entries: vec![
+				cas_entry(b"multi_a", Some(b"a1"), b"a2"),
+				cas_entry(b"multi_b", Some(b"wrong"), b"b2"),
+			],
+		})
+		.await
+		.unwrap();
+	assert!(matches!(
+		result,
+		ProposalResult::CommandError(CommandError::ExpectedValueDoesNotMatch { .. })
+	));
+
+	for (key, expected) in [(b"multi_a", b"a1"), (b"multi_b", b"b1")] {
+		let output = ctx
+			.op(epoxy::ops::kv::get_local::Input {
+				replica_id,
+				key: key.to_vec(),
+			})
+			.await
+			.unwrap();
+		assert_eq!(output.value, Some(expected.to_vec()));
+	}
+
+	// Duplicate keys are rejected
+	let result = ctx
+		.op(epoxy::ops::kv::check_and_set::Input {
+			entries: vec![
+				cas_entry(b"multi_a", Some(b"a1"), b"a2"),
+				cas_entry(b"multi_a", Some(b"a2"), b"a3"),
+			],
+		})
+		.await;
+	assert!(result.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_multi_key_check_and_set_cross_replica() {
+	let test_ctx = TestCtx::new_with(THREE_REPLICAS).await.unwrap();
+	let ctx_a = test_ctx.get_ctx(THREE_REPLICAS[0]);
+	let ctx_b = test_ctx.get_ctx(THREE_REPLICAS[1]);
+
+	let result = ctx_a
+		.op(epoxy::ops::kv::check_and_set::Input {
+			entries: vec![
+				cas_entry(b"cross_a", None, b"a1"),
+				cas_entry(b"cross_b", None, b"b1"),
+			],
+		})
+		.await
+		.unwrap();
+	assert!(matches!(result, ProposalResult::Committed));
+
+	// Another replica sees the committed values when checking
+	let result = ctx_b
+		.op(epoxy::ops::kv::check_and_set::Input {
+			entries: vec![
+				cas_entry(b"cross_a", None, b"a2"),
+				cas_entry(b"cross_b", Some(b"b1"), b"b2"),
+			],
+		})
+		.await
+		.unwrap();
+	assert!(matches!(
+		result,
+		ProposalResult::CommandError(CommandError::ExpectedValueDoesNotMatch { .. })
+	));
+
+	let result = ctx_b
+		.op(epoxy::ops::kv::check_and_set::Input {
+			entries: vec![
+				cas_entry(b"cross_a", Some(b"a1"), b"a2"),
+				cas_entry(b"cross_b", Some(b"b1"), b"b2"),
+			],
+		})
+		.await
+		.unwrap();
+	assert!(matches!(result, ProposalResult::Committed));
+
+	// Both keys are updated on every replica
+	for replica_id in THREE_REPLICAS {
+		let ctx = test_ctx.get_ctx(*replica_id);
+		for (key, expected) in [(b"cross_a", b"a2"), (b"cross_b", b"b2")] {
+			let output = ctx
+				.op(epoxy::ops::kv::get_linearizable::Input { key: key.to_vec() })
+				.await
+				.unwrap();
+			assert_eq!(output.value, Some(expected.to_vec()));
+		}
+	}
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_list_local() {
+	let test_ctx = TestCtx::new_with(THREE_REPLICAS).await.unwrap();
+	let replica_id = THREE_REPLICAS[0];
+	let ctx = test_ctx.get_ctx(replica_id);
+
+	for key in [
+		b"list/a".as_slice(),
+		b"list/b",
+		b"list/c",
+		b"list/\xff\xff",
+		b"lis",
+		b"other/a",
+	] {
+		let result = execute_command(
+			ctx,
+			protocol::CommandKind::SetCommand(protocol::SetCommand {
+				key: key.to_vec(),
+				value: Some(key.to_vec()),
+			}),
+			false,
+		)
+		.await
+		.unwrap();
+		assert!(matches!(result, ProposalResult::Committed));
+	}
+
+	// Paginate through the prefix
+	let mut keys = Vec::new();
+	let mut after_key = None;
+	loop {
+		let output = ctx
+			.op(epoxy::ops::kv::list_local::Input {
+				replica_id,
+				prefix: b"list/".to_vec(),
+				after_key: after_key.clone(),
+				limit: 2,
+			})
+			.await
+			.unwrap();
+
+		for entry in output.entries {
+			assert_eq!(entry.key, entry.value);
+			keys.push(entry.key);
+		}
+
+		after_key = output.last_key;
+		if after_key.is_none() {
+			break;
+		}
+	}
+
+	assert_eq!(
+		keys,
+		vec![
+			b"list/a".to_vec(),
+			b"list/b".to_vec(),
+			b"list/c".to_vec(),
+			b"list/\xff\xff".to_vec(),
+		]
+	);
+}
@@ -0,0 +1,100 @@
+mod common;
+
+use epoxy::ops::propose::ProposalResult;
+use epoxy_protocol::protocol;
+use gas::prelude::*;
+
+use common::{THREE_REPLICAS, TestCtx, utils::execute_command};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_kv_get_linearizable_remote_write() {
+	let test_ctx = TestCtx::new_with(THREE_REPLICAS).await.unwrap();
+
+	let writer_replica_id = THREE_REPLICAS[0];
+	let writer_ctx = test_ctx.get_ctx(writer_replica_id);
+
+	let reader_replica_id = THREE_REPLICAS[1];
+	let reader_ctx = test_ctx.get_ctx(reader_replica_id);
+
+	let key = b"test_linearizable";
+
+	// Write the value multiple times on replica 1 and immediately read on replica 2 without
+	// waiting for the commits to propagate
+	for i in 0..3 {
+		let value = format!("value_{i}").into_bytes();
+
+		let result = execute_command(
+			writer_ctx,
+			protocol::CommandKind::SetCommand(protocol::SetCommand {
+				key: key.to_vec(),
+				value: Some(value.clone()),
+			}),
+			false,
+		)
+		.await
+		.unwrap();
+		assert!(matches!(result, ProposalResult::Committed));
+
+		let output = reader_ctx
+			.op(epoxy::ops::kv::get_linearizable::Input { key: key.to_vec() })
+			.await
+			.unwrap();
+		assert_eq!(output.value, Some(value));
+	}
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_kv_get_linearizable_nonexistent() {
+	let test_ctx = TestCtx::new_with(THREE_REPLICAS).await.unwrap();
+	let replica_id = THREE_REPLICAS[0];
+	let ctx = test_ctx.get_ctx(replica_id);
+
+	let output = ctx
+		.op(epoxy::ops::kv::get_linearizable::Input {
+			key: b"nonexistent_key".to_vec(),
+		})
+		.await
+		.unwrap();
+	assert_eq!(output.value, None);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_kv_get_linearizable_http() {
+	let test_ctx = TestCtx::new_with(THREE_REPLICAS).await.unwrap();
+
+	let writer_ctx = test_ctx.get_ctx(THREE_REPLICAS[0]);
+	let reader_replica_id = THREE_REPLICAS[2];
+
+	let key = b"test_linearizable_http";
+	let value = b"value".to_vec();
+
+	let result = execute_command(
+		writer_ctx,
+		protocol::CommandKind::SetCommand(protocol::SetCommand {
+			key: key.to_vec(),
+			value: Some(value.clone()),
+		}),
+		false,
+	)
+	.await
+	.unwrap();
+	assert!(matches!(result, ProposalResult::Committed));
+
+	let res = reqwest::Client::new()
+		.post(format!(
+			"http://127.0.0.1:{}/v{}/epoxy/kv/get-linearizable",
+			test_ctx.api_peer_port(reader_replica_id),
+			epoxy_protocol::PROTOCOL_VERSION,
+		))
+		.body(
+			serde_bare::to_vec(&protocol::KvGetLinearizableRequest { key: key.to_vec() }).unwrap(),
+		)
+		.send()
+		.await
+		.unwrap();
+	assert!(res.status().is_success());
+
+	let body = res.bytes().await.unwrap();
+	let response = serde_bare::from_slice::<protocol::KvGetLinearizableResponse>(&body).unwrap();
+	assert_eq!(response.value, Some(value));
+}
@@ -39,6 +39,7 @@ async fn reconfig_1_to_2() {
 		commands,
 		init_replica_count: 1,
 		new_replica_count: 1,
+		compact: false,
 	})
 	.await
 }
@@ -58,6 +59,7 @@ async fn reconfig_3_to_4() {
 		commands,
 		init_replica_count: 3,
 		new_replica_count: 1,
+		compact: false,
 	})
 	.await
 }
@@ -77,6 +79,7 @@ async fn reconfig_3_to_5() {
 		commands,
 		init_replica_count: 3,
 		new_replica_count: 2,
+		compact: false,
 	})
 	.await
 }
@@ -133,6 +136,7 @@ async fn reconfig_check_and_set() {
 		commands,
 		init_replica_count: 3,
 		new_replica_count: 1,
+		compact: false,
 	})
 	.await
 }
@@ -191,6 +195,7 @@ async fn reconfig_repeated_sets() {
 		commands,
 		init_replica_count: 3,
 		new_replica_count: 1,
+		compact: false,
 	})
 	.await
 }
@@ -215,6 +220,48 @@ async fn reconfig_recover_chunking() {
 		commands,
 		init_replica_count: 1,
 		new_replica_count: 1,
+		compact: false,
+	})
+	.await
+}
+
+/// Tests joining a cluster after the log has been compacted
+#[tokio::test]
+async fn reconfig_after_compaction() {
+	let test_key = b"counter".to_vec();
+
+	let commands = vec![
+		protocol::CommandKind::SetCommand(protocol::SetCommand {
+			key: test_key.clone(),
+			value: Some(b"0".to_vec()),
+		}),
+		protocol::CommandKind::CheckAndSetCommand(protocol::CheckAndSetCommand {
+			key: test_key.clone(),
+			expect_one_of: vec![Some(b"0".to_vec())],
+			new_value: Some(b"1".to_vec()),
+		}),
+		protocol::CommandKind::SetCommand(protocol::SetCommand {
+			key: b"test_key_1".to_vec(),
+			value: Some(b"test_value_1".to_vec()),
+		}),
+		protocol::CommandKind::SetCommand(protocol::SetCommand {
+			key: b"test_key_2".to_vec(),
+			value: Some(b"test_value_2".to_vec()),
+		}),
+	];
+
+	let expected_keys = vec![
+		(test_key.clone(), b"1".to_vec()),
+		(b"test_key_1".to_vec(), b"test_value_1".to_vec()),
+		(b"test_key_2".to_vec(), b"test_value_2".to_vec()),
+	];
+
+	test_inner(TestConfig {
+		expected_keys,
+		commands,
+		init_replica_count: 3,
+		new_replica_count: 1,
+		compact: true,
 	})
 	.await
 }
@@ -242,6 +289,7 @@ async fn reconfig_download_chunking() {
 		commands,
 		init_replica_count: 1,
 		new_replica_count: 1,
+		compact: false,
 	})
 	.await
 }
@@ -251,6 +299,8 @@ struct TestConfig {
 	commands: Vec<protocol::CommandKind>,
 	init_replica_count: ReplicaId,
 	new_replica_count: ReplicaId,
+	/// Compact the log on all initial replicas before adding new replicas.
+	compact: bool,
 }
 
 async fn test_inner(config: TestConfig) {
@@ -281,6 +331,12 @@ async fn test_inner(config: TestConfig) {
 		);
 	}
 
+	if config.compact {
+		compact_all_replicas(&test_ctx, &init_replica_ids)
+			.await
+			.unwrap();
+	}
+
 	// Stop the leader
 	tracing::info!(leader_replica_id, "stopping leader replica");
 	test_ctx
@@ -383,6 +439,50 @@ async fn test_inner(config: TestConfig) {
 	}
 }
 
+/// Compacts the log on every replica and verifies that all log entries were removed.
+async fn compact_all_replicas(test_ctx: &TestCtx, replica_ids: &[ReplicaId]) -> Result<()> {
+	// Each replica only advances its own committed prefix, so the first round publishes the
+	// prefixes and the second round compacts below them
+	for round in 0..2 {
+		for &replica_id in replica_ids {
+			let ctx = test_ctx.get_ctx(replica_id);
+			let res = ctx.op(epoxy::ops::compact_log::Input {}).await?;
+			tracing::info!(round, replica_id, watermark = ?res.watermark, "compacted log");
+		}
+	}
+
+	for &replica_id in replica_ids {
+		let ctx = test_ctx.get_ctx(replica_id);
+		let log_count = ctx
+			.udb()?
+			.run(move |tx| async move {
+				let subspace = epoxy::keys::subspace(replica_id);
+
+				let range = RangeOption {
+					begin: KeySelector::first_greater_or_equal(subspace.pack(&(LOG,))),
+					end: KeySelector::first_greater_than(subspace.pack(&(LOG + 1,))),
+					mode: StreamingMode::WantAll,
+					..Default::default()
+				};
+
+				let mut stream = tx.get_ranges_keyvalues(range, Serializable);
+				let mut count = 0;
+				while stream.try_next().await?.is_some() {
+					count += 1;
+				}
+
+				Result::Ok(count)
+			})
+			.await?;
+		ensure!(
+			log_count == 0,
+			"replica {replica_id} still has {log_count} log entries after compaction"
+		);
+	}
+
+	Ok(())
+}
+
 /// Verify that all replicas have the expected configuration with the given epoch.
 async fn verify_configuration_propagated(test_ctx: &TestCtx, expected_epoch: u64) -> Result<()> {
 	tracing::info!(expected_epoch, "verifying configuration propagation");
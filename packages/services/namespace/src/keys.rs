@@ -192,16 +192,18 @@ impl<'de> TupleUnpack<'de> for ByNameKey {
 	}
 }
 
-#[derive(Clone, Copy, Debug, Serialize, Deserialize, strum::FromRepr, ToSchema)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, strum::FromRepr, ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum RunnerConfigVariant {
 	Serverless = 0,
+	Normal = 1,
 }
 
 impl RunnerConfigVariant {
 	pub fn parse(v: &str) -> Option<Self> {
 		match v {
 			"serverless" => Some(RunnerConfigVariant::Serverless),
+			"normal" => Some(RunnerConfigVariant::Normal),
 			_ => None,
 		}
 	}
@@ -211,6 +213,7 @@ impl std::fmt::Display for RunnerConfigVariant {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		match self {
 			RunnerConfigVariant::Serverless => write!(f, "serverless"),
+			RunnerConfigVariant::Normal => write!(f, "normal"),
 		}
 	}
 }
@@ -243,9 +246,7 @@ impl FormalKey for RunnerConfigKey {
 
 	fn serialize(&self, value: Self::Value) -> Result<Vec<u8>> {
 		rivet_data::versioned::NamespaceRunnerConfig::latest(value.into())
-			.serialize_with_embedded_version(
-				rivet_data::PEGBOARD_NAMESPACE_RUNNER_ALLOC_IDX_VERSION,
-			)
+			.serialize_with_embedded_version(rivet_data::NAMESPACE_RUNNER_CONFIG_VERSION)
 	}
 }
 
@@ -336,9 +337,7 @@ impl FormalKey for RunnerConfigByVariantKey {
 
 	fn serialize(&self, value: Self::Value) -> Result<Vec<u8>> {
 		rivet_data::versioned::NamespaceRunnerConfig::latest(value.into())
-			.serialize_with_embedded_version(
-				rivet_data::PEGBOARD_NAMESPACE_RUNNER_ALLOC_IDX_VERSION,
-			)
+			.serialize_with_embedded_version(rivet_data::NAMESPACE_RUNNER_CONFIG_VERSION)
 	}
 }
 
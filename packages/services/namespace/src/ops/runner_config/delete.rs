@@ -42,7 +42,7 @@ pub async fn namespace_runner_config_delete(ctx: &OperationCtx, input: &Input) -
 
 	// Purge cache in all dcs
 	ctx.op(internal::ops::cache::purge_global::Input {
-		base_key: "namespace.runner_config.{}.get_global".to_string(),
+		base_key: "namespace.runner_config.get_global".to_string(),
 		keys: vec![(input.namespace_id, input.name.as_str()).cache_key().into()],
 	})
 	.await?;
@@ -1,8 +1,12 @@
 use gas::prelude::*;
 use rivet_cache::CacheKey;
 use universaldb::options::MutationType;
+use universaldb::utils::IsolationLevel::*;
 
-use crate::{errors, keys, types::RunnerConfig};
+use crate::{
+	errors, keys,
+	types::{AllocationStrategy, RunnerConfig, RunnerConfigKind},
+};
 
 #[derive(Debug)]
 pub struct Input {
@@ -21,11 +25,77 @@ pub async fn namespace_runner_config_upsert(ctx: &OperationCtx, input: &Input) -
 		.run(|tx| async move {
 			let tx = tx.with_subspace(keys::subspace());
 
-			// TODO: Once other types of configs get added, delete previous config before writing
-			tx.write(
-				&keys::RunnerConfigKey::new(input.namespace_id, input.name.clone()),
-				input.config.clone(),
-			)?;
+			if let AllocationStrategy::RandomTopN { n } = input.config.allocation_strategy {
+				if n == 0 {
+					return Ok(Err(errors::RunnerConfig::Invalid {
+						reason: "`random_top_n.n` cannot be 0".to_string(),
+					}));
+				}
+			}
+
+			if input
+				.config
+				.rollout
+				.canary_percent
+				.is_some_and(|canary_percent| canary_percent > 100)
+			{
+				return Ok(Err(errors::RunnerConfig::Invalid {
+					reason: "`rollout.canary_percent` cannot be greater than 100".to_string(),
+				}));
+			}
+
+			if input
+				.config
+				.rollout
+				.rollback
+				.is_some_and(|rollback| rollback.max_crash_rate_percent > 100)
+			{
+				return Ok(Err(errors::RunnerConfig::Invalid {
+					reason: "`rollout.rollback.max_crash_rate_percent` cannot be greater than 100"
+						.to_string(),
+				}));
+			}
+
+			if input
+				.config
+				.rollout
+				.rollback
+				.is_some_and(|rollback| rollback.window_ms == Some(0))
+			{
+				return Ok(Err(errors::RunnerConfig::Invalid {
+					reason: "`rollout.rollback.window_ms` cannot be 0".to_string(),
+				}));
+			}
+
+			if input
+				.config
+				.max_pending_duration_ms
+				.is_some_and(|max_pending_duration_ms| {
+					max_pending_duration_ms <= 0
+						|| max_pending_duration_ms > crate::types::MAX_PENDING_DURATION_MS
+				}) {
+				return Ok(Err(errors::RunnerConfig::Invalid {
+					reason: "`max_pending_duration_ms` must be greater than 0 and at most 30 days"
+						.to_string(),
+				}));
+			}
+
+			let runner_config_key =
+				keys::RunnerConfigKey::new(input.namespace_id, input.name.clone());
+
+			// Clear secondary idx of previous config if the variant changed
+			if let Some(old_config) = tx.read_opt(&runner_config_key, Serializable).await? {
+				let old_variant = old_config.variant();
+				if old_variant != input.config.variant() {
+					tx.delete(&keys::RunnerConfigByVariantKey::new(
+						input.namespace_id,
+						old_variant,
+						input.name.clone(),
+					));
+				}
+			}
+
+			tx.write(&runner_config_key, input.config.clone())?;
 
 			// Write to secondary idx
 			tx.write(
@@ -37,8 +107,8 @@ pub async fn namespace_runner_config_upsert(ctx: &OperationCtx, input: &Input) -
 				input.config.clone(),
 			)?;
 
-			match &input.config {
-				RunnerConfig::Serverless {
+			match &input.config.kind {
+				RunnerConfigKind::Serverless {
 					url,
 					slots_per_runner,
 					..
@@ -68,6 +138,7 @@ pub async fn namespace_runner_config_upsert(ctx: &OperationCtx, input: &Input) -
 						MutationType::Add,
 					);
 				}
+				RunnerConfigKind::Normal {} => {}
 			}
 
 			Ok(Ok(()))
@@ -77,9 +148,8 @@ pub async fn namespace_runner_config_upsert(ctx: &OperationCtx, input: &Input) -
 		.map_err(|err| err.build())?;
 
 	// Purge cache in all dcs
-	let variant_str = serde_json::to_string(&input.config.variant())?;
 	ctx.op(internal::ops::cache::purge_global::Input {
-		base_key: format!("namespace.runner_config.{variant_str}.get_global"),
+		base_key: "namespace.runner_config.get_global".to_string(),
 		keys: vec![(input.namespace_id, input.name.as_str()).cache_key().into()],
 	})
 	.await?;
@@ -3,6 +3,9 @@ use utoipa::ToSchema;
 
 use crate::keys;
 
+/// Max `max_pending_duration_ms` of a runner config or actor.
+pub const MAX_PENDING_DURATION_MS: i64 = util::duration::days(30);
+
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct Namespace {
 	pub namespace_id: Id,
@@ -11,9 +14,29 @@ pub struct Namespace {
 	pub create_ts: i64,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, ToSchema)]
+pub struct RunnerConfig {
+	#[serde(flatten)]
+	pub kind: RunnerConfigKind,
+	/// How actors are distributed across runners with this name.
+	#[serde(default)]
+	pub allocation_strategy: AllocationStrategy,
+	/// How new runner versions receive actors.
+	#[serde(default)]
+	pub rollout: RolloutPolicy,
+	/// Default `max_pending_duration_ms` of actors in this namespace that are allocated to runners with
+	/// this name. Actors that set their own value are not affected. At most 30 days.
+	#[serde(default)]
+	pub max_pending_duration_ms: Option<i64>,
+	/// Put running low priority actors with the `sleep` crash policy to sleep when a higher priority
+	/// actor cannot be allocated. Preempted actors are allocated again once woken.
+	#[serde(default)]
+	pub preempt_low_priority: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Hash, ToSchema)]
 #[serde(rename_all = "snake_case")]
-pub enum RunnerConfig {
+pub enum RunnerConfigKind {
 	Serverless {
 		url: String,
 		/// Seconds.
@@ -23,53 +46,182 @@ pub enum RunnerConfig {
 		max_runners: u32,
 		runners_margin: u32,
 	},
+	/// Runners that connect on their own. Only used to configure allocation.
+	Normal {},
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Hash, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AllocationStrategy {
+	/// Fill the runner with the least remaining slots first.
+	BinPack,
+	/// Allocate to the runner with the most remaining slots.
+	#[default]
+	Spread,
+	/// Allocate to a random runner out of the `n` runners with the most remaining slots. Reduces
+	/// contention when many actors are allocated at once.
+	RandomTopN { n: u32 },
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Hash, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RolloutPolicy {
+	/// Percentage of new actors allocated to the newest runner version. The rest are allocated to the
+	/// previous version. Defaults to 100.
+	#[serde(default)]
+	pub canary_percent: Option<u32>,
+	/// Only allocate new actors to runners of this version.
+	#[serde(default)]
+	pub pinned_version: Option<u32>,
+	/// Stop allocating to a runner version if its actors crash too often.
+	#[serde(default)]
+	pub rollback: Option<RollbackPolicy>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Hash, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RollbackPolicy {
+	/// Percentage of allocated actors that have to crash for the version to be rolled back.
+	pub max_crash_rate_percent: u32,
+	/// Amount of actors that have to be allocated to the version within the window before its crash
+	/// rate is checked.
+	pub min_allocations: u32,
+	/// Length of the window crashes and allocations are counted over. Counts are reset once the window
+	/// passes. Defaults to 10 minutes.
+	#[serde(default)]
+	pub window_ms: Option<u64>,
+}
+
+impl RollbackPolicy {
+	pub const DEFAULT_WINDOW_MS: u64 = 10 * 60 * 1000;
+
+	pub fn window_ms(&self) -> u64 {
+		self.window_ms.unwrap_or(Self::DEFAULT_WINDOW_MS)
+	}
 }
 
 impl RunnerConfig {
 	pub fn variant(&self) -> keys::RunnerConfigVariant {
-		match self {
-			RunnerConfig::Serverless { .. } => keys::RunnerConfigVariant::Serverless,
+		match self.kind {
+			RunnerConfigKind::Serverless { .. } => keys::RunnerConfigVariant::Serverless,
+			RunnerConfigKind::Normal { .. } => keys::RunnerConfigVariant::Normal,
 		}
 	}
 }
 
-impl From<RunnerConfig> for rivet_data::generated::namespace_runner_config_v1::Data {
+impl From<RunnerConfig> for rivet_data::generated::namespace_runner_config_v3::Data {
 	fn from(value: RunnerConfig) -> Self {
-		match value {
-			RunnerConfig::Serverless {
-				url,
-				request_lifespan,
-				slots_per_runner,
-				min_runners,
-				max_runners,
-				runners_margin,
-			} => rivet_data::generated::namespace_runner_config_v1::Data::Serverless(
-				rivet_data::generated::namespace_runner_config_v1::Serverless {
+		use rivet_data::generated::namespace_runner_config_v3::*;
+
+		Data {
+			kind: match value.kind {
+				RunnerConfigKind::Serverless {
 					url,
 					request_lifespan,
 					slots_per_runner,
 					min_runners,
 					max_runners,
 					runners_margin,
-				},
-			),
+				} => Kind::Serverless(Serverless {
+					url,
+					request_lifespan,
+					slots_per_runner,
+					min_runners,
+					max_runners,
+					runners_margin,
+				}),
+				RunnerConfigKind::Normal {} => Kind::Normal,
+			},
+			allocation_strategy: value.allocation_strategy.into(),
+			rollout: value.rollout.into(),
+			max_pending_duration_ms: value.max_pending_duration_ms,
+			preempt_low_priority: value.preempt_low_priority,
 		}
 	}
 }
 
-impl From<rivet_data::generated::namespace_runner_config_v1::Data> for RunnerConfig {
-	fn from(value: rivet_data::generated::namespace_runner_config_v1::Data) -> Self {
-		match value {
-			rivet_data::generated::namespace_runner_config_v1::Data::Serverless(o) => {
-				RunnerConfig::Serverless {
+impl From<rivet_data::generated::namespace_runner_config_v3::Data> for RunnerConfig {
+	fn from(value: rivet_data::generated::namespace_runner_config_v3::Data) -> Self {
+		use rivet_data::generated::namespace_runner_config_v3::*;
+
+		RunnerConfig {
+			kind: match value.kind {
+				Kind::Serverless(o) => RunnerConfigKind::Serverless {
 					url: o.url,
 					request_lifespan: o.request_lifespan,
 					slots_per_runner: o.slots_per_runner,
 					min_runners: o.min_runners,
 					max_runners: o.max_runners,
 					runners_margin: o.runners_margin,
-				}
+				},
+				Kind::Normal => RunnerConfigKind::Normal {},
+			},
+			allocation_strategy: value.allocation_strategy.into(),
+			rollout: value.rollout.into(),
+			max_pending_duration_ms: value.max_pending_duration_ms,
+			preempt_low_priority: value.preempt_low_priority,
+		}
+	}
+}
+
+impl From<AllocationStrategy>
+	for rivet_data::generated::namespace_runner_config_v3::AllocationStrategy
+{
+	fn from(value: AllocationStrategy) -> Self {
+		match value {
+			AllocationStrategy::BinPack => Self::BinPack,
+			AllocationStrategy::Spread => Self::Spread,
+			AllocationStrategy::RandomTopN { n } => Self::RandomTopN(
+				rivet_data::generated::namespace_runner_config_v3::RandomTopN { n },
+			),
+		}
+	}
+}
+
+impl From<rivet_data::generated::namespace_runner_config_v3::AllocationStrategy>
+	for AllocationStrategy
+{
+	fn from(value: rivet_data::generated::namespace_runner_config_v3::AllocationStrategy) -> Self {
+		match value {
+			rivet_data::generated::namespace_runner_config_v3::AllocationStrategy::BinPack => {
+				AllocationStrategy::BinPack
+			}
+			rivet_data::generated::namespace_runner_config_v3::AllocationStrategy::Spread => {
+				AllocationStrategy::Spread
 			}
+			rivet_data::generated::namespace_runner_config_v3::AllocationStrategy::RandomTopN(
+				o,
+			) => AllocationStrategy::RandomTopN { n: o.n },
+		}
+	}
+}
+
+impl From<RolloutPolicy> for rivet_data::generated::namespace_runner_config_v3::RolloutPolicy {
+	fn from(value: RolloutPolicy) -> Self {
+		rivet_data::generated::namespace_runner_config_v3::RolloutPolicy {
+			canary_percent: value.canary_percent,
+			pinned_version: value.pinned_version,
+			rollback: value.rollback.map(|rollback| {
+				rivet_data::generated::namespace_runner_config_v3::RollbackPolicy {
+					max_crash_rate_percent: rollback.max_crash_rate_percent,
+					min_allocations: rollback.min_allocations,
+					window_ms: rollback.window_ms,
+				}
+			}),
+		}
+	}
+}
+
+impl From<rivet_data::generated::namespace_runner_config_v3::RolloutPolicy> for RolloutPolicy {
+	fn from(value: rivet_data::generated::namespace_runner_config_v3::RolloutPolicy) -> Self {
+		RolloutPolicy {
+			canary_percent: value.canary_percent,
+			pinned_version: value.pinned_version,
+			rollback: value.rollback.map(|rollback| RollbackPolicy {
+				max_crash_rate_percent: rollback.max_crash_rate_percent,
+				min_allocations: rollback.min_allocations,
+				window_ms: rollback.window_ms,
+			}),
 		}
 	}
 }
@@ -18,6 +18,50 @@ pub enum Actor {
 	)]
 	InputTooLarge { max_size: usize },
 
+	#[error("invalid_slots", "Actor slots must be at least 1.")]
+	InvalidSlots,
+
+	#[error(
+		"slots_exceed_runner_capacity",
+		"Actor slots exceed the total slots of every runner.",
+		"Actor requires {slots} slots but runners have at most {max_slots} total slots."
+	)]
+	SlotsExceedRunnerCapacity { slots: u32, max_slots: u32 },
+
+	#[error(
+		"invalid_max_pending_duration",
+		"Actor max pending duration must be greater than 0 and at most 30 days."
+	)]
+	InvalidMaxPendingDuration,
+
+	#[error(
+		"invalid_ttl",
+		"Actor TTL and idle timeout must be greater than 0 and at most 1 year, and destroy at timestamp must be in the future and at most 1 year away."
+	)]
+	InvalidTtl,
+
+	#[error(
+		"invalid_restart_policy",
+		"Actor restart policy window and backoff must be greater than 0, backoff must be at most 1 hour and backoff max must not be less than backoff base."
+	)]
+	InvalidRestartPolicy,
+
+	#[error(
+		"pending_allocation_timeout",
+		"Actor was not allocated to a runner in time. There may not be enough runner capacity.",
+		"Actor was not allocated to a runner within {max_pending_duration_ms}ms. There may not be enough runner capacity."
+	)]
+	PendingAllocationTimeout { max_pending_duration_ms: i64 },
+
+	#[error("not_running", "Actor is not running on a runner.")]
+	NotRunning,
+
+	#[error(
+		"invalid_migration_target",
+		"Runner to migrate the actor to must be a different, non-draining runner in the same namespace with the actor's runner name."
+	)]
+	InvalidMigrationTarget,
+
 	#[error("empty_key", "Key label cannot be empty.")]
 	EmptyKey,
 
@@ -3,6 +3,7 @@ use std::result::Result::Ok;
 use anyhow::*;
 use gas::prelude::*;
 use universaldb::prelude::*;
+use versioned_data_util::OwnedVersionedData;
 
 #[derive(Debug)]
 pub struct CreateTsKey {
@@ -269,3 +270,282 @@ impl<'de> TupleUnpack<'de> for DestroyTsKey {
 		Ok((input, v))
 	}
 }
+
+#[derive(Debug)]
+pub struct LogEntryKey {
+	actor_id: Id,
+	pub idx: u64,
+}
+
+impl LogEntryKey {
+	pub fn new(actor_id: Id, idx: u64) -> Self {
+		LogEntryKey { actor_id, idx }
+	}
+
+	pub fn subspace(actor_id: Id) -> LogEntrySubspaceKey {
+		LogEntrySubspaceKey::new(actor_id)
+	}
+}
+
+impl FormalKey for LogEntryKey {
+	type Value = rivet_types::actors::ActorLogEntry;
+
+	fn deserialize(&self, raw: &[u8]) -> Result<Self::Value> {
+		Ok(
+			rivet_data::versioned::ActorLogEntryKeyData::deserialize_with_embedded_version(raw)?
+				.into_latest()?
+				.into(),
+		)
+	}
+
+	fn serialize(&self, value: Self::Value) -> Result<Vec<u8>> {
+		rivet_data::versioned::ActorLogEntryKeyData::latest(value.into())
+			.serialize_with_embedded_version(rivet_data::PEGBOARD_ACTOR_LOG_ENTRY_VERSION)
+	}
+}
+
+impl TuplePack for LogEntryKey {
+	fn pack<W: std::io::Write>(
+		&self,
+		w: &mut W,
+		tuple_depth: TupleDepth,
+	) -> std::io::Result<VersionstampOffset> {
+		let t = (ACTOR, LOG, self.actor_id, self.idx);
+		t.pack(w, tuple_depth)
+	}
+}
+
+impl<'de> TupleUnpack<'de> for LogEntryKey {
+	fn unpack(input: &[u8], tuple_depth: TupleDepth) -> PackResult<(&[u8], Self)> {
+		let (input, (_, _, actor_id, idx)) = <(usize, usize, Id, u64)>::unpack(input, tuple_depth)?;
+
+		let v = LogEntryKey { actor_id, idx };
+
+		Ok((input, v))
+	}
+}
+
+pub struct LogEntrySubspaceKey {
+	actor_id: Id,
+}
+
+impl LogEntrySubspaceKey {
+	fn new(actor_id: Id) -> Self {
+		LogEntrySubspaceKey { actor_id }
+	}
+}
+
+impl TuplePack for LogEntrySubspaceKey {
+	fn pack<W: std::io::Write>(
+		&self,
+		w: &mut W,
+		tuple_depth: TupleDepth,
+	) -> std::io::Result<VersionstampOffset> {
+		let t = (ACTOR, LOG, self.actor_id);
+		t.pack(w, tuple_depth)
+	}
+}
+
+#[derive(Debug)]
+pub struct PlacementKey {
+	actor_id: Id,
+}
+
+impl PlacementKey {
+	pub fn new(actor_id: Id) -> Self {
+		PlacementKey { actor_id }
+	}
+}
+
+impl FormalKey for PlacementKey {
+	type Value = rivet_data::converted::ActorPlacementKeyData;
+
+	fn deserialize(&self, raw: &[u8]) -> Result<Self::Value> {
+		rivet_data::versioned::ActorPlacementKeyData::deserialize_with_embedded_version(raw)?
+			.try_into()
+	}
+
+	fn serialize(&self, value: Self::Value) -> Result<Vec<u8>> {
+		rivet_data::versioned::ActorPlacementKeyData::latest(value.try_into()?)
+			.serialize_with_embedded_version(rivet_data::PEGBOARD_ACTOR_PLACEMENT_VERSION)
+	}
+}
+
+impl TuplePack for PlacementKey {
+	fn pack<W: std::io::Write>(
+		&self,
+		w: &mut W,
+		tuple_depth: TupleDepth,
+	) -> std::io::Result<VersionstampOffset> {
+		let t = (ACTOR, DATA, self.actor_id, PLACEMENT);
+		t.pack(w, tuple_depth)
+	}
+}
+
+impl<'de> TupleUnpack<'de> for PlacementKey {
+	fn unpack(input: &[u8], tuple_depth: TupleDepth) -> PackResult<(&[u8], Self)> {
+		let (input, (_, _, actor_id, _)) = <(usize, usize, Id, usize)>::unpack(input, tuple_depth)?;
+		let v = PlacementKey { actor_id };
+
+		Ok((input, v))
+	}
+}
+
+#[derive(Debug)]
+pub struct SlotsKey {
+	actor_id: Id,
+}
+
+impl SlotsKey {
+	pub fn new(actor_id: Id) -> Self {
+		SlotsKey { actor_id }
+	}
+}
+
+impl FormalKey for SlotsKey {
+	/// Amount of runner slots the actor consumes when allocated.
+	type Value = u32;
+
+	fn deserialize(&self, raw: &[u8]) -> Result<Self::Value> {
+		Ok(u32::from_be_bytes(raw.try_into()?))
+	}
+
+	fn serialize(&self, value: Self::Value) -> Result<Vec<u8>> {
+		Ok(value.to_be_bytes().to_vec())
+	}
+}
+
+impl TuplePack for SlotsKey {
+	fn pack<W: std::io::Write>(
+		&self,
+		w: &mut W,
+		tuple_depth: TupleDepth,
+	) -> std::io::Result<VersionstampOffset> {
+		let t = (ACTOR, DATA, self.actor_id, SLOTS);
+		t.pack(w, tuple_depth)
+	}
+}
+
+impl<'de> TupleUnpack<'de> for SlotsKey {
+	fn unpack(input: &[u8], tuple_depth: TupleDepth) -> PackResult<(&[u8], Self)> {
+		let (input, (_, _, actor_id, _)) = <(usize, usize, Id, usize)>::unpack(input, tuple_depth)?;
+		let v = SlotsKey { actor_id };
+
+		Ok((input, v))
+	}
+}
+
+#[derive(Debug)]
+pub struct ExcludeRunnerIdKey {
+	actor_id: Id,
+}
+
+impl ExcludeRunnerIdKey {
+	pub fn new(actor_id: Id) -> Self {
+		ExcludeRunnerIdKey { actor_id }
+	}
+}
+
+impl FormalKey for ExcludeRunnerIdKey {
+	/// Runner the actor is being migrated away from while it is pending allocation.
+	type Value = Id;
+
+	fn deserialize(&self, raw: &[u8]) -> Result<Self::Value> {
+		Ok(Id::from_slice(raw)?)
+	}
+
+	fn serialize(&self, value: Self::Value) -> Result<Vec<u8>> {
+		Ok(value.as_bytes())
+	}
+}
+
+impl TuplePack for ExcludeRunnerIdKey {
+	fn pack<W: std::io::Write>(
+		&self,
+		w: &mut W,
+		tuple_depth: TupleDepth,
+	) -> std::io::Result<VersionstampOffset> {
+		let t = (ACTOR, DATA, self.actor_id, EXCLUDE_RUNNER_ID);
+		t.pack(w, tuple_depth)
+	}
+}
+
+impl<'de> TupleUnpack<'de> for ExcludeRunnerIdKey {
+	fn unpack(input: &[u8], tuple_depth: TupleDepth) -> PackResult<(&[u8], Self)> {
+		let (input, (_, _, actor_id, _)) = <(usize, usize, Id, usize)>::unpack(input, tuple_depth)?;
+		let v = ExcludeRunnerIdKey { actor_id };
+
+		Ok((input, v))
+	}
+}
+
+#[derive(Debug)]
+pub struct AlarmKey {
+	actor_id: Id,
+	pub name: String,
+}
+
+impl AlarmKey {
+	pub fn new(actor_id: Id, name: String) -> Self {
+		AlarmKey { actor_id, name }
+	}
+
+	pub fn subspace(actor_id: Id) -> AlarmSubspaceKey {
+		AlarmSubspaceKey::new(actor_id)
+	}
+}
+
+impl FormalKey for AlarmKey {
+	type Value = rivet_data::converted::ActorAlarmKeyData;
+
+	fn deserialize(&self, raw: &[u8]) -> Result<Self::Value> {
+		rivet_data::versioned::ActorAlarmKeyData::deserialize_with_embedded_version(raw)?.try_into()
+	}
+
+	fn serialize(&self, value: Self::Value) -> Result<Vec<u8>> {
+		rivet_data::versioned::ActorAlarmKeyData::latest(value.try_into()?)
+			.serialize_with_embedded_version(rivet_data::PEGBOARD_ACTOR_ALARM_VERSION)
+	}
+}
+
+impl TuplePack for AlarmKey {
+	fn pack<W: std::io::Write>(
+		&self,
+		w: &mut W,
+		tuple_depth: TupleDepth,
+	) -> std::io::Result<VersionstampOffset> {
+		let t = (ACTOR, DATA, self.actor_id, ALARM, &self.name);
+		t.pack(w, tuple_depth)
+	}
+}
+
+impl<'de> TupleUnpack<'de> for AlarmKey {
+	fn unpack(input: &[u8], tuple_depth: TupleDepth) -> PackResult<(&[u8], Self)> {
+		let (input, (_, _, actor_id, _, name)) =
+			<(usize, usize, Id, usize, String)>::unpack(input, tuple_depth)?;
+		let v = AlarmKey { actor_id, name };
+
+		Ok((input, v))
+	}
+}
+
+pub struct AlarmSubspaceKey {
+	actor_id: Id,
+}
+
+impl AlarmSubspaceKey {
+	fn new(actor_id: Id) -> Self {
+		AlarmSubspaceKey { actor_id }
+	}
+}
+
+impl TuplePack for AlarmSubspaceKey {
+	fn pack<W: std::io::Write>(
+		&self,
+		w: &mut W,
+		tuple_depth: TupleDepth,
+	) -> std::io::Result<VersionstampOffset> {
+		let t = (ACTOR, DATA, self.actor_id, ALARM);
+		t.pack(w, tuple_depth)
+	}
+}
@@ -38,6 +38,26 @@ impl RunnerAllocIdxKey {
 		RunnerAllocIdxSubspaceKey::new(namespace_id, name)
 	}
 
+	/// All runners with the given version, ordered by remaining slots descending.
+	pub fn version_subspace(
+		namespace_id: Id,
+		name: String,
+		version: u32,
+	) -> RunnerAllocIdxSubspaceKey {
+		RunnerAllocIdxSubspaceKey::new_with_version(namespace_id, name, version, None)
+	}
+
+	/// All runners with the given version that have no remaining slots. These are sorted after all
+	/// other runners of the same version, so the start of this subspace is the end of the range of
+	/// runners that have remaining slots.
+	pub fn full_subspace(
+		namespace_id: Id,
+		name: String,
+		version: u32,
+	) -> RunnerAllocIdxSubspaceKey {
+		RunnerAllocIdxSubspaceKey::new_with_version(namespace_id, name, version, Some(0))
+	}
+
 	pub fn entire_subspace() -> RunnerAllocIdxSubspaceKey {
 		RunnerAllocIdxSubspaceKey::entire()
 	}
@@ -104,6 +124,8 @@ impl<'de> TupleUnpack<'de> for RunnerAllocIdxKey {
 pub struct RunnerAllocIdxSubspaceKey {
 	pub namespace_id: Option<Id>,
 	pub name: Option<String>,
+	pub version: Option<u32>,
+	pub remaining_millislots: Option<u32>,
 }
 
 impl RunnerAllocIdxSubspaceKey {
@@ -111,6 +133,22 @@ impl RunnerAllocIdxSubspaceKey {
 		RunnerAllocIdxSubspaceKey {
 			namespace_id: Some(namespace_id),
 			name: Some(name),
+			version: None,
+			remaining_millislots: None,
+		}
+	}
+
+	fn new_with_version(
+		namespace_id: Id,
+		name: String,
+		version: u32,
+		remaining_millislots: Option<u32>,
+	) -> Self {
+		RunnerAllocIdxSubspaceKey {
+			namespace_id: Some(namespace_id),
+			name: Some(name),
+			version: Some(version),
+			remaining_millislots,
 		}
 	}
 
@@ -118,6 +156,8 @@ impl RunnerAllocIdxSubspaceKey {
 		RunnerAllocIdxSubspaceKey {
 			namespace_id: None,
 			name: None,
+			version: None,
+			remaining_millislots: None,
 		}
 	}
 }
@@ -138,6 +178,15 @@ impl TuplePack for RunnerAllocIdxSubspaceKey {
 
 			if let Some(name) = &self.name {
 				offset += name.pack(w, tuple_depth)?;
+
+				if let Some(version) = &self.version {
+					// See `RunnerAllocIdxKey`
+					offset += (-(*version as i32)).pack(w, tuple_depth)?;
+
+					if let Some(remaining_millislots) = &self.remaining_millislots {
+						offset += (-(*remaining_millislots as i32)).pack(w, tuple_depth)?;
+					}
+				}
 			}
 		}
 
@@ -145,6 +194,8 @@ impl TuplePack for RunnerAllocIdxSubspaceKey {
 	}
 }
 
+/// Allocation queue from before actor priorities were added. New entries are written to
+/// `PendingActorByPriorityKey`, this is only read in order to move existing entries over.
 #[derive(Debug)]
 pub struct PendingActorByRunnerNameSelectorKey {
 	pub namespace_id: Id,
@@ -248,6 +299,300 @@ impl TuplePack for PendingActorByRunnerNameSelectorSubspaceKey {
 	}
 }
 
+#[derive(Debug)]
+pub struct PendingActorByPriorityKey {
+	pub namespace_id: Id,
+	pub runner_name_selector: String,
+	/// See `rivet_types::actors::ActorPriority`.
+	pub priority: u8,
+	pub ts: i64,
+	pub actor_id: Id,
+}
+
+impl PendingActorByPriorityKey {
+	pub fn new(
+		namespace_id: Id,
+		runner_name_selector: String,
+		priority: u8,
+		ts: i64,
+		actor_id: Id,
+	) -> Self {
+		PendingActorByPriorityKey {
+			namespace_id,
+			runner_name_selector,
+			priority,
+			ts,
+			actor_id,
+		}
+	}
+
+	pub fn subspace(
+		namespace_id: Id,
+		runner_name_selector: String,
+	) -> PendingActorByPrioritySubspaceKey {
+		PendingActorByPrioritySubspaceKey::new(namespace_id, runner_name_selector, None)
+	}
+
+	/// All pending actors with the given priority, ordered by pending ts.
+	pub fn priority_subspace(
+		namespace_id: Id,
+		runner_name_selector: String,
+		priority: u8,
+	) -> PendingActorByPrioritySubspaceKey {
+		PendingActorByPrioritySubspaceKey::new(namespace_id, runner_name_selector, Some(priority))
+	}
+}
+
+impl FormalKey for PendingActorByPriorityKey {
+	/// Generation.
+	type Value = u32;
+
+	fn deserialize(&self, raw: &[u8]) -> Result<Self::Value> {
+		Ok(u32::from_be_bytes(raw.try_into()?))
+	}
+
+	fn serialize(&self, value: Self::Value) -> Result<Vec<u8>> {
+		Ok(value.to_be_bytes().to_vec())
+	}
+}
+
+impl TuplePack for PendingActorByPriorityKey {
+	fn pack<W: std::io::Write>(
+		&self,
+		w: &mut W,
+		tuple_depth: TupleDepth,
+	) -> std::io::Result<VersionstampOffset> {
+		let t = (
+			NAMESPACE,
+			PENDING_ACTOR_BY_PRIORITY,
+			self.namespace_id,
+			&self.runner_name_selector,
+			// Stored in reverse order (higher priorities are first)
+			-(self.priority as i32),
+			self.ts,
+			self.actor_id,
+		);
+		t.pack(w, tuple_depth)
+	}
+}
+
+impl<'de> TupleUnpack<'de> for PendingActorByPriorityKey {
+	fn unpack(input: &[u8], tuple_depth: TupleDepth) -> PackResult<(&[u8], Self)> {
+		let (input, (_, _, namespace_id, runner_name_selector, priority, ts, actor_id)) =
+			<(usize, usize, Id, String, i32, i64, Id)>::unpack(input, tuple_depth)?;
+
+		let v = PendingActorByPriorityKey {
+			namespace_id,
+			runner_name_selector,
+			priority: -priority as u8,
+			ts,
+			actor_id,
+		};
+
+		Ok((input, v))
+	}
+}
+
+pub struct PendingActorByPrioritySubspaceKey {
+	pub namespace_id: Id,
+	pub runner_name_selector: String,
+	pub priority: Option<u8>,
+}
+
+impl PendingActorByPrioritySubspaceKey {
+	pub fn new(namespace_id: Id, runner_name_selector: String, priority: Option<u8>) -> Self {
+		PendingActorByPrioritySubspaceKey {
+			namespace_id,
+			runner_name_selector,
+			priority,
+		}
+	}
+}
+
+impl TuplePack for PendingActorByPrioritySubspaceKey {
+	fn pack<W: std::io::Write>(
+		&self,
+		w: &mut W,
+		tuple_depth: TupleDepth,
+	) -> std::io::Result<VersionstampOffset> {
+		let mut offset = VersionstampOffset::None { size: 0 };
+
+		let t = (
+			NAMESPACE,
+			PENDING_ACTOR_BY_PRIORITY,
+			self.namespace_id,
+			&self.runner_name_selector,
+		);
+		offset += t.pack(w, tuple_depth)?;
+
+		if let Some(priority) = &self.priority {
+			// See `PendingActorByPriorityKey`
+			offset += (-(*priority as i32)).pack(w, tuple_depth)?;
+		}
+
+		Ok(offset)
+	}
+}
+
+/// Running low priority actors that can be put to sleep to make room for higher priority actors.
+#[derive(Debug)]
+pub struct PreemptibleActorKey {
+	pub namespace_id: Id,
+	pub runner_name_selector: String,
+	pub actor_id: Id,
+}
+
+impl PreemptibleActorKey {
+	pub fn new(namespace_id: Id, runner_name_selector: String, actor_id: Id) -> Self {
+		PreemptibleActorKey {
+			namespace_id,
+			runner_name_selector,
+			actor_id,
+		}
+	}
+
+	pub fn subspace(namespace_id: Id, runner_name_selector: String) -> PreemptibleActorSubspaceKey {
+		PreemptibleActorSubspaceKey::new(namespace_id, runner_name_selector)
+	}
+}
+
+impl FormalKey for PreemptibleActorKey {
+	/// Amount of runner slots the actor consumes.
+	type Value = u32;
+
+	fn deserialize(&self, raw: &[u8]) -> Result<Self::Value> {
+		Ok(u32::from_be_bytes(raw.try_into()?))
+	}
+
+	fn serialize(&self, value: Self::Value) -> Result<Vec<u8>> {
+		Ok(value.to_be_bytes().to_vec())
+	}
+}
+
+impl TuplePack for PreemptibleActorKey {
+	fn pack<W: std::io::Write>(
+		&self,
+		w: &mut W,
+		tuple_depth: TupleDepth,
+	) -> std::io::Result<VersionstampOffset> {
+		let t = (
+			NAMESPACE,
+			PREEMPTIBLE_ACTOR,
+			self.namespace_id,
+			&self.runner_name_selector,
+			self.actor_id,
+		);
+		t.pack(w, tuple_depth)
+	}
+}
+
+impl<'de> TupleUnpack<'de> for PreemptibleActorKey {
+	fn unpack(input: &[u8], tuple_depth: TupleDepth) -> PackResult<(&[u8], Self)> {
+		let (input, (_, _, namespace_id, runner_name_selector, actor_id)) =
+			<(usize, usize, Id, String, Id)>::unpack(input, tuple_depth)?;
+
+		let v = PreemptibleActorKey {
+			namespace_id,
+			runner_name_selector,
+			actor_id,
+		};
+
+		Ok((input, v))
+	}
+}
+
+pub struct PreemptibleActorSubspaceKey {
+	namespace_id: Id,
+	runner_name_selector: String,
+}
+
+impl PreemptibleActorSubspaceKey {
+	pub fn new(namespace_id: Id, runner_name_selector: String) -> Self {
+		PreemptibleActorSubspaceKey {
+			namespace_id,
+			runner_name_selector,
+		}
+	}
+}
+
+impl TuplePack for PreemptibleActorSubspaceKey {
+	fn pack<W: std::io::Write>(
+		&self,
+		w: &mut W,
+		tuple_depth: TupleDepth,
+	) -> std::io::Result<VersionstampOffset> {
+		let t = (
+			NAMESPACE,
+			PREEMPTIBLE_ACTOR,
+			self.namespace_id,
+			&self.runner_name_selector,
+		);
+		t.pack(w, tuple_depth)
+	}
+}
+
+/// Amount of actors in the `PendingActorByPriorityKey` queue. Kept so the queue depth can be read
+/// without scanning the queue.
+#[derive(Debug)]
+pub struct PendingActorCountKey {
+	namespace_id: Id,
+	runner_name_selector: String,
+}
+
+impl PendingActorCountKey {
+	pub fn new(namespace_id: Id, runner_name_selector: String) -> Self {
+		PendingActorCountKey {
+			namespace_id,
+			runner_name_selector,
+		}
+	}
+}
+
+impl FormalKey for PendingActorCountKey {
+	/// Count.
+	type Value = i64;
+
+	fn deserialize(&self, raw: &[u8]) -> Result<Self::Value> {
+		// NOTE: Atomic ops use little endian
+		Ok(i64::from_le_bytes(raw.try_into()?))
+	}
+
+	fn serialize(&self, value: Self::Value) -> Result<Vec<u8>> {
+		// NOTE: Atomic ops use little endian
+		Ok(value.to_le_bytes().to_vec())
+	}
+}
+
+impl TuplePack for PendingActorCountKey {
+	fn pack<W: std::io::Write>(
+		&self,
+		w: &mut W,
+		tuple_depth: TupleDepth,
+	) -> std::io::Result<VersionstampOffset> {
+		let t = (
+			NAMESPACE,
+			PENDING_ACTOR_COUNT,
+			self.namespace_id,
+			&self.runner_name_selector,
+		);
+		t.pack(w, tuple_depth)
+	}
+}
+
+impl<'de> TupleUnpack<'de> for PendingActorCountKey {
+	fn unpack(input: &[u8], tuple_depth: TupleDepth) -> PackResult<(&[u8], Self)> {
+		let (input, (_, _, namespace_id, runner_name_selector)) =
+			<(usize, usize, Id, String)>::unpack(input, tuple_depth)?;
+
+		let v = PendingActorCountKey {
+			namespace_id,
+			runner_name_selector,
+		};
+
+		Ok((input, v))
+	}
+}
+
 #[derive(Debug)]
 pub struct ActiveActorKey {
 	namespace_id: Id,
@@ -1365,3 +1710,281 @@ impl TuplePack for RunnerNameSubspaceKey {
 		t.pack(w, tuple_depth)
 	}
 }
+
+#[derive(Debug)]
+pub struct RolloutAllocationsKey {
+	namespace_id: Id,
+	name: String,
+	version: u32,
+	window_start_ts: i64,
+}
+
+impl RolloutAllocationsKey {
+	pub fn new(namespace_id: Id, name: String, version: u32, window_start_ts: i64) -> Self {
+		RolloutAllocationsKey {
+			namespace_id,
+			name,
+			version,
+			window_start_ts,
+		}
+	}
+
+	pub fn subspace(namespace_id: Id, name: String, version: u32) -> RolloutAllocationsSubspaceKey {
+		RolloutAllocationsSubspaceKey::new(namespace_id, name, version)
+	}
+}
+
+impl FormalKey for RolloutAllocationsKey {
+	/// Amount of actors allocated to runners of this version during the window.
+	type Value = i64;
+
+	fn deserialize(&self, raw: &[u8]) -> Result<Self::Value> {
+		// NOTE: Atomic ops use little endian
+		Ok(i64::from_le_bytes(raw.try_into()?))
+	}
+
+	fn serialize(&self, value: Self::Value) -> Result<Vec<u8>> {
+		// NOTE: Atomic ops use little endian
+		Ok(value.to_le_bytes().to_vec())
+	}
+}
+
+impl TuplePack for RolloutAllocationsKey {
+	fn pack<W: std::io::Write>(
+		&self,
+		w: &mut W,
+		tuple_depth: TupleDepth,
+	) -> std::io::Result<VersionstampOffset> {
+		let t = (
+			NAMESPACE,
+			ROLLOUT,
+			self.namespace_id,
+			&self.name,
+			self.version,
+			ALLOCATIONS,
+			self.window_start_ts,
+		);
+		t.pack(w, tuple_depth)
+	}
+}
+
+impl<'de> TupleUnpack<'de> for RolloutAllocationsKey {
+	fn unpack(input: &[u8], tuple_depth: TupleDepth) -> PackResult<(&[u8], Self)> {
+		let (input, (_, _, namespace_id, name, version, _, window_start_ts)) =
+			<(usize, usize, Id, String, u32, usize, i64)>::unpack(input, tuple_depth)?;
+
+		let v = RolloutAllocationsKey {
+			namespace_id,
+			name,
+			version,
+			window_start_ts,
+		};
+
+		Ok((input, v))
+	}
+}
+
+pub struct RolloutAllocationsSubspaceKey {
+	namespace_id: Id,
+	name: String,
+	version: u32,
+}
+
+impl RolloutAllocationsSubspaceKey {
+	pub fn new(namespace_id: Id, name: String, version: u32) -> Self {
+		RolloutAllocationsSubspaceKey {
+			namespace_id,
+			name,
+			version,
+		}
+	}
+}
+
+impl TuplePack for RolloutAllocationsSubspaceKey {
+	fn pack<W: std::io::Write>(
+		&self,
+		w: &mut W,
+		tuple_depth: TupleDepth,
+	) -> std::io::Result<VersionstampOffset> {
+		let t = (
+			NAMESPACE,
+			ROLLOUT,
+			self.namespace_id,
+			&self.name,
+			self.version,
+			ALLOCATIONS,
+		);
+		t.pack(w, tuple_depth)
+	}
+}
+
+#[derive(Debug)]
+pub struct RolloutCrashesKey {
+	namespace_id: Id,
+	name: String,
+	version: u32,
+	window_start_ts: i64,
+}
+
+impl RolloutCrashesKey {
+	pub fn new(namespace_id: Id, name: String, version: u32, window_start_ts: i64) -> Self {
+		RolloutCrashesKey {
+			namespace_id,
+			name,
+			version,
+			window_start_ts,
+		}
+	}
+
+	pub fn subspace(namespace_id: Id, name: String, version: u32) -> RolloutCrashesSubspaceKey {
+		RolloutCrashesSubspaceKey::new(namespace_id, name, version)
+	}
+}
+
+impl FormalKey for RolloutCrashesKey {
+	/// Amount of actors that crashed on runners of this version during the window.
+	type Value = i64;
+
+	fn deserialize(&self, raw: &[u8]) -> Result<Self::Value> {
+		// NOTE: Atomic ops use little endian
+		Ok(i64::from_le_bytes(raw.try_into()?))
+	}
+
+	fn serialize(&self, value: Self::Value) -> Result<Vec<u8>> {
+		// NOTE: Atomic ops use little endian
+		Ok(value.to_le_bytes().to_vec())
+	}
+}
+
+impl TuplePack for RolloutCrashesKey {
+	fn pack<W: std::io::Write>(
+		&self,
+		w: &mut W,
+		tuple_depth: TupleDepth,
+	) -> std::io::Result<VersionstampOffset> {
+		let t = (
+			NAMESPACE,
+			ROLLOUT,
+			self.namespace_id,
+			&self.name,
+			self.version,
+			CRASHES,
+			self.window_start_ts,
+		);
+		t.pack(w, tuple_depth)
+	}
+}
+
+impl<'de> TupleUnpack<'de> for RolloutCrashesKey {
+	fn unpack(input: &[u8], tuple_depth: TupleDepth) -> PackResult<(&[u8], Self)> {
+		let (input, (_, _, namespace_id, name, version, _, window_start_ts)) =
+			<(usize, usize, Id, String, u32, usize, i64)>::unpack(input, tuple_depth)?;
+
+		let v = RolloutCrashesKey {
+			namespace_id,
+			name,
+			version,
+			window_start_ts,
+		};
+
+		Ok((input, v))
+	}
+}
+
+pub struct RolloutCrashesSubspaceKey {
+	namespace_id: Id,
+	name: String,
+	version: u32,
+}
+
+impl RolloutCrashesSubspaceKey {
+	pub fn new(namespace_id: Id, name: String, version: u32) -> Self {
+		RolloutCrashesSubspaceKey {
+			namespace_id,
+			name,
+			version,
+		}
+	}
+}
+
+impl TuplePack for RolloutCrashesSubspaceKey {
+	fn pack<W: std::io::Write>(
+		&self,
+		w: &mut W,
+		tuple_depth: TupleDepth,
+	) -> std::io::Result<VersionstampOffset> {
+		let t = (
+			NAMESPACE,
+			ROLLOUT,
+			self.namespace_id,
+			&self.name,
+			self.version,
+			CRASHES,
+		);
+		t.pack(w, tuple_depth)
+	}
+}
+
+#[derive(Debug)]
+pub struct RolloutRolledBackTsKey {
+	namespace_id: Id,
+	name: String,
+	version: u32,
+}
+
+impl RolloutRolledBackTsKey {
+	pub fn new(namespace_id: Id, name: String, version: u32) -> Self {
+		RolloutRolledBackTsKey {
+			namespace_id,
+			name,
+			version,
+		}
+	}
+}
+
+impl FormalKey for RolloutRolledBackTsKey {
+	/// When this version was automatically rolled back. Actors are not allocated to rolled back
+	/// versions.
+	type Value = i64;
+
+	fn deserialize(&self, raw: &[u8]) -> Result<Self::Value> {
+		Ok(i64::from_be_bytes(raw.try_into()?))
+	}
+
+	fn serialize(&self, value: Self::Value) -> Result<Vec<u8>> {
+		Ok(value.to_be_bytes().to_vec())
+	}
+}
+
+impl TuplePack for RolloutRolledBackTsKey {
+	fn pack<W: std::io::Write>(
+		&self,
+		w: &mut W,
+		tuple_depth: TupleDepth,
+	) -> std::io::Result<VersionstampOffset> {
+		let t = (
+			NAMESPACE,
+			ROLLOUT,
+			self.namespace_id,
+			&self.name,
+			self.version,
+			ROLLED_BACK_TS,
+		);
+		t.pack(w, tuple_depth)
+	}
+}
+
+impl<'de> TupleUnpack<'de> for RolloutRolledBackTsKey {
+	fn unpack(input: &[u8], tuple_depth: TupleDepth) -> PackResult<(&[u8], Self)> {
+		let (input, (_, _, namespace_id, name, version, _)) =
+			<(usize, usize, Id, String, u32, usize)>::unpack(input, tuple_depth)?;
+
+		let v = RolloutRolledBackTsKey {
+			namespace_id,
+			name,
+			version,
+		};
+
+		Ok((input, v))
+	}
+}
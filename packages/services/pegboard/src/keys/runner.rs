@@ -507,6 +507,51 @@ impl<'de> TupleUnpack<'de> for VersionKey {
 	}
 }
 
+#[derive(Debug)]
+pub struct ProtocolVersionKey {
+	runner_id: Id,
+}
+
+impl ProtocolVersionKey {
+	pub fn new(runner_id: Id) -> Self {
+		ProtocolVersionKey { runner_id }
+	}
+}
+
+impl FormalKey for ProtocolVersionKey {
+	type Value = u16;
+
+	fn deserialize(&self, raw: &[u8]) -> Result<Self::Value> {
+		Ok(u16::from_be_bytes(raw.try_into()?))
+	}
+
+	fn serialize(&self, value: Self::Value) -> Result<Vec<u8>> {
+		Ok(value.to_be_bytes().to_vec())
+	}
+}
+
+impl TuplePack for ProtocolVersionKey {
+	fn pack<W: std::io::Write>(
+		&self,
+		w: &mut W,
+		tuple_depth: TupleDepth,
+	) -> std::io::Result<VersionstampOffset> {
+		let t = (RUNNER, DATA, self.runner_id, PROTOCOL_VERSION);
+		t.pack(w, tuple_depth)
+	}
+}
+
+impl<'de> TupleUnpack<'de> for ProtocolVersionKey {
+	fn unpack(input: &[u8], tuple_depth: TupleDepth) -> PackResult<(&[u8], Self)> {
+		let (input, (_, _, runner_id, _)) =
+			<(usize, usize, Id, usize)>::unpack(input, tuple_depth)?;
+
+		let v = ProtocolVersionKey { runner_id };
+
+		Ok((input, v))
+	}
+}
+
 #[derive(Debug)]
 pub struct StopTsKey {
 	runner_id: Id,
@@ -815,3 +860,152 @@ impl<'de> TupleUnpack<'de> for MetadataChunkKey {
 		Ok((input, v))
 	}
 }
+
+#[derive(Debug)]
+pub struct LabelKey {
+	runner_id: Id,
+	pub label: String,
+}
+
+impl LabelKey {
+	pub fn new(runner_id: Id, label: String) -> Self {
+		LabelKey { runner_id, label }
+	}
+
+	pub fn subspace(runner_id: Id) -> LabelSubspaceKey {
+		LabelSubspaceKey::new(runner_id)
+	}
+}
+
+impl FormalKey for LabelKey {
+	/// Label value.
+	type Value = String;
+
+	fn deserialize(&self, raw: &[u8]) -> Result<Self::Value> {
+		String::from_utf8(raw.to_vec()).map_err(Into::into)
+	}
+
+	fn serialize(&self, value: Self::Value) -> Result<Vec<u8>> {
+		Ok(value.into_bytes())
+	}
+}
+
+impl TuplePack for LabelKey {
+	fn pack<W: std::io::Write>(
+		&self,
+		w: &mut W,
+		tuple_depth: TupleDepth,
+	) -> std::io::Result<VersionstampOffset> {
+		let t = (RUNNER, DATA, self.runner_id, LABEL, &self.label);
+		t.pack(w, tuple_depth)
+	}
+}
+
+impl<'de> TupleUnpack<'de> for LabelKey {
+	fn unpack(input: &[u8], tuple_depth: TupleDepth) -> PackResult<(&[u8], Self)> {
+		let (input, (_, _, runner_id, data, label)) =
+			<(usize, usize, Id, usize, String)>::unpack(input, tuple_depth)?;
+		if data != LABEL {
+			return Err(PackError::Message("expected LABEL data".into()));
+		}
+
+		let v = LabelKey { runner_id, label };
+
+		Ok((input, v))
+	}
+}
+
+pub struct LabelSubspaceKey {
+	runner_id: Id,
+}
+
+impl LabelSubspaceKey {
+	fn new(runner_id: Id) -> Self {
+		LabelSubspaceKey { runner_id }
+	}
+}
+
+impl TuplePack for LabelSubspaceKey {
+	fn pack<W: std::io::Write>(
+		&self,
+		w: &mut W,
+		tuple_depth: TupleDepth,
+	) -> std::io::Result<VersionstampOffset> {
+		let t = (RUNNER, DATA, self.runner_id, LABEL);
+		t.pack(w, tuple_depth)
+	}
+}
+
+/// Number of actors allocated to a runner in the given anti-affinity group.
+#[derive(Debug)]
+pub struct AntiAffinityCountKey {
+	runner_id: Id,
+	group: String,
+}
+
+impl AntiAffinityCountKey {
+	pub fn new(runner_id: Id, group: String) -> Self {
+		AntiAffinityCountKey { runner_id, group }
+	}
+
+	pub fn subspace(runner_id: Id) -> AntiAffinityCountSubspaceKey {
+		AntiAffinityCountSubspaceKey::new(runner_id)
+	}
+}
+
+impl FormalKey for AntiAffinityCountKey {
+	type Value = i64;
+
+	fn deserialize(&self, raw: &[u8]) -> Result<Self::Value> {
+		// NOTE: Atomic ops use little endian
+		Ok(i64::from_le_bytes(raw.try_into()?))
+	}
+
+	fn serialize(&self, value: Self::Value) -> Result<Vec<u8>> {
+		// NOTE: Atomic ops use little endian
+		Ok(value.to_le_bytes().to_vec())
+	}
+}
+
+impl TuplePack for AntiAffinityCountKey {
+	fn pack<W: std::io::Write>(
+		&self,
+		w: &mut W,
+		tuple_depth: TupleDepth,
+	) -> std::io::Result<VersionstampOffset> {
+		let t = (RUNNER, ANTI_AFFINITY, self.runner_id, &self.group);
+		t.pack(w, tuple_depth)
+	}
+}
+
+impl<'de> TupleUnpack<'de> for AntiAffinityCountKey {
+	fn unpack(input: &[u8], tuple_depth: TupleDepth) -> PackResult<(&[u8], Self)> {
+		let (input, (_, _, runner_id, group)) =
+			<(usize, usize, Id, String)>::unpack(input, tuple_depth)?;
+
+		let v = AntiAffinityCountKey { runner_id, group };
+
+		Ok((input, v))
+	}
+}
+
+pub struct AntiAffinityCountSubspaceKey {
+	runner_id: Id,
+}
+
+impl AntiAffinityCountSubspaceKey {
+	fn new(runner_id: Id) -> Self {
+		AntiAffinityCountSubspaceKey { runner_id }
+	}
+}
+
+impl TuplePack for AntiAffinityCountSubspaceKey {
+	fn pack<W: std::io::Write>(
+		&self,
+		w: &mut W,
+		tuple_depth: TupleDepth,
+	) -> std::io::Result<VersionstampOffset> {
+		let t = (RUNNER, ANTI_AFFINITY, self.runner_id);
+		t.pack(w, tuple_depth)
+	}
+}
@@ -47,6 +47,12 @@ lazy_static::lazy_static! {
 		.with_boundaries(MICRO_BUCKETS.to_vec())
 		.build();
 
+	/// Expected attributes: "outcome"
+	pub static ref ACTOR_PENDING_ALLOCATION_DURATION: Histogram<f64> = METER.f64_histogram("rivet_pegboard_actor_pending_allocation_duration")
+		.with_description("Duration an actor waited in the allocation queue.")
+		.with_boundaries(BUCKETS.to_vec())
+		.build();
+
 	/// Has no expected attributes
 	pub static ref ACTOR_START_DURATION: Histogram<f64> = METER.f64_histogram("rivet_pegboard_actor_start_duration")
 		.with_description("Total duration from actor creation to starting state.")
@@ -0,0 +1,98 @@
+use futures_util::TryStreamExt;
+use gas::prelude::*;
+use rivet_types::actors::ActorLogEntry;
+use universaldb::options::StreamingMode;
+use universaldb::utils::IsolationLevel::*;
+
+use crate::keys;
+
+/// Max number of log lines kept per actor. Older lines are removed when new lines are appended.
+const MAX_LOG_ENTRIES: u64 = 10_000;
+/// Longer log lines are truncated.
+const MAX_LOG_MESSAGE_LEN: usize = util::file_size::kibibytes(4) as usize;
+/// Max number of log lines written at once, so a single append stays well below the transaction size
+/// limit. Extra lines are dropped.
+const MAX_APPEND_ENTRIES: usize = 256;
+
+#[derive(Debug)]
+pub struct Input {
+	pub actor_id: Id,
+	pub entries: Vec<ActorLogEntry>,
+}
+
+#[operation]
+pub async fn pegboard_actor_append_logs(ctx: &OperationCtx, input: &Input) -> Result<()> {
+	if input.entries.is_empty() {
+		return Ok(());
+	}
+
+	if input.entries.len() > MAX_APPEND_ENTRIES {
+		tracing::warn!(
+			actor_id=?input.actor_id,
+			count=input.entries.len(),
+			"too many log lines in one append, dropping extra lines",
+		);
+	}
+
+	ctx.udb()?
+		.run(|tx| async move {
+			let tx = tx.with_subspace(keys::subspace());
+
+			let log_subspace =
+				keys::subspace().subspace(&keys::actor::LogEntryKey::subspace(input.actor_id));
+
+			// Read the index of the last log line
+			let last_entry = tx
+				.get_ranges_keyvalues(
+					universaldb::RangeOption {
+						mode: StreamingMode::Exact,
+						limit: Some(1),
+						reverse: true,
+						..(&log_subspace).into()
+					},
+					Serializable,
+				)
+				.try_next()
+				.await?;
+			let mut idx = if let Some(entry) = last_entry {
+				tx.unpack::<keys::actor::LogEntryKey>(entry.key())?.idx + 1
+			} else {
+				// Start at 1 so that 0 can be used as `after_idx` to read from the first line
+				1
+			};
+
+			for entry in input.entries.iter().take(MAX_APPEND_ENTRIES) {
+				let mut entry = entry.clone();
+				truncate_message(&mut entry.message);
+
+				tx.write(&keys::actor::LogEntryKey::new(input.actor_id, idx), entry)?;
+				idx += 1;
+			}
+
+			// Remove lines past the limit
+			if idx > MAX_LOG_ENTRIES {
+				let (start, _) = log_subspace.range();
+				let end = tx.pack(&keys::actor::LogEntryKey::new(
+					input.actor_id,
+					idx - MAX_LOG_ENTRIES,
+				));
+				tx.clear_range(&start, &end);
+			}
+
+			Ok(())
+		})
+		.custom_instrument(tracing::info_span!("actor_append_logs_tx"))
+		.await?;
+
+	Ok(())
+}
+
+fn truncate_message(message: &mut String) {
+	if message.len() > MAX_LOG_MESSAGE_LEN {
+		let mut len = MAX_LOG_MESSAGE_LEN;
+		while !message.is_char_boundary(len) {
+			len -= 1;
+		}
+		message.truncate(len);
+	}
+}
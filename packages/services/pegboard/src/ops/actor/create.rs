@@ -1,7 +1,7 @@
 use anyhow::Result;
 use gas::prelude::*;
 use rivet_api_util::{Method, request_remote_datacenter};
-use rivet_types::actors::{Actor, CrashPolicy};
+use rivet_types::actors::{Actor, ActorPlacement, ActorPriority, CrashPolicy, RestartPolicy};
 
 #[derive(Debug)]
 pub struct Input {
@@ -11,6 +11,14 @@ pub struct Input {
 	pub key: Option<String>,
 	pub runner_name_selector: String,
 	pub crash_policy: CrashPolicy,
+	pub placement: Option<ActorPlacement>,
+	pub slots: Option<u32>,
+	pub max_pending_duration_ms: Option<i64>,
+	pub priority: Option<ActorPriority>,
+	pub restart_policy: Option<RestartPolicy>,
+	pub ttl_ms: Option<i64>,
+	pub destroy_at_ts: Option<i64>,
+	pub idle_timeout_ms: Option<i64>,
 	pub input: Option<String>,
 	/// If true, will handle ForwardToDatacenter errors by forwarding the request to the correct datacenter.
 	/// Used by api-public. api-peer should set this to false.
@@ -40,6 +48,18 @@ pub async fn pegboard_actor_create(ctx: &OperationCtx, input: &Input) -> Result<
 		.subscribe::<crate::workflows::actor::DestroyStarted>(("actor_id", input.actor_id))
 		.await?;
 
+	// Actors without their own limit use the default of the runner config
+	let max_pending_duration_ms = if input.max_pending_duration_ms.is_some() {
+		input.max_pending_duration_ms
+	} else {
+		ctx.op(namespace::ops::runner_config::get_global::Input {
+			runners: vec![(input.namespace_id, input.runner_name_selector.clone())],
+		})
+		.await?
+		.first()
+		.and_then(|runner_config| runner_config.config.max_pending_duration_ms)
+	};
+
 	// Dispatch actor workflow
 	ctx.workflow(crate::workflows::actor::Input {
 		actor_id: input.actor_id,
@@ -49,6 +69,14 @@ pub async fn pegboard_actor_create(ctx: &OperationCtx, input: &Input) -> Result<
 		runner_name_selector: input.runner_name_selector.clone(),
 		input: input.input.clone(),
 		crash_policy: input.crash_policy,
+		placement: input.placement.clone(),
+		slots: input.slots,
+		max_pending_duration_ms,
+		priority: input.priority,
+		restart_policy: input.restart_policy.clone(),
+		ttl_ms: input.ttl_ms,
+		destroy_at_ts: input.destroy_at_ts,
+		idle_timeout_ms: input.idle_timeout_ms,
 	})
 	.tag("actor_id", input.actor_id)
 	.dispatch()
@@ -76,7 +104,15 @@ pub async fn pegboard_actor_create(ctx: &OperationCtx, input: &Input) -> Result<
 						input.key.clone(),
 						input.runner_name_selector.clone(),
 						input.input.clone(),
-					input.crash_policy
+						input.crash_policy,
+						input.placement.clone(),
+						input.slots,
+						input.max_pending_duration_ms,
+						input.priority,
+						input.restart_policy.clone(),
+						input.ttl_ms,
+						input.destroy_at_ts,
+						input.idle_timeout_ms,
 					).await;
 				}
 			}
@@ -116,6 +152,14 @@ async fn forward_to_datacenter(
 	runner_name_selector: String,
 	input: Option<String>,
 	crash_policy: CrashPolicy,
+	placement: Option<ActorPlacement>,
+	slots: Option<u32>,
+	max_pending_duration_ms: Option<i64>,
+	priority: Option<ActorPriority>,
+	restart_policy: Option<RestartPolicy>,
+	ttl_ms: Option<i64>,
+	destroy_at_ts: Option<i64>,
+	idle_timeout_ms: Option<i64>,
 ) -> Result<Output> {
 	// Get the datacenter configuration
 	let _target_dc = ctx
@@ -149,6 +193,14 @@ async fn forward_to_datacenter(
 			input,
 			runner_name_selector,
 			crash_policy,
+			placement,
+			slots,
+			max_pending_duration_ms,
+			priority,
+			restart_policy,
+			ttl_ms,
+			destroy_at_ts,
+			idle_timeout_ms,
 		}),
 	)
 	.await?;
@@ -8,6 +8,8 @@ pub struct Input {
 	pub namespace_id: Id,
 	pub name: String,
 	pub key: String,
+	/// See `get_reservation_for_key::Input::linearizable`.
+	pub linearizable: bool,
 }
 
 #[derive(Debug)]
@@ -23,6 +25,7 @@ pub async fn pegboard_actor_get_for_key(ctx: &OperationCtx, input: &Input) -> Re
 			namespace_id: input.namespace_id,
 			name: input.name.clone(),
 			key: input.key.clone(),
+			linearizable: input.linearizable,
 		})
 		.await?;
 
@@ -41,6 +44,7 @@ pub async fn pegboard_actor_get_for_key(ctx: &OperationCtx, input: &Input) -> Re
 				key: Some(input.key.clone()),
 				include_destroyed: false,
 				created_before: None,
+				cursor: None,
 				limit: 1,
 			})
 			.await?;
@@ -8,6 +8,9 @@ pub struct Input {
 	pub namespace_id: Id,
 	pub name: String,
 	pub key: String,
+	/// Double-check with a linearizable read if the key is not found optimistically. Optimistic
+	/// reads can miss reservations made in other datacenters that have not propagated yet.
+	pub linearizable: bool,
 }
 
 #[derive(Debug)]
@@ -28,7 +31,7 @@ pub async fn pegboard_actor_get_reservation_for_key(
 	);
 
 	// Get the reservation ID using optimistic read (global consistency)
-	let value = ctx
+	let mut value = ctx
 		.op(epoxy::ops::kv::get_optimistic::Input {
 			replica_id: ctx.config().epoxy_replica_id(),
 			key: keys::subspace().pack(&reservation_key),
@@ -36,6 +39,17 @@ pub async fn pegboard_actor_get_reservation_for_key(
 		.await?
 		.value;
 
+	// A missing reservation is rare for callers that ask for this, so the consensus round is
+	// acceptable
+	if value.is_none() && input.linearizable {
+		value = ctx
+			.op(epoxy::ops::kv::get_linearizable::Input {
+				key: keys::subspace().pack(&reservation_key),
+			})
+			.await?
+			.value;
+	}
+
 	// Deserialize the reservation ID if it exists
 	let reservation_id = match value {
 		Some(value) => Some(reservation_key.deserialize(&value)?),
@@ -0,0 +1,59 @@
+use base64::prelude::*;
+use futures_util::{StreamExt, TryStreamExt};
+use gas::prelude::*;
+use rivet_types::actors::ActorAlarm;
+use universaldb::options::StreamingMode;
+use universaldb::utils::IsolationLevel::*;
+
+use crate::keys;
+
+#[derive(Debug)]
+pub struct Input {
+	pub actor_id: Id,
+}
+
+#[derive(Debug)]
+pub struct Output {
+	/// Pending named alarms ordered by the time they fire.
+	pub alarms: Vec<ActorAlarm>,
+}
+
+#[operation]
+pub async fn pegboard_actor_list_alarms(ctx: &OperationCtx, input: &Input) -> Result<Output> {
+	let mut alarms = ctx
+		.udb()?
+		.run(|tx| async move {
+			let tx = tx.with_subspace(keys::subspace());
+
+			let alarm_subspace =
+				keys::subspace().subspace(&keys::actor::AlarmKey::subspace(input.actor_id));
+
+			let alarms = tx
+				.get_ranges_keyvalues(
+					universaldb::RangeOption {
+						mode: StreamingMode::WantAll,
+						..(&alarm_subspace).into()
+					},
+					Snapshot,
+				)
+				.map(|res| -> Result<ActorAlarm> {
+					let (key, alarm) = tx.read_entry::<keys::actor::AlarmKey>(&res?)?;
+
+					Ok(ActorAlarm {
+						name: key.name,
+						ts: alarm.ts,
+						payload: alarm.payload.map(|x| BASE64_STANDARD.encode(x)),
+					})
+				})
+				.try_collect::<Vec<_>>()
+				.await?;
+
+			Ok(alarms)
+		})
+		.custom_instrument(tracing::info_span!("actor_list_alarms_tx"))
+		.await?;
+
+	alarms.sort_by_key(|alarm| alarm.ts);
+
+	Ok(Output { alarms })
+}
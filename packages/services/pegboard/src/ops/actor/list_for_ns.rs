@@ -13,6 +13,9 @@ pub struct Input {
 	pub key: Option<String>,
 	pub include_destroyed: bool,
 	pub created_before: Option<i64>,
+	/// Only return actors listed after this `(create_ts, actor_id)`. Takes precedence over
+	/// `created_before` and allows paging through actors with the same create timestamp.
+	pub cursor: Option<(i64, Id)>,
 	pub limit: usize,
 }
 
@@ -37,7 +40,15 @@ pub async fn pegboard_actor_list_for_ns(ctx: &OperationCtx, input: &Input) -> Re
 				));
 				let (start, end) = actor_subspace.range();
 
-				let end = if let Some(created_before) = input.created_before {
+				let end = if let Some((create_ts, actor_id)) = input.cursor {
+					tx.pack(&keys::ns::ActorByKeyKey::new(
+						input.namespace_id,
+						input.name.clone(),
+						key.clone(),
+						create_ts,
+						actor_id,
+					))
+				} else if let Some(created_before) = input.created_before {
 					universaldb::utils::end_of_key_range(&tx.pack(
 						&keys::ns::ActorByKeyKey::subspace_with_create_ts(
 							input.namespace_id,
@@ -78,7 +89,14 @@ pub async fn pegboard_actor_list_for_ns(ctx: &OperationCtx, input: &Input) -> Re
 				));
 				let (start, end) = actor_subspace.range();
 
-				let end = if let Some(created_before) = input.created_before {
+				let end = if let Some((create_ts, actor_id)) = input.cursor {
+					tx.pack(&keys::ns::AllActorKey::new(
+						input.namespace_id,
+						input.name.clone(),
+						create_ts,
+						actor_id,
+					))
+				} else if let Some(created_before) = input.created_before {
 					universaldb::utils::end_of_key_range(&tx.pack(
 						&keys::ns::AllActorKey::subspace_with_create_ts(
 							input.namespace_id,
@@ -115,7 +133,14 @@ pub async fn pegboard_actor_list_for_ns(ctx: &OperationCtx, input: &Input) -> Re
 				);
 				let (start, end) = actor_subspace.range();
 
-				let end = if let Some(created_before) = input.created_before {
+				let end = if let Some((create_ts, actor_id)) = input.cursor {
+					tx.pack(&keys::ns::ActiveActorKey::new(
+						input.namespace_id,
+						input.name.clone(),
+						create_ts,
+						actor_id,
+					))
+				} else if let Some(created_before) = input.created_before {
 					universaldb::utils::end_of_key_range(&tx.pack(
 						&keys::ns::ActiveActorKey::subspace_with_create_ts(
 							input.namespace_id,
@@ -0,0 +1,75 @@
+use futures_util::{StreamExt, TryStreamExt};
+use gas::prelude::*;
+use rivet_types::actors::ActorLogEntry;
+use universaldb::options::StreamingMode;
+use universaldb::utils::IsolationLevel::*;
+
+use crate::keys;
+
+#[derive(Debug)]
+pub struct Input {
+	pub actor_id: Id,
+	/// Only return the first `limit` lines after this index. Indexes start at 1, so 0 reads from
+	/// the first line. If none, returns the last `limit` lines.
+	pub after_idx: Option<u64>,
+	pub limit: usize,
+}
+
+#[derive(Debug)]
+pub struct Output {
+	/// Log lines in the order they were written.
+	pub entries: Vec<ActorLogEntry>,
+	/// Index of the last returned line. Pass as `after_idx` to read newer lines.
+	pub last_idx: Option<u64>,
+}
+
+#[operation]
+pub async fn pegboard_actor_list_logs(ctx: &OperationCtx, input: &Input) -> Result<Output> {
+	let entries = ctx
+		.udb()?
+		.run(|tx| async move {
+			let tx = tx.with_subspace(keys::subspace());
+
+			let log_subspace =
+				keys::subspace().subspace(&keys::actor::LogEntryKey::subspace(input.actor_id));
+			let (start, end) = log_subspace.range();
+
+			let (start, reverse) = if let Some(after_idx) = input.after_idx {
+				let mut start = tx.pack(&keys::actor::LogEntryKey::new(input.actor_id, after_idx));
+				// Skip the given line
+				start.push(0xFF);
+
+				(start, false)
+			} else {
+				(start, true)
+			};
+
+			let mut entries = tx
+				.get_ranges_keyvalues(
+					universaldb::RangeOption {
+						mode: StreamingMode::WantAll,
+						limit: Some(input.limit),
+						reverse,
+						..(start, end).into()
+					},
+					// NOTE: This is not Serializable to prevent contention with appending logs
+					Snapshot,
+				)
+				.map(|res| tx.read_entry::<keys::actor::LogEntryKey>(&res?))
+				.try_collect::<Vec<_>>()
+				.await?;
+
+			if reverse {
+				entries.reverse();
+			}
+
+			Ok(entries)
+		})
+		.custom_instrument(tracing::info_span!("actor_list_logs_tx"))
+		.await?;
+
+	Ok(Output {
+		last_idx: entries.last().map(|(key, _)| key.idx).or(input.after_idx),
+		entries: entries.into_iter().map(|(_, entry)| entry).collect(),
+	})
+}
@@ -1,7 +1,10 @@
+pub mod append_logs;
 pub mod create;
 pub mod get;
 pub mod get_for_key;
 pub mod get_reservation_for_key;
 pub mod get_runner;
+pub mod list_alarms;
 pub mod list_for_ns;
+pub mod list_logs;
 pub mod list_names;
@@ -0,0 +1,135 @@
+use futures_util::TryStreamExt;
+use gas::prelude::*;
+use rivet_types::actors::ActorPriority;
+use universaldb::options::StreamingMode;
+use universaldb::utils::IsolationLevel::*;
+
+use crate::keys;
+
+/// Max number of queue entries read when looking for the position of an actor.
+pub const MAX_POSITION_SCAN: usize = 10_000;
+
+#[derive(Debug)]
+pub struct Input {
+	pub namespace_id: Id,
+	pub runner_name_selector: String,
+	/// Actor to find the position of in the queue.
+	pub actor_id: Option<Id>,
+}
+
+#[derive(Debug)]
+pub struct Output {
+	/// Amount of actors waiting to be allocated.
+	pub depth: usize,
+	pub oldest_pending_allocation_ts: Option<i64>,
+	/// 0-based position of `actor_id` in the queue, taking priority into account. `None` if the actor
+	/// is not in the queue or is further back than `MAX_POSITION_SCAN`.
+	pub position: Option<usize>,
+}
+
+#[operation]
+pub async fn pegboard_runner_get_queue(ctx: &OperationCtx, input: &Input) -> Result<Output> {
+	ctx.udb()?
+		.run(|tx| async move {
+			let tx = tx.with_subspace(keys::subspace());
+
+			// Legacy queue entries are not counted until moved to the priority queue
+			crate::workflows::runner::migrate_legacy_pending_actors(
+				&tx,
+				input.namespace_id,
+				&input.runner_name_selector,
+			)
+			.await?;
+
+			let depth = tx
+				.read_opt(
+					&keys::ns::PendingActorCountKey::new(
+						input.namespace_id,
+						input.runner_name_selector.clone(),
+					),
+					// NOTE: This is not Serializable to prevent contention with the allocation queue
+					Snapshot,
+				)
+				.await?
+				.unwrap_or_default()
+				.max(0) as usize;
+
+			// Each priority is sorted by pending ts, so the oldest entry is the first one of any priority
+			let mut oldest_pending_allocation_ts = None;
+			for priority in [
+				ActorPriority::High,
+				ActorPriority::Normal,
+				ActorPriority::Low,
+			] {
+				let priority_subspace = keys::subspace().subspace(
+					&keys::ns::PendingActorByPriorityKey::priority_subspace(
+						input.namespace_id,
+						input.runner_name_selector.clone(),
+						priority as u8,
+					),
+				);
+
+				let mut stream = tx.get_ranges_keyvalues(
+					universaldb::RangeOption {
+						mode: StreamingMode::Exact,
+						limit: Some(1),
+						..(&priority_subspace).into()
+					},
+					Snapshot,
+				);
+
+				if let Some(entry) = stream.try_next().await? {
+					let (queue_key, _) =
+						tx.read_entry::<keys::ns::PendingActorByPriorityKey>(&entry)?;
+
+					oldest_pending_allocation_ts = Some(
+						oldest_pending_allocation_ts
+							.map_or(queue_key.ts, |ts: i64| ts.min(queue_key.ts)),
+					);
+				}
+			}
+
+			let position = if let Some(actor_id) = input.actor_id {
+				let pending_actor_subspace =
+					keys::subspace().subspace(&keys::ns::PendingActorByPriorityKey::subspace(
+						input.namespace_id,
+						input.runner_name_selector.clone(),
+					));
+
+				let mut stream = tx.get_ranges_keyvalues(
+					universaldb::RangeOption {
+						mode: StreamingMode::Iterator,
+						limit: Some(MAX_POSITION_SCAN),
+						..(&pending_actor_subspace).into()
+					},
+					Snapshot,
+				);
+
+				let mut idx = 0;
+				let mut position = None;
+				while let Some(entry) = stream.try_next().await? {
+					let (queue_key, _) =
+						tx.read_entry::<keys::ns::PendingActorByPriorityKey>(&entry)?;
+
+					if queue_key.actor_id == actor_id {
+						position = Some(idx);
+						break;
+					}
+
+					idx += 1;
+				}
+
+				position
+			} else {
+				None
+			};
+
+			Ok(Output {
+				depth,
+				oldest_pending_allocation_ts,
+				position,
+			})
+		})
+		.custom_instrument(tracing::info_span!("runner_get_queue_tx"))
+		.await
+}
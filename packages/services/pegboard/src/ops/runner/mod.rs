@@ -1,5 +1,6 @@
 pub mod get;
 pub mod get_by_key;
+pub mod get_queue;
 pub mod list_for_ns;
 pub mod list_names;
 pub mod update_alloc_idx;
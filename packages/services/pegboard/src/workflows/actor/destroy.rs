@@ -24,6 +24,9 @@ pub(crate) struct Input {
 pub(crate) async fn pegboard_actor_destroy(ctx: &mut WorkflowCtx, input: &Input) -> Result<()> {
 	ctx.msg(DestroyStarted {})
 		.tag("actor_id", input.actor_id)
+		.tag("namespace_id", input.namespace_id)
+		.topic(input.namespace_id)
+		.tag("name", &input.name)
 		.send()
 		.await?;
 
@@ -35,7 +38,14 @@ pub(crate) async fn pegboard_actor_destroy(ctx: &mut WorkflowCtx, input: &Input)
 
 	// Destroy actor
 	if let (Some(runner_workflow_id), true) = (res.runner_workflow_id, &input.kill) {
-		kill(ctx, input.actor_id, input.generation, runner_workflow_id).await?;
+		kill(
+			ctx,
+			input.actor_id,
+			input.generation,
+			runner_workflow_id,
+			protocol::StopIntent::Stop,
+		)
+		.await?;
 	}
 
 	// Clear KV
@@ -46,6 +56,9 @@ pub(crate) async fn pegboard_actor_destroy(ctx: &mut WorkflowCtx, input: &Input)
 
 	ctx.msg(DestroyComplete {})
 		.tag("actor_id", input.actor_id)
+		.tag("namespace_id", input.namespace_id)
+		.topic(input.namespace_id)
+		.tag("name", &input.name)
 		.send()
 		.await?;
 
@@ -79,6 +92,11 @@ async fn update_state_and_db(
 
 				tx.write(&keys::actor::DestroyTsKey::new(input.actor_id), destroy_ts)?;
 
+				// Clear named alarms
+				tx.clear_subspace_range(
+					&keys::subspace().subspace(&keys::actor::AlarmKey::subspace(input.actor_id)),
+				);
+
 				if let Some(runner_id) = state.runner_id {
 					clear_slot(
 						input.actor_id,
@@ -86,6 +104,8 @@ async fn update_state_and_db(
 						&state.runner_name_selector,
 						runner_id,
 						state.for_serverless,
+						state.anti_affinity_group.as_deref(),
+						state.slots,
 						&tx,
 					)
 					.await?;
@@ -128,6 +148,27 @@ async fn update_state_and_db(
 	Ok(UpdateStateAndDbOutput { runner_workflow_id })
 }
 
+#[derive(Debug, Serialize, Deserialize, Hash)]
+pub(crate) struct ClearLogsInput {
+	pub actor_id: Id,
+}
+
+#[activity(ClearLogs)]
+pub(crate) async fn clear_logs(ctx: &ActivityCtx, input: &ClearLogsInput) -> Result<()> {
+	ctx.udb()?
+		.run(|tx| async move {
+			tx.clear_subspace_range(
+				&keys::subspace().subspace(&keys::actor::LogEntryKey::subspace(input.actor_id)),
+			);
+
+			Ok(())
+		})
+		.custom_instrument(tracing::info_span!("actor_clear_logs_tx"))
+		.await?;
+
+	Ok(())
+}
+
 #[derive(Debug, Serialize, Deserialize, Hash)]
 struct ClearKvInput {
 	actor_id: Id,
@@ -164,11 +205,35 @@ pub(crate) async fn clear_slot(
 	runner_name_selector: &str,
 	runner_id: Id,
 	for_serverless: bool,
+	anti_affinity_group: Option<&str>,
+	slots: u32,
 	tx: &universaldb::Transaction,
 ) -> Result<()> {
 	let tx = tx.with_subspace(keys::subspace());
 
 	tx.delete(&keys::actor::RunnerIdKey::new(actor_id));
+	tx.delete(&keys::ns::PreemptibleActorKey::new(
+		namespace_id,
+		runner_name_selector.to_string(),
+		actor_id,
+	));
+
+	if let Some(group) = anti_affinity_group {
+		let anti_affinity_key =
+			keys::runner::AntiAffinityCountKey::new(runner_id, group.to_string());
+
+		// Delete the key once the runner has no actors of this group left so counts don't
+		// accumulate for every group a runner has ever hosted
+		let count = tx
+			.read_opt(&anti_affinity_key, Serializable)
+			.await?
+			.unwrap_or_default();
+		if count > 1 {
+			tx.write(&anti_affinity_key, count - 1)?;
+		} else {
+			tx.delete(&anti_affinity_key);
+		}
+	}
 
 	// This is cleared when the state changes as well as when the actor is destroyed to ensure
 	// consistency during rescheduling and forced deletion.
@@ -195,7 +260,7 @@ pub(crate) async fn clear_slot(
 	)?;
 
 	let old_runner_remaining_millislots = (runner_remaining_slots * 1000) / runner_total_slots;
-	let new_runner_remaining_slots = runner_remaining_slots + 1;
+	let new_runner_remaining_slots = (runner_remaining_slots + slots).min(runner_total_slots);
 
 	// Write new remaining slots
 	tx.write(&runner_remaining_slots_key, new_runner_remaining_slots)?;
@@ -240,7 +305,7 @@ pub(crate) async fn clear_slot(
 				namespace_id,
 				runner_name_selector.to_string(),
 			),
-			&(-1i32).to_le_bytes(),
+			&(-(slots as i32)).to_le_bytes(),
 			MutationType::Add,
 		);
 	}
@@ -253,10 +318,12 @@ pub(crate) async fn kill(
 	actor_id: Id,
 	generation: u32,
 	runner_workflow_id: Id,
+	intent: protocol::StopIntent,
 ) -> Result<()> {
 	ctx.signal(protocol::Command::StopActor {
 		actor_id,
 		generation,
+		intent,
 	})
 	.to_workflow_id(runner_workflow_id)
 	.send()
@@ -1,17 +1,24 @@
 use futures_util::FutureExt;
 use gas::prelude::*;
 use rivet_runner_protocol::protocol;
-use rivet_types::actors::CrashPolicy;
+use rivet_types::actors::{ActorPlacement, ActorPriority, CrashPolicy, RestartPolicy};
 
 use crate::{errors, workflows::runner::AllocatePendingActorsInput};
 
 mod actor_keys;
 mod destroy;
+pub(crate) mod placement;
 mod runtime;
 mod setup;
 
 /// Time to delay an actor from rescheduling after a rescheduling failure.
 const BASE_RETRY_TIMEOUT_MS: usize = 2000;
+/// Max random delay added to the restart backoff.
+const BACKOFF_JITTER_MS: u64 = 500;
+/// The default max restart backoff is the base backoff doubled this many times.
+const DEFAULT_BACKOFF_MAX_EXPONENT: u32 = 8;
+/// Max base and max restart backoff.
+const MAX_BACKOFF_MS: u64 = util::duration::hours(1) as u64;
 /// How long to wait after creating and not receiving a starting state before setting actor as lost.
 const ACTOR_START_THRESHOLD_MS: i64 = util::duration::seconds(30);
 /// How long to wait after stopping and not receiving a stop state before setting actor as lost.
@@ -19,6 +26,14 @@ const ACTOR_STOP_THRESHOLD_MS: i64 = util::duration::seconds(30);
 /// How long an actor goes without retries before it's retry count is reset to 0, effectively resetting its
 /// backoff to 0.
 const RETRY_RESET_DURATION_MS: i64 = util::duration::minutes(10);
+/// Max amount of named alarms an actor can have at once.
+const MAX_NAMED_ALARMS: usize = 128;
+/// Max size of the payload of a single named alarm.
+const MAX_ALARM_PAYLOAD_BYTES: usize = 64 * 1024;
+/// Max TTL and idle timeout of an actor and how far in the future its destroy timestamp can be.
+const MAX_TTL_MS: i64 = util::duration::days(365);
+/// How long captured logs are kept after the actor is destroyed.
+const LOG_RETENTION_MS: i64 = util::duration::days(3);
 
 #[derive(Clone, Debug, Serialize, Deserialize, Hash)]
 pub struct Input {
@@ -32,6 +47,44 @@ pub struct Input {
 
 	/// Arbitrary user string.
 	pub input: Option<String>,
+
+	#[serde(default)]
+	pub placement: Option<ActorPlacement>,
+	/// Amount of runner slots the actor consumes. Defaults to 1.
+	#[serde(default)]
+	pub slots: Option<u32>,
+	/// Destroy the actor if it waits longer than this for allocation.
+	#[serde(default)]
+	pub max_pending_duration_ms: Option<i64>,
+	/// Order in the allocation queue relative to other pending actors. Defaults to normal.
+	#[serde(default)]
+	pub priority: Option<ActorPriority>,
+	/// Limits restarts when the crash policy is `CrashPolicy::Restart`.
+	#[serde(default)]
+	pub restart_policy: Option<RestartPolicy>,
+	/// Destroy the actor this long after it was created.
+	#[serde(default)]
+	pub ttl_ms: Option<i64>,
+	/// Destroy the actor at this timestamp.
+	#[serde(default)]
+	pub destroy_at_ts: Option<i64>,
+	/// Destroy the actor once it has been sleeping for this long.
+	#[serde(default)]
+	pub idle_timeout_ms: Option<i64>,
+}
+
+impl Input {
+	/// Timestamp at which the actor is destroyed regardless of its state, based on `ttl_ms` and
+	/// `destroy_at_ts`.
+	pub fn expire_ts(&self, create_ts: i64) -> Option<i64> {
+		[
+			self.ttl_ms.map(|ttl_ms| create_ts.saturating_add(ttl_ms)),
+			self.destroy_at_ts,
+		]
+		.into_iter()
+		.flatten()
+		.min()
+	}
 }
 
 #[derive(Deserialize, Serialize, Clone)]
@@ -48,6 +101,12 @@ pub struct State {
 
 	#[serde(default)]
 	pub for_serverless: bool,
+	/// Set if the actor was created with anti-affinity.
+	#[serde(default)]
+	pub anti_affinity_group: Option<String>,
+	/// Amount of runner slots the actor consumes.
+	#[serde(default = "default_slots")]
+	pub slots: u32,
 
 	pub start_ts: Option<i64>,
 	// NOTE: This is not the alarm ts, this is when the actor started sleeping. See `LifecycleState` for alarm
@@ -69,6 +128,8 @@ impl State {
 		namespace_id: Id,
 		runner_name_selector: String,
 		crash_policy: CrashPolicy,
+		anti_affinity_group: Option<String>,
+		slots: u32,
 		create_ts: i64,
 	) -> Self {
 		State {
@@ -83,6 +144,8 @@ impl State {
 			create_complete_ts: None,
 
 			for_serverless: false,
+			anti_affinity_group,
+			slots,
 
 			start_ts: None,
 			pending_allocation_ts: None,
@@ -97,6 +160,10 @@ impl State {
 	}
 }
 
+fn default_slots() -> u32 {
+	1
+}
+
 #[workflow]
 pub async fn pegboard_actor(ctx: &mut WorkflowCtx, input: &Input) -> Result<()> {
 	// Actor creation follows a careful sequence to prevent race conditions:
@@ -117,13 +184,23 @@ pub async fn pegboard_actor(ctx: &mut WorkflowCtx, input: &Input) -> Result<()>
 			name: input.name.clone(),
 			key: input.key.clone(),
 			namespace_id: input.namespace_id,
+			runner_name_selector: input.runner_name_selector.clone(),
 			input: input.input.clone(),
+			slots: input.slots,
+			max_pending_duration_ms: input.max_pending_duration_ms,
+			restart_policy: input.restart_policy.clone(),
+			ttl_ms: input.ttl_ms,
+			destroy_at_ts: input.destroy_at_ts,
+			idle_timeout_ms: input.idle_timeout_ms,
 		})
 		.await?;
 
 	if let Err(error) = validation_res {
 		ctx.msg(Failed { error })
 			.tag("actor_id", input.actor_id)
+			.tag("namespace_id", input.namespace_id)
+			.topic(input.namespace_id)
+			.tag("name", &input.name)
 			.send()
 			.await?;
 
@@ -138,6 +215,8 @@ pub async fn pegboard_actor(ctx: &mut WorkflowCtx, input: &Input) -> Result<()>
 		namespace_id: input.namespace_id,
 		runner_name_selector: input.runner_name_selector.clone(),
 		crash_policy: input.crash_policy,
+		placement: input.placement.clone(),
+		slots: input.slots.unwrap_or_else(default_slots),
 		create_ts: ctx.create_ts(),
 	})
 	.await?;
@@ -160,6 +239,9 @@ pub async fn pegboard_actor(ctx: &mut WorkflowCtx, input: &Input) -> Result<()>
 					},
 				})
 				.tag("actor_id", input.actor_id)
+				.tag("namespace_id", input.namespace_id)
+				.topic(input.namespace_id)
+				.tag("name", &input.name)
 				.send()
 				.await?;
 
@@ -185,6 +267,9 @@ pub async fn pegboard_actor(ctx: &mut WorkflowCtx, input: &Input) -> Result<()>
 					},
 				})
 				.tag("actor_id", input.actor_id)
+				.tag("namespace_id", input.namespace_id)
+				.topic(input.namespace_id)
+				.tag("name", &input.name)
 				.send()
 				.await?;
 
@@ -213,10 +298,13 @@ pub async fn pegboard_actor(ctx: &mut WorkflowCtx, input: &Input) -> Result<()>
 
 	ctx.msg(CreateComplete {})
 		.tag("actor_id", input.actor_id)
+		.tag("namespace_id", input.namespace_id)
+		.topic(input.namespace_id)
+		.tag("name", &input.name)
 		.send()
 		.await?;
 
-	let Some(allocate_res) = runtime::spawn_actor(ctx, input, 0).await? else {
+	let Some(allocate_res) = runtime::spawn_actor(ctx, input, 0, None).await? else {
 		// Destroyed early
 		ctx.workflow(destroy::Input {
 			namespace_id: input.namespace_id,
@@ -232,6 +320,8 @@ pub async fn pegboard_actor(ctx: &mut WorkflowCtx, input: &Input) -> Result<()>
 		return Ok(());
 	};
 
+	let expire_ts = input.expire_ts(ctx.create_ts());
+
 	let lifecycle_res = ctx
 		.loope(
 			runtime::LifecycleState::new(allocate_res.runner_id, allocate_res.runner_workflow_id),
@@ -239,28 +329,81 @@ pub async fn pegboard_actor(ctx: &mut WorkflowCtx, input: &Input) -> Result<()>
 				let input = input.clone();
 
 				async move {
-					let sig = if let Some(gc_timeout_ts) = state.gc_timeout_ts {
-						// Listen for signal with gc timeout. if a timeout happens, it means this actor is lost
-						if let Some(sig) = ctx.listen_until::<Main>(gc_timeout_ts).await? {
-							sig
-						} else {
-							tracing::warn!(actor_id=?input.actor_id, "actor lost");
+					let timer = if let Some(gc_timeout_ts) = state.gc_timeout_ts {
+						Some((gc_timeout_ts, LifecycleTimer::GcTimeout))
+					} else {
+						let named_alarm_ts = state.named_alarms.values().min().copied();
+						// The legacy alarm only wakes the actor, so it does not apply while running
+						let alarm_ts = state.alarm_ts.filter(|_| state.sleeping);
 
-							// Fake signal
-							Main::Lost(Lost {
-								generation: state.generation,
-							})
+						match (alarm_ts, named_alarm_ts) {
+							(Some(alarm_ts), Some(named_alarm_ts)) if named_alarm_ts < alarm_ts => {
+								Some((named_alarm_ts, LifecycleTimer::NamedAlarm))
+							}
+							(Some(alarm_ts), _) => Some((alarm_ts, LifecycleTimer::Alarm)),
+							(None, Some(named_alarm_ts)) => {
+								Some((named_alarm_ts, LifecycleTimer::NamedAlarm))
+							}
+							(None, None) => None,
+						}
+					};
+
+					// Scheduled destruction takes precedence over other timers if it is earlier
+					let destroy_ts = [expire_ts, state.idle_destroy_ts]
+						.into_iter()
+						.flatten()
+						.min();
+					let timer = match (timer, destroy_ts) {
+						(Some((ts, _)), Some(destroy_ts)) if destroy_ts < ts => {
+							Some((destroy_ts, LifecycleTimer::Destroy))
 						}
-					} else if let Some(alarm_ts) = state.alarm_ts {
-						// Listen for signal with timeout. if a timeout happens, it means this actor should
-						// wake up
-						if let Some(sig) = ctx.listen_until::<Main>(alarm_ts).await? {
+						(None, Some(destroy_ts)) => Some((destroy_ts, LifecycleTimer::Destroy)),
+						(timer, _) => timer,
+					};
+
+					let sig = if let Some((ts, timer)) = timer {
+						// Listen for signal with timeout. If a timeout happens, act according to the timer
+						if let Some(sig) = ctx.listen_until::<Main>(ts).await? {
 							sig
 						} else {
-							tracing::debug!(actor_id=?input.actor_id, "actor wake");
+							match timer {
+								LifecycleTimer::GcTimeout => {
+									tracing::warn!(actor_id=?input.actor_id, "actor lost");
+
+									// Fake signal
+									Main::Lost(Lost {
+										generation: state.generation,
+									})
+								}
+								LifecycleTimer::Alarm => {
+									tracing::debug!(actor_id=?input.actor_id, "actor wake");
 
-							// Fake signal
-							Main::Wake(Wake {})
+									// Fake signal
+									Main::Wake(Wake {})
+								}
+								LifecycleTimer::NamedAlarm => {
+									if state.sleeping {
+										tracing::debug!(
+											actor_id=?input.actor_id,
+											"actor wake for named alarm",
+										);
+
+										// Fake signal. Due alarms are fired once the actor is running
+										Main::Wake(Wake {})
+									} else {
+										runtime::fire_named_alarms(ctx, &input, state, Some(ts))
+											.await?;
+
+										return Ok(Loop::Continue);
+									}
+								}
+								LifecycleTimer::Destroy => {
+									tracing::debug!(actor_id=?input.actor_id, "actor expired");
+
+									// Fake signal
+									Main::Destroy(Destroy {})
+								}
+							}
 						}
 					} else {
 						// Listen for signal normally
@@ -277,23 +420,7 @@ pub async fn pegboard_actor(ctx: &mut WorkflowCtx, input: &Input) -> Result<()>
 							match sig {
 								protocol::Event::ActorIntent { intent, .. } => match intent {
 									protocol::ActorIntent::Sleep => {
-										state.gc_timeout_ts =
-											Some(util::timestamp::now() + ACTOR_STOP_THRESHOLD_MS);
-										state.sleeping = true;
-
-										ctx.activity(runtime::SetSleepingInput {
-											actor_id: input.actor_id,
-										})
-										.await?;
-
-										// Send signal to kill actor now that we know it will be sleeping
-										destroy::kill(
-											ctx,
-											input.actor_id,
-											state.generation,
-											state.runner_workflow_id,
-										)
-										.await?;
+										start_sleep(ctx, &input, state).await?;
 									}
 									protocol::ActorIntent::Stop => {
 										state.gc_timeout_ts =
@@ -309,6 +436,7 @@ pub async fn pegboard_actor(ctx: &mut WorkflowCtx, input: &Input) -> Result<()>
 											input.actor_id,
 											state.generation,
 											state.runner_workflow_id,
+											protocol::StopIntent::Stop,
 										)
 										.await?;
 									}
@@ -328,8 +456,16 @@ pub async fn pegboard_actor(ctx: &mut WorkflowCtx, input: &Input) -> Result<()>
 											runner_id: state.runner_id,
 										})
 										.tag("actor_id", input.actor_id)
+										.tag("namespace_id", input.namespace_id)
+										.topic(input.namespace_id)
+										.tag("name", &input.name)
 										.send()
 										.await?;
+
+										// Includes alarms that were fired during a previous run but never
+										// acknowledged
+										runtime::fire_named_alarms(ctx, &input, state, None)
+											.await?;
 									}
 									protocol::ActorState::Stopped { code, .. } => {
 										if let Some(res) =
@@ -343,6 +479,50 @@ pub async fn pegboard_actor(ctx: &mut WorkflowCtx, input: &Input) -> Result<()>
 								protocol::Event::ActorSetAlarm { alarm_ts, .. } => {
 									state.alarm_ts = alarm_ts;
 								}
+								protocol::Event::ActorSetNamedAlarm { alarm, .. } => {
+									if !state.named_alarms.contains_key(&alarm.name)
+										&& state.named_alarms.len() >= MAX_NAMED_ALARMS
+									{
+										tracing::warn!(
+											actor_id=?input.actor_id,
+											name=%alarm.name,
+											"too many named alarms, ignoring",
+										);
+
+										return Ok(Loop::Continue);
+									}
+
+									let name = alarm.name.clone();
+									let alarm_ts = alarm.ts;
+
+									if ctx
+										.activity(runtime::SetNamedAlarmInput {
+											actor_id: input.actor_id,
+											alarm,
+										})
+										.await?
+									{
+										state.named_alarms.insert(name, alarm_ts);
+									}
+								}
+								protocol::Event::ActorCancelAlarm { name, .. } => {
+									// Also cancels alarms that fired but were not acknowledged yet, which are
+									// no longer tracked in the state
+									state.named_alarms.remove(&name);
+
+									ctx.activity(runtime::CancelNamedAlarmInput {
+										actor_id: input.actor_id,
+										name,
+									})
+									.await?;
+								}
+								protocol::Event::ActorAckAlarms { alarms, .. } => {
+									ctx.activity(runtime::AckNamedAlarmsInput {
+										actor_id: input.actor_id,
+										alarms,
+									})
+									.await?;
+								}
 							}
 						}
 						Main::Wake(_sig) => {
@@ -350,6 +530,7 @@ pub async fn pegboard_actor(ctx: &mut WorkflowCtx, input: &Input) -> Result<()>
 							// circumstances.
 							if state.sleeping {
 								state.alarm_ts = None;
+								state.idle_destroy_ts = None;
 								state.sleeping = false;
 
 								if runtime::reschedule_actor(ctx, &input, state).await? {
@@ -368,6 +549,73 @@ pub async fn pegboard_actor(ctx: &mut WorkflowCtx, input: &Input) -> Result<()>
 								);
 							}
 						}
+						Main::Migrate(sig) => {
+							// Sleeping actors are not on a runner and will be allocated to any runner
+							// when woken
+							if state.sleeping || state.migration.is_some() {
+								tracing::debug!(
+									actor_id=?input.actor_id,
+									"cannot migrate actor that is sleeping or already migrating",
+								);
+
+								return Ok(Loop::Continue);
+							}
+
+							// Don't stop the actor if it cannot be moved to the requested runner
+							if let Some(runner_id) = sig.runner_id {
+								let fits = ctx
+									.v(2)
+									.activity(runtime::CheckMigrationTargetInput { runner_id })
+									.await?;
+
+								if !fits {
+									tracing::warn!(
+										actor_id=?input.actor_id,
+										?runner_id,
+										"migration target cannot fit actor, ignoring migration",
+									);
+
+									return Ok(Loop::Continue);
+								}
+							}
+
+							state.gc_timeout_ts =
+								Some(util::timestamp::now() + ACTOR_STOP_THRESHOLD_MS);
+							state.migration = Some(runtime::Migration {
+								from_runner_id: state.runner_id,
+								target_runner_id: sig.runner_id,
+							});
+
+							ctx.activity(runtime::SetNotConnectableInput {
+								actor_id: input.actor_id,
+							})
+							.await?;
+
+							// The stop intent tells the runner to persist the actor's state for the new
+							// runner. The actor is rescheduled in `handle_stopped` once stopped.
+							destroy::kill(
+								ctx,
+								input.actor_id,
+								state.generation,
+								state.runner_workflow_id,
+								protocol::StopIntent::Migrate,
+							)
+							.await?;
+						}
+						Main::Preempt(_) => {
+							if state.sleeping || state.migration.is_some() {
+								tracing::debug!(
+									actor_id=?input.actor_id,
+									"cannot preempt actor that is sleeping or migrating",
+								);
+
+								return Ok(Loop::Continue);
+							}
+
+							tracing::debug!(actor_id=?input.actor_id, "actor preempted");
+
+							start_sleep(ctx, &input, state).await?;
+						}
 						Main::Lost(sig) => {
 							// Ignore state updates for previous generations
 							if sig.generation != state.generation {
@@ -426,9 +674,52 @@ pub async fn pegboard_actor(ctx: &mut WorkflowCtx, input: &Input) -> Result<()>
 			.await?;
 	}
 
+	// Logs are kept for a while so actors that were destroyed after crashing can still be debugged
+	ctx.v(2).sleep(LOG_RETENTION_MS).await?;
+
+	ctx.v(2)
+		.activity(destroy::ClearLogsInput {
+			actor_id: input.actor_id,
+		})
+		.await?;
+
 	Ok(())
 }
 
+/// Stops the actor and deallocates it once stopped. The actor is allocated again when woken.
+async fn start_sleep(
+	ctx: &mut WorkflowCtx,
+	input: &Input,
+	state: &mut runtime::LifecycleState,
+) -> Result<()> {
+	state.gc_timeout_ts = Some(util::timestamp::now() + ACTOR_STOP_THRESHOLD_MS);
+	state.sleeping = true;
+
+	ctx.activity(runtime::SetSleepingInput {
+		actor_id: input.actor_id,
+	})
+	.await?;
+
+	ctx.v(2)
+		.msg(Sleeping {})
+		.tag("actor_id", input.actor_id)
+		.tag("namespace_id", input.namespace_id)
+		.topic(input.namespace_id)
+		.tag("name", &input.name)
+		.send()
+		.await?;
+
+	// Send signal to kill actor now that we know it will be sleeping
+	destroy::kill(
+		ctx,
+		input.actor_id,
+		state.generation,
+		state.runner_workflow_id,
+		protocol::StopIntent::Sleep,
+	)
+	.await
+}
+
 async fn handle_stopped(
 	ctx: &mut WorkflowCtx,
 	input: &Input,
@@ -445,8 +736,12 @@ async fn handle_stopped(
 
 	state.gc_timeout_ts = None;
 
+	let failed = matches!(code, None | Some(protocol::StopCode::Error));
+
 	ctx.activity(runtime::DeallocateInput {
 		actor_id: input.actor_id,
+		// Actors stopped because their runner was lost did not crash on their own
+		crashed: failed && !lost && !state.sleeping && state.migration.is_none(),
 	})
 	.await?;
 
@@ -467,10 +762,67 @@ async fn handle_stopped(
 			.await?;
 	}
 
-	if !state.sleeping {
-		let failed = matches!(code, None | Some(protocol::StopCode::Error));
+	// Actors that started sleeping while being migrated are left sleeping
+	if state.sleeping {
+		state.migration = None;
+	}
+
+	if state.migration.is_some() {
+		tracing::debug!(actor_id=?input.actor_id, "rescheduling migrated actor");
+
+		// Migrations are not failures, so they should not be backed off
+		state.reschedule_state = Default::default();
+
+		// Kill old actor immediately if lost
+		if lost {
+			destroy::kill(
+				ctx,
+				input.actor_id,
+				state.generation,
+				state.runner_workflow_id,
+				protocol::StopIntent::Stop,
+			)
+			.await?;
+		}
 
-		match (failed, input.crash_policy) {
+		if runtime::reschedule_actor(ctx, &input, state).await? {
+			// Destroyed early
+			return Ok(Some(runtime::LifecycleRes {
+				generation: state.generation,
+				// False here because if we received the destroy signal, it is
+				// guaranteed that we did not allocate another actor.
+				kill: false,
+			}));
+		}
+	} else if !state.sleeping {
+		if failed {
+			ctx.v(2)
+				.msg(Crashed { lost })
+				.tag("actor_id", input.actor_id)
+				.tag("namespace_id", input.namespace_id)
+				.topic(input.namespace_id)
+				.tag("name", &input.name)
+				.send()
+				.await?;
+		}
+
+		// Fall back to the restart policy's limit action once the actor is crash looping
+		let crash_policy = if failed
+			&& input.crash_policy == CrashPolicy::Restart
+			&& runtime::restart_limit_exceeded(ctx, input, state).await?
+		{
+			tracing::warn!(actor_id=?input.actor_id, "actor exceeded max restarts");
+
+			input
+				.restart_policy
+				.as_ref()
+				.map(|restart_policy| restart_policy.limit_action.into())
+				.unwrap_or_default()
+		} else {
+			input.crash_policy
+		};
+
+		match (failed, crash_policy) {
 			(true, CrashPolicy::Restart) => {
 				// Kill old actor immediately if lost
 				if lost {
@@ -479,6 +831,7 @@ async fn handle_stopped(
 						input.actor_id,
 						state.generation,
 						state.runner_workflow_id,
+						protocol::StopIntent::Stop,
 					)
 					.await?;
 				}
@@ -502,6 +855,15 @@ async fn handle_stopped(
 					actor_id: input.actor_id,
 				})
 				.await?;
+
+				ctx.v(2)
+					.msg(Sleeping {})
+					.tag("actor_id", input.actor_id)
+					.tag("namespace_id", input.namespace_id)
+					.topic(input.namespace_id)
+					.tag("name", &input.name)
+					.send()
+					.await?;
 			}
 			_ => {
 				ctx.activity(runtime::SetCompleteInput {}).await?;
@@ -511,6 +873,9 @@ async fn handle_stopped(
 						error: errors::Actor::DestroyedWhileWaitingForReady,
 					})
 					.tag("actor_id", input.actor_id)
+					.tag("namespace_id", input.namespace_id)
+					.topic(input.namespace_id)
+					.tag("name", &input.name)
 					.send()
 					.await?;
 				}
@@ -523,9 +888,31 @@ async fn handle_stopped(
 		}
 	}
 
+	// Start the idle timeout now that the actor is no longer running
+	if state.sleeping {
+		if let Some(idle_timeout_ms) = input.idle_timeout_ms {
+			state.idle_destroy_ts = Some(
+				ctx.activity(runtime::GetIdleDestroyTsInput { idle_timeout_ms })
+					.await?,
+			);
+		}
+	}
+
 	Ok(None)
 }
 
+/// Timers that can interrupt the lifecycle loop.
+enum LifecycleTimer {
+	/// The actor did not respond in time and is lost.
+	GcTimeout,
+	/// The actor set an alarm to be woken.
+	Alarm,
+	/// One of the actor's named alarms is due.
+	NamedAlarm,
+	/// The actor reached its TTL or idle timeout.
+	Destroy,
+}
+
 #[message("pegboard_actor_create_complete")]
 pub struct CreateComplete {}
 
@@ -539,6 +926,18 @@ pub struct Ready {
 	pub runner_id: Id,
 }
 
+/// Sent when the actor goes to sleep, either by its own intent or because it crashed with
+/// `CrashPolicy::Sleep`.
+#[message("pegboard_actor_sleeping")]
+pub struct Sleeping {}
+
+/// Sent when the actor stops with an error or its runner is lost. Followed by a reschedule, sleep
+/// or destroy depending on the crash policy.
+#[message("pegboard_actor_crashed")]
+pub struct Crashed {
+	pub lost: bool,
+}
+
 #[signal("pegboard_actor_allocate")]
 #[derive(Debug)]
 pub struct Allocate {
@@ -549,6 +948,18 @@ pub struct Allocate {
 #[signal("pegboard_actor_wake")]
 pub struct Wake {}
 
+/// Stops the actor and allocates it to a different runner with a new generation. The actor keeps its
+/// id, key and KV data.
+#[signal("pegboard_actor_migrate")]
+pub struct Migrate {
+	/// Runner to move the actor to. The migration is ignored if this runner cannot fit the actor.
+	pub runner_id: Option<Id>,
+}
+
+/// Puts a running low priority actor to sleep to make room for a higher priority actor.
+#[signal("pegboard_actor_preempt")]
+pub struct Preempt {}
+
 #[signal("pegboard_actor_lost")]
 pub struct Lost {
 	pub generation: u32,
@@ -574,4 +985,6 @@ join_signal!(Main {
 	Wake,
 	Lost,
 	Destroy,
+	Migrate,
+	Preempt,
 });
@@ -0,0 +1,638 @@
+use std::collections::HashMap;
+
+use futures_util::{StreamExt, TryStreamExt};
+use gas::prelude::*;
+use namespace::types::{AllocationStrategy, RollbackPolicy, RolloutPolicy};
+use rand::Rng;
+use rivet_data::converted::{ActorPlacementKeyData, RunnerAllocIdxKeyData};
+use universaldb::options::{MutationType, StreamingMode};
+use universaldb::utils::IsolationLevel::*;
+
+use crate::keys;
+
+/// Max number of eligible runners to compare when allocating an actor with placement constraints.
+const MAX_PLACEMENT_CANDIDATES: usize = 16;
+
+/// Picks the runner to allocate an actor to out of the runners of the versions chosen by the rollout
+/// policy that have enough remaining slots for the actor. Versions are tried in order, so a canary
+/// falls back to the other version if none of its runners can fit the actor. Returns `None` if no
+/// runner is eligible.
+///
+/// Does not add any conflict ranges, the caller should add a read conflict on the chosen key.
+pub(crate) async fn select_runner(
+	tx: &universaldb::Transaction,
+	namespace_id: Id,
+	runner_name: &str,
+	strategy: AllocationStrategy,
+	rollout: &RolloutPolicy,
+	slots: u32,
+	placement: Option<&ActorPlacementKeyData>,
+	exclude_runner_id: Option<Id>,
+	ping_threshold_ts: i64,
+) -> Result<Option<(keys::ns::RunnerAllocIdxKey, RunnerAllocIdxKeyData)>> {
+	let tx = tx.with_subspace(keys::subspace());
+
+	for version in select_versions(&tx, namespace_id, runner_name, rollout).await? {
+		let chosen = select_runner_for_version(
+			&tx,
+			namespace_id,
+			runner_name,
+			version,
+			strategy,
+			slots,
+			placement,
+			exclude_runner_id,
+			ping_threshold_ts,
+		)
+		.await?;
+
+		if chosen.is_some() {
+			return Ok(chosen);
+		}
+	}
+
+	Ok(None)
+}
+
+async fn select_runner_for_version(
+	tx: &universaldb::Transaction,
+	namespace_id: Id,
+	runner_name: &str,
+	version: u32,
+	strategy: AllocationStrategy,
+	slots: u32,
+	placement: Option<&ActorPlacementKeyData>,
+	exclude_runner_id: Option<Id>,
+	ping_threshold_ts: i64,
+) -> Result<Option<(keys::ns::RunnerAllocIdxKey, RunnerAllocIdxKeyData)>> {
+	// Only read runners of the chosen version that have remaining slots. Full runners are sorted
+	// last so neither scan direction has to skip over them.
+	let (start, _) = keys::subspace()
+		.subspace(&keys::ns::RunnerAllocIdxKey::version_subspace(
+			namespace_id,
+			runner_name.to_string(),
+			version,
+		))
+		.range();
+	let (end, _) = keys::subspace()
+		.subspace(&keys::ns::RunnerAllocIdxKey::full_subspace(
+			namespace_id,
+			runner_name.to_string(),
+			version,
+		))
+		.range();
+
+	let mut stream = tx.get_ranges_keyvalues(
+		universaldb::RangeOption {
+			mode: StreamingMode::Iterator,
+			// Runners are sorted by remaining slots descending, bin packing starts with the fullest
+			reverse: matches!(strategy, AllocationStrategy::BinPack),
+			..(start, end).into()
+		},
+		// NOTE: This is not Serializable because we don't want to conflict with all of the keys, just
+		// the one we choose
+		Snapshot,
+	);
+
+	let random = matches!(strategy, AllocationStrategy::RandomTopN { .. });
+	let max_candidates = match strategy {
+		AllocationStrategy::RandomTopN { n } => (n as usize).max(1),
+		_ if placement.is_some() => MAX_PLACEMENT_CANDIDATES,
+		_ => 1,
+	};
+	let mut candidates = Vec::new();
+
+	while let Some(entry) = stream.try_next().await? {
+		let (runner_alloc_key, runner_alloc_key_data) =
+			tx.read_entry::<keys::ns::RunnerAllocIdxKey>(&entry)?;
+
+		// Scan by last ping
+		if runner_alloc_key.last_ping_ts < ping_threshold_ts {
+			continue;
+		}
+
+		// Runner is not full but cannot fit this actor
+		if runner_alloc_key_data.remaining_slots < slots {
+			continue;
+		}
+
+		// Migrating away from this runner
+		if exclude_runner_id == Some(runner_alloc_key.runner_id) {
+			continue;
+		}
+
+		let score = if let Some(placement) = placement {
+			// Skip runners missing required labels
+			let Some(score) = score_runner(tx, runner_alloc_key.runner_id, placement).await? else {
+				continue;
+			};
+
+			score
+		} else {
+			PlacementScore::default()
+		};
+
+		let is_best = placement.is_none_or(|placement| score.is_best(placement));
+		candidates.push((runner_alloc_key, runner_alloc_key_data, score));
+
+		if candidates.len() >= max_candidates || (is_best && !random) {
+			break;
+		}
+	}
+
+	// Ties keep the earlier runner in scan order unless picking randomly
+	let Some(best_score) = candidates.iter().map(|(_, _, score)| *score).max() else {
+		return Ok(None);
+	};
+	candidates.retain(|(_, _, score)| *score == best_score);
+
+	let idx = if random {
+		rand::thread_rng().gen_range(0..candidates.len())
+	} else {
+		0
+	};
+	let (runner_alloc_key, runner_alloc_key_data, _) = candidates.swap_remove(idx);
+
+	Ok(Some((runner_alloc_key, runner_alloc_key_data)))
+}
+
+/// Returns true if any runner of the versions chosen by the rollout policy could take the actor once it
+/// has enough free slots. Used to tell actors that wait for capacity apart from actors that no runner
+/// can take because of their placement constraints or excluded runner.
+pub(crate) async fn has_eligible_runner(
+	tx: &universaldb::Transaction,
+	namespace_id: Id,
+	runner_name: &str,
+	rollout: &RolloutPolicy,
+	slots: u32,
+	placement: Option<&ActorPlacementKeyData>,
+	exclude_runner_id: Option<Id>,
+	ping_threshold_ts: i64,
+) -> Result<bool> {
+	let tx = tx.with_subspace(keys::subspace());
+
+	for version in select_versions(&tx, namespace_id, runner_name, rollout).await? {
+		// Includes full runners
+		let version_subspace =
+			keys::subspace().subspace(&keys::ns::RunnerAllocIdxKey::version_subspace(
+				namespace_id,
+				runner_name.to_string(),
+				version,
+			));
+		let mut stream = tx.get_ranges_keyvalues(
+			universaldb::RangeOption {
+				mode: StreamingMode::Iterator,
+				..(&version_subspace).into()
+			},
+			Snapshot,
+		);
+
+		while let Some(entry) = stream.try_next().await? {
+			let (runner_alloc_key, runner_alloc_key_data) =
+				tx.read_entry::<keys::ns::RunnerAllocIdxKey>(&entry)?;
+
+			if runner_alloc_key.last_ping_ts < ping_threshold_ts
+				|| runner_alloc_key_data.total_slots < slots
+				|| exclude_runner_id == Some(runner_alloc_key.runner_id)
+			{
+				continue;
+			}
+
+			if let Some(placement) = placement {
+				if score_runner(&tx, runner_alloc_key.runner_id, placement)
+					.await?
+					.is_none()
+				{
+					continue;
+				}
+			}
+
+			return Ok(true);
+		}
+	}
+
+	Ok(false)
+}
+
+/// Returns the allocation index entry of the given runner if it is eligible for allocation and can fit
+/// the actor. Used when migrating an actor to a specific runner, so the rollout policy and placement
+/// constraints are not applied.
+///
+/// Does not add any conflict ranges, the caller should add a read conflict on the returned key.
+pub(crate) async fn select_target_runner(
+	tx: &universaldb::Transaction,
+	namespace_id: Id,
+	runner_name: &str,
+	runner_id: Id,
+	slots: u32,
+	ping_threshold_ts: i64,
+) -> Result<Option<(keys::ns::RunnerAllocIdxKey, RunnerAllocIdxKeyData)>> {
+	let tx = tx.with_subspace(keys::subspace());
+
+	let (
+		namespace_id_entry,
+		name_entry,
+		version_entry,
+		remaining_slots_entry,
+		total_slots_entry,
+		last_ping_ts_entry,
+	) = tokio::try_join!(
+		tx.read_opt(&keys::runner::NamespaceIdKey::new(runner_id), Snapshot),
+		tx.read_opt(&keys::runner::NameKey::new(runner_id), Snapshot),
+		tx.read_opt(&keys::runner::VersionKey::new(runner_id), Snapshot),
+		tx.read_opt(&keys::runner::RemainingSlotsKey::new(runner_id), Snapshot),
+		tx.read_opt(&keys::runner::TotalSlotsKey::new(runner_id), Snapshot),
+		tx.read_opt(&keys::runner::LastPingTsKey::new(runner_id), Snapshot),
+	)?;
+
+	let (
+		Some(runner_namespace_id),
+		Some(name),
+		Some(version),
+		Some(remaining_slots),
+		Some(total_slots),
+		Some(last_ping_ts),
+	) = (
+		namespace_id_entry,
+		name_entry,
+		version_entry,
+		remaining_slots_entry,
+		total_slots_entry,
+		last_ping_ts_entry,
+	)
+	else {
+		return Ok(None);
+	};
+
+	if runner_namespace_id != namespace_id
+		|| name != runner_name
+		|| last_ping_ts < ping_threshold_ts
+		|| remaining_slots < slots
+	{
+		return Ok(None);
+	}
+
+	let runner_alloc_key = keys::ns::RunnerAllocIdxKey::new(
+		namespace_id,
+		name,
+		version,
+		(remaining_slots * 1000) / total_slots,
+		last_ping_ts,
+		runner_id,
+	);
+
+	// Draining and expired runners are not in the allocation index
+	let Some(runner_alloc_key_data) = tx.read_opt(&runner_alloc_key, Snapshot).await? else {
+		return Ok(None);
+	};
+
+	Ok(Some((runner_alloc_key, runner_alloc_key_data)))
+}
+
+/// Picks which runner versions to allocate to, in order of preference. Defaults to the highest version
+/// that was not rolled back.
+async fn select_versions(
+	tx: &universaldb::Transaction,
+	namespace_id: Id,
+	runner_name: &str,
+	rollout: &RolloutPolicy,
+) -> Result<Vec<u32>> {
+	if let Some(version) = rollout.pinned_version {
+		return Ok(vec![version]);
+	}
+
+	// Rolled back versions are only skipped while the rollback policy is enabled, removing it undoes
+	// the rollback
+	let skip_rolled_back = rollout.rollback.is_some();
+
+	let newest_version =
+		next_version(tx, namespace_id, runner_name, skip_rolled_back, None).await?;
+	let Some(newest_version) = newest_version else {
+		// Every version was rolled back, allocating to the newest one is better than not at all
+		return Ok(next_version(tx, namespace_id, runner_name, false, None)
+			.await?
+			.into_iter()
+			.collect());
+	};
+
+	let Some(canary_percent) = rollout.canary_percent else {
+		return Ok(vec![newest_version]);
+	};
+
+	// Send all actors to the newest version if there is nothing to fall back to
+	let Some(previous_version) = next_version(
+		tx,
+		namespace_id,
+		runner_name,
+		skip_rolled_back,
+		Some(newest_version),
+	)
+	.await?
+	else {
+		return Ok(vec![newest_version]);
+	};
+
+	if rand::thread_rng().gen_range(0..100) < canary_percent {
+		Ok(vec![newest_version, previous_version])
+	} else {
+		Ok(vec![previous_version, newest_version])
+	}
+}
+
+/// Returns the highest version of the runners with the given name that is lower than `below`,
+/// optionally skipping rolled back versions.
+async fn next_version(
+	tx: &universaldb::Transaction,
+	namespace_id: Id,
+	runner_name: &str,
+	skip_rolled_back: bool,
+	mut below: Option<u32>,
+) -> Result<Option<u32>> {
+	let runner_alloc_subspace = keys::subspace().subspace(&keys::ns::RunnerAllocIdxKey::subspace(
+		namespace_id,
+		runner_name.to_string(),
+	));
+	let (_, end) = runner_alloc_subspace.range();
+
+	loop {
+		// Runners are sorted by version descending, so lower versions come after the subspace of
+		// the current version
+		let start = if let Some(below) = below {
+			let (_, start) = keys::subspace()
+				.subspace(&keys::ns::RunnerAllocIdxKey::version_subspace(
+					namespace_id,
+					runner_name.to_string(),
+					below,
+				))
+				.range();
+			start
+		} else {
+			let (start, _) = runner_alloc_subspace.range();
+			start
+		};
+
+		let mut stream = tx.get_ranges_keyvalues(
+			universaldb::RangeOption {
+				mode: StreamingMode::Exact,
+				limit: Some(1),
+				..(start, end.clone()).into()
+			},
+			// NOTE: This is not Serializable because we don't want to conflict with all of the keys,
+			// just the one we choose
+			Snapshot,
+		);
+		let Some(entry) = stream.try_next().await? else {
+			return Ok(None);
+		};
+		let (runner_alloc_key, _) = tx.read_entry::<keys::ns::RunnerAllocIdxKey>(&entry)?;
+
+		if skip_rolled_back
+			&& tx
+				.exists(
+					&keys::ns::RolloutRolledBackTsKey::new(
+						namespace_id,
+						runner_name.to_string(),
+						runner_alloc_key.version,
+					),
+					Snapshot,
+				)
+				.await?
+		{
+			below = Some(runner_alloc_key.version);
+			continue;
+		}
+
+		return Ok(Some(runner_alloc_key.version));
+	}
+}
+
+/// Start of the rollback window the given timestamp falls in.
+fn window_start_ts(rollback: &RollbackPolicy, ts: i64) -> i64 {
+	let window_ms = (rollback.window_ms() as i64).max(1);
+
+	ts - ts.rem_euclid(window_ms)
+}
+
+/// Counts an actor allocation towards the crash rate of the runner version. Allocations are only
+/// counted while the rollback policy is enabled.
+pub(crate) fn record_allocation(
+	tx: &universaldb::Transaction,
+	namespace_id: Id,
+	runner_name: &str,
+	version: u32,
+	rollback: Option<&RollbackPolicy>,
+) -> Result<()> {
+	let Some(rollback) = rollback else {
+		return Ok(());
+	};
+
+	let tx = tx.with_subspace(keys::subspace());
+
+	let allocations_key = keys::ns::RolloutAllocationsKey::new(
+		namespace_id,
+		runner_name.to_string(),
+		version,
+		window_start_ts(rollback, util::timestamp::now()),
+	);
+
+	// Clear counts of previous windows
+	let (start, _) = keys::subspace()
+		.subspace(&keys::ns::RolloutAllocationsKey::subspace(
+			namespace_id,
+			runner_name.to_string(),
+			version,
+		))
+		.range();
+	tx.clear_range(&start, &tx.pack(&allocations_key));
+
+	tx.atomic_op(&allocations_key, &1i64.to_le_bytes(), MutationType::Add);
+
+	Ok(())
+}
+
+/// Counts an actor crash towards the crash rate of the runner's version and rolls the version back if
+/// its crash rate within the current window exceeds the rollback policy's threshold. The last version
+/// that was not rolled back is never rolled back.
+pub(crate) async fn record_crash(
+	tx: &universaldb::Transaction,
+	namespace_id: Id,
+	runner_name: &str,
+	runner_id: Id,
+	rollback: Option<RollbackPolicy>,
+) -> Result<()> {
+	let Some(rollback) = rollback else {
+		return Ok(());
+	};
+
+	let tx = tx.with_subspace(keys::subspace());
+
+	let version = tx
+		.read(&keys::runner::VersionKey::new(runner_id), Serializable)
+		.await?;
+	let window_start_ts = window_start_ts(&rollback, util::timestamp::now());
+
+	let crashes_key = keys::ns::RolloutCrashesKey::new(
+		namespace_id,
+		runner_name.to_string(),
+		version,
+		window_start_ts,
+	);
+
+	// Clear counts of previous windows
+	let (start, _) = keys::subspace()
+		.subspace(&keys::ns::RolloutCrashesKey::subspace(
+			namespace_id,
+			runner_name.to_string(),
+			version,
+		))
+		.range();
+	tx.clear_range(&start, &tx.pack(&crashes_key));
+
+	tx.atomic_op(&crashes_key, &1i64.to_le_bytes(), MutationType::Add);
+
+	let rolled_back_ts_key =
+		keys::ns::RolloutRolledBackTsKey::new(namespace_id, runner_name.to_string(), version);
+
+	let (allocations, crashes, rolled_back) = tokio::try_join!(
+		tx.read_opt(
+			&keys::ns::RolloutAllocationsKey::new(
+				namespace_id,
+				runner_name.to_string(),
+				version,
+				window_start_ts,
+			),
+			// NOTE: This is not Serializable because these counters are updated by every allocation
+			// and crash
+			Snapshot,
+		),
+		tx.read_opt(&crashes_key, Snapshot),
+		tx.exists(&rolled_back_ts_key, Serializable),
+	)?;
+	let allocations = allocations.unwrap_or_default();
+	// Include the crash added above
+	let crashes = crashes.unwrap_or_default() + 1;
+
+	if rolled_back || allocations < rollback.min_allocations as i64 {
+		return Ok(());
+	}
+
+	if crashes * 100 >= allocations * rollback.max_crash_rate_percent as i64 {
+		// Rolling back the only remaining version would leave no runners to allocate to
+		let has_other_version =
+			match next_version(&tx, namespace_id, runner_name, true, None).await? {
+				Some(other_version) if other_version == version => {
+					next_version(&tx, namespace_id, runner_name, true, Some(version))
+						.await?
+						.is_some()
+				}
+				Some(_) => true,
+				None => false,
+			};
+
+		if !has_other_version {
+			tracing::warn!(
+				?namespace_id,
+				%runner_name,
+				%version,
+				%allocations,
+				%crashes,
+				"runner version crash rate exceeded threshold but it is the last eligible version, not rolling back"
+			);
+
+			return Ok(());
+		}
+
+		tracing::warn!(
+			?namespace_id,
+			%runner_name,
+			%version,
+			%allocations,
+			%crashes,
+			"runner version crash rate exceeded threshold, rolling back"
+		);
+
+		tx.write(&rolled_back_ts_key, util::timestamp::now())?;
+	}
+
+	Ok(())
+}
+
+/// How well a runner matches an actor's placement constraints. Higher is better.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct PlacementScore {
+	/// Ranked before preferred labels so actors of the same group are spread first.
+	no_anti_affinity_conflict: bool,
+	preferred_matches: usize,
+}
+
+impl PlacementScore {
+	/// Whether no other runner can score higher than this one.
+	fn is_best(&self, placement: &ActorPlacementKeyData) -> bool {
+		self.no_anti_affinity_conflict && self.preferred_matches == placement.preferred_labels.len()
+	}
+}
+
+/// Returns `None` if the runner is missing any of the required labels.
+async fn score_runner(
+	tx: &universaldb::Transaction,
+	runner_id: Id,
+	placement: &ActorPlacementKeyData,
+) -> Result<Option<PlacementScore>> {
+	let tx = tx.with_subspace(keys::subspace());
+
+	let labels = if placement.required_labels.is_empty() && placement.preferred_labels.is_empty() {
+		HashMap::new()
+	} else {
+		let label_subspace =
+			keys::subspace().subspace(&keys::runner::LabelKey::subspace(runner_id));
+
+		tx.get_ranges_keyvalues(
+			universaldb::RangeOption {
+				mode: StreamingMode::WantAll,
+				..(&label_subspace).into()
+			},
+			// NOTE: This is not Serializable because labels only change when a runner reconnects
+			Snapshot,
+		)
+		.map(|res| {
+			let (key, value) = tx.read_entry::<keys::runner::LabelKey>(&res?)?;
+			Ok((key.label, value))
+		})
+		.try_collect::<HashMap<_, _>>()
+		.await?
+	};
+
+	let has_required = placement
+		.required_labels
+		.iter()
+		.all(|(k, v)| labels.get(k) == Some(v));
+	if !has_required {
+		return Ok(None);
+	}
+
+	let preferred_matches = placement
+		.preferred_labels
+		.iter()
+		.filter(|(k, v)| labels.get(*k) == Some(*v))
+		.count();
+
+	let no_anti_affinity_conflict = if let Some(group) = &placement.anti_affinity_group {
+		let count = tx
+			.read_opt(
+				&keys::runner::AntiAffinityCountKey::new(runner_id, group.clone()),
+				// NOTE: This is not Serializable because anti-affinity is a preference, not a guarantee
+				Snapshot,
+			)
+			.await?
+			.unwrap_or_default();
+
+		count <= 0
+	} else {
+		true
+	};
+
+	Ok(Some(PlacementScore {
+		no_anti_affinity_conflict,
+		preferred_matches,
+	}))
+}
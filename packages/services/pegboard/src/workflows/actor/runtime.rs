@@ -1,18 +1,24 @@
-use std::time::Instant;
+use std::{
+	collections::HashMap,
+	time::{Duration, Instant},
+};
 
-use futures_util::StreamExt;
-use futures_util::{FutureExt, TryStreamExt};
+use base64::prelude::*;
+use futures_util::{FutureExt, StreamExt, TryStreamExt};
 use gas::prelude::*;
+use rand::Rng;
 use rivet_metrics::KeyValue;
 use rivet_runner_protocol::protocol;
+use rivet_types::actors::{ActorPriority, CrashPolicy, RestartPolicy};
 use universaldb::options::{ConflictRangeType, MutationType, StreamingMode};
 use universaldb::utils::{FormalKey, IsolationLevel::*};
 
-use crate::{keys, metrics, workflows::runner::RUNNER_ELIGIBLE_THRESHOLD_MS};
+use crate::{errors, keys, metrics, workflows::runner::RUNNER_ELIGIBLE_THRESHOLD_MS};
 
 use super::{
-	ACTOR_START_THRESHOLD_MS, Allocate, BASE_RETRY_TIMEOUT_MS, Destroy, Input, PendingAllocation,
-	RETRY_RESET_DURATION_MS, State, destroy,
+	ACTOR_START_THRESHOLD_MS, Allocate, BACKOFF_JITTER_MS, BASE_RETRY_TIMEOUT_MS,
+	DEFAULT_BACKOFF_MAX_EXPONENT, Destroy, Failed, Input, MAX_ALARM_PAYLOAD_BYTES,
+	PendingAllocation, Preempt, RETRY_RESET_DURATION_MS, State, destroy, placement,
 };
 
 #[derive(Deserialize, Serialize)]
@@ -27,6 +33,15 @@ pub struct LifecycleState {
 	pub sleeping: bool,
 	pub alarm_ts: Option<i64>,
 	pub gc_timeout_ts: Option<i64>,
+	/// Set while the actor is being stopped in order to be moved to a different runner.
+	#[serde(default)]
+	pub migration: Option<Migration>,
+	/// When the actor will be destroyed for being asleep longer than its idle timeout.
+	#[serde(default)]
+	pub idle_destroy_ts: Option<i64>,
+	/// Timestamps of the actor's named alarms by name. Payloads are only stored in UDB.
+	#[serde(default)]
+	pub named_alarms: HashMap<String, i64>,
 
 	pub reschedule_state: RescheduleState,
 }
@@ -40,11 +55,22 @@ impl LifecycleState {
 			sleeping: false,
 			alarm_ts: None,
 			gc_timeout_ts: Some(util::timestamp::now() + ACTOR_START_THRESHOLD_MS),
+			migration: None,
+			idle_destroy_ts: None,
+			named_alarms: HashMap::new(),
 			reschedule_state: RescheduleState::default(),
 		}
 	}
 }
 
+#[derive(Deserialize, Serialize, Clone)]
+pub struct Migration {
+	/// Runner the actor is being moved off of.
+	pub from_runner_id: Id,
+	/// Runner to move the actor to. If not set, any other runner is chosen.
+	pub target_runner_id: Option<Id>,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct LifecycleRes {
 	pub generation: u32,
@@ -62,6 +88,8 @@ struct UpdateRunnerInput {
 	actor_id: Id,
 	runner_id: Id,
 	runner_workflow_id: Id,
+	#[serde(default)]
+	priority: ActorPriority,
 }
 
 // This is called when allocated by an outside source while the actor was pending.
@@ -69,6 +97,36 @@ struct UpdateRunnerInput {
 async fn update_runner(ctx: &ActivityCtx, input: &UpdateRunnerInput) -> Result<()> {
 	let mut state = ctx.state::<State>()?;
 
+	if let Some(pending_allocation_ts) = state.pending_allocation_ts {
+		let dt = (util::timestamp::now() - pending_allocation_ts) as f64 / 1000.0;
+		metrics::ACTOR_PENDING_ALLOCATION_DURATION
+			.record(dt, &[KeyValue::new("outcome", "allocated")]);
+	}
+
+	if is_preemptible(input.priority, state.crash_policy) {
+		let namespace_id = state.namespace_id;
+		let runner_name_selector = &state.runner_name_selector;
+		let slots = state.slots;
+
+		ctx.udb()?
+			.run(|tx| async move {
+				let tx = tx.with_subspace(keys::subspace());
+
+				tx.write(
+					&keys::ns::PreemptibleActorKey::new(
+						namespace_id,
+						runner_name_selector.clone(),
+						input.actor_id,
+					),
+					slots,
+				)?;
+
+				Ok(())
+			})
+			.custom_instrument(tracing::info_span!("actor_update_runner_tx"))
+			.await?;
+	}
+
 	state.sleep_ts = None;
 	state.pending_allocation_ts = None;
 	state.runner_id = Some(input.runner_id);
@@ -77,11 +135,23 @@ async fn update_runner(ctx: &ActivityCtx, input: &UpdateRunnerInput) -> Result<(
 	Ok(())
 }
 
+/// Low priority actors that sleep on crash can be woken again later, so they can be put to sleep to
+/// make room for higher priority actors.
+fn is_preemptible(priority: ActorPriority, crash_policy: CrashPolicy) -> bool {
+	priority == ActorPriority::Low && crash_policy == CrashPolicy::Sleep
+}
+
 #[derive(Debug, Serialize, Deserialize, Hash)]
 struct AllocateActorInput {
 	actor_id: Id,
 	generation: u32,
 	runner_name_selector: String,
+	#[serde(default)]
+	priority: ActorPriority,
+	#[serde(default)]
+	exclude_runner_id: Option<Id>,
+	#[serde(default)]
+	target_runner_id: Option<Id>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -99,6 +169,22 @@ async fn allocate_actor(
 	let start_instant = Instant::now();
 	let mut state = ctx.state::<State>()?;
 	let namespace_id = state.namespace_id;
+	let slots = state.slots;
+	let preemptible = is_preemptible(input.priority, state.crash_policy);
+
+	let (allocation_strategy, rollout) = ctx
+		.op(namespace::ops::runner_config::get_global::Input {
+			runners: vec![(namespace_id, input.runner_name_selector.clone())],
+		})
+		.await?
+		.first()
+		.map(|runner_config| {
+			(
+				runner_config.config.allocation_strategy,
+				runner_config.config.rollout,
+			)
+		})
+		.unwrap_or_default();
 
 	// NOTE: This txn should closely resemble the one found in the allocate_pending_actors activity of the
 	// client wf
@@ -128,24 +214,39 @@ async fn allocate_actor(
 						namespace_id,
 						input.runner_name_selector.clone(),
 					),
-					&1u32.to_le_bytes(),
+					&slots.to_le_bytes(),
 					MutationType::Add,
 				);
 			}
 
-			// Check if a queue exists
-			let pending_actor_subspace = keys::subspace().subspace(
-				&keys::ns::PendingActorByRunnerNameSelectorKey::subspace(
+			crate::workflows::runner::migrate_legacy_pending_actors(
+				&tx,
+				namespace_id,
+				&input.runner_name_selector,
+			)
+			.await?;
+
+			// Check if a queue of actors with the same or a higher priority exists. Actors with a lower
+			// priority do not block this actor from being allocated.
+			let (start, _) = keys::subspace()
+				.subspace(&keys::ns::PendingActorByPriorityKey::subspace(
 					namespace_id,
 					input.runner_name_selector.clone(),
-				),
-			);
+				))
+				.range();
+			let (_, end) = keys::subspace()
+				.subspace(&keys::ns::PendingActorByPriorityKey::priority_subspace(
+					namespace_id,
+					input.runner_name_selector.clone(),
+					input.priority as u8,
+				))
+				.range();
 			let queue_exists = tx
 				.get_ranges_keyvalues(
 					universaldb::RangeOption {
 						mode: StreamingMode::Exact,
 						limit: Some(1),
-						..(&pending_actor_subspace).into()
+						..(start, end).into()
 					},
 					// NOTE: This is not Serializable because we don't want to conflict with other
 					// inserts/clears to this range
@@ -156,64 +257,69 @@ async fn allocate_actor(
 				.is_some();
 
 			if !queue_exists {
-				let runner_alloc_subspace =
-					keys::subspace().subspace(&keys::ns::RunnerAllocIdxKey::subspace(
-						namespace_id,
-						input.runner_name_selector.clone(),
-					));
-
-				let mut stream = tx.get_ranges_keyvalues(
-					universaldb::RangeOption {
-						mode: StreamingMode::Iterator,
-						..(&runner_alloc_subspace).into()
-					},
-					// NOTE: This is not Serializable because we don't want to conflict with all of the
-					// keys, just the one we choose
-					Snapshot,
-				);
-
-				let mut highest_version = None;
-
-				loop {
-					let Some(entry) = stream.try_next().await? else {
-						break;
-					};
-
-					let (old_runner_alloc_key, old_runner_alloc_key_data) =
-						tx.read_entry::<keys::ns::RunnerAllocIdxKey>(&entry)?;
+				let placement = tx
+					.read_opt(
+						&keys::actor::PlacementKey::new(input.actor_id),
+						Serializable,
+					)
+					.await?;
 
-					if let Some(highest_version) = highest_version {
-						// We have passed all of the runners with the highest version. This is reachable if
-						// the ping of the highest version workers makes them ineligible
-						if old_runner_alloc_key.version < highest_version {
-							break;
-						}
-					} else {
-						highest_version = Some(old_runner_alloc_key.version);
-					}
+				let target = if let Some(target_runner_id) = input.target_runner_id {
+					placement::select_target_runner(
+						&tx,
+						namespace_id,
+						&input.runner_name_selector,
+						target_runner_id,
+						slots,
+						ping_threshold_ts,
+					)
+					.await?
+				} else {
+					None
+				};
 
-					// An empty runner means we have reached the end of the runners with the highest version
-					if old_runner_alloc_key.remaining_millislots == 0 {
-						break;
+				// Fall back to any other runner if the migration target no longer fits the actor. The
+				// target is checked before the actor is stopped, so this only happens if the target
+				// filled up or went away in the meantime.
+				let chosen = if target.is_some() {
+					target
+				} else {
+					if let Some(target_runner_id) = input.target_runner_id {
+						tracing::warn!(
+							actor_id=?input.actor_id,
+							?target_runner_id,
+							"migration target cannot fit actor anymore, falling back to any runner",
+						);
 					}
 
-					// Scan by last ping
-					if old_runner_alloc_key.last_ping_ts < ping_threshold_ts {
-						continue;
-					}
+					placement::select_runner(
+						&tx,
+						namespace_id,
+						&input.runner_name_selector,
+						allocation_strategy,
+						&rollout,
+						slots,
+						placement.as_ref(),
+						input.exclude_runner_id,
+						ping_threshold_ts,
+					)
+					.await?
+				};
 
+				if let Some((old_runner_alloc_key, old_runner_alloc_key_data)) = chosen {
 					// Add read conflict only for this key
 					tx.add_conflict_key(&old_runner_alloc_key, ConflictRangeType::Read)?;
 
 					// Clear old entry
 					tx.delete(&old_runner_alloc_key);
 
-					let new_remaining_slots =
-						old_runner_alloc_key_data.remaining_slots.saturating_sub(1);
+					let new_remaining_slots = old_runner_alloc_key_data
+						.remaining_slots
+						.saturating_sub(slots);
 					let new_remaining_millislots =
 						(new_remaining_slots * 1000) / old_runner_alloc_key_data.total_slots;
 
-					// Write new allocation key with 1 less slot
+					// Write new allocation key with the actor's slots subtracted
 					tx.write(
 						&keys::ns::RunnerAllocIdxKey::new(
 							namespace_id,
@@ -251,8 +357,41 @@ async fn allocate_actor(
 						input.generation,
 					)?;
 
+					placement::record_allocation(
+						&tx,
+						namespace_id,
+						&input.runner_name_selector,
+						old_runner_alloc_key.version,
+						rollout.rollback.as_ref(),
+					)?;
+
+					if let Some(group) =
+						placement.and_then(|placement| placement.anti_affinity_group)
+					{
+						tx.atomic_op(
+							&keys::runner::AntiAffinityCountKey::new(
+								old_runner_alloc_key.runner_id,
+								group,
+							),
+							&1i64.to_le_bytes(),
+							MutationType::Add,
+						);
+					}
+
+					if preemptible {
+						tx.write(
+							&keys::ns::PreemptibleActorKey::new(
+								namespace_id,
+								input.runner_name_selector.clone(),
+								input.actor_id,
+							),
+							slots,
+						)?;
+					}
+
 					// Set actor as not sleeping
 					tx.delete(&keys::actor::SleepTsKey::new(input.actor_id));
+					tx.delete(&keys::actor::ExcludeRunnerIdKey::new(input.actor_id));
 
 					return Ok((
 						for_serverless,
@@ -272,14 +411,31 @@ async fn allocate_actor(
 			// want. If a runner reads from the queue while this is being inserted, one of the two txns will
 			// retry and we ensure the actor does not end up in queue limbo.
 			tx.write(
-				&keys::ns::PendingActorByRunnerNameSelectorKey::new(
+				&keys::ns::PendingActorByPriorityKey::new(
 					namespace_id,
 					input.runner_name_selector.clone(),
+					input.priority as u8,
 					pending_ts,
 					input.actor_id,
 				),
 				input.generation,
 			)?;
+			tx.atomic_op(
+				&keys::ns::PendingActorCountKey::new(
+					namespace_id,
+					input.runner_name_selector.clone(),
+				),
+				&1i64.to_le_bytes(),
+				MutationType::Add,
+			);
+
+			// Keep migrated actors off of their old runner when allocated from the queue
+			if let Some(exclude_runner_id) = input.exclude_runner_id {
+				tx.write(
+					&keys::actor::ExcludeRunnerIdKey::new(input.actor_id),
+					exclude_runner_id,
+				)?;
+			}
 
 			return Ok((for_serverless, Err(pending_ts)));
 		})
@@ -307,6 +463,49 @@ async fn allocate_actor(
 	Ok(res)
 }
 
+#[derive(Debug, Serialize, Deserialize, Hash)]
+pub struct CheckMigrationTargetInput {
+	pub runner_id: Id,
+}
+
+/// Returns whether the actor can currently be allocated to the given migration target.
+#[activity(CheckMigrationTarget)]
+pub async fn check_migration_target(
+	ctx: &ActivityCtx,
+	input: &CheckMigrationTargetInput,
+) -> Result<bool> {
+	let state = ctx.state::<State>()?;
+	let namespace_id = state.namespace_id;
+	let runner_name_selector = &state.runner_name_selector;
+	let slots = state.slots;
+
+	if state.runner_id == Some(input.runner_id) {
+		return Ok(false);
+	}
+
+	let fits = ctx
+		.udb()?
+		.run(|tx| async move {
+			let ping_threshold_ts = util::timestamp::now() - RUNNER_ELIGIBLE_THRESHOLD_MS;
+
+			let target = placement::select_target_runner(
+				&tx,
+				namespace_id,
+				runner_name_selector,
+				input.runner_id,
+				slots,
+				ping_threshold_ts,
+			)
+			.await?;
+
+			Ok(target.is_some())
+		})
+		.custom_instrument(tracing::info_span!("actor_check_migration_target_tx"))
+		.await?;
+
+	Ok(fits)
+}
+
 #[derive(Debug, Serialize, Deserialize, Hash)]
 pub struct SetNotConnectableInput {
 	pub actor_id: Id,
@@ -334,6 +533,9 @@ pub async fn set_not_connectable(ctx: &ActivityCtx, input: &SetNotConnectableInp
 #[derive(Debug, Serialize, Deserialize, Hash)]
 pub struct DeallocateInput {
 	pub actor_id: Id,
+	/// Whether the actor stopped due to a crash. Counts towards the runner version's crash rate.
+	#[serde(default)]
+	pub crashed: bool,
 }
 
 #[activity(Deallocate)]
@@ -343,6 +545,19 @@ pub async fn deallocate(ctx: &ActivityCtx, input: &DeallocateInput) -> Result<()
 	let namespace_id = state.namespace_id;
 	let runner_id = state.runner_id;
 	let for_serverless = state.for_serverless;
+	let anti_affinity_group = state.anti_affinity_group.as_deref();
+	let slots = state.slots;
+
+	let rollback = if input.crashed && runner_id.is_some() {
+		ctx.op(namespace::ops::runner_config::get_global::Input {
+			runners: vec![(namespace_id, runner_name_selector.clone())],
+		})
+		.await?
+		.first()
+		.and_then(|runner_config| runner_config.config.rollout.rollback)
+	} else {
+		None
+	};
 
 	ctx.udb()?
 		.run(|tx| async move {
@@ -351,12 +566,25 @@ pub async fn deallocate(ctx: &ActivityCtx, input: &DeallocateInput) -> Result<()
 			tx.delete(&keys::actor::ConnectableKey::new(input.actor_id));
 
 			if let Some(runner_id) = runner_id {
+				if input.crashed {
+					placement::record_crash(
+						&tx,
+						namespace_id,
+						runner_name_selector,
+						runner_id,
+						rollback,
+					)
+					.await?;
+				}
+
 				destroy::clear_slot(
 					input.actor_id,
 					namespace_id,
 					runner_name_selector,
 					runner_id,
 					for_serverless,
+					anti_affinity_group,
+					slots,
 					&tx,
 				)
 				.await?;
@@ -366,7 +594,7 @@ pub async fn deallocate(ctx: &ActivityCtx, input: &DeallocateInput) -> Result<()
 						namespace_id,
 						runner_name_selector.clone(),
 					),
-					&(-1i32).to_le_bytes(),
+					&(-(slots as i32)).to_le_bytes(),
 					MutationType::Add,
 				);
 			}
@@ -388,6 +616,7 @@ pub async fn spawn_actor(
 	ctx: &mut WorkflowCtx,
 	input: &Input,
 	generation: u32,
+	migration: Option<&Migration>,
 ) -> Result<Option<AllocateActorOutput>> {
 	// Attempt allocation
 	let allocate_res = ctx
@@ -395,6 +624,9 @@ pub async fn spawn_actor(
 			actor_id: input.actor_id,
 			runner_name_selector: input.runner_name_selector.clone(),
 			generation,
+			priority: input.priority.unwrap_or_default(),
+			exclude_runner_id: migration.map(|migration| migration.from_runner_id),
+			target_runner_id: migration.and_then(|migration| migration.target_runner_id),
 		})
 		.await?;
 
@@ -410,14 +642,60 @@ pub async fn spawn_actor(
 				.send()
 				.await?;
 
+			// Make room for this actor by putting a low priority actor to sleep. Its slots are given to
+			// the highest priority pending actor once it stops.
+			let priority = input.priority.unwrap_or_default();
+			if priority != ActorPriority::Low {
+				let preempted_actor_id = ctx
+					.v(2)
+					.activity(PreemptActorInput {
+						namespace_id: input.namespace_id,
+						runner_name_selector: input.runner_name_selector.clone(),
+						slots: input.slots.unwrap_or(1),
+					})
+					.await?;
+
+				if let Some(preempted_actor_id) = preempted_actor_id {
+					tracing::debug!(
+						actor_id=?input.actor_id,
+						?preempted_actor_id,
+						"preempting low priority actor",
+					);
+
+					ctx.v(2)
+						.signal(Preempt {})
+						.to_workflow::<super::Workflow>()
+						.tag("actor_id", preempted_actor_id)
+						.send()
+						.await?;
+				}
+			}
+
 			// If allocation fails, the allocate txn already inserted this actor into the queue. Now we wait for
-			// an `Allocate` signal
-			match ctx.listen::<PendingAllocation>().await? {
-				PendingAllocation::Allocate(sig) => {
+			// an `Allocate` signal, at most until the pending timeout or the actor's expiry
+			let pending_timeout_ts = input
+				.max_pending_duration_ms
+				.map(|max_pending_duration_ms| {
+					pending_allocation_ts.saturating_add(max_pending_duration_ms)
+				});
+			let expire_ts = input.expire_ts(ctx.create_ts());
+			let expired = expire_ts.is_some_and(|expire_ts| {
+				pending_timeout_ts.is_none_or(|pending_timeout_ts| expire_ts <= pending_timeout_ts)
+			});
+			let sig = if let Some(ts) = [pending_timeout_ts, expire_ts].into_iter().flatten().min()
+			{
+				ctx.listen_until::<PendingAllocation>(ts).await?
+			} else {
+				Some(ctx.listen::<PendingAllocation>().await?)
+			};
+
+			match sig {
+				Some(PendingAllocation::Allocate(sig)) => {
 					ctx.activity(UpdateRunnerInput {
 						actor_id: input.actor_id,
 						runner_id: sig.runner_id,
 						runner_workflow_id: sig.runner_workflow_id,
+						priority: input.priority.unwrap_or_default(),
 					})
 					.await?;
 
@@ -426,7 +704,7 @@ pub async fn spawn_actor(
 						runner_workflow_id: sig.runner_workflow_id,
 					}
 				}
-				PendingAllocation::Destroy(_) => {
+				Some(PendingAllocation::Destroy(_)) => {
 					tracing::debug!(actor_id=?input.actor_id, "destroying before actor allocated");
 
 					let cleared = ctx
@@ -434,7 +712,9 @@ pub async fn spawn_actor(
 							actor_id: input.actor_id,
 							namespace_id: input.namespace_id,
 							runner_name_selector: input.runner_name_selector.clone(),
+							priority: input.priority.unwrap_or_default(),
 							pending_allocation_ts,
+							timed_out: false,
 						})
 						.await?;
 
@@ -447,12 +727,67 @@ pub async fn spawn_actor(
 							actor_id: input.actor_id,
 							runner_id: sig.runner_id,
 							runner_workflow_id: sig.runner_workflow_id,
+							priority: input.priority.unwrap_or_default(),
 						})
 						.await?;
 					}
 
 					return Ok(None);
 				}
+				None => {
+					let cleared = ctx
+						.activity(ClearPendingAllocationInput {
+							actor_id: input.actor_id,
+							namespace_id: input.namespace_id,
+							runner_name_selector: input.runner_name_selector.clone(),
+							priority: input.priority.unwrap_or_default(),
+							pending_allocation_ts,
+							timed_out: !expired,
+						})
+						.await?;
+
+					if cleared {
+						if expired {
+							tracing::debug!(actor_id=?input.actor_id, "actor expired before allocated");
+						} else {
+							let max_pending_duration_ms = input
+								.max_pending_duration_ms
+								.context("should have max pending duration")?;
+
+							tracing::warn!(actor_id=?input.actor_id, "timed out waiting for allocation");
+
+							ctx.msg(Failed {
+								error: errors::Actor::PendingAllocationTimeout {
+									max_pending_duration_ms,
+								},
+							})
+							.tag("actor_id", input.actor_id)
+							.tag("namespace_id", input.namespace_id)
+							.topic(input.namespace_id)
+							.tag("name", &input.name)
+							.send()
+							.await?;
+						}
+
+						return Ok(None);
+					}
+
+					// Allocated right as the timeout was reached, continue as normal
+					let sig = ctx.listen::<Allocate>().await?;
+
+					ctx.activity(UpdateRunnerInput {
+						actor_id: input.actor_id,
+						runner_id: sig.runner_id,
+						runner_workflow_id: sig.runner_workflow_id,
+						priority: input.priority.unwrap_or_default(),
+					})
+					.await?;
+
+					AllocateActorOutput {
+						runner_id: sig.runner_id,
+						runner_workflow_id: sig.runner_workflow_id,
+					}
+				}
 			}
 		}
 	};
@@ -485,26 +820,25 @@ pub async fn reschedule_actor(
 	tracing::debug!(actor_id=?input.actor_id, "rescheduling actor");
 
 	let next_generation = state.generation + 1;
+	let migration = state.migration.clone();
 
 	// Waits for the actor to be ready (or destroyed) and automatically retries if failed to allocate.
 	let res = ctx
 		.loope(state.reschedule_state.clone(), |ctx, resched_state| {
 			let input = input.clone();
+			let migration = migration.clone();
 
 			async move {
+				let restart_policy = input.restart_policy.clone().unwrap_or_default();
+
 				// Determine next backoff sleep duration
-				let mut backoff = util::backoff::Backoff::new_at(
-					8,
-					None,
-					BASE_RETRY_TIMEOUT_MS,
-					500,
-					resched_state.retry_count,
-				);
+				let backoff_ms = restart_backoff_ms(&restart_policy, resched_state.retry_count);
 
 				let (now, reset) = ctx
 					.v(2)
 					.activity(CompareRetryInput {
 						last_retry_ts: resched_state.last_retry_ts,
+						reset_duration_ms: restart_policy.window_ms,
 					})
 					.await?;
 
@@ -515,13 +849,32 @@ pub async fn reschedule_actor(
 				};
 				resched_state.last_retry_ts = now;
 
+				// Destroy instead of restarting if the actor expires before the backoff is over
+				if let Some(expire_ts) = input.expire_ts(ctx.create_ts()) {
+					let backoff_ms = if resched_state.retry_count > 0 {
+						backoff_ms as i64
+					} else {
+						0
+					};
+
+					if expire_ts <= now.saturating_add(backoff_ms) {
+						tracing::debug!(actor_id=?input.actor_id, "actor expired before restart");
+
+						ctx.listen_until::<Destroy>(expire_ts).await?;
+
+						return Ok(Loop::Break(None));
+					}
+				}
+
 				// Don't sleep for first retry
 				if resched_state.retry_count > 0 {
-					let next = backoff.step().expect("should not have max retry");
+					let jitter_ms = rand::thread_rng().gen_range(0..BACKOFF_JITTER_MS);
 
 					// Sleep for backoff or destroy early
 					if let Some(_sig) = ctx
-						.listen_with_timeout::<Destroy>(Instant::from(next) - Instant::now())
+						.listen_with_timeout::<Destroy>(Duration::from_millis(
+							backoff_ms + jitter_ms,
+						))
 						.await?
 					{
 						tracing::debug!("destroying before actor start");
@@ -530,7 +883,9 @@ pub async fn reschedule_actor(
 					}
 				}
 
-				if let Some(res) = spawn_actor(ctx, &input, next_generation).await? {
+				if let Some(res) =
+					spawn_actor(ctx, &input, next_generation, migration.as_ref()).await?
+				{
 					Ok(Loop::Break(Some((resched_state.clone(), res))))
 				} else {
 					// Destroyed early
@@ -546,6 +901,7 @@ pub async fn reschedule_actor(
 		state.generation = next_generation;
 		state.runner_id = res.runner_id;
 		state.runner_workflow_id = res.runner_workflow_id;
+		state.migration = None;
 
 		// Save reschedule state in global state
 		state.reschedule_state = reschedule_state;
@@ -564,7 +920,11 @@ pub struct ClearPendingAllocationInput {
 	actor_id: Id,
 	namespace_id: Id,
 	runner_name_selector: String,
+	#[serde(default)]
+	priority: ActorPriority,
 	pending_allocation_ts: i64,
+	#[serde(default)]
+	timed_out: bool,
 }
 
 #[activity(ClearPendingAllocation)]
@@ -576,36 +936,429 @@ pub async fn clear_pending_allocation(
 	let cleared = ctx
 		.udb()?
 		.run(|tx| async move {
-			let pending_alloc_key =
-				keys::subspace().pack(&keys::ns::PendingActorByRunnerNameSelectorKey::new(
-					input.namespace_id,
-					input.runner_name_selector.clone(),
-					input.pending_allocation_ts,
-					input.actor_id,
-				));
+			let tx = tx.with_subspace(keys::subspace());
 
-			let exists = tx.get(&pending_alloc_key, Serializable).await?.is_some();
+			let pending_alloc_key = keys::ns::PendingActorByPriorityKey::new(
+				input.namespace_id,
+				input.runner_name_selector.clone(),
+				input.priority as u8,
+				input.pending_allocation_ts,
+				input.actor_id,
+			);
+			// Actors queued before priorities were added may still be in the legacy queue
+			let legacy_pending_alloc_key = keys::ns::PendingActorByRunnerNameSelectorKey::new(
+				input.namespace_id,
+				input.runner_name_selector.clone(),
+				input.pending_allocation_ts,
+				input.actor_id,
+			);
 
-			tx.clear(&pending_alloc_key);
+			let (exists, legacy_exists) = tokio::try_join!(
+				tx.exists(&pending_alloc_key, Serializable),
+				tx.exists(&legacy_pending_alloc_key, Serializable),
+			)?;
 
-			Ok(exists)
+			tx.delete(&pending_alloc_key);
+			tx.delete(&legacy_pending_alloc_key);
+			tx.delete(&keys::actor::ExcludeRunnerIdKey::new(input.actor_id));
+
+			if exists {
+				tx.atomic_op(
+					&keys::ns::PendingActorCountKey::new(
+						input.namespace_id,
+						input.runner_name_selector.clone(),
+					),
+					&(-1i64).to_le_bytes(),
+					MutationType::Add,
+				);
+			}
+
+			Ok(exists || legacy_exists)
 		})
 		.await?;
 
+	if cleared {
+		let dt = (util::timestamp::now() - input.pending_allocation_ts) as f64 / 1000.0;
+		metrics::ACTOR_PENDING_ALLOCATION_DURATION.record(
+			dt,
+			&[KeyValue::new(
+				"outcome",
+				if input.timed_out {
+					"timed_out"
+				} else {
+					"destroyed"
+				},
+			)],
+		);
+	}
+
 	Ok(cleared)
 }
 
+#[derive(Debug, Serialize, Deserialize, Hash)]
+struct PreemptActorInput {
+	namespace_id: Id,
+	runner_name_selector: String,
+	slots: u32,
+}
+
+/// Picks a running low priority actor that frees enough slots for the pending actor and removes it
+/// from the preemptible index so it is not picked twice. Returns `None` if preemption is disabled in
+/// the runner config.
+#[activity(PreemptActor)]
+async fn preempt_actor(ctx: &ActivityCtx, input: &PreemptActorInput) -> Result<Option<Id>> {
+	let preempt_low_priority = ctx
+		.op(namespace::ops::runner_config::get_global::Input {
+			runners: vec![(input.namespace_id, input.runner_name_selector.clone())],
+		})
+		.await?
+		.first()
+		.is_some_and(|runner_config| runner_config.config.preempt_low_priority);
+	if !preempt_low_priority {
+		return Ok(None);
+	}
+
+	ctx.udb()?
+		.run(|tx| async move {
+			let tx = tx.with_subspace(keys::subspace());
+
+			let preemptible_subspace =
+				keys::subspace().subspace(&keys::ns::PreemptibleActorKey::subspace(
+					input.namespace_id,
+					input.runner_name_selector.clone(),
+				));
+			let mut stream = tx.get_ranges_keyvalues(
+				universaldb::RangeOption {
+					mode: StreamingMode::Iterator,
+					..(&preemptible_subspace).into()
+				},
+				// NOTE: This is not Serializable because we don't want to conflict with all of the keys,
+				// just the one we choose
+				Snapshot,
+			);
+
+			while let Some(entry) = stream.try_next().await? {
+				let (preemptible_key, slots) =
+					tx.read_entry::<keys::ns::PreemptibleActorKey>(&entry)?;
+
+				if slots < input.slots {
+					continue;
+				}
+
+				tx.add_conflict_key(&preemptible_key, ConflictRangeType::Read)?;
+				tx.delete(&preemptible_key);
+
+				return Ok(Some(preemptible_key.actor_id));
+			}
+
+			Ok(None)
+		})
+		.custom_instrument(tracing::info_span!("actor_preempt_tx"))
+		.await
+}
+
 #[derive(Debug, Serialize, Deserialize, Hash)]
 struct CompareRetryInput {
 	last_retry_ts: i64,
+	/// Defaults to `RETRY_RESET_DURATION_MS`.
+	#[serde(default)]
+	reset_duration_ms: Option<i64>,
 }
 
 #[activity(CompareRetry)]
 async fn compare_retry(ctx: &ActivityCtx, input: &CompareRetryInput) -> Result<(i64, bool)> {
 	let now = util::timestamp::now();
+	let reset_duration_ms = input.reset_duration_ms.unwrap_or(RETRY_RESET_DURATION_MS);
+
+	// If the last retry ts is more than the reset duration ago, reset retry count
+	Ok((now, input.last_retry_ts < now - reset_duration_ms))
+}
+
+/// Returns true if restarting the actor again would exceed the max restarts of its restart policy.
+pub async fn restart_limit_exceeded(
+	ctx: &mut WorkflowCtx,
+	input: &Input,
+	state: &LifecycleState,
+) -> Result<bool> {
+	let Some(restart_policy) = &input.restart_policy else {
+		return Ok(false);
+	};
+	let Some(max_restarts) = restart_policy.max_restarts else {
+		return Ok(false);
+	};
+
+	ctx.activity(CheckRestartLimitInput {
+		last_retry_ts: state.reschedule_state.last_retry_ts,
+		retry_count: state.reschedule_state.retry_count,
+		reset_duration_ms: restart_policy.window_ms,
+		max_restarts,
+	})
+	.await
+}
+
+#[derive(Debug, Serialize, Deserialize, Hash)]
+pub struct GetIdleDestroyTsInput {
+	pub idle_timeout_ms: i64,
+}
+
+#[activity(GetIdleDestroyTs)]
+pub async fn get_idle_destroy_ts(ctx: &ActivityCtx, input: &GetIdleDestroyTsInput) -> Result<i64> {
+	Ok(util::timestamp::now().saturating_add(input.idle_timeout_ms))
+}
+
+/// Sends all due named alarms to the actor. The actor must be running. `alarm_ts` is the timestamp of the
+/// timer that fired, if any.
+///
+/// Alarms are only removed from UDB once the runner acknowledges them, so alarms that were fired before
+/// but not acknowledged are sent again.
+pub async fn fire_named_alarms(
+	ctx: &mut WorkflowCtx,
+	input: &Input,
+	state: &mut LifecycleState,
+	alarm_ts: Option<i64>,
+) -> Result<()> {
+	let alarms = ctx
+		.v(2)
+		.activity(GetDueAlarmsInput {
+			actor_id: input.actor_id,
+			runner_id: state.runner_id,
+		})
+		.await?;
+
+	for alarm in &alarms {
+		state.named_alarms.remove(&alarm.name);
+	}
+	if let Some(alarm_ts) = alarm_ts {
+		// Guarantees the timer that fired does not fire again, even if the runner does not support alarms
+		state.named_alarms.retain(|_, ts| *ts > alarm_ts);
+	}
+
+	if alarms.is_empty() {
+		return Ok(());
+	}
+
+	tracing::debug!(actor_id=?input.actor_id, count=alarms.len(), "firing named alarms");
+
+	ctx.v(2)
+		.signal(protocol::Command::FireAlarms {
+			actor_id: input.actor_id,
+			generation: state.generation,
+			alarms,
+		})
+		.to_workflow_id(state.runner_workflow_id)
+		.send()
+		.await?;
+
+	Ok(())
+}
+
+#[derive(Debug, Serialize, Deserialize, Hash)]
+pub struct SetNamedAlarmInput {
+	pub actor_id: Id,
+	pub alarm: protocol::ActorAlarm,
+}
+
+/// Returns false if the alarm is invalid and was not set.
+#[activity(SetNamedAlarm)]
+pub async fn set_named_alarm(ctx: &ActivityCtx, input: &SetNamedAlarmInput) -> Result<bool> {
+	let payload = match input
+		.alarm
+		.payload
+		.as_ref()
+		.map(|x| BASE64_STANDARD.decode(x))
+		.transpose()
+	{
+		Ok(payload) => payload,
+		Err(err) => {
+			tracing::warn!(actor_id=?input.actor_id, ?err, "invalid alarm payload");
+			return Ok(false);
+		}
+	};
+
+	if payload
+		.as_ref()
+		.is_some_and(|x| x.len() > MAX_ALARM_PAYLOAD_BYTES)
+	{
+		tracing::warn!(actor_id=?input.actor_id, name=%input.alarm.name, "alarm payload too large");
+		return Ok(false);
+	}
+
+	ctx.udb()?
+		.run(|tx| {
+			let payload = payload.clone();
+
+			async move {
+				let tx = tx.with_subspace(keys::subspace());
+
+				tx.write(
+					&keys::actor::AlarmKey::new(input.actor_id, input.alarm.name.clone()),
+					rivet_data::converted::ActorAlarmKeyData {
+						ts: input.alarm.ts,
+						payload,
+					},
+				)?;
+
+				Ok(())
+			}
+		})
+		.await?;
+
+	Ok(true)
+}
+
+#[derive(Debug, Serialize, Deserialize, Hash)]
+pub struct CancelNamedAlarmInput {
+	pub actor_id: Id,
+	pub name: String,
+}
+
+#[activity(CancelNamedAlarm)]
+pub async fn cancel_named_alarm(ctx: &ActivityCtx, input: &CancelNamedAlarmInput) -> Result<()> {
+	ctx.udb()?
+		.run(|tx| async move {
+			let tx = tx.with_subspace(keys::subspace());
+
+			tx.delete(&keys::actor::AlarmKey::new(
+				input.actor_id,
+				input.name.clone(),
+			));
+
+			Ok(())
+		})
+		.await?;
+
+	Ok(())
+}
+
+#[derive(Debug, Serialize, Deserialize, Hash)]
+pub struct AckNamedAlarmsInput {
+	pub actor_id: Id,
+	pub alarms: Vec<protocol::ActorAlarmAck>,
+}
+
+#[activity(AckNamedAlarms)]
+pub async fn ack_named_alarms(ctx: &ActivityCtx, input: &AckNamedAlarmsInput) -> Result<()> {
+	ctx.udb()?
+		.run(|tx| async move {
+			let tx = tx.with_subspace(keys::subspace());
+
+			for ack in &input.alarms {
+				let alarm_key = keys::actor::AlarmKey::new(input.actor_id, ack.name.clone());
+
+				// The alarm may have been set again after it fired, in which case it is kept
+				if let Some(alarm) = tx.read_opt(&alarm_key, Serializable).await? {
+					if alarm.ts == ack.ts {
+						tx.delete(&alarm_key);
+					}
+				}
+			}
+
+			Ok(())
+		})
+		.await?;
+
+	Ok(())
+}
+
+#[derive(Debug, Serialize, Deserialize, Hash)]
+struct GetDueAlarmsInput {
+	actor_id: Id,
+	runner_id: Id,
+}
+
+/// Returns nothing if the runner does not support alarms, they are fired once the actor runs on a runner
+/// that does.
+#[activity(GetDueAlarms)]
+async fn get_due_alarms(
+	ctx: &ActivityCtx,
+	input: &GetDueAlarmsInput,
+) -> Result<Vec<protocol::ActorAlarm>> {
+	let now = util::timestamp::now();
+
+	ctx.udb()?
+		.run(|tx| async move {
+			let tx = tx.with_subspace(keys::subspace());
+
+			let protocol_version = tx
+				.read_opt(
+					&keys::runner::ProtocolVersionKey::new(input.runner_id),
+					Snapshot,
+				)
+				.await?
+				.unwrap_or_default();
+			if protocol_version < 2 {
+				return Ok(Vec::new());
+			}
+
+			let alarm_subspace =
+				keys::subspace().subspace(&keys::actor::AlarmKey::subspace(input.actor_id));
+
+			let entries = tx
+				.get_ranges_keyvalues(
+					universaldb::RangeOption {
+						mode: StreamingMode::WantAll,
+						..(&alarm_subspace).into()
+					},
+					Serializable,
+				)
+				.map(|res| tx.read_entry::<keys::actor::AlarmKey>(&res?))
+				.try_collect::<Vec<_>>()
+				.await?;
+
+			let mut alarms = entries
+				.into_iter()
+				.filter(|(_, alarm)| alarm.ts <= now)
+				.map(|(key, alarm)| protocol::ActorAlarm {
+					name: key.name,
+					ts: alarm.ts,
+					payload: alarm.payload.map(|x| BASE64_STANDARD.encode(x)),
+				})
+				.collect::<Vec<_>>();
+
+			// Deliver in the order the alarms were scheduled
+			alarms.sort_by_key(|alarm| alarm.ts);
+
+			Ok(alarms)
+		})
+		.await
+}
+
+/// Delay before restarting an actor that already restarted `retry_count` times in a row, without jitter.
+fn restart_backoff_ms(restart_policy: &RestartPolicy, retry_count: usize) -> u64 {
+	let backoff_base_ms = restart_policy
+		.backoff_base_ms
+		.unwrap_or(BASE_RETRY_TIMEOUT_MS as u64);
+	let backoff_max_ms = restart_policy
+		.backoff_max_ms
+		.unwrap_or(backoff_base_ms.saturating_mul(1 << DEFAULT_BACKOFF_MAX_EXPONENT));
+
+	backoff_base_ms
+		.saturating_mul(1u64.checked_shl(retry_count as u32).unwrap_or(u64::MAX))
+		.min(backoff_max_ms)
+}
+
+#[derive(Debug, Serialize, Deserialize, Hash)]
+struct CheckRestartLimitInput {
+	last_retry_ts: i64,
+	retry_count: usize,
+	reset_duration_ms: Option<i64>,
+	max_restarts: u32,
+}
+
+#[activity(CheckRestartLimit)]
+async fn check_restart_limit(ctx: &ActivityCtx, input: &CheckRestartLimitInput) -> Result<bool> {
+	let now = util::timestamp::now();
+	let reset_duration_ms = input.reset_duration_ms.unwrap_or(RETRY_RESET_DURATION_MS);
+
+	// Mirrors the retry count calculation in `reschedule_actor`. The restart about to happen would be
+	// number `retry_count + 1` of the current streak of consecutive restarts.
+	let retry_count = if input.last_retry_ts < now - reset_duration_ms {
+		0
+	} else {
+		input.retry_count + 1
+	};
 
-	// If the last retry ts is more than RETRY_RESET_DURATION_MS, reset retry count
-	Ok((now, input.last_retry_ts < now - RETRY_RESET_DURATION_MS))
+	Ok(retry_count + 1 > input.max_restarts as usize)
 }
 
 #[derive(Debug, Serialize, Deserialize, Hash)]
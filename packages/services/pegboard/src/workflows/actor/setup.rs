@@ -1,20 +1,34 @@
 use gas::prelude::*;
-use rivet_data::converted::ActorNameKeyData;
-use rivet_types::actors::CrashPolicy;
+use rivet_data::converted::{ActorNameKeyData, ActorPlacementKeyData};
+use rivet_types::actors::{ActorPlacement, CrashPolicy, RestartPolicy};
 use universaldb::utils::IsolationLevel::*;
 
-use super::State;
+use super::{BASE_RETRY_TIMEOUT_MS, MAX_BACKOFF_MS, MAX_TTL_MS, State};
 
 use crate::{errors, keys};
 
 const MAX_INPUT_SIZE: usize = util::file_size::mebibytes(4) as usize;
+/// Max number of runners read when checking if any runner can fit an actor's slots.
+const MAX_CAPACITY_CHECK_RUNNERS: usize = 1024;
 
 #[derive(Debug, Clone, Serialize, Deserialize, Hash)]
 pub struct ValidateInput {
 	pub namespace_id: Id,
 	pub name: String,
 	pub key: Option<String>,
+	#[serde(default)]
+	pub runner_name_selector: String,
 	pub input: Option<String>,
+	pub slots: Option<u32>,
+	pub max_pending_duration_ms: Option<i64>,
+	#[serde(default)]
+	pub restart_policy: Option<RestartPolicy>,
+	#[serde(default)]
+	pub ttl_ms: Option<i64>,
+	#[serde(default)]
+	pub destroy_at_ts: Option<i64>,
+	#[serde(default)]
+	pub idle_timeout_ms: Option<i64>,
 }
 
 #[activity(Validate)]
@@ -43,6 +57,61 @@ pub async fn validate(
 		}));
 	}
 
+	if input.slots == Some(0) {
+		return Ok(Err(errors::Actor::InvalidSlots));
+	}
+
+	if let Some(slots) = input.slots.filter(|slots| *slots > 1) {
+		if let Some(max_slots) = max_runner_slots(ctx, input).await? {
+			if slots > max_slots {
+				return Ok(Err(errors::Actor::SlotsExceedRunnerCapacity {
+					slots,
+					max_slots,
+				}));
+			}
+		}
+	}
+
+	if input
+		.max_pending_duration_ms
+		.is_some_and(|max_pending_duration_ms| {
+			max_pending_duration_ms <= 0
+				|| max_pending_duration_ms > namespace::types::MAX_PENDING_DURATION_MS
+		}) {
+		return Ok(Err(errors::Actor::InvalidMaxPendingDuration));
+	}
+
+	let now = util::timestamp::now();
+	if input
+		.ttl_ms
+		.is_some_and(|ttl_ms| ttl_ms <= 0 || ttl_ms > MAX_TTL_MS)
+		|| input
+			.idle_timeout_ms
+			.is_some_and(|idle_timeout_ms| idle_timeout_ms <= 0 || idle_timeout_ms > MAX_TTL_MS)
+		|| input.destroy_at_ts.is_some_and(|destroy_at_ts| {
+			destroy_at_ts <= now || destroy_at_ts > now.saturating_add(MAX_TTL_MS)
+		}) {
+		return Ok(Err(errors::Actor::InvalidTtl));
+	}
+
+	if let Some(restart_policy) = &input.restart_policy {
+		let invalid_window = restart_policy
+			.window_ms
+			.is_some_and(|window_ms| window_ms <= 0);
+		let backoff_base_ms = restart_policy
+			.backoff_base_ms
+			.unwrap_or(BASE_RETRY_TIMEOUT_MS as u64);
+		let invalid_backoff = backoff_base_ms == 0
+			|| backoff_base_ms > MAX_BACKOFF_MS
+			|| restart_policy.backoff_max_ms.is_some_and(|backoff_max_ms| {
+				backoff_max_ms < backoff_base_ms || backoff_max_ms > MAX_BACKOFF_MS
+			});
+
+		if invalid_window || invalid_backoff {
+			return Ok(Err(errors::Actor::InvalidRestartPolicy));
+		}
+	}
+
 	if let Some(k) = &input.key {
 		if k.is_empty() {
 			return Ok(Err(errors::Actor::EmptyKey));
@@ -58,6 +127,42 @@ pub async fn validate(
 	Ok(Ok(()))
 }
 
+/// Returns the most slots a runner for this actor can have. Returns `None` if unknown because no
+/// runners are connected, since runners with more slots may connect later.
+async fn max_runner_slots(ctx: &ActivityCtx, input: &ValidateInput) -> Result<Option<u32>> {
+	let runner_config = ctx
+		.op(namespace::ops::runner_config::get_global::Input {
+			runners: vec![(input.namespace_id, input.runner_name_selector.clone())],
+		})
+		.await?;
+
+	// Serverless runners are all started with the same amount of slots
+	if let Some(runner_config) = runner_config.first() {
+		if let namespace::types::RunnerConfigKind::Serverless {
+			slots_per_runner, ..
+		} = &runner_config.config.kind
+		{
+			return Ok(Some(*slots_per_runner));
+		}
+	}
+
+	let runners_res = ctx
+		.op(crate::ops::runner::list_for_ns::Input {
+			namespace_id: input.namespace_id,
+			name: Some(input.runner_name_selector.clone()),
+			include_stopped: false,
+			created_before: None,
+			limit: MAX_CAPACITY_CHECK_RUNNERS,
+		})
+		.await?;
+
+	Ok(runners_res
+		.runners
+		.iter()
+		.map(|runner| runner.total_slots)
+		.max())
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Hash)]
 pub struct InitStateAndUdbInput {
 	pub actor_id: Id,
@@ -66,6 +171,8 @@ pub struct InitStateAndUdbInput {
 	pub namespace_id: Id,
 	pub runner_name_selector: String,
 	pub crash_policy: CrashPolicy,
+	pub placement: Option<ActorPlacement>,
+	pub slots: u32,
 	pub create_ts: i64,
 }
 
@@ -79,6 +186,11 @@ pub async fn insert_state_and_db(ctx: &ActivityCtx, input: &InitStateAndUdbInput
 		input.namespace_id,
 		input.runner_name_selector.clone(),
 		input.crash_policy,
+		input
+			.placement
+			.as_ref()
+			.and_then(|placement| placement.anti_affinity.then(|| input.name.clone())),
+		input.slots,
 		input.create_ts,
 	));
 
@@ -95,6 +207,20 @@ pub async fn insert_state_and_db(ctx: &ActivityCtx, input: &InitStateAndUdbInput
 				ctx.workflow_id(),
 			)?;
 
+			// Read during allocation
+			tx.write(&keys::actor::SlotsKey::new(input.actor_id), input.slots)?;
+
+			if let Some(placement) = &input.placement {
+				tx.write(
+					&keys::actor::PlacementKey::new(input.actor_id),
+					ActorPlacementKeyData {
+						required_labels: placement.required_labels.0.clone(),
+						preferred_labels: placement.preferred_labels.0.clone(),
+						anti_affinity_group: placement.anti_affinity.then(|| input.name.clone()),
+					},
+				)?;
+			}
+
 			Ok(())
 		})
 		.custom_instrument(tracing::info_span!("actor_insert_tx"))
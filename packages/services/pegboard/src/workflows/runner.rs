@@ -2,10 +2,14 @@ use futures_util::{FutureExt, StreamExt, TryStreamExt};
 use gas::prelude::*;
 use rivet_data::converted::{ActorNameKeyData, MetadataKeyData, RunnerByKeyKeyData};
 use rivet_runner_protocol::protocol;
-use universaldb::options::{ConflictRangeType, StreamingMode};
+use rivet_types::actors::ActorPriority;
+use universaldb::options::{ConflictRangeType, MutationType, StreamingMode};
 use universaldb::utils::{FormalChunkedKey, IsolationLevel::*};
 
-use crate::{keys, workflows::actor::Allocate};
+use crate::{
+	keys,
+	workflows::actor::{Allocate, placement},
+};
 
 /// How long after last ping before considering a runner ineligible for allocation.
 pub const RUNNER_ELIGIBLE_THRESHOLD_MS: i64 = util::duration::seconds(10);
@@ -87,6 +91,8 @@ pub async fn pegboard_runner(ctx: &mut WorkflowCtx, input: &Input) -> Result<()>
 							last_command_idx,
 							prepopulate_actor_names,
 							metadata,
+							labels,
+							protocol_version,
 							..
 						} => {
 							let init_data = ctx
@@ -96,6 +102,8 @@ pub async fn pegboard_runner(ctx: &mut WorkflowCtx, input: &Input) -> Result<()>
 									last_command_idx: last_command_idx.unwrap_or(-1),
 									prepopulate_actor_names,
 									metadata,
+									labels,
+									protocol_version,
 								})
 								.await?;
 
@@ -237,6 +245,7 @@ pub async fn pegboard_runner(ctx: &mut WorkflowCtx, input: &Input) -> Result<()>
 									.map(|(actor_id, generation)| protocol::Command::StopActor {
 										actor_id,
 										generation,
+										intent: protocol::StopIntent::Stop,
 									})
 									.collect::<Vec<_>>();
 
@@ -669,6 +678,11 @@ async fn clear_db(ctx: &ActivityCtx, input: &ClearDbInput) -> Result<()> {
 				RunnerState::Stopped => {
 					tx.write(&keys::runner::StopTsKey::new(input.runner_id), now)?;
 
+					// Nothing can be allocated to this runner anymore
+					tx.delete_key_subspace(&keys::runner::AntiAffinityCountKey::subspace(
+						input.runner_id,
+					));
+
 					// Update namespace indexes
 					tx.delete(&keys::ns::ActiveRunnerKey::new(
 						namespace_id,
@@ -707,6 +721,10 @@ struct ProcessInitInput {
 	last_command_idx: i64,
 	prepopulate_actor_names: Option<util::serde::HashableMap<String, protocol::ActorName>>,
 	metadata: Option<String>,
+	#[serde(default)]
+	labels: Option<util::serde::HashableMap<String, String>>,
+	#[serde(default)]
+	protocol_version: u16,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -740,6 +758,15 @@ async fn process_init(ctx: &ActivityCtx, input: &ProcessInitInput) -> Result<Pro
 				}
 			}
 
+			// Replace labels from the previous connection
+			tx.delete_key_subspace(&keys::runner::LabelKey::subspace(input.runner_id));
+			for (label, value) in input.labels.iter().flatten() {
+				tx.write(
+					&keys::runner::LabelKey::new(input.runner_id, label.clone()),
+					value.clone(),
+				)?;
+			}
+
 			if let Some(metadata) = &input.metadata {
 				let metadata = MetadataKeyData {
 					metadata: serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(
@@ -758,6 +785,12 @@ async fn process_init(ctx: &ActivityCtx, input: &ProcessInitInput) -> Result<Pro
 				}
 			}
 
+			// Written on every init since a runner can reconnect with a different protocol version
+			tx.write(
+				&keys::runner::ProtocolVersionKey::new(input.runner_id),
+				input.protocol_version,
+			)?;
+
 			Ok(())
 		})
 		.custom_instrument(tracing::info_span!("runner_populate_actor_names_tx"))
@@ -945,6 +978,20 @@ pub(crate) async fn allocate_pending_actors(
 	ctx: &ActivityCtx,
 	input: &AllocatePendingActorsInput,
 ) -> Result<AllocatePendingActorsOutput> {
+	let (allocation_strategy, rollout) = ctx
+		.op(namespace::ops::runner_config::get_global::Input {
+			runners: vec![(input.namespace_id, input.name.clone())],
+		})
+		.await?
+		.first()
+		.map(|runner_config| {
+			(
+				runner_config.config.allocation_strategy,
+				runner_config.config.rollout,
+			)
+		})
+		.unwrap_or_default();
+
 	// NOTE: This txn should closely resemble the one found in the allocate_actor activity of the actor wf
 	let res = ctx
 		.udb()?
@@ -952,12 +999,14 @@ pub(crate) async fn allocate_pending_actors(
 			let tx = tx.with_subspace(keys::subspace());
 			let mut results = Vec::new();
 
-			let pending_actor_subspace = keys::subspace().subspace(
-				&keys::ns::PendingActorByRunnerNameSelectorKey::subspace(
+			migrate_legacy_pending_actors(&tx, input.namespace_id, &input.name).await?;
+
+			// Sorted by priority descending, then by pending ts
+			let pending_actor_subspace =
+				keys::subspace().subspace(&keys::ns::PendingActorByPriorityKey::subspace(
 					input.namespace_id,
 					input.name.clone(),
-				),
-			);
+				));
 			let mut queue_stream = tx.get_ranges_keyvalues(
 				universaldb::RangeOption {
 					mode: StreamingMode::Iterator,
@@ -969,120 +1018,149 @@ pub(crate) async fn allocate_pending_actors(
 			);
 			let ping_threshold_ts = util::timestamp::now() - RUNNER_ELIGIBLE_THRESHOLD_MS;
 
-			'queue_loop: loop {
+			loop {
 				let Some(queue_entry) = queue_stream.try_next().await? else {
 					break;
 				};
 
 				let (queue_key, generation) =
-					tx.read_entry::<keys::ns::PendingActorByRunnerNameSelectorKey>(&queue_entry)?;
-
-				let runner_alloc_subspace = keys::subspace().subspace(
-					&keys::ns::RunnerAllocIdxKey::subspace(input.namespace_id, input.name.clone()),
-				);
-
-				let mut stream = tx.get_ranges_keyvalues(
-					universaldb::RangeOption {
-						mode: StreamingMode::Iterator,
-						// Containers bin pack so we reverse the order
-						reverse: true,
-						..(&runner_alloc_subspace).into()
-					},
-					// NOTE: This is not Serializable because we don't want to conflict with all of the
-					// keys, just the one we choose
-					Snapshot,
-				);
-
-				let mut highest_version = None;
-
-				loop {
-					let Some(entry) = stream.try_next().await? else {
-						break;
-					};
-
-					let (old_runner_alloc_key, old_runner_alloc_key_data) =
-						tx.read_entry::<keys::ns::RunnerAllocIdxKey>(&entry)?;
+					tx.read_entry::<keys::ns::PendingActorByPriorityKey>(&queue_entry)?;
+
+				let exclude_runner_id_key =
+					keys::actor::ExcludeRunnerIdKey::new(queue_key.actor_id);
+				let (placement, slots, exclude_runner_id) = tokio::try_join!(
+					tx.read_opt(
+						&keys::actor::PlacementKey::new(queue_key.actor_id),
+						Serializable,
+					),
+					tx.read_opt(
+						&keys::actor::SlotsKey::new(queue_key.actor_id),
+						Serializable
+					),
+					tx.read_opt(&exclude_runner_id_key, Serializable),
+				)?;
+				// Actors created before slots were configurable consume 1 slot
+				let slots = slots.unwrap_or(1);
 
-					if let Some(highest_version) = highest_version {
-						// We have passed all of the runners with the highest version. This is reachable if
-						// the ping of the highest version workers makes them ineligible
-						if old_runner_alloc_key.version < highest_version {
-							break;
-						}
-					} else {
-						highest_version = Some(old_runner_alloc_key.version);
-					}
+				let chosen = placement::select_runner(
+					&tx,
+					input.namespace_id,
+					&input.name,
+					allocation_strategy,
+					&rollout,
+					slots,
+					placement.as_ref(),
+					exclude_runner_id,
+					ping_threshold_ts,
+				)
+				.await?;
 
-					// An empty runner means we have reached the end of the runners with the highest version
-					if old_runner_alloc_key.remaining_millislots == 0 {
+				let Some((old_runner_alloc_key, old_runner_alloc_key_data)) = chosen else {
+					// Nothing behind an actor that waits for capacity is allocated, otherwise smaller or lower
+					// priority actors further down the queue would keep taking the capacity it needs
+					if placement::has_eligible_runner(
+						&tx,
+						input.namespace_id,
+						&input.name,
+						&rollout,
+						slots,
+						placement.as_ref(),
+						exclude_runner_id,
+						ping_threshold_ts,
+					)
+					.await?
+					{
 						break;
 					}
 
-					// Scan by last ping
-					if old_runner_alloc_key.last_ping_ts < ping_threshold_ts {
-						continue;
-					}
+					// No runner can take this actor regardless of capacity, try the next one in the queue
+					continue;
+				};
 
-					// Add read conflict only for this runner key
-					tx.add_conflict_key(&old_runner_alloc_key, ConflictRangeType::Read)?;
-					tx.delete(&old_runner_alloc_key);
+				// Add read conflict only for this runner key
+				tx.add_conflict_key(&old_runner_alloc_key, ConflictRangeType::Read)?;
+				tx.delete(&old_runner_alloc_key);
+
+				// Add read conflict for the queue key
+				tx.add_conflict_key(&queue_key, ConflictRangeType::Read)?;
+				tx.delete(&queue_key);
+				tx.delete(&exclude_runner_id_key);
+				tx.atomic_op(
+					&keys::ns::PendingActorCountKey::new(input.namespace_id, input.name.clone()),
+					&(-1i64).to_le_bytes(),
+					MutationType::Add,
+				);
 
-					// Add read conflict for the queue key
-					tx.add_conflict_key(&queue_key, ConflictRangeType::Read)?;
-					tx.delete(&queue_key);
+				let new_remaining_slots = old_runner_alloc_key_data
+					.remaining_slots
+					.saturating_sub(slots);
+				let new_remaining_millislots =
+					(new_remaining_slots * 1000) / old_runner_alloc_key_data.total_slots;
 
-					let new_remaining_slots =
-						old_runner_alloc_key_data.remaining_slots.saturating_sub(1);
-					let new_remaining_millislots =
-						(new_remaining_slots * 1000) / old_runner_alloc_key_data.total_slots;
+				// Write new allocation key with the actor's slots subtracted
+				tx.write(
+					&keys::ns::RunnerAllocIdxKey::new(
+						input.namespace_id,
+						input.name.clone(),
+						old_runner_alloc_key.version,
+						new_remaining_millislots,
+						old_runner_alloc_key.last_ping_ts,
+						old_runner_alloc_key.runner_id,
+					),
+					rivet_data::converted::RunnerAllocIdxKeyData {
+						workflow_id: old_runner_alloc_key_data.workflow_id,
+						remaining_slots: new_remaining_slots,
+						total_slots: old_runner_alloc_key_data.total_slots,
+					},
+				)?;
 
-					// Write new allocation key with 1 less slot
-					tx.write(
-						&keys::ns::RunnerAllocIdxKey::new(
-							input.namespace_id,
-							input.name.clone(),
-							old_runner_alloc_key.version,
-							new_remaining_millislots,
-							old_runner_alloc_key.last_ping_ts,
-							old_runner_alloc_key.runner_id,
-						),
-						rivet_data::converted::RunnerAllocIdxKeyData {
-							workflow_id: old_runner_alloc_key_data.workflow_id,
-							remaining_slots: new_remaining_slots,
-							total_slots: old_runner_alloc_key_data.total_slots,
-						},
-					)?;
+				// Update runner record
+				tx.write(
+					&keys::runner::RemainingSlotsKey::new(old_runner_alloc_key.runner_id),
+					new_remaining_slots,
+				)?;
 
-					// Update runner record
-					tx.write(
-						&keys::runner::RemainingSlotsKey::new(old_runner_alloc_key.runner_id),
-						new_remaining_slots,
-					)?;
+				// Set runner id of actor
+				tx.write(
+					&keys::actor::RunnerIdKey::new(queue_key.actor_id),
+					old_runner_alloc_key.runner_id,
+				)?;
 
-					// Set runner id of actor
-					tx.write(
-						&keys::actor::RunnerIdKey::new(queue_key.actor_id),
+				// Insert actor index key
+				tx.write(
+					&keys::runner::ActorKey::new(
 						old_runner_alloc_key.runner_id,
-					)?;
+						queue_key.actor_id,
+					),
+					generation,
+				)?;
 
-					// Insert actor index key
-					tx.write(
-						&keys::runner::ActorKey::new(
+				placement::record_allocation(
+					&tx,
+					input.namespace_id,
+					&input.name,
+					old_runner_alloc_key.version,
+					rollout.rollback.as_ref(),
+				)?;
+
+				if let Some(group) = placement.and_then(|placement| placement.anti_affinity_group) {
+					tx.atomic_op(
+						&keys::runner::AntiAffinityCountKey::new(
 							old_runner_alloc_key.runner_id,
-							queue_key.actor_id,
+							group,
 						),
-						generation,
-					)?;
-
-					results.push(ActorAllocation {
-						actor_id: queue_key.actor_id,
-						signal: Allocate {
-							runner_id: old_runner_alloc_key.runner_id,
-							runner_workflow_id: old_runner_alloc_key_data.workflow_id,
-						},
-					});
-					continue 'queue_loop;
+						&1i64.to_le_bytes(),
+						MutationType::Add,
+					);
 				}
+
+				results.push(ActorAllocation {
+					actor_id: queue_key.actor_id,
+					signal: Allocate {
+						runner_id: old_runner_alloc_key.runner_id,
+						runner_workflow_id: old_runner_alloc_key_data.workflow_id,
+					},
+				});
 			}
 
 			Ok(results)
@@ -1093,6 +1171,54 @@ pub(crate) async fn allocate_pending_actors(
 	Ok(AllocatePendingActorsOutput { allocations: res })
 }
 
+/// Moves actors queued before priorities were added to the priority queue. They keep their original
+/// pending ts and are treated as normal priority.
+pub(crate) async fn migrate_legacy_pending_actors(
+	tx: &universaldb::Transaction,
+	namespace_id: Id,
+	runner_name_selector: &str,
+) -> Result<()> {
+	let legacy_pending_actor_subspace =
+		keys::subspace().subspace(&keys::ns::PendingActorByRunnerNameSelectorKey::subspace(
+			namespace_id,
+			runner_name_selector.to_string(),
+		));
+	let mut legacy_queue_stream = tx.get_ranges_keyvalues(
+		universaldb::RangeOption {
+			mode: StreamingMode::WantAll,
+			..(&legacy_pending_actor_subspace).into()
+		},
+		Snapshot,
+	);
+
+	while let Some(queue_entry) = legacy_queue_stream.try_next().await? {
+		let (legacy_queue_key, generation) =
+			tx.read_entry::<keys::ns::PendingActorByRunnerNameSelectorKey>(&queue_entry)?;
+
+		// Conflicts with the actor clearing itself from the queue
+		tx.add_conflict_key(&legacy_queue_key, ConflictRangeType::Read)?;
+		tx.delete(&legacy_queue_key);
+
+		tx.write(
+			&keys::ns::PendingActorByPriorityKey::new(
+				namespace_id,
+				runner_name_selector.to_string(),
+				ActorPriority::default() as u8,
+				legacy_queue_key.ts,
+				legacy_queue_key.actor_id,
+			),
+			generation,
+		)?;
+		tx.atomic_op(
+			&keys::ns::PendingActorCountKey::new(namespace_id, runner_name_selector.to_string()),
+			&1i64.to_le_bytes(),
+			MutationType::Add,
+		);
+	}
+
+	Ok(())
+}
+
 #[message("pegboard_runner_to_ws")]
 pub struct ToWs {
 	pub runner_id: Id,
@@ -135,3 +135,61 @@ impl TryFrom<ActorNameKeyData> for pegboard_namespace_actor_name_v1::Data {
 		})
 	}
 }
+
+pub struct ActorPlacementKeyData {
+	pub required_labels: util::serde::HashableMap<String, String>,
+	pub preferred_labels: util::serde::HashableMap<String, String>,
+	/// Set to the actor name if the actor should avoid runners with actors of the same group.
+	pub anti_affinity_group: Option<String>,
+}
+
+impl TryFrom<pegboard_actor_placement_v1::Data> for ActorPlacementKeyData {
+	type Error = anyhow::Error;
+
+	fn try_from(value: pegboard_actor_placement_v1::Data) -> Result<Self> {
+		Ok(ActorPlacementKeyData {
+			required_labels: value.required_labels,
+			preferred_labels: value.preferred_labels,
+			anti_affinity_group: value.anti_affinity_group,
+		})
+	}
+}
+
+impl TryFrom<ActorPlacementKeyData> for pegboard_actor_placement_v1::Data {
+	type Error = anyhow::Error;
+
+	fn try_from(value: ActorPlacementKeyData) -> Result<Self> {
+		Ok(pegboard_actor_placement_v1::Data {
+			required_labels: value.required_labels,
+			preferred_labels: value.preferred_labels,
+			anti_affinity_group: value.anti_affinity_group,
+		})
+	}
+}
+
+pub struct ActorAlarmKeyData {
+	pub ts: i64,
+	pub payload: Option<Vec<u8>>,
+}
+
+impl TryFrom<pegboard_actor_alarm_v1::Data> for ActorAlarmKeyData {
+	type Error = anyhow::Error;
+
+	fn try_from(value: pegboard_actor_alarm_v1::Data) -> Result<Self> {
+		Ok(ActorAlarmKeyData {
+			ts: value.ts,
+			payload: value.payload,
+		})
+	}
+}
+
+impl TryFrom<ActorAlarmKeyData> for pegboard_actor_alarm_v1::Data {
+	type Error = anyhow::Error;
+
+	fn try_from(value: ActorAlarmKeyData) -> Result<Self> {
+		Ok(pegboard_actor_alarm_v1::Data {
+			ts: value.ts,
+			payload: value.payload,
+		})
+	}
+}
@@ -175,18 +175,19 @@ impl OwnedVersionedData for ActorNameKeyData {
 
 pub enum NamespaceRunnerConfig {
 	V1(namespace_runner_config_v1::Data),
+	V2(namespace_runner_config_v2::Data),
+	V3(namespace_runner_config_v3::Data),
 }
 
 impl OwnedVersionedData for NamespaceRunnerConfig {
-	type Latest = namespace_runner_config_v1::Data;
+	type Latest = namespace_runner_config_v3::Data;
 
-	fn latest(latest: namespace_runner_config_v1::Data) -> Self {
-		NamespaceRunnerConfig::V1(latest)
+	fn latest(latest: namespace_runner_config_v3::Data) -> Self {
+		NamespaceRunnerConfig::V3(latest)
 	}
 
 	fn into_latest(self) -> Result<Self::Latest> {
-		#[allow(irrefutable_let_patterns)]
-		if let NamespaceRunnerConfig::V1(data) = self {
+		if let NamespaceRunnerConfig::V3(data) = self {
 			Ok(data)
 		} else {
 			bail!("version not latest");
@@ -196,6 +197,8 @@ impl OwnedVersionedData for NamespaceRunnerConfig {
 	fn deserialize_version(payload: &[u8], version: u16) -> Result<Self> {
 		match version {
 			1 => Ok(NamespaceRunnerConfig::V1(serde_bare::from_slice(payload)?)),
+			2 => Ok(NamespaceRunnerConfig::V2(serde_bare::from_slice(payload)?)),
+			3 => Ok(NamespaceRunnerConfig::V3(serde_bare::from_slice(payload)?)),
 			_ => bail!("invalid version: {version}"),
 		}
 	}
@@ -203,6 +206,196 @@ impl OwnedVersionedData for NamespaceRunnerConfig {
 	fn serialize_version(self, _version: u16) -> Result<Vec<u8>> {
 		match self {
 			NamespaceRunnerConfig::V1(data) => serde_bare::to_vec(&data).map_err(Into::into),
+			NamespaceRunnerConfig::V2(data) => serde_bare::to_vec(&data).map_err(Into::into),
+			NamespaceRunnerConfig::V3(data) => serde_bare::to_vec(&data).map_err(Into::into),
+		}
+	}
+
+	fn deserialize_converters() -> Vec<impl Fn(Self) -> Result<Self>> {
+		vec![Self::v1_to_v2 as fn(Self) -> Result<Self>, Self::v2_to_v3]
+	}
+}
+
+impl NamespaceRunnerConfig {
+	fn v1_to_v2(self) -> Result<Self> {
+		let NamespaceRunnerConfig::V1(data) = self else {
+			bail!("unexpected version");
+		};
+
+		let kind = match data {
+			namespace_runner_config_v1::Data::Serverless(serverless) => {
+				namespace_runner_config_v2::Kind::Serverless(
+					namespace_runner_config_v2::Serverless {
+						url: serverless.url,
+						request_lifespan: serverless.request_lifespan,
+						slots_per_runner: serverless.slots_per_runner,
+						min_runners: serverless.min_runners,
+						max_runners: serverless.max_runners,
+						runners_margin: serverless.runners_margin,
+					},
+				)
+			}
+		};
+
+		Ok(NamespaceRunnerConfig::V2(
+			namespace_runner_config_v2::Data {
+				kind,
+				// Runner configs without a strategy allocated to the runner with the most
+				// remaining slots
+				allocation_strategy: namespace_runner_config_v2::AllocationStrategy::Spread,
+			},
+		))
+	}
+
+	fn v2_to_v3(self) -> Result<Self> {
+		let NamespaceRunnerConfig::V2(data) = self else {
+			bail!("unexpected version");
+		};
+
+		let kind = match data.kind {
+			namespace_runner_config_v2::Kind::Serverless(serverless) => {
+				namespace_runner_config_v3::Kind::Serverless(
+					namespace_runner_config_v3::Serverless {
+						url: serverless.url,
+						request_lifespan: serverless.request_lifespan,
+						slots_per_runner: serverless.slots_per_runner,
+						min_runners: serverless.min_runners,
+						max_runners: serverless.max_runners,
+						runners_margin: serverless.runners_margin,
+					},
+				)
+			}
+			namespace_runner_config_v2::Kind::Normal => namespace_runner_config_v3::Kind::Normal,
+		};
+
+		let allocation_strategy = match data.allocation_strategy {
+			namespace_runner_config_v2::AllocationStrategy::BinPack => {
+				namespace_runner_config_v3::AllocationStrategy::BinPack
+			}
+			namespace_runner_config_v2::AllocationStrategy::Spread => {
+				namespace_runner_config_v3::AllocationStrategy::Spread
+			}
+			namespace_runner_config_v2::AllocationStrategy::RandomTopN(o) => {
+				namespace_runner_config_v3::AllocationStrategy::RandomTopN(
+					namespace_runner_config_v3::RandomTopN { n: o.n },
+				)
+			}
+		};
+
+		Ok(NamespaceRunnerConfig::V3(
+			namespace_runner_config_v3::Data {
+				kind,
+				allocation_strategy,
+				rollout: namespace_runner_config_v3::RolloutPolicy {
+					canary_percent: None,
+					pinned_version: None,
+					rollback: None,
+				},
+				max_pending_duration_ms: None,
+				preempt_low_priority: false,
+			},
+		))
+	}
+}
+
+pub enum ActorLogEntryKeyData {
+	V1(pegboard_actor_log_entry_v1::Data),
+}
+
+impl OwnedVersionedData for ActorLogEntryKeyData {
+	type Latest = pegboard_actor_log_entry_v1::Data;
+
+	fn latest(latest: pegboard_actor_log_entry_v1::Data) -> Self {
+		ActorLogEntryKeyData::V1(latest)
+	}
+
+	fn into_latest(self) -> Result<Self::Latest> {
+		#[allow(irrefutable_let_patterns)]
+		if let ActorLogEntryKeyData::V1(data) = self {
+			Ok(data)
+		} else {
+			bail!("version not latest");
+		}
+	}
+
+	fn deserialize_version(payload: &[u8], version: u16) -> Result<Self> {
+		match version {
+			1 => Ok(ActorLogEntryKeyData::V1(serde_bare::from_slice(payload)?)),
+			_ => bail!("invalid version: {version}"),
+		}
+	}
+
+	fn serialize_version(self, _version: u16) -> Result<Vec<u8>> {
+		match self {
+			ActorLogEntryKeyData::V1(data) => serde_bare::to_vec(&data).map_err(Into::into),
+		}
+	}
+}
+
+pub enum ActorPlacementKeyData {
+	V1(pegboard_actor_placement_v1::Data),
+}
+
+impl OwnedVersionedData for ActorPlacementKeyData {
+	type Latest = pegboard_actor_placement_v1::Data;
+
+	fn latest(latest: pegboard_actor_placement_v1::Data) -> Self {
+		ActorPlacementKeyData::V1(latest)
+	}
+
+	fn into_latest(self) -> Result<Self::Latest> {
+		#[allow(irrefutable_let_patterns)]
+		if let ActorPlacementKeyData::V1(data) = self {
+			Ok(data)
+		} else {
+			bail!("version not latest");
+		}
+	}
+
+	fn deserialize_version(payload: &[u8], version: u16) -> Result<Self> {
+		match version {
+			1 => Ok(ActorPlacementKeyData::V1(serde_bare::from_slice(payload)?)),
+			_ => bail!("invalid version: {version}"),
+		}
+	}
+
+	fn serialize_version(self, _version: u16) -> Result<Vec<u8>> {
+		match self {
+			ActorPlacementKeyData::V1(data) => serde_bare::to_vec(&data).map_err(Into::into),
+		}
+	}
+}
+
+pub enum ActorAlarmKeyData {
+	V1(pegboard_actor_alarm_v1::Data),
+}
+
+impl OwnedVersionedData for ActorAlarmKeyData {
+	type Latest = pegboard_actor_alarm_v1::Data;
+
+	fn latest(latest: pegboard_actor_alarm_v1::Data) -> Self {
+		ActorAlarmKeyData::V1(latest)
+	}
+
+	fn into_latest(self) -> Result<Self::Latest> {
+		#[allow(irrefutable_let_patterns)]
+		if let ActorAlarmKeyData::V1(data) = self {
+			Ok(data)
+		} else {
+			bail!("version not latest");
+		}
+	}
+
+	fn deserialize_version(payload: &[u8], version: u16) -> Result<Self> {
+		match version {
+			1 => Ok(ActorAlarmKeyData::V1(serde_bare::from_slice(payload)?)),
+			_ => bail!("invalid version: {version}"),
+		}
+	}
+
+	fn serialize_version(self, _version: u16) -> Result<Vec<u8>> {
+		match self {
+			ActorAlarmKeyData::V1(data) => serde_bare::to_vec(&data).map_err(Into::into),
 		}
 	}
 }
@@ -1,22 +1,25 @@
 use anyhow::{Ok, Result, bail};
 use versioned_data_util::OwnedVersionedData;
 
-use crate::{PROTOCOL_VERSION, generated::v1};
+use crate::{
+	PROTOCOL_VERSION,
+	generated::{v1, v2},
+};
 
 pub enum Request {
 	V1(v1::Request),
+	V2(v2::Request),
 }
 
 impl OwnedVersionedData for Request {
-	type Latest = v1::Request;
+	type Latest = v2::Request;
 
-	fn latest(latest: v1::Request) -> Self {
-		Request::V1(latest)
+	fn latest(latest: v2::Request) -> Self {
+		Request::V2(latest)
 	}
 
 	fn into_latest(self) -> Result<Self::Latest> {
-		#[allow(irrefutable_let_patterns)]
-		if let Request::V1(data) = self {
+		if let Request::V2(data) = self {
 			Ok(data)
 		} else {
 			bail!("version not latest");
@@ -26,6 +29,7 @@ impl OwnedVersionedData for Request {
 	fn deserialize_version(payload: &[u8], version: u16) -> Result<Self> {
 		match version {
 			1 => Ok(Request::V1(serde_bare::from_slice(payload)?)),
+			2 => Ok(Request::V2(serde_bare::from_slice(payload)?)),
 			_ => bail!("invalid version: {version}"),
 		}
 	}
@@ -33,30 +37,72 @@ impl OwnedVersionedData for Request {
 	fn serialize_version(self, _version: u16) -> Result<Vec<u8>> {
 		match self {
 			Request::V1(data) => serde_bare::to_vec(&data).map_err(Into::into),
+			Request::V2(data) => serde_bare::to_vec(&data).map_err(Into::into),
 		}
 	}
+
+	fn deserialize_converters() -> Vec<impl Fn(Self) -> Result<Self>> {
+		vec![Self::v1_to_v2]
+	}
+
+	fn serialize_converters() -> Vec<impl Fn(Self) -> Result<Self>> {
+		vec![Self::v2_to_v1]
+	}
 }
 
 impl Request {
 	pub fn serialize(self) -> Result<Vec<u8>> {
 		<Self as OwnedVersionedData>::serialize(self, PROTOCOL_VERSION)
 	}
+
+	fn v1_to_v2(self) -> Result<Self> {
+		let Request::V1(data) = self else {
+			bail!("unexpected version");
+		};
+
+		// v2 only appends new union variants, so every v1 value is a valid v2 value
+		Ok(Request::V2(serde_bare::from_slice(&serde_bare::to_vec(
+			&data,
+		)?)?))
+	}
+
+	fn v2_to_v1(self) -> Result<Self> {
+		let Request::V2(data) = self else {
+			bail!("unexpected version");
+		};
+
+		// Requests added in v2 cannot be handled by v1 replicas
+		if matches!(
+			data.kind,
+			v2::RequestKind::GetCommittedPrefixRequest
+				| v2::RequestKind::DownloadSnapshotRequest(_)
+				| v2::RequestKind::KvGetLinearizableRequest(_)
+				| v2::RequestKind::GetReplicaStatusRequest
+				| v2::RequestKind::GetCommandErrorsRequest(_)
+		) {
+			bail!("request not supported by protocol v1");
+		}
+
+		Ok(Request::V1(serde_bare::from_slice(&serde_bare::to_vec(
+			&data,
+		)?)?))
+	}
 }
 
 pub enum Response {
 	V1(v1::Response),
+	V2(v2::Response),
 }
 
 impl OwnedVersionedData for Response {
-	type Latest = v1::Response;
+	type Latest = v2::Response;
 
-	fn latest(latest: v1::Response) -> Self {
-		Response::V1(latest)
+	fn latest(latest: v2::Response) -> Self {
+		Response::V2(latest)
 	}
 
 	fn into_latest(self) -> Result<Self::Latest> {
-		#[allow(irrefutable_let_patterns)]
-		if let Response::V1(data) = self {
+		if let Response::V2(data) = self {
 			Ok(data)
 		} else {
 			bail!("version not latest");
@@ -66,6 +112,7 @@ impl OwnedVersionedData for Response {
 	fn deserialize_version(payload: &[u8], version: u16) -> Result<Self> {
 		match version {
 			1 => Ok(Response::V1(serde_bare::from_slice(payload)?)),
+			2 => Ok(Response::V2(serde_bare::from_slice(payload)?)),
 			_ => bail!("invalid version: {version}"),
 		}
 	}
@@ -73,30 +120,70 @@ impl OwnedVersionedData for Response {
 	fn serialize_version(self, _version: u16) -> Result<Vec<u8>> {
 		match self {
 			Response::V1(data) => serde_bare::to_vec(&data).map_err(Into::into),
+			Response::V2(data) => serde_bare::to_vec(&data).map_err(Into::into),
 		}
 	}
+
+	fn deserialize_converters() -> Vec<impl Fn(Self) -> Result<Self>> {
+		vec![Self::v1_to_v2]
+	}
+
+	fn serialize_converters() -> Vec<impl Fn(Self) -> Result<Self>> {
+		vec![Self::v2_to_v1]
+	}
 }
 
 impl Response {
-	pub fn deserialize(buf: &[u8]) -> Result<v1::Response> {
+	pub fn deserialize(buf: &[u8]) -> Result<v2::Response> {
 		<Self as OwnedVersionedData>::deserialize(buf, PROTOCOL_VERSION)
 	}
+
+	fn v1_to_v2(self) -> Result<Self> {
+		let Response::V1(data) = self else {
+			bail!("unexpected version");
+		};
+
+		Ok(Response::V2(serde_bare::from_slice(&serde_bare::to_vec(
+			&data,
+		)?)?))
+	}
+
+	fn v2_to_v1(self) -> Result<Self> {
+		let Response::V2(data) = self else {
+			bail!("unexpected version");
+		};
+
+		if matches!(
+			data.kind,
+			v2::ResponseKind::GetCommittedPrefixResponse(_)
+				| v2::ResponseKind::DownloadSnapshotResponse(_)
+				| v2::ResponseKind::KvGetLinearizableResponse(_)
+				| v2::ResponseKind::GetReplicaStatusResponse(_)
+				| v2::ResponseKind::GetCommandErrorsResponse(_)
+		) {
+			bail!("response not supported by protocol v1");
+		}
+
+		Ok(Response::V1(serde_bare::from_slice(&serde_bare::to_vec(
+			&data,
+		)?)?))
+	}
 }
 
 pub enum LogEntry {
 	V1(v1::LogEntry),
+	V2(v2::LogEntry),
 }
 
 impl OwnedVersionedData for LogEntry {
-	type Latest = v1::LogEntry;
+	type Latest = v2::LogEntry;
 
-	fn latest(latest: v1::LogEntry) -> Self {
-		LogEntry::V1(latest)
+	fn latest(latest: v2::LogEntry) -> Self {
+		LogEntry::V2(latest)
 	}
 
 	fn into_latest(self) -> Result<Self::Latest> {
-		#[allow(irrefutable_let_patterns)]
-		if let LogEntry::V1(data) = self {
+		if let LogEntry::V2(data) = self {
 			Ok(data)
 		} else {
 			bail!("version not latest");
@@ -106,6 +193,7 @@ impl OwnedVersionedData for LogEntry {
 	fn deserialize_version(payload: &[u8], version: u16) -> Result<Self> {
 		match version {
 			1 => Ok(LogEntry::V1(serde_bare::from_slice(payload)?)),
+			2 => Ok(LogEntry::V2(serde_bare::from_slice(payload)?)),
 			_ => bail!("invalid version: {version}"),
 		}
 	}
@@ -113,8 +201,17 @@ impl OwnedVersionedData for LogEntry {
 	fn serialize_version(self, _version: u16) -> Result<Vec<u8>> {
 		match self {
 			LogEntry::V1(data) => serde_bare::to_vec(&data).map_err(Into::into),
+			LogEntry::V2(data) => serde_bare::to_vec(&data).map_err(Into::into),
 		}
 	}
+
+	fn deserialize_converters() -> Vec<impl Fn(Self) -> Result<Self>> {
+		vec![Self::v1_to_v2]
+	}
+
+	fn serialize_converters() -> Vec<impl Fn(Self) -> Result<Self>> {
+		vec![Self::v2_to_v1]
+	}
 }
 
 impl LogEntry {
@@ -122,25 +219,53 @@ impl LogEntry {
 		<Self as OwnedVersionedData>::serialize(self, PROTOCOL_VERSION)
 	}
 
-	pub fn deserialize(buf: &[u8]) -> Result<v1::LogEntry> {
+	pub fn deserialize(buf: &[u8]) -> Result<v2::LogEntry> {
 		<Self as OwnedVersionedData>::deserialize(buf, PROTOCOL_VERSION)
 	}
+
+	fn v1_to_v2(self) -> Result<Self> {
+		let LogEntry::V1(data) = self else {
+			bail!("unexpected version");
+		};
+
+		Ok(LogEntry::V2(serde_bare::from_slice(&serde_bare::to_vec(
+			&data,
+		)?)?))
+	}
+
+	fn v2_to_v1(self) -> Result<Self> {
+		let LogEntry::V2(data) = self else {
+			bail!("unexpected version");
+		};
+
+		if data
+			.commands
+			.iter()
+			.any(|x| matches!(x.kind, v2::CommandKind::ReadCommand(_)))
+		{
+			bail!("read command not supported by protocol v1");
+		}
+
+		Ok(LogEntry::V1(serde_bare::from_slice(&serde_bare::to_vec(
+			&data,
+		)?)?))
+	}
 }
 
 pub enum ClusterConfig {
 	V1(v1::ClusterConfig),
+	V2(v2::ClusterConfig),
 }
 
 impl OwnedVersionedData for ClusterConfig {
-	type Latest = v1::ClusterConfig;
+	type Latest = v2::ClusterConfig;
 
-	fn latest(latest: v1::ClusterConfig) -> Self {
-		ClusterConfig::V1(latest)
+	fn latest(latest: v2::ClusterConfig) -> Self {
+		ClusterConfig::V2(latest)
 	}
 
 	fn into_latest(self) -> Result<Self::Latest> {
-		#[allow(irrefutable_let_patterns)]
-		if let ClusterConfig::V1(data) = self {
+		if let ClusterConfig::V2(data) = self {
 			Ok(data)
 		} else {
 			bail!("version not latest");
@@ -150,6 +275,7 @@ impl OwnedVersionedData for ClusterConfig {
 	fn deserialize_version(payload: &[u8], version: u16) -> Result<Self> {
 		match version {
 			1 => Ok(ClusterConfig::V1(serde_bare::from_slice(payload)?)),
+			2 => Ok(ClusterConfig::V2(serde_bare::from_slice(payload)?)),
 			_ => bail!("invalid version: {version}"),
 		}
 	}
@@ -157,8 +283,17 @@ impl OwnedVersionedData for ClusterConfig {
 	fn serialize_version(self, _version: u16) -> Result<Vec<u8>> {
 		match self {
 			ClusterConfig::V1(data) => serde_bare::to_vec(&data).map_err(Into::into),
+			ClusterConfig::V2(data) => serde_bare::to_vec(&data).map_err(Into::into),
 		}
 	}
+
+	fn deserialize_converters() -> Vec<impl Fn(Self) -> Result<Self>> {
+		vec![Self::v1_to_v2]
+	}
+
+	fn serialize_converters() -> Vec<impl Fn(Self) -> Result<Self>> {
+		vec![Self::v2_to_v1]
+	}
 }
 
 impl ClusterConfig {
@@ -166,25 +301,45 @@ impl ClusterConfig {
 		<Self as OwnedVersionedData>::serialize(self, PROTOCOL_VERSION)
 	}
 
-	pub fn deserialize(buf: &[u8]) -> Result<v1::ClusterConfig> {
+	pub fn deserialize(buf: &[u8]) -> Result<v2::ClusterConfig> {
 		<Self as OwnedVersionedData>::deserialize(buf, PROTOCOL_VERSION)
 	}
+
+	fn v1_to_v2(self) -> Result<Self> {
+		let ClusterConfig::V1(data) = self else {
+			bail!("unexpected version");
+		};
+
+		Ok(ClusterConfig::V2(serde_bare::from_slice(
+			&serde_bare::to_vec(&data)?,
+		)?))
+	}
+
+	fn v2_to_v1(self) -> Result<Self> {
+		let ClusterConfig::V2(data) = self else {
+			bail!("unexpected version");
+		};
+
+		Ok(ClusterConfig::V1(serde_bare::from_slice(
+			&serde_bare::to_vec(&data)?,
+		)?))
+	}
 }
 
 pub enum Ballot {
 	V1(v1::Ballot),
+	V2(v2::Ballot),
 }
 
 impl OwnedVersionedData for Ballot {
-	type Latest = v1::Ballot;
+	type Latest = v2::Ballot;
 
-	fn latest(latest: v1::Ballot) -> Self {
-		Ballot::V1(latest)
+	fn latest(latest: v2::Ballot) -> Self {
+		Ballot::V2(latest)
 	}
 
 	fn into_latest(self) -> Result<Self::Latest> {
-		#[allow(irrefutable_let_patterns)]
-		if let Ballot::V1(data) = self {
+		if let Ballot::V2(data) = self {
 			Ok(data)
 		} else {
 			bail!("version not latest");
@@ -194,6 +349,7 @@ impl OwnedVersionedData for Ballot {
 	fn deserialize_version(payload: &[u8], version: u16) -> Result<Self> {
 		match version {
 			1 => Ok(Ballot::V1(serde_bare::from_slice(payload)?)),
+			2 => Ok(Ballot::V2(serde_bare::from_slice(payload)?)),
 			_ => bail!("invalid version: {version}"),
 		}
 	}
@@ -201,6 +357,37 @@ impl OwnedVersionedData for Ballot {
 	fn serialize_version(self, _version: u16) -> Result<Vec<u8>> {
 		match self {
 			Ballot::V1(data) => serde_bare::to_vec(&data).map_err(Into::into),
+			Ballot::V2(data) => serde_bare::to_vec(&data).map_err(Into::into),
 		}
 	}
+
+	fn deserialize_converters() -> Vec<impl Fn(Self) -> Result<Self>> {
+		vec![Self::v1_to_v2]
+	}
+
+	fn serialize_converters() -> Vec<impl Fn(Self) -> Result<Self>> {
+		vec![Self::v2_to_v1]
+	}
+}
+
+impl Ballot {
+	fn v1_to_v2(self) -> Result<Self> {
+		let Ballot::V1(data) = self else {
+			bail!("unexpected version");
+		};
+
+		Ok(Ballot::V2(serde_bare::from_slice(&serde_bare::to_vec(
+			&data,
+		)?)?))
+	}
+
+	fn v2_to_v1(self) -> Result<Self> {
+		let Ballot::V2(data) = self else {
+			bail!("unexpected version");
+		};
+
+		Ok(Ballot::V1(serde_bare::from_slice(&serde_bare::to_vec(
+			&data,
+		)?)?))
+	}
 }
@@ -27,6 +27,12 @@ pub enum ToServer {
 		last_command_idx: Option<i64>,
 		prepopulate_actor_names: Option<util::serde::HashableMap<String, ActorName>>,
 		metadata: Option<String>,
+		#[serde(default)]
+		labels: Option<util::serde::HashableMap<String, String>>,
+		/// Protocol version of the runner's connection. Not part of the wire format, set by the
+		/// runner WS after decoding.
+		#[serde(default)]
+		protocol_version: u16,
 	},
 	Events(Vec<EventWrapper>),
 	AckCommands {
@@ -64,7 +70,28 @@ pub enum Command {
 	StopActor {
 		actor_id: Id,
 		generation: u32,
+		#[serde(default)]
+		intent: StopIntent,
 	},
+	/// Only sent to runners that support protocol v2 or later.
+	FireAlarms {
+		actor_id: Id,
+		generation: u32,
+		alarms: Vec<ActorAlarm>,
+	},
+}
+
+/// Why the engine is stopping an actor.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum StopIntent {
+	/// The actor is being destroyed or its runner was lost.
+	#[default]
+	Stop,
+	/// The actor is being put to sleep and can be woken up later.
+	Sleep,
+	/// The actor will be started again on another runner.
+	Migrate,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Hash)]
@@ -76,6 +103,21 @@ pub struct ActorConfig {
 	pub input: Option<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Hash)]
+pub struct ActorAlarm {
+	pub name: String,
+	pub ts: i64,
+	/// Arbitrary user-defined binary data, base64 encoded.
+	pub payload: Option<String>,
+}
+
+/// Identifies a fired alarm by its name and the timestamp it was scheduled for.
+#[derive(Debug, Clone, Serialize, Deserialize, Hash)]
+pub struct ActorAlarmAck {
+	pub name: String,
+	pub ts: i64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Hash)]
 pub struct EventWrapper {
 	pub index: i64,
@@ -101,6 +143,21 @@ pub enum Event {
 		generation: u32,
 		alarm_ts: Option<i64>,
 	},
+	ActorSetNamedAlarm {
+		actor_id: Id,
+		generation: u32,
+		alarm: ActorAlarm,
+	},
+	ActorCancelAlarm {
+		actor_id: Id,
+		generation: u32,
+		name: String,
+	},
+	ActorAckAlarms {
+		actor_id: Id,
+		generation: u32,
+		alarms: Vec<ActorAlarmAck>,
+	},
 }
 
 impl Event {
@@ -110,6 +167,9 @@ impl Event {
 			Event::ActorIntent { actor_id, .. } => *actor_id,
 			Event::ActorStateUpdate { actor_id, .. } => *actor_id,
 			Event::ActorSetAlarm { actor_id, .. } => *actor_id,
+			Event::ActorSetNamedAlarm { actor_id, .. } => *actor_id,
+			Event::ActorCancelAlarm { actor_id, .. } => *actor_id,
+			Event::ActorAckAlarms { actor_id, .. } => *actor_id,
 		}
 	}
 
@@ -119,6 +179,9 @@ impl Event {
 			Event::ActorIntent { generation, .. } => *generation,
 			Event::ActorStateUpdate { generation, .. } => *generation,
 			Event::ActorSetAlarm { generation, .. } => *generation,
+			Event::ActorSetNamedAlarm { generation, .. } => *generation,
+			Event::ActorCancelAlarm { generation, .. } => *generation,
+			Event::ActorAckAlarms { generation, .. } => *generation,
 		}
 	}
 }
@@ -3,22 +3,26 @@ use base64::prelude::*;
 use gas::prelude::*;
 use versioned_data_util::OwnedVersionedData;
 
-use crate::{PROTOCOL_VERSION, generated::v1, protocol};
+use crate::{
+	PROTOCOL_VERSION,
+	generated::{v1, v2},
+	protocol,
+};
 
 pub enum ToClient {
 	V1(v1::ToClient),
+	V2(v2::ToClient),
 }
 
 impl OwnedVersionedData for ToClient {
-	type Latest = v1::ToClient;
+	type Latest = v2::ToClient;
 
-	fn latest(latest: v1::ToClient) -> Self {
-		ToClient::V1(latest)
+	fn latest(latest: v2::ToClient) -> Self {
+		ToClient::V2(latest)
 	}
 
 	fn into_latest(self) -> Result<Self::Latest> {
-		#[allow(irrefutable_let_patterns)]
-		if let ToClient::V1(data) = self {
+		if let ToClient::V2(data) = self {
 			Ok(data)
 		} else {
 			bail!("version not latest");
@@ -28,6 +32,7 @@ impl OwnedVersionedData for ToClient {
 	fn deserialize_version(payload: &[u8], version: u16) -> Result<Self> {
 		match version {
 			1 => Ok(ToClient::V1(serde_bare::from_slice(payload)?)),
+			2 => Ok(ToClient::V2(serde_bare::from_slice(payload)?)),
 			_ => bail!("invalid version: {version}"),
 		}
 	}
@@ -35,26 +40,114 @@ impl OwnedVersionedData for ToClient {
 	fn serialize_version(self, _version: u16) -> Result<Vec<u8>> {
 		match self {
 			ToClient::V1(data) => serde_bare::to_vec(&data).map_err(Into::into),
+			ToClient::V2(data) => serde_bare::to_vec(&data).map_err(Into::into),
 		}
 	}
+
+	fn deserialize_converters() -> Vec<impl Fn(Self) -> Result<Self>> {
+		vec![Self::v1_to_v2]
+	}
+
+	fn serialize_converters() -> Vec<impl Fn(Self) -> Result<Self>> {
+		vec![Self::v2_to_v1]
+	}
 }
 
 impl ToClient {
-	pub fn deserialize(buf: &[u8]) -> Result<v1::ToClient> {
+	pub fn deserialize(buf: &[u8]) -> Result<v2::ToClient> {
 		<Self as OwnedVersionedData>::deserialize(buf, PROTOCOL_VERSION)
 	}
+
+	fn v1_to_v2(self) -> Result<Self> {
+		let ToClient::V1(data) = self else {
+			bail!("unexpected version");
+		};
+
+		match data {
+			v1::ToClient::ToClientCommands(commands) => {
+				let commands = commands
+					.into_iter()
+					.map(|command| {
+						let inner = match command.inner {
+							v1::Command::CommandStartActor(cmd) => v2::Command::CommandStartActor(
+								serde_bare::from_slice(&serde_bare::to_vec(&cmd)?)?,
+							),
+							// Stop intents were added in v2
+							v1::Command::CommandStopActor(cmd) => {
+								v2::Command::CommandStopActor(v2::CommandStopActor {
+									actor_id: cmd.actor_id,
+									generation: cmd.generation,
+									intent: v2::StopIntent::Stop,
+								})
+							}
+						};
+
+						Ok(v2::CommandWrapper {
+							index: command.index,
+							inner,
+						})
+					})
+					.collect::<Result<_>>()?;
+
+				Ok(ToClient::V2(v2::ToClient::ToClientCommands(commands)))
+			}
+			// v2 otherwise only appends new union variants, so other v1 messages are valid v2
+			// messages
+			data => Ok(ToClient::V2(serde_bare::from_slice(&serde_bare::to_vec(
+				&data,
+			)?)?)),
+		}
+	}
+
+	fn v2_to_v1(self) -> Result<Self> {
+		let ToClient::V2(data) = self else {
+			bail!("unexpected version");
+		};
+
+		match data {
+			v2::ToClient::ToClientCommands(commands) => {
+				let mut v1_commands = Vec::with_capacity(commands.len());
+				for command in commands {
+					let inner = match command.inner {
+						v2::Command::CommandStartActor(cmd) => v1::Command::CommandStartActor(
+							serde_bare::from_slice(&serde_bare::to_vec(&cmd)?)?,
+						),
+						// Stop intents are not supported by v1 runners
+						v2::Command::CommandStopActor(cmd) => {
+							v1::Command::CommandStopActor(v1::CommandStopActor {
+								actor_id: cmd.actor_id,
+								generation: cmd.generation,
+							})
+						}
+						// Commands added in v2 are not supported by v1 runners
+						v2::Command::CommandFireAlarms(_) => continue,
+					};
+
+					v1_commands.push(v1::CommandWrapper {
+						index: command.index,
+						inner,
+					});
+				}
+
+				Ok(ToClient::V1(v1::ToClient::ToClientCommands(v1_commands)))
+			}
+			data => Ok(ToClient::V1(serde_bare::from_slice(&serde_bare::to_vec(
+				&data,
+			)?)?)),
+		}
+	}
 }
 
 impl TryFrom<protocol::ToClient> for ToClient {
 	type Error = anyhow::Error;
 
 	fn try_from(value: protocol::ToClient) -> Result<Self> {
-		Ok(ToClient::V1(match value {
+		Ok(ToClient::V2(match value {
 			protocol::ToClient::Init {
 				runner_id,
 				last_event_idx,
 				metadata,
-			} => v1::ToClient::ToClientInit(v1::ToClientInit {
+			} => v2::ToClient::ToClientInit(v2::ToClientInit {
 				runner_id: runner_id.to_string(),
 				last_event_idx,
 				metadata: metadata.try_into()?,
@@ -65,37 +158,37 @@ impl TryFrom<protocol::ToClient> for ToClient {
 					.map(|c| c.try_into())
 					.collect::<Result<_>>()?;
 
-				v1::ToClient::ToClientCommands(commands)
+				v2::ToClient::ToClientCommands(commands)
 			}
 			protocol::ToClient::AckEvents { last_event_idx } => {
-				v1::ToClient::ToClientAckEvents(v1::ToClientAckEvents { last_event_idx })
+				v2::ToClient::ToClientAckEvents(v2::ToClientAckEvents { last_event_idx })
 			}
 		}))
 	}
 }
 
-impl TryFrom<protocol::ProtocolMetadata> for v1::ProtocolMetadata {
+impl TryFrom<protocol::ProtocolMetadata> for v2::ProtocolMetadata {
 	type Error = anyhow::Error;
 
 	fn try_from(value: protocol::ProtocolMetadata) -> Result<Self> {
-		Ok(v1::ProtocolMetadata {
+		Ok(v2::ProtocolMetadata {
 			runner_lost_threshold: value.runner_lost_threshold,
 		})
 	}
 }
 
-impl TryFrom<protocol::CommandWrapper> for v1::CommandWrapper {
+impl TryFrom<protocol::CommandWrapper> for v2::CommandWrapper {
 	type Error = anyhow::Error;
 
 	fn try_from(value: protocol::CommandWrapper) -> Result<Self> {
-		Ok(v1::CommandWrapper {
+		Ok(v2::CommandWrapper {
 			index: value.index,
 			inner: value.inner.try_into()?,
 		})
 	}
 }
 
-impl TryFrom<protocol::Command> for v1::Command {
+impl TryFrom<protocol::Command> for v2::Command {
 	type Error = anyhow::Error;
 
 	fn try_from(value: protocol::Command) -> Result<Self> {
@@ -104,7 +197,7 @@ impl TryFrom<protocol::Command> for v1::Command {
 				actor_id,
 				generation,
 				config,
-			} => Ok(v1::Command::CommandStartActor(v1::CommandStartActor {
+			} => Ok(v2::Command::CommandStartActor(v2::CommandStartActor {
 				actor_id: actor_id.to_string(),
 				generation,
 				config: (*config).try_into()?,
@@ -112,19 +205,58 @@ impl TryFrom<protocol::Command> for v1::Command {
 			protocol::Command::StopActor {
 				actor_id,
 				generation,
-			} => Ok(v1::Command::CommandStopActor(v1::CommandStopActor {
+				intent,
+			} => Ok(v2::Command::CommandStopActor(v2::CommandStopActor {
 				actor_id: actor_id.to_string(),
 				generation,
+				intent: intent.into(),
+			})),
+			protocol::Command::FireAlarms {
+				actor_id,
+				generation,
+				alarms,
+			} => Ok(v2::Command::CommandFireAlarms(v2::CommandFireAlarms {
+				actor_id: actor_id.to_string(),
+				generation,
+				alarms: alarms
+					.into_iter()
+					.map(|x| x.try_into())
+					.collect::<Result<_>>()?,
 			})),
 		}
 	}
 }
 
-impl TryFrom<protocol::ActorConfig> for v1::ActorConfig {
+impl From<protocol::StopIntent> for v2::StopIntent {
+	fn from(value: protocol::StopIntent) -> Self {
+		match value {
+			protocol::StopIntent::Stop => v2::StopIntent::Stop,
+			protocol::StopIntent::Sleep => v2::StopIntent::Sleep,
+			protocol::StopIntent::Migrate => v2::StopIntent::Migrate,
+		}
+	}
+}
+
+impl TryFrom<protocol::ActorAlarm> for v2::ActorAlarm {
+	type Error = anyhow::Error;
+
+	fn try_from(value: protocol::ActorAlarm) -> Result<Self> {
+		Ok(v2::ActorAlarm {
+			name: value.name,
+			ts: value.ts,
+			payload: value
+				.payload
+				.map(|x| BASE64_STANDARD.decode(x))
+				.transpose()?,
+		})
+	}
+}
+
+impl TryFrom<protocol::ActorConfig> for v2::ActorConfig {
 	type Error = anyhow::Error;
 
 	fn try_from(value: protocol::ActorConfig) -> Result<Self> {
-		Ok(v1::ActorConfig {
+		Ok(v2::ActorConfig {
 			name: value.name,
 			key: value.key,
 			create_ts: value.create_ts,
@@ -135,18 +267,18 @@ impl TryFrom<protocol::ActorConfig> for v1::ActorConfig {
 
 pub enum ToServer {
 	V1(v1::ToServer),
+	V2(v2::ToServer),
 }
 
 impl OwnedVersionedData for ToServer {
-	type Latest = v1::ToServer;
+	type Latest = v2::ToServer;
 
-	fn latest(latest: v1::ToServer) -> Self {
-		ToServer::V1(latest)
+	fn latest(latest: v2::ToServer) -> Self {
+		ToServer::V2(latest)
 	}
 
 	fn into_latest(self) -> Result<Self::Latest> {
-		#[allow(irrefutable_let_patterns)]
-		if let ToServer::V1(data) = self {
+		if let ToServer::V2(data) = self {
 			Ok(data)
 		} else {
 			bail!("version not latest");
@@ -156,6 +288,7 @@ impl OwnedVersionedData for ToServer {
 	fn deserialize_version(payload: &[u8], version: u16) -> Result<Self> {
 		match version {
 			1 => Ok(ToServer::V1(serde_bare::from_slice(payload)?)),
+			2 => Ok(ToServer::V2(serde_bare::from_slice(payload)?)),
 			_ => bail!("invalid version: {version}"),
 		}
 	}
@@ -163,28 +296,129 @@ impl OwnedVersionedData for ToServer {
 	fn serialize_version(self, _version: u16) -> Result<Vec<u8>> {
 		match self {
 			ToServer::V1(data) => serde_bare::to_vec(&data).map_err(Into::into),
+			ToServer::V2(data) => serde_bare::to_vec(&data).map_err(Into::into),
 		}
 	}
+
+	fn deserialize_converters() -> Vec<impl Fn(Self) -> Result<Self>> {
+		vec![Self::v1_to_v2]
+	}
+
+	fn serialize_converters() -> Vec<impl Fn(Self) -> Result<Self>> {
+		vec![Self::v2_to_v1]
+	}
 }
 
 impl ToServer {
 	pub fn serialize(self) -> Result<Vec<u8>> {
 		<Self as OwnedVersionedData>::serialize(self, PROTOCOL_VERSION)
 	}
+
+	fn v1_to_v2(self) -> Result<Self> {
+		let ToServer::V1(data) = self else {
+			bail!("unexpected version");
+		};
+
+		match data {
+			// Labels were added to init in v2
+			v1::ToServer::ToServerInit(init) => {
+				Ok(ToServer::V2(v2::ToServer::ToServerInit(v2::ToServerInit {
+					name: init.name,
+					version: init.version,
+					total_slots: init.total_slots,
+					last_command_idx: init.last_command_idx,
+					prepopulate_actor_names: init.prepopulate_actor_names.map(|x| {
+						x.into_iter()
+							.map(|(k, v)| {
+								(
+									k,
+									v2::ActorName {
+										metadata: v.metadata,
+									},
+								)
+							})
+							.collect()
+					}),
+					metadata: init.metadata,
+					labels: None,
+				})))
+			}
+			// v2 otherwise only appends new union variants, so other v1 messages are valid v2
+			// messages
+			data => Ok(ToServer::V2(serde_bare::from_slice(&serde_bare::to_vec(
+				&data,
+			)?)?)),
+		}
+	}
+
+	fn v2_to_v1(self) -> Result<Self> {
+		let ToServer::V2(data) = self else {
+			bail!("unexpected version");
+		};
+
+		let mut data = match data {
+			// Labels are not supported by v1
+			v2::ToServer::ToServerInit(init) => {
+				return Ok(ToServer::V1(v1::ToServer::ToServerInit(v1::ToServerInit {
+					name: init.name,
+					version: init.version,
+					total_slots: init.total_slots,
+					last_command_idx: init.last_command_idx,
+					prepopulate_actor_names: init.prepopulate_actor_names.map(|x| {
+						x.into_iter()
+							.map(|(k, v)| {
+								(
+									k,
+									v1::ActorName {
+										metadata: v.metadata,
+									},
+								)
+							})
+							.collect()
+					}),
+					metadata: init.metadata,
+				})));
+			}
+			data => data,
+		};
+
+		match &mut data {
+			// Events added in v2 are not supported by v1
+			v2::ToServer::ToServerEvents(events) => {
+				events.retain(|e| {
+					!matches!(
+						e.inner,
+						v2::Event::EventActorSetNamedAlarm(_)
+							| v2::Event::EventActorCancelAlarm(_)
+							| v2::Event::EventActorAckAlarms(_)
+					)
+				});
+			}
+			// Logs are best effort, callers are expected to drop them for v1
+			v2::ToServer::ToServerActorLogs(_) => {
+				bail!("actor logs are not supported by protocol v1");
+			}
+			_ => {}
+		}
+
+		Ok(ToServer::V1(serde_bare::from_slice(&serde_bare::to_vec(
+			&data,
+		)?)?))
+	}
 }
 
-impl From<v1::ActorName> for protocol::ActorName {
-	fn from(value: v1::ActorName) -> Self {
+impl From<v2::ActorName> for protocol::ActorName {
+	fn from(value: v2::ActorName) -> Self {
 		protocol::ActorName {
 			metadata: value.metadata,
 		}
 	}
 }
 
-impl TryFrom<v1::EventWrapper> for protocol::EventWrapper {
+impl TryFrom<v2::EventWrapper> for protocol::EventWrapper {
 	type Error = anyhow::Error;
 
-	fn try_from(value: v1::EventWrapper) -> Result<Self> {
+	fn try_from(value: v2::EventWrapper) -> Result<Self> {
 		Ok(protocol::EventWrapper {
 			index: value.index,
 			inner: value.inner.try_into()?,
@@ -192,48 +426,80 @@ impl TryFrom<v1::EventWrapper> for protocol::EventWrapper {
 	}
 }
 
-impl TryFrom<v1::Event> for protocol::Event {
+impl TryFrom<v2::Event> for protocol::Event {
 	type Error = anyhow::Error;
 
-	fn try_from(value: v1::Event) -> Result<Self> {
+	fn try_from(value: v2::Event) -> Result<Self> {
 		match value {
-			v1::Event::EventActorIntent(event) => Ok(protocol::Event::ActorIntent {
+			v2::Event::EventActorIntent(event) => Ok(protocol::Event::ActorIntent {
 				actor_id: util::Id::parse(&event.actor_id)?,
 				generation: event.generation,
 				intent: event.intent.try_into()?,
 			}),
-			v1::Event::EventActorStateUpdate(event) => Ok(protocol::Event::ActorStateUpdate {
+			v2::Event::EventActorStateUpdate(event) => Ok(protocol::Event::ActorStateUpdate {
 				actor_id: util::Id::parse(&event.actor_id)?,
 				generation: event.generation,
 				state: event.state.try_into()?,
 			}),
-			v1::Event::EventActorSetAlarm(event) => Ok(protocol::Event::ActorSetAlarm {
+			v2::Event::EventActorSetAlarm(event) => Ok(protocol::Event::ActorSetAlarm {
 				actor_id: util::Id::parse(&event.actor_id)?,
 				generation: event.generation,
 				alarm_ts: event.alarm_ts,
 			}),
+			v2::Event::EventActorSetNamedAlarm(event) => Ok(protocol::Event::ActorSetNamedAlarm {
+				actor_id: util::Id::parse(&event.actor_id)?,
+				generation: event.generation,
+				alarm: event.alarm.into(),
+			}),
+			v2::Event::EventActorCancelAlarm(event) => Ok(protocol::Event::ActorCancelAlarm {
+				actor_id: util::Id::parse(&event.actor_id)?,
+				generation: event.generation,
+				name: event.name,
+			}),
+			v2::Event::EventActorAckAlarms(event) => Ok(protocol::Event::ActorAckAlarms {
+				actor_id: util::Id::parse(&event.actor_id)?,
+				generation: event.generation,
+				alarms: event
+					.alarms
+					.into_iter()
+					.map(|alarm| protocol::ActorAlarmAck {
+						name: alarm.name,
+						ts: alarm.ts,
+					})
+					.collect(),
+			}),
+		}
+	}
+}
+
+impl From<v2::ActorAlarm> for protocol::ActorAlarm {
+	fn from(value: v2::ActorAlarm) -> Self {
+		protocol::ActorAlarm {
+			name: value.name,
+			ts: value.ts,
+			payload: value.payload.map(|x| BASE64_STANDARD.encode(x)),
 		}
 	}
 }
 
-impl TryFrom<v1::ActorIntent> for protocol::ActorIntent {
+impl TryFrom<v2::ActorIntent> for protocol::ActorIntent {
 	type Error = anyhow::Error;
 
-	fn try_from(value: v1::ActorIntent) -> Result<Self> {
+	fn try_from(value: v2::ActorIntent) -> Result<Self> {
 		match value {
-			v1::ActorIntent::ActorIntentSleep => Ok(protocol::ActorIntent::Sleep),
-			v1::ActorIntent::ActorIntentStop => Ok(protocol::ActorIntent::Stop),
+			v2::ActorIntent::ActorIntentSleep => Ok(protocol::ActorIntent::Sleep),
+			v2::ActorIntent::ActorIntentStop => Ok(protocol::ActorIntent::Stop),
 		}
 	}
 }
 
-impl TryFrom<v1::ActorState> for protocol::ActorState {
+impl TryFrom<v2::ActorState> for protocol::ActorState {
 	type Error = anyhow::Error;
 
-	fn try_from(value: v1::ActorState) -> Result<Self> {
+	fn try_from(value: v2::ActorState) -> Result<Self> {
 		match value {
-			v1::ActorState::ActorStateRunning => Ok(protocol::ActorState::Running),
-			v1::ActorState::ActorStateStopped(stopped) => Ok(protocol::ActorState::Stopped {
+			v2::ActorState::ActorStateRunning => Ok(protocol::ActorState::Running),
+			v2::ActorState::ActorStateStopped(stopped) => Ok(protocol::ActorState::Stopped {
 				code: stopped.code.try_into()?,
 				message: stopped.message,
 			}),
@@ -241,23 +507,23 @@ impl TryFrom<v1::ActorState> for protocol::ActorState {
 	}
 }
 
-impl TryFrom<v1::StopCode> for protocol::StopCode {
+impl TryFrom<v2::StopCode> for protocol::StopCode {
 	type Error = anyhow::Error;
 
-	fn try_from(value: v1::StopCode) -> Result<Self> {
+	fn try_from(value: v2::StopCode) -> Result<Self> {
 		match value {
-			v1::StopCode::Ok => Ok(protocol::StopCode::Ok),
-			v1::StopCode::Error => Ok(protocol::StopCode::Error),
+			v2::StopCode::Ok => Ok(protocol::StopCode::Ok),
+			v2::StopCode::Error => Ok(protocol::StopCode::Error),
 		}
 	}
 }
 
-impl TryFrom<v1::ToServer> for protocol::ToServer {
+impl TryFrom<v2::ToServer> for protocol::ToServer {
 	type Error = anyhow::Error;
 
-	fn try_from(value: v1::ToServer) -> Result<Self> {
+	fn try_from(value: v2::ToServer) -> Result<Self> {
 		match value {
-			v1::ToServer::ToServerInit(init) => Ok(protocol::ToServer::Init {
+			v2::ToServer::ToServerInit(init) => Ok(protocol::ToServer::Init {
 				name: init.name,
 				version: init.version,
 				total_slots: init.total_slots,
@@ -266,25 +532,31 @@ impl TryFrom<v1::ToServer> for protocol::ToServer {
 					.prepopulate_actor_names
 					.map(|x| x.into_iter().map(|(k, v)| (k, v.into())).collect()),
 				metadata: init.metadata,
+				labels: init.labels,
+				protocol_version: 0,
 			}),
-			v1::ToServer::ToServerEvents(events) => Ok(protocol::ToServer::Events(
+			v2::ToServer::ToServerEvents(events) => Ok(protocol::ToServer::Events(
 				events
 					.into_iter()
 					.map(|e| e.try_into())
 					.collect::<Result<_>>()?,
 			)),
-			v1::ToServer::ToServerAckCommands(ack) => Ok(protocol::ToServer::AckCommands {
+			v2::ToServer::ToServerAckCommands(ack) => Ok(protocol::ToServer::AckCommands {
 				last_command_idx: ack.last_command_idx,
 			}),
-			v1::ToServer::ToServerStopping => Ok(protocol::ToServer::Stopping),
-			v1::ToServer::ToServerPing(_) => {
+			v2::ToServer::ToServerStopping => Ok(protocol::ToServer::Stopping),
+			v2::ToServer::ToServerPing(_) => {
 				// NOTE: Ping is handled at the websocket level and never reaches the workflow.
 				bail!("Ping variant should not be converted")
 			}
-			v1::ToServer::ToServerKvRequest(_) => {
+			v2::ToServer::ToServerKvRequest(_) => {
 				// NOTE: KV is handled at the websocket level and never reaches the workflow.
 				bail!("KV variant should not be converted")
 			}
+			v2::ToServer::ToServerActorLogs(_) => {
+				// NOTE: Logs are handled at the websocket level and never reach the workflow.
+				bail!("ActorLogs variant should not be converted")
+			}
 		}
 	}
 }
@@ -0,0 +1,236 @@
+use anyhow::*;
+use async_trait::async_trait;
+use rivet_runner_protocol as rp;
+
+use crate::{
+	Runner, TunnelWebSocket,
+	http::{Request, Response},
+	kv::KvListOptions,
+};
+
+#[derive(Debug, Clone)]
+pub struct ActorConfig {
+	pub name: String,
+	pub key: Option<String>,
+	pub create_ts: i64,
+	pub input: Option<Vec<u8>>,
+}
+
+impl From<rp::ActorConfig> for ActorConfig {
+	fn from(value: rp::ActorConfig) -> Self {
+		ActorConfig {
+			name: value.name,
+			key: value.key,
+			create_ts: value.create_ts,
+			input: value.input,
+		}
+	}
+}
+
+/// Named alarm persisted by the engine.
+#[derive(Debug, Clone)]
+pub struct ActorAlarm {
+	pub name: String,
+	pub ts: i64,
+	pub payload: Option<Vec<u8>>,
+}
+
+impl From<rp::ActorAlarm> for ActorAlarm {
+	fn from(value: rp::ActorAlarm) -> Self {
+		ActorAlarm {
+			name: value.name,
+			ts: value.ts,
+			payload: value.payload,
+		}
+	}
+}
+
+/// Handle to an actor running on this runner.
+#[derive(Clone)]
+pub struct Actor {
+	pub actor_id: String,
+	pub generation: u32,
+	pub config: ActorConfig,
+	runner: Runner,
+}
+
+impl Actor {
+	pub(crate) fn new(
+		actor_id: String,
+		generation: u32,
+		config: ActorConfig,
+		runner: Runner,
+	) -> Self {
+		Actor {
+			actor_id,
+			generation,
+			config,
+			runner,
+		}
+	}
+
+	pub fn runner(&self) -> &Runner {
+		&self.runner
+	}
+
+	/// Asks the engine to put this actor to sleep. The engine sends a stop command once it is safe
+	/// to stop.
+	pub fn sleep(&self) {
+		self.runner.send_actor_intent(
+			&self.actor_id,
+			self.generation,
+			rp::ActorIntent::ActorIntentSleep,
+		);
+	}
+
+	/// Asks the engine to stop this actor.
+	pub fn stop(&self) {
+		self.runner.send_actor_intent(
+			&self.actor_id,
+			self.generation,
+			rp::ActorIntent::ActorIntentStop,
+		);
+	}
+
+	/// Sets the alarm that wakes this actor. `None` clears the alarm.
+	pub fn set_alarm(&self, alarm_ts: Option<i64>) {
+		self.runner
+			.send_event(rp::Event::EventActorSetAlarm(rp::EventActorSetAlarm {
+				actor_id: self.actor_id.clone(),
+				generation: self.generation,
+				alarm_ts,
+			}));
+	}
+
+	/// Sets a named alarm, replacing any alarm with the same name. Unlike `set_alarm`, named alarms
+	/// also fire while the actor is running and are delivered to `ActorHandler::on_alarms_fired`.
+	pub fn set_named_alarm(&self, name: impl Into<String>, ts: i64, payload: Option<Vec<u8>>) {
+		self.runner.send_event(rp::Event::EventActorSetNamedAlarm(
+			rp::EventActorSetNamedAlarm {
+				actor_id: self.actor_id.clone(),
+				generation: self.generation,
+				alarm: rp::ActorAlarm {
+					name: name.into(),
+					ts,
+					payload,
+				},
+			},
+		));
+	}
+
+	/// Cancels the named alarm. Does nothing if the alarm does not exist.
+	pub fn cancel_alarm(&self, name: impl Into<String>) {
+		self.runner.send_event(rp::Event::EventActorCancelAlarm(
+			rp::EventActorCancelAlarm {
+				actor_id: self.actor_id.clone(),
+				generation: self.generation,
+				name: name.into(),
+			},
+		));
+	}
+
+	/// Ships a log line to the engine. Lines are dropped while the runner is disconnected.
+	pub fn log(&self, stream: rp::ActorLogStream, message: impl Into<String>) {
+		self.runner.send_actor_logs(
+			&self.actor_id,
+			self.generation,
+			vec![rp::ActorLogEntry {
+				stream,
+				ts: rivet_util::timestamp::now(),
+				message: message.into(),
+			}],
+		);
+	}
+
+	/// Returns values in the same order as the given keys.
+	pub async fn kv_get(&self, keys: Vec<Vec<u8>>) -> Result<Vec<Option<Vec<u8>>>> {
+		self.runner.kv_get(&self.actor_id, keys).await
+	}
+
+	pub async fn kv_list_all(&self, opts: KvListOptions) -> Result<Vec<(Vec<u8>, Vec<u8>)>> {
+		self.runner
+			.kv_list(&self.actor_id, rp::KvListQuery::KvListAllQuery, opts)
+			.await
+	}
+
+	pub async fn kv_list_range(
+		&self,
+		start: Vec<u8>,
+		end: Vec<u8>,
+		exclusive: bool,
+		opts: KvListOptions,
+	) -> Result<Vec<(Vec<u8>, Vec<u8>)>> {
+		self.runner
+			.kv_list(
+				&self.actor_id,
+				rp::KvListQuery::KvListRangeQuery(rp::KvListRangeQuery {
+					start,
+					end,
+					exclusive,
+				}),
+				opts,
+			)
+			.await
+	}
+
+	pub async fn kv_list_prefix(
+		&self,
+		prefix: Vec<u8>,
+		opts: KvListOptions,
+	) -> Result<Vec<(Vec<u8>, Vec<u8>)>> {
+		self.runner
+			.kv_list(
+				&self.actor_id,
+				rp::KvListQuery::KvListPrefixQuery(rp::KvListPrefixQuery { key: prefix }),
+				opts,
+			)
+			.await
+	}
+
+	pub async fn kv_put(&self, entries: Vec<(Vec<u8>, Vec<u8>)>) -> Result<()> {
+		self.runner.kv_put(&self.actor_id, entries).await
+	}
+
+	pub async fn kv_delete(&self, keys: Vec<Vec<u8>>) -> Result<()> {
+		self.runner.kv_delete(&self.actor_id, keys).await
+	}
+
+	/// Deletes all keys of this actor.
+	pub async fn kv_drop(&self) -> Result<()> {
+		self.runner.kv_drop(&self.actor_id).await
+	}
+}
+
+/// Implemented by services hosting actors on a runner.
+#[async_trait]
+pub trait ActorHandler: Send + Sync + 'static {
+	/// Called when the engine starts an actor on this runner. The engine is notified that the actor
+	/// is running once this returns. If this fails, the actor is reported as crashed without calling
+	/// `on_actor_stop`.
+	async fn on_actor_start(&self, actor: Actor) -> Result<()>;
+
+	/// Called when the actor is stopped, either by the engine or because the runner lost its
+	/// connection for longer than the runner lost threshold. If this fails, the actor is reported as
+	/// crashed.
+	async fn on_actor_stop(&self, actor: Actor) -> Result<()>;
+
+	/// Called when named alarms of the actor fire. Alarms are delivered at least once: they are
+	/// removed from the engine once this returns successfully, otherwise they are delivered again
+	/// the next time the actor is started. Sleeping actors are started before their alarms are
+	/// delivered.
+	async fn on_alarms_fired(&self, actor: Actor, alarms: Vec<ActorAlarm>) -> Result<()> {
+		let _ = (actor, alarms);
+
+		Ok(())
+	}
+
+	/// Handles an HTTP request tunneled to the actor. Errors are returned to the client as a 500.
+	async fn fetch(&self, actor: Actor, req: Request<Vec<u8>>) -> Result<Response<Vec<u8>>>;
+
+	/// Handles a WebSocket tunneled to the actor. The socket is closed once this returns.
+	async fn websocket(&self, actor: Actor, req: Request<()>, ws: TunnelWebSocket) -> Result<()> {
+		let _ = (actor, req, ws);
+
+		bail!("websockets not implemented")
+	}
+}
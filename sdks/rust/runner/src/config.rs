@@ -0,0 +1,55 @@
+use std::collections::HashMap;
+
+#[derive(Debug, Clone)]
+pub struct RunnerConfig {
+	/// Engine endpoint, e.g. `http://127.0.0.1:6420`.
+	pub endpoint: String,
+	/// Overrides `endpoint` for the runner websocket.
+	pub pegboard_endpoint: Option<String>,
+	/// Overrides `pegboard_endpoint` for the tunnel websocket.
+	pub pegboard_relay_endpoint: Option<String>,
+	pub namespace: String,
+	pub runner_name: String,
+	/// Identifies this runner across reconnects.
+	pub runner_key: String,
+	pub version: u32,
+	pub total_slots: u32,
+	/// Actor names and their metadata to register on connect.
+	pub prepopulate_actor_names: HashMap<String, serde_json::Value>,
+	pub metadata: Option<serde_json::Value>,
+	/// Labels matched against actor placement constraints.
+	pub labels: HashMap<String, String>,
+}
+
+impl RunnerConfig {
+	pub fn new(
+		endpoint: impl Into<String>,
+		namespace: impl Into<String>,
+		runner_name: impl Into<String>,
+		runner_key: impl Into<String>,
+	) -> Self {
+		RunnerConfig {
+			endpoint: endpoint.into(),
+			pegboard_endpoint: None,
+			pegboard_relay_endpoint: None,
+			namespace: namespace.into(),
+			runner_name: runner_name.into(),
+			runner_key: runner_key.into(),
+			version: 1,
+			total_slots: 100,
+			prepopulate_actor_names: HashMap::new(),
+			metadata: None,
+			labels: HashMap::new(),
+		}
+	}
+
+	pub(crate) fn pegboard_endpoint(&self) -> &str {
+		self.pegboard_endpoint.as_deref().unwrap_or(&self.endpoint)
+	}
+
+	pub(crate) fn pegboard_relay_endpoint(&self) -> &str {
+		self.pegboard_relay_endpoint
+			.as_deref()
+			.unwrap_or_else(|| self.pegboard_endpoint())
+	}
+}
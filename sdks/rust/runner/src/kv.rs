@@ -0,0 +1,238 @@
+use std::{collections::HashMap, time::Duration};
+
+use anyhow::*;
+use rivet_runner_protocol as rp;
+use tokio::sync::oneshot;
+
+use crate::Runner;
+
+/// How long to wait for a KV response before failing the request.
+const KV_EXPIRE: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Default)]
+pub struct KvListOptions {
+	pub reverse: Option<bool>,
+	pub limit: Option<u64>,
+}
+
+#[derive(Default)]
+pub(crate) struct KvState {
+	next_request_id: u32,
+	requests: HashMap<u32, KvRequest>,
+}
+
+struct KvRequest {
+	actor_id: String,
+	data: rp::KvRequestData,
+	/// Whether the request was sent on the current connection.
+	sent: bool,
+	tx: oneshot::Sender<Result<rp::KvResponseData>>,
+}
+
+impl KvState {
+	/// Returns requests queued while disconnected and marks them as sent.
+	pub(crate) fn take_unsent(&mut self) -> Vec<rp::ToServer> {
+		self.requests
+			.iter_mut()
+			.filter(|(_, req)| !req.sent)
+			.map(|(request_id, req)| {
+				req.sent = true;
+
+				rp::ToServer::ToServerKvRequest(rp::ToServerKvRequest {
+					actor_id: req.actor_id.clone(),
+					request_id: *request_id,
+					data: req.data.clone(),
+				})
+			})
+			.collect()
+	}
+
+	/// Fails requests that were sent on a connection that closed, since the response is lost.
+	pub(crate) fn fail_sent(&mut self, reason: &str) {
+		let request_ids = self
+			.requests
+			.iter()
+			.filter(|(_, req)| req.sent)
+			.map(|(request_id, _)| *request_id)
+			.collect::<Vec<_>>();
+
+		for request_id in request_ids {
+			if let Some(req) = self.requests.remove(&request_id) {
+				let _ = req.tx.send(Err(anyhow!("{reason}")));
+			}
+		}
+	}
+
+	pub(crate) fn fail_all(&mut self, reason: &str) {
+		for (_, req) in self.requests.drain() {
+			let _ = req.tx.send(Err(anyhow!("{reason}")));
+		}
+	}
+}
+
+impl Runner {
+	pub(crate) async fn kv_get(
+		&self,
+		actor_id: &str,
+		keys: Vec<Vec<u8>>,
+	) -> Result<Vec<Option<Vec<u8>>>> {
+		let res = self
+			.send_kv_request(
+				actor_id,
+				rp::KvRequestData::KvGetRequest(rp::KvGetRequest { keys: keys.clone() }),
+			)
+			.await?;
+
+		let rp::KvResponseData::KvGetResponse(res) = res else {
+			bail!("unexpected kv response: {res:?}");
+		};
+
+		// The engine omits missing keys, map the found values back to the requested order
+		let mut found = res
+			.keys
+			.into_iter()
+			.zip(res.values)
+			.collect::<HashMap<_, _>>();
+
+		Ok(keys.iter().map(|key| found.remove(key)).collect())
+	}
+
+	pub(crate) async fn kv_list(
+		&self,
+		actor_id: &str,
+		query: rp::KvListQuery,
+		opts: KvListOptions,
+	) -> Result<Vec<(Vec<u8>, Vec<u8>)>> {
+		let res = self
+			.send_kv_request(
+				actor_id,
+				rp::KvRequestData::KvListRequest(rp::KvListRequest {
+					query,
+					reverse: opts.reverse,
+					limit: opts.limit,
+				}),
+			)
+			.await?;
+
+		let rp::KvResponseData::KvListResponse(res) = res else {
+			bail!("unexpected kv response: {res:?}");
+		};
+
+		Ok(res.keys.into_iter().zip(res.values).collect())
+	}
+
+	pub(crate) async fn kv_put(
+		&self,
+		actor_id: &str,
+		entries: Vec<(Vec<u8>, Vec<u8>)>,
+	) -> Result<()> {
+		let (keys, values) = entries.into_iter().unzip();
+		let res = self
+			.send_kv_request(
+				actor_id,
+				rp::KvRequestData::KvPutRequest(rp::KvPutRequest { keys, values }),
+			)
+			.await?;
+
+		ensure!(
+			matches!(res, rp::KvResponseData::KvPutResponse),
+			"unexpected kv response: {res:?}"
+		);
+
+		Ok(())
+	}
+
+	pub(crate) async fn kv_delete(&self, actor_id: &str, keys: Vec<Vec<u8>>) -> Result<()> {
+		let res = self
+			.send_kv_request(
+				actor_id,
+				rp::KvRequestData::KvDeleteRequest(rp::KvDeleteRequest { keys }),
+			)
+			.await?;
+
+		ensure!(
+			matches!(res, rp::KvResponseData::KvDeleteResponse),
+			"unexpected kv response: {res:?}"
+		);
+
+		Ok(())
+	}
+
+	pub(crate) async fn kv_drop(&self, actor_id: &str) -> Result<()> {
+		let res = self
+			.send_kv_request(actor_id, rp::KvRequestData::KvDropRequest)
+			.await?;
+
+		ensure!(
+			matches!(res, rp::KvResponseData::KvDropResponse),
+			"unexpected kv response: {res:?}"
+		);
+
+		Ok(())
+	}
+
+	pub(crate) fn handle_kv_response(&self, res: rp::ToClientKvResponse) {
+		let Some(req) = self.state().kv.requests.remove(&res.request_id) else {
+			tracing::warn!(
+				request_id = res.request_id,
+				"received kv response for unknown request"
+			);
+			return;
+		};
+
+		let data = match res.data {
+			rp::KvResponseData::KvErrorResponse(err) => Err(anyhow!("kv error: {}", err.message)),
+			data => Ok(data),
+		};
+
+		let _ = req.tx.send(data);
+	}
+
+	/// Sends a KV request, queueing it until reconnect if the runner is disconnected.
+	async fn send_kv_request(
+		&self,
+		actor_id: &str,
+		data: rp::KvRequestData,
+	) -> Result<rp::KvResponseData> {
+		ensure!(!self.is_shutdown(), "runner is shut down");
+
+		let (tx, rx) = oneshot::channel();
+
+		let request_id = {
+			let mut state = self.state();
+
+			let request_id = state.kv.next_request_id;
+			state.kv.next_request_id = request_id.wrapping_add(1);
+
+			let sent = state.is_connected();
+			if sent {
+				state.send(rp::ToServer::ToServerKvRequest(rp::ToServerKvRequest {
+					actor_id: actor_id.to_string(),
+					request_id,
+					data: data.clone(),
+				}));
+			}
+
+			state.kv.requests.insert(
+				request_id,
+				KvRequest {
+					actor_id: actor_id.to_string(),
+					data,
+					sent,
+					tx,
+				},
+			);
+
+			request_id
+		};
+
+		match tokio::time::timeout(KV_EXPIRE, rx).await {
+			Result::Ok(Result::Ok(res)) => res,
+			Result::Ok(Err(_)) => bail!("kv request dropped"),
+			Err(_) => {
+				self.state().kv.requests.remove(&request_id);
+				bail!("kv request timed out")
+			}
+		}
+	}
+}
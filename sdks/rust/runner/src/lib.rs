@@ -0,0 +1,24 @@
+//! Native runner for hosting Rivet actors from Rust.
+//!
+//! Connects to the engine over the runner websocket (`x-rivet-target: runner-ws`) and the tunnel
+//! websocket (`x-rivet-target: tunnel`). Lifecycle commands, events, KV requests and tunneled
+//! HTTP/WebSocket traffic are forwarded to an [`ActorHandler`].
+
+mod actor;
+mod config;
+mod kv;
+mod runner;
+mod tunnel;
+mod utils;
+mod websocket;
+
+pub use actor::{Actor, ActorAlarm, ActorConfig, ActorHandler};
+pub use config::RunnerConfig;
+pub use kv::KvListOptions;
+pub use runner::Runner;
+pub use websocket::{TunnelWebSocket, WebSocketMessage};
+
+// Re-export so implementors use the same `http` types as the runner
+pub use tokio_tungstenite::tungstenite::http;
+
+pub const PROTOCOL_VERSION: u16 = rivet_runner_protocol::PROTOCOL_VERSION;
@@ -0,0 +1,682 @@
+use std::{
+	collections::{HashMap, VecDeque},
+	sync::{Arc, Mutex, MutexGuard},
+	time::Duration,
+};
+
+use anyhow::*;
+use futures_util::{SinkExt, StreamExt};
+use rivet_runner_protocol::{self as rp, versioned};
+use rivet_util::serde::HashableMap;
+use tokio::sync::{mpsc, watch};
+use tokio_tungstenite::tungstenite::{
+	Message,
+	protocol::{CloseFrame, frame::coding::CloseCode},
+};
+use versioned_data_util::OwnedVersionedData;
+
+use crate::{Actor, ActorConfig, ActorHandler, RunnerConfig, kv::KvState, tunnel::Tunnel, utils};
+
+const PING_INTERVAL: Duration = Duration::from_secs(1);
+/// How often to acknowledge received commands so the engine can prune them.
+const ACK_COMMANDS_INTERVAL: Duration = Duration::from_secs(5 * 60);
+/// How often to check if all actors stopped during a graceful shutdown.
+const SHUTDOWN_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Handle to a runner connected to the engine. Cheap to clone.
+#[derive(Clone)]
+pub struct Runner(Arc<RunnerInner>);
+
+struct RunnerInner {
+	config: RunnerConfig,
+	handler: Arc<dyn ActorHandler>,
+	tunnel: Tunnel,
+	state: Mutex<RunnerState>,
+	/// Whether the engine acknowledged the init packet on the current connection.
+	connected: watch::Sender<bool>,
+	shutdown: watch::Sender<bool>,
+}
+
+pub(crate) struct RunnerState {
+	runner_id: Option<String>,
+	actors: HashMap<String, ActorEntry>,
+	/// Outgoing messages for the current runner websocket, if connected.
+	sender: Option<mpsc::UnboundedSender<Message>>,
+	/// Incremented on every connection. Used to detect reconnects while the runner lost timer is
+	/// running.
+	connection_idx: u64,
+	last_command_idx: Option<i64>,
+	next_event_idx: i64,
+	/// Events not yet acknowledged by the engine. Resent on reconnect.
+	event_history: VecDeque<rp::EventWrapper>,
+	/// How long the engine waits for a disconnected runner before rescheduling its actors.
+	runner_lost_threshold: Option<Duration>,
+	pub(crate) kv: KvState,
+}
+
+impl RunnerState {
+	pub(crate) fn is_connected(&self) -> bool {
+		self.sender.is_some()
+	}
+
+	/// Sends a message on the current connection. Messages are dropped if disconnected.
+	pub(crate) fn send(&self, msg: rp::ToServer) {
+		let Some(sender) = &self.sender else {
+			tracing::debug!("runner websocket not connected, dropping message");
+			return;
+		};
+
+		match versioned::ToServer::latest(msg).serialize() {
+			Result::Ok(buf) => {
+				if sender.send(Message::Binary(buf.into())).is_err() {
+					tracing::debug!("runner websocket closed, dropping message");
+				}
+			}
+			Err(err) => {
+				tracing::error!(?err, "failed to serialize runner message");
+			}
+		}
+	}
+}
+
+struct ActorEntry {
+	generation: u32,
+	config: ActorConfig,
+}
+
+impl Runner {
+	/// Connects the tunnel and starts the runner connection in the background. Use
+	/// `wait_connected` to wait for the engine to register the runner.
+	pub async fn start<H: ActorHandler>(config: RunnerConfig, handler: H) -> Result<Runner> {
+		let pegboard_url = utils::build_url(config.pegboard_endpoint(), &config)?;
+		let tunnel_url = utils::build_url(config.pegboard_relay_endpoint(), &config)?;
+
+		let runner = Runner(Arc::new(RunnerInner {
+			config,
+			handler: Arc::new(handler),
+			tunnel: Tunnel::new(tunnel_url),
+			state: Mutex::new(RunnerState {
+				runner_id: None,
+				actors: HashMap::new(),
+				sender: None,
+				connection_idx: 0,
+				last_command_idx: None,
+				next_event_idx: 0,
+				event_history: VecDeque::new(),
+				runner_lost_threshold: None,
+				kv: KvState::default(),
+			}),
+			connected: watch::Sender::new(false),
+			shutdown: watch::Sender::new(false),
+		}));
+
+		tracing::info!("starting runner");
+
+		// Connect the tunnel first so the runner does not appear ready before it can accept
+		// requests
+		runner.0.tunnel.start(runner.clone()).await?;
+
+		tokio::spawn(runner.clone().pegboard_loop(pegboard_url));
+
+		Ok(runner)
+	}
+
+	/// Waits until the engine acknowledged the runner.
+	pub async fn wait_connected(&self) -> Result<()> {
+		let mut connected_rx = self.0.connected.subscribe();
+		connected_rx
+			.wait_for(|x| *x)
+			.await
+			.context("runner dropped")?;
+
+		Ok(())
+	}
+
+	pub fn config(&self) -> &RunnerConfig {
+		&self.0.config
+	}
+
+	/// Id assigned by the engine. Set once connected.
+	pub fn runner_id(&self) -> Option<String> {
+		self.state().runner_id.clone()
+	}
+
+	pub fn has_actor(&self, actor_id: &str, generation: Option<u32>) -> bool {
+		self.state()
+			.actors
+			.get(actor_id)
+			.map(|x| generation.is_none_or(|g| g == x.generation))
+			.unwrap_or(false)
+	}
+
+	pub fn actor(&self, actor_id: &str) -> Option<Actor> {
+		let state = self.state();
+		let entry = state.actors.get(actor_id)?;
+
+		Some(Actor::new(
+			actor_id.to_string(),
+			entry.generation,
+			entry.config.clone(),
+			self.clone(),
+		))
+	}
+
+	/// Stops an actor locally and notifies the engine.
+	pub async fn stop_actor(&self, actor_id: &str, generation: Option<u32>) {
+		let actor = {
+			let mut state = self.state();
+			match state.actors.get(actor_id) {
+				Some(entry) if generation.is_none_or(|g| g == entry.generation) => {}
+				Some(_) => {
+					tracing::warn!(%actor_id, ?generation, "actor generation mismatch");
+					return;
+				}
+				None => {
+					tracing::warn!(%actor_id, "actor not found");
+					return;
+				}
+			}
+
+			let entry = state.actors.remove(actor_id).expect("checked above");
+			Actor::new(
+				actor_id.to_string(),
+				entry.generation,
+				entry.config,
+				self.clone(),
+			)
+		};
+
+		// Close in-flight requests and websockets
+		self.0.tunnel.unregister_actor(actor_id);
+
+		let res = self.0.handler.on_actor_stop(actor.clone()).await;
+		if let Err(err) = &res {
+			tracing::error!(%actor_id, ?err, "error in on_actor_stop");
+		}
+
+		self.send_actor_stopped(&actor.actor_id, actor.generation, res);
+	}
+
+	/// Shuts down the runner.
+	///
+	/// If `immediate` is false, the engine is notified that the runner is stopping and this waits
+	/// for the engine to stop all actors before closing the connection.
+	pub async fn shutdown(&self, immediate: bool) {
+		tracing::info!(?immediate, "shutting down runner");
+		self.0.shutdown.send_replace(true);
+
+		if !immediate && self.state().is_connected() {
+			self.state().send(rp::ToServer::ToServerStopping);
+
+			loop {
+				{
+					let state = self.state();
+					if state.actors.is_empty() || !state.is_connected() {
+						break;
+					}
+				}
+
+				tokio::time::sleep(SHUTDOWN_POLL_INTERVAL).await;
+			}
+		}
+
+		{
+			let mut state = self.state();
+			state.kv.fail_all("runner shut down");
+
+			if let Some(sender) = state.sender.take() {
+				let _ = sender.send(Message::Close(Some(CloseFrame {
+					code: CloseCode::Normal,
+					reason: "Stopping".into(),
+				})));
+			}
+		}
+
+		self.0.tunnel.shutdown();
+	}
+
+	pub(crate) fn handler(&self) -> &Arc<dyn ActorHandler> {
+		&self.0.handler
+	}
+
+	pub(crate) fn state(&self) -> MutexGuard<'_, RunnerState> {
+		self.0.state.lock().expect("poisoned")
+	}
+
+	pub(crate) fn is_shutdown(&self) -> bool {
+		*self.0.shutdown.borrow()
+	}
+
+	pub(crate) fn send_actor_intent(
+		&self,
+		actor_id: &str,
+		generation: u32,
+		intent: rp::ActorIntent,
+	) {
+		self.send_event(rp::Event::EventActorIntent(rp::EventActorIntent {
+			actor_id: actor_id.to_string(),
+			generation,
+			intent,
+		}));
+	}
+
+	/// Logs are not events since they are best effort and are not resent after a reconnect.
+	pub(crate) fn send_actor_logs(
+		&self,
+		actor_id: &str,
+		generation: u32,
+		entries: Vec<rp::ActorLogEntry>,
+	) {
+		self.state()
+			.send(rp::ToServer::ToServerActorLogs(rp::ToServerActorLogs {
+				actor_id: actor_id.to_string(),
+				generation,
+				entries,
+			}));
+	}
+
+	/// Notifies the engine that the actor stopped. The actor is reported as crashed if `res` is an
+	/// error.
+	fn send_actor_stopped(&self, actor_id: &str, generation: u32, res: Result<()>) {
+		let (code, message) = match res {
+			Result::Ok(()) => (rp::StopCode::Ok, None),
+			Err(err) => (rp::StopCode::Error, Some(format!("{err:#}"))),
+		};
+
+		self.send_event(rp::Event::EventActorStateUpdate(
+			rp::EventActorStateUpdate {
+				actor_id: actor_id.to_string(),
+				generation,
+				state: rp::ActorState::ActorStateStopped(rp::ActorStateStopped { code, message }),
+			},
+		));
+	}
+
+	/// Assigns the next event index and sends the event. Events are kept until acknowledged so they
+	/// can be resent after a reconnect.
+	pub(crate) fn send_event(&self, inner: rp::Event) {
+		let mut state = self.state();
+
+		let event = rp::EventWrapper {
+			index: state.next_event_idx,
+			inner,
+		};
+		state.next_event_idx += 1;
+		state.event_history.push_back(event.clone());
+
+		tracing::debug!(index = event.index, "sending event");
+		state.send(rp::ToServer::ToServerEvents(vec![event]));
+	}
+
+	// MARK: Connection
+	async fn pegboard_loop(self, url: String) {
+		let mut shutdown_rx = self.0.shutdown.subscribe();
+		let mut attempt = 0;
+
+		loop {
+			match self.run_connection(&url, &mut attempt).await {
+				Result::Ok(()) => tracing::info!("runner websocket closed"),
+				Err(err) => tracing::warn!(?err, "runner websocket failed"),
+			}
+
+			self.on_disconnect();
+
+			if self.is_shutdown() {
+				break;
+			}
+
+			let delay = utils::reconnect_delay(attempt);
+			attempt += 1;
+			tracing::info!(?delay, "reconnecting runner websocket");
+
+			tokio::select! {
+				_ = tokio::time::sleep(delay) => {}
+				_ = shutdown_rx.wait_for(|x| *x) => break,
+			}
+		}
+	}
+
+	async fn run_connection(&self, url: &str, attempt: &mut usize) -> Result<()> {
+		let ws = utils::connect(url, "runner-ws").await?;
+		let (mut ws_tx, mut ws_rx) = ws.split();
+		let (tx, mut rx) = mpsc::unbounded_channel();
+
+		{
+			let mut state = self.state();
+			state.connection_idx += 1;
+			state.sender = Some(tx);
+
+			// Init has to be the first message on the connection
+			let init = self.init_packet(state.last_command_idx)?;
+			state.send(rp::ToServer::ToServerInit(init));
+
+			for msg in state.kv.take_unsent() {
+				state.send(msg);
+			}
+		}
+
+		let write = async {
+			while let Some(msg) = rx.recv().await {
+				ws_tx.send(msg).await?;
+			}
+
+			Ok(())
+		};
+
+		let read = async {
+			while let Some(msg) = ws_rx.next().await {
+				match msg? {
+					Message::Binary(buf) => {
+						if self.handle_message(&buf)? {
+							*attempt = 0;
+						}
+					}
+					Message::Close(frame) => {
+						tracing::info!(?frame, "runner websocket closed by server");
+						break;
+					}
+					_ => {}
+				}
+			}
+
+			Ok(())
+		};
+
+		let ping = async {
+			let mut interval = tokio::time::interval(PING_INTERVAL);
+			loop {
+				interval.tick().await;
+
+				self.state()
+					.send(rp::ToServer::ToServerPing(rp::ToServerPing {
+						ts: rivet_util::timestamp::now(),
+					}));
+			}
+		};
+
+		let ack = async {
+			let mut interval = tokio::time::interval(ACK_COMMANDS_INTERVAL);
+			interval.tick().await;
+			loop {
+				interval.tick().await;
+
+				let state = self.state();
+				if let Some(last_command_idx) = state.last_command_idx {
+					state.send(rp::ToServer::ToServerAckCommands(rp::ToServerAckCommands {
+						last_command_idx,
+					}));
+				}
+			}
+		};
+
+		tokio::select! {
+			res = write => res,
+			res = read => res,
+			_ = ping => Ok(()),
+			_ = ack => Ok(()),
+		}
+	}
+
+	fn init_packet(&self, last_command_idx: Option<i64>) -> Result<rp::ToServerInit> {
+		let config = &self.0.config;
+
+		let prepopulate_actor_names = config
+			.prepopulate_actor_names
+			.iter()
+			.map(|(name, metadata)| {
+				Ok((
+					name.clone(),
+					rp::ActorName {
+						metadata: serde_json::to_string(metadata)?,
+					},
+				))
+			})
+			.collect::<Result<HashableMap<_, _>>>()?;
+
+		Ok(rp::ToServerInit {
+			name: config.runner_name.clone(),
+			version: config.version,
+			total_slots: config.total_slots,
+			last_command_idx,
+			prepopulate_actor_names: Some(prepopulate_actor_names),
+			metadata: config
+				.metadata
+				.as_ref()
+				.map(serde_json::to_string)
+				.transpose()?,
+			labels: Some(
+				config
+					.labels
+					.iter()
+					.map(|(k, v)| (k.clone(), v.clone()))
+					.collect(),
+			),
+		})
+	}
+
+	fn on_disconnect(&self) {
+		let (connection_idx, runner_lost_threshold) = {
+			let mut state = self.state();
+			state.sender = None;
+			state.kv.fail_sent("runner websocket closed");
+
+			(state.connection_idx, state.runner_lost_threshold)
+		};
+		self.0.connected.send_replace(false);
+
+		if self.is_shutdown() {
+			return;
+		}
+
+		// The engine reschedules actors once the runner has been disconnected for longer than the
+		// threshold, so they have to be stopped locally too
+		if let Some(threshold) = runner_lost_threshold {
+			tracing::info!(?threshold, "starting runner lost timeout");
+
+			let runner = self.clone();
+			tokio::spawn(async move {
+				tokio::time::sleep(threshold).await;
+
+				let lost = {
+					let state = runner.state();
+					state.connection_idx == connection_idx && !state.is_connected()
+				};
+				if lost && !runner.is_shutdown() {
+					runner.stop_all_actors().await;
+				}
+			});
+		}
+	}
+
+	async fn stop_all_actors(&self) {
+		tracing::info!("stopping all actors due to runner lost threshold exceeded");
+
+		let actor_ids = self.state().actors.keys().cloned().collect::<Vec<_>>();
+		for actor_id in actor_ids {
+			self.stop_actor(&actor_id, None).await;
+		}
+	}
+
+	/// Returns true if the message was the init response.
+	fn handle_message(&self, buf: &[u8]) -> Result<bool> {
+		let msg = versioned::ToClient::deserialize(buf)?;
+
+		match msg {
+			rp::ToClient::ToClientInit(init) => {
+				tracing::info!(
+					runner_id = %init.runner_id,
+					last_event_idx = init.last_event_idx,
+					"received init"
+				);
+
+				{
+					let mut state = self.state();
+					state.runner_id = Some(init.runner_id);
+					state.runner_lost_threshold =
+						u64::try_from(init.metadata.runner_lost_threshold)
+							.ok()
+							.filter(|x| *x > 0)
+							.map(Duration::from_millis);
+
+					// Resend events the engine has not received
+					state
+						.event_history
+						.retain(|x| x.index > init.last_event_idx);
+					if !state.event_history.is_empty() {
+						let events = state.event_history.iter().cloned().collect();
+						state.send(rp::ToServer::ToServerEvents(events));
+					}
+				}
+
+				self.0.connected.send_replace(true);
+
+				return Ok(true);
+			}
+			rp::ToClient::ToClientCommands(commands) => {
+				tracing::debug!(count = commands.len(), "received commands");
+
+				for command in commands {
+					self.handle_command(command);
+				}
+			}
+			rp::ToClient::ToClientAckEvents(ack) => {
+				self.state()
+					.event_history
+					.retain(|x| x.index > ack.last_event_idx);
+			}
+			rp::ToClient::ToClientKvResponse(res) => {
+				self.handle_kv_response(res);
+			}
+		}
+
+		Ok(false)
+	}
+
+	fn handle_command(&self, command: rp::CommandWrapper) {
+		match command.inner {
+			rp::Command::CommandStartActor(cmd) => {
+				self.start_actor(cmd.actor_id, cmd.generation, cmd.config.into());
+			}
+			rp::Command::CommandStopActor(cmd) => {
+				tracing::debug!(actor_id = %cmd.actor_id, intent = ?cmd.intent, "stopping actor");
+
+				let runner = self.clone();
+				tokio::spawn(async move {
+					runner.stop_actor(&cmd.actor_id, Some(cmd.generation)).await;
+				});
+			}
+			rp::Command::CommandFireAlarms(cmd) => {
+				self.fire_alarms(cmd);
+			}
+		}
+
+		self.state().last_command_idx = Some(command.index);
+	}
+
+	fn fire_alarms(&self, cmd: rp::CommandFireAlarms) {
+		let Some(actor) = self
+			.actor(&cmd.actor_id)
+			.filter(|actor| actor.generation == cmd.generation)
+		else {
+			tracing::warn!(
+				actor_id = %cmd.actor_id,
+				generation = cmd.generation,
+				"alarms fired for unknown actor"
+			);
+			return;
+		};
+
+		tracing::debug!(actor_id = %actor.actor_id, count = cmd.alarms.len(), "alarms fired");
+
+		let acks = cmd
+			.alarms
+			.iter()
+			.map(|alarm| rp::ActorAlarmAck {
+				name: alarm.name.clone(),
+				ts: alarm.ts,
+			})
+			.collect();
+		let alarms = cmd.alarms.into_iter().map(Into::into).collect();
+		let runner = self.clone();
+		tokio::spawn(async move {
+			match runner
+				.0
+				.handler
+				.on_alarms_fired(actor.clone(), alarms)
+				.await
+			{
+				Ok(()) => {
+					runner.send_event(rp::Event::EventActorAckAlarms(rp::EventActorAckAlarms {
+						actor_id: actor.actor_id.clone(),
+						generation: actor.generation,
+						alarms: acks,
+					}));
+				}
+				// Not acknowledged, the alarms are fired again when the actor is next started
+				Err(err) => {
+					tracing::error!(actor_id = %actor.actor_id, ?err, "error in on_alarms_fired");
+				}
+			}
+		});
+	}
+
+	fn start_actor(&self, actor_id: String, generation: u32, config: ActorConfig) {
+		tracing::info!(%actor_id, ?generation, name = %config.name, "starting actor");
+
+		// Registered before starting so the actor can use KV and alarms in `on_actor_start`
+		self.state().actors.insert(
+			actor_id.clone(),
+			ActorEntry {
+				generation,
+				config: config.clone(),
+			},
+		);
+
+		let actor = Actor::new(actor_id, generation, config, self.clone());
+		let runner = self.clone();
+		tokio::spawn(async move {
+			let res = runner.0.handler.on_actor_start(actor.clone()).await;
+
+			// Actor may have been stopped while starting
+			let removed = {
+				let mut state = runner.state();
+				match state.actors.get(&actor.actor_id) {
+					Some(entry) if entry.generation == actor.generation => {
+						if res.is_err() {
+							state.actors.remove(&actor.actor_id);
+						}
+						false
+					}
+					_ => true,
+				}
+			};
+
+			match res {
+				Result::Ok(()) => {
+					if removed {
+						tracing::debug!(actor_id = %actor.actor_id, "actor stopped before it finished starting");
+						return;
+					}
+
+					runner.send_event(rp::Event::EventActorStateUpdate(
+						rp::EventActorStateUpdate {
+							actor_id: actor.actor_id.clone(),
+							generation: actor.generation,
+							state: rp::ActorState::ActorStateRunning,
+						},
+					));
+				}
+				Err(err) => {
+					tracing::error!(actor_id = %actor.actor_id, ?err, "error in on_actor_start");
+
+					if removed {
+						return;
+					}
+
+					runner.0.tunnel.unregister_actor(&actor.actor_id);
+					runner.send_actor_stopped(&actor.actor_id, actor.generation, Err(err));
+				}
+			}
+		});
+	}
+}
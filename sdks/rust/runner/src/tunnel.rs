@@ -0,0 +1,642 @@
+use std::{
+	collections::HashMap,
+	sync::{Arc, Mutex, MutexGuard},
+	time::{Duration, Instant},
+};
+
+use anyhow::*;
+use futures_util::{SinkExt, StreamExt};
+use rivet_tunnel_protocol::{self as tp, MessageId, MessageKind, RequestId, versioned};
+use rivet_util::serde::HashableMap;
+use tokio::{
+	sync::{mpsc, watch},
+	task::AbortHandle,
+};
+use tokio_tungstenite::tungstenite::{
+	Message,
+	protocol::{CloseFrame, frame::coding::CloseCode},
+};
+use versioned_data_util::OwnedVersionedData;
+
+use crate::{
+	Runner, TunnelWebSocket, WebSocketMessage,
+	http::{self, Request, Response},
+	utils::{self, WsStream},
+};
+
+const GC_INTERVAL: Duration = Duration::from_secs(60);
+const MESSAGE_ACK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Connection to the pegboard tunnel. Carries HTTP requests and WebSockets from the gateway to
+/// actors on this runner.
+#[derive(Clone)]
+pub(crate) struct Tunnel(Arc<TunnelInner>);
+
+struct TunnelInner {
+	url: String,
+	state: Mutex<TunnelState>,
+	shutdown: watch::Sender<bool>,
+}
+
+#[derive(Default)]
+struct TunnelState {
+	sender: Option<mpsc::UnboundedSender<Message>>,
+	/// Sent messages waiting for an ack from the gateway.
+	pending_messages: HashMap<MessageId, PendingMessage>,
+	requests: HashMap<RequestId, InFlightRequest>,
+	websockets: HashMap<RequestId, InFlightWebSocket>,
+}
+
+struct PendingMessage {
+	request_id: RequestId,
+	send_instant: Instant,
+}
+
+struct InFlightRequest {
+	actor_id: String,
+	/// Set while the request body is being streamed in chunks.
+	streaming: Option<(tp::ToServerRequestStart, Vec<u8>)>,
+	handle: Option<AbortHandle>,
+}
+
+struct InFlightWebSocket {
+	actor_id: String,
+	tx: mpsc::UnboundedSender<WebSocketMessage>,
+}
+
+impl Tunnel {
+	pub(crate) fn new(url: String) -> Self {
+		Tunnel(Arc::new(TunnelInner {
+			url,
+			state: Mutex::new(TunnelState::default()),
+			shutdown: watch::Sender::new(false),
+		}))
+	}
+
+	/// Connects the tunnel, then reconnects in the background until shut down.
+	pub(crate) async fn start(&self, runner: Runner) -> Result<()> {
+		let ws = utils::connect(&self.0.url, "tunnel").await?;
+
+		tokio::spawn(self.clone().tunnel_loop(runner, ws));
+		tokio::spawn(self.clone().gc());
+
+		Ok(())
+	}
+
+	pub(crate) fn shutdown(&self) {
+		self.0.shutdown.send_replace(true);
+
+		let mut state = self.state();
+		for (_, req) in state.requests.drain() {
+			if let Some(handle) = req.handle {
+				handle.abort();
+			}
+		}
+		state.websockets.clear();
+		state.pending_messages.clear();
+
+		if let Some(sender) = state.sender.take() {
+			let _ = sender.send(Message::Close(Some(CloseFrame {
+				code: CloseCode::Normal,
+				reason: "Stopping".into(),
+			})));
+		}
+	}
+
+	/// Aborts in-flight requests and closes WebSockets of a stopped actor.
+	pub(crate) fn unregister_actor(&self, actor_id: &str) {
+		let (request_ids, websocket_ids) = {
+			let state = self.state();
+
+			let request_ids = state
+				.requests
+				.iter()
+				.filter(|(_, req)| req.actor_id == actor_id)
+				.map(|(request_id, _)| *request_id)
+				.collect::<Vec<_>>();
+			let websocket_ids = state
+				.websockets
+				.iter()
+				.filter(|(_, ws)| ws.actor_id == actor_id)
+				.map(|(request_id, _)| *request_id)
+				.collect::<Vec<_>>();
+
+			(request_ids, websocket_ids)
+		};
+
+		for request_id in request_ids {
+			if let Some(handle) = self
+				.state()
+				.requests
+				.remove(&request_id)
+				.and_then(|req| req.handle)
+			{
+				handle.abort();
+			}
+
+			self.send_message(request_id, MessageKind::ToClientResponseAbort);
+		}
+
+		for request_id in websocket_ids {
+			self.close_websocket(request_id, Some(1000), Some("Actor stopped".to_string()));
+		}
+	}
+
+	pub(crate) fn websocket_open(&self, request_id: &RequestId) -> bool {
+		self.state().websockets.contains_key(request_id)
+	}
+
+	pub(crate) fn close_websocket(
+		&self,
+		request_id: RequestId,
+		code: Option<u16>,
+		reason: Option<String>,
+	) {
+		if self.state().websockets.remove(&request_id).is_none() {
+			return;
+		}
+
+		self.send_message(
+			request_id,
+			MessageKind::ToClientWebSocketClose(tp::ToClientWebSocketClose { code, reason }),
+		);
+	}
+
+	pub(crate) fn send_message(&self, request_id: RequestId, message_kind: MessageKind) {
+		let message_id = uuid::Uuid::new_v4().into_bytes();
+
+		let mut state = self.state();
+		state.pending_messages.insert(
+			message_id,
+			PendingMessage {
+				request_id,
+				send_instant: Instant::now(),
+			},
+		);
+		state.send(tp::RunnerMessage {
+			request_id,
+			message_id,
+			message_kind,
+		});
+	}
+
+	fn state(&self) -> MutexGuard<'_, TunnelState> {
+		self.0.state.lock().expect("poisoned")
+	}
+
+	fn is_shutdown(&self) -> bool {
+		*self.0.shutdown.borrow()
+	}
+
+	// MARK: Connection
+	async fn tunnel_loop(self, runner: Runner, ws: WsStream) {
+		let mut shutdown_rx = self.0.shutdown.subscribe();
+		let mut ws = Some(ws);
+		let mut attempt = 0;
+
+		loop {
+			let res = match ws.take() {
+				Some(ws) => self.run_connection(&runner, ws).await,
+				None => match utils::connect(&self.0.url, "tunnel").await {
+					Result::Ok(ws) => {
+						attempt = 0;
+						self.run_connection(&runner, ws).await
+					}
+					Err(err) => Err(err),
+				},
+			};
+
+			match res {
+				Result::Ok(()) => tracing::info!("tunnel websocket closed"),
+				Err(err) => tracing::warn!(?err, "tunnel websocket failed"),
+			}
+
+			self.state().sender = None;
+
+			if self.is_shutdown() {
+				break;
+			}
+
+			let delay = utils::reconnect_delay(attempt);
+			attempt += 1;
+			tracing::info!(?delay, "reconnecting tunnel websocket");
+
+			tokio::select! {
+				_ = tokio::time::sleep(delay) => {}
+				_ = shutdown_rx.wait_for(|x| *x) => break,
+			}
+		}
+	}
+
+	async fn run_connection(&self, runner: &Runner, ws: WsStream) -> Result<()> {
+		let (mut ws_tx, mut ws_rx) = ws.split();
+		let (tx, mut rx) = mpsc::unbounded_channel();
+
+		self.state().sender = Some(tx);
+
+		let write = async {
+			while let Some(msg) = rx.recv().await {
+				ws_tx.send(msg).await?;
+			}
+
+			Ok(())
+		};
+
+		let read = async {
+			while let Some(msg) = ws_rx.next().await {
+				match msg? {
+					Message::Binary(buf) => {
+						if let Err(err) = self.handle_message(runner, &buf) {
+							tracing::error!(?err, "error handling tunnel message");
+						}
+					}
+					Message::Close(frame) => {
+						tracing::info!(?frame, "tunnel websocket closed by server");
+						break;
+					}
+					_ => {}
+				}
+			}
+
+			Ok(())
+		};
+
+		tokio::select! {
+			res = write => res,
+			res = read => res,
+		}
+	}
+
+	/// Purges messages that were not acked in time and closes their requests.
+	async fn gc(self) {
+		let mut shutdown_rx = self.0.shutdown.subscribe();
+		let mut interval = tokio::time::interval(GC_INTERVAL);
+
+		loop {
+			tokio::select! {
+				_ = interval.tick() => {}
+				_ = shutdown_rx.wait_for(|x| *x) => break,
+			}
+
+			let now = Instant::now();
+			let mut expired_request_ids = Vec::new();
+
+			{
+				let mut state = self.state();
+				state.pending_messages.retain(|_, msg| {
+					if now.duration_since(msg.send_instant) > MESSAGE_ACK_TIMEOUT {
+						expired_request_ids.push(msg.request_id);
+						false
+					} else {
+						true
+					}
+				});
+			}
+
+			for request_id in expired_request_ids {
+				tracing::warn!(?request_id, "purging unacked tunnel message");
+
+				if let Some(handle) = self
+					.state()
+					.requests
+					.remove(&request_id)
+					.and_then(|req| req.handle)
+				{
+					handle.abort();
+				}
+
+				if let Some(ws) = self.state().websockets.remove(&request_id) {
+					let _ = ws.tx.send(WebSocketMessage::Close {
+						code: Some(1000),
+						reason: Some("Message acknowledgment timeout".to_string()),
+					});
+				}
+			}
+		}
+	}
+
+	// MARK: Messages
+	fn handle_message(&self, runner: &Runner, buf: &[u8]) -> Result<()> {
+		let msg = versioned::RunnerMessage::deserialize(buf)?;
+
+		if let MessageKind::Ack = msg.message_kind {
+			self.state().pending_messages.remove(&msg.message_id);
+			return Ok(());
+		}
+
+		self.send_ack(msg.request_id, msg.message_id);
+
+		match msg.message_kind {
+			MessageKind::ToServerRequestStart(start) => {
+				self.handle_request_start(runner, msg.request_id, start);
+			}
+			MessageKind::ToServerRequestChunk(chunk) => {
+				self.handle_request_chunk(runner, msg.request_id, chunk);
+			}
+			MessageKind::ToServerRequestAbort => {
+				if let Some(handle) = self
+					.state()
+					.requests
+					.remove(&msg.request_id)
+					.and_then(|req| req.handle)
+				{
+					handle.abort();
+				}
+			}
+			MessageKind::ToServerWebSocketOpen(open) => {
+				self.handle_websocket_open(runner, msg.request_id, open);
+			}
+			MessageKind::ToServerWebSocketMessage(ws_msg) => {
+				let ws_msg = if ws_msg.binary {
+					WebSocketMessage::Binary(ws_msg.data)
+				} else {
+					WebSocketMessage::Text(String::from_utf8_lossy(&ws_msg.data).into_owned())
+				};
+
+				if let Some(ws) = self.state().websockets.get(&msg.request_id) {
+					let _ = ws.tx.send(ws_msg);
+				}
+			}
+			MessageKind::ToServerWebSocketClose(close) => {
+				// Dropping the sender ends the socket's receive stream after the close message
+				if let Some(ws) = self.state().websockets.remove(&msg.request_id) {
+					let _ = ws.tx.send(WebSocketMessage::Close {
+						code: close.code,
+						reason: close.reason,
+					});
+				}
+			}
+			// Only sent by the runner
+			MessageKind::Ack
+			| MessageKind::ToClientResponseStart(_)
+			| MessageKind::ToClientResponseChunk(_)
+			| MessageKind::ToClientResponseAbort
+			| MessageKind::ToClientWebSocketOpen
+			| MessageKind::ToClientWebSocketMessage(_)
+			| MessageKind::ToClientWebSocketClose(_) => {}
+		}
+
+		Ok(())
+	}
+
+	fn send_ack(&self, request_id: RequestId, message_id: MessageId) {
+		self.state().send(tp::RunnerMessage {
+			request_id,
+			message_id,
+			message_kind: MessageKind::Ack,
+		});
+	}
+
+	fn handle_request_start(
+		&self,
+		runner: &Runner,
+		request_id: RequestId,
+		start: tp::ToServerRequestStart,
+	) {
+		if start.stream {
+			// Buffer the body until the last chunk arrives
+			let body = start.body.clone().unwrap_or_default();
+			self.state().requests.insert(
+				request_id,
+				InFlightRequest {
+					actor_id: start.actor_id.clone(),
+					streaming: Some((start, body)),
+					handle: None,
+				},
+			);
+		} else {
+			let body = start.body.clone().unwrap_or_default();
+			self.dispatch_request(runner, request_id, start, body);
+		}
+	}
+
+	fn handle_request_chunk(
+		&self,
+		runner: &Runner,
+		request_id: RequestId,
+		chunk: tp::ToServerRequestChunk,
+	) {
+		let finished = {
+			let mut state = self.state();
+			let Some(req) = state.requests.get_mut(&request_id) else {
+				tracing::debug!(?request_id, "received chunk for unknown request");
+				return;
+			};
+			let Some((_, body)) = &mut req.streaming else {
+				tracing::warn!(?request_id, "received chunk for non-streaming request");
+				return;
+			};
+
+			body.extend(chunk.body);
+
+			if chunk.finish {
+				req.streaming.take()
+			} else {
+				None
+			}
+		};
+
+		if let Some((start, body)) = finished {
+			self.dispatch_request(runner, request_id, start, body);
+		}
+	}
+
+	fn dispatch_request(
+		&self,
+		runner: &Runner,
+		request_id: RequestId,
+		start: tp::ToServerRequestStart,
+		body: Vec<u8>,
+	) {
+		self.state().requests.insert(
+			request_id,
+			InFlightRequest {
+				actor_id: start.actor_id.clone(),
+				streaming: None,
+				handle: None,
+			},
+		);
+
+		let tunnel = self.clone();
+		let runner = runner.clone();
+		let handle = tokio::spawn(async move {
+			let res = match runner.actor(&start.actor_id) {
+				Some(actor) => match build_request(&start, body) {
+					Result::Ok(req) => match runner.handler().fetch(actor, req).await {
+						Result::Ok(res) => res,
+						Err(err) => {
+							tracing::error!(actor_id = %start.actor_id, ?err, "error handling request");
+							text_response(500, "Internal Server Error")
+						}
+					},
+					Err(err) => {
+						tracing::warn!(?err, "invalid tunneled request");
+						text_response(400, "Bad Request")
+					}
+				},
+				None => {
+					tracing::warn!(actor_id = %start.actor_id, "ignoring request for unknown actor");
+					text_response(404, "Actor not found")
+				}
+			};
+
+			// Request was aborted while being handled
+			if tunnel.state().requests.remove(&request_id).is_none() {
+				return;
+			}
+
+			tunnel.send_message(
+				request_id,
+				MessageKind::ToClientResponseStart(response_start(res)),
+			);
+		});
+
+		if let Some(req) = self.state().requests.get_mut(&request_id) {
+			req.handle = Some(handle.abort_handle());
+		}
+	}
+
+	fn handle_websocket_open(
+		&self,
+		runner: &Runner,
+		request_id: RequestId,
+		open: tp::ToServerWebSocketOpen,
+	) {
+		let Some(actor) = runner.actor(&open.actor_id) else {
+			tracing::warn!(actor_id = %open.actor_id, "ignoring websocket for unknown actor");
+			self.send_message(
+				request_id,
+				MessageKind::ToClientWebSocketClose(tp::ToClientWebSocketClose {
+					code: Some(1011),
+					reason: Some("Actor not found".to_string()),
+				}),
+			);
+			return;
+		};
+
+		let req = match build_websocket_request(&open) {
+			Result::Ok(req) => req,
+			Err(err) => {
+				tracing::warn!(?err, "invalid tunneled websocket request");
+				self.send_message(
+					request_id,
+					MessageKind::ToClientWebSocketClose(tp::ToClientWebSocketClose {
+						code: Some(1002),
+						reason: Some("Bad Request".to_string()),
+					}),
+				);
+				return;
+			}
+		};
+
+		let (tx, rx) = mpsc::unbounded_channel();
+		self.state().websockets.insert(
+			request_id,
+			InFlightWebSocket {
+				actor_id: open.actor_id,
+				tx,
+			},
+		);
+		self.send_message(request_id, MessageKind::ToClientWebSocketOpen);
+
+		let ws = TunnelWebSocket::new(request_id, self.clone(), rx);
+		let tunnel = self.clone();
+		let runner = runner.clone();
+		tokio::spawn(async move {
+			let actor_id = actor.actor_id.clone();
+
+			match runner.handler().websocket(actor, req, ws).await {
+				Result::Ok(()) => {
+					tunnel.close_websocket(request_id, Some(1000), None);
+				}
+				Err(err) => {
+					tracing::error!(%actor_id, ?err, "error handling websocket");
+					tunnel.close_websocket(
+						request_id,
+						Some(1011),
+						Some("Server Error".to_string()),
+					);
+				}
+			}
+		});
+	}
+}
+
+impl TunnelState {
+	/// Sends a message on the current connection. Messages are dropped if disconnected.
+	fn send(&self, msg: tp::RunnerMessage) {
+		let Some(sender) = &self.sender else {
+			tracing::debug!("tunnel websocket not connected, dropping message");
+			return;
+		};
+
+		match versioned::RunnerMessage::latest(msg).serialize() {
+			Result::Ok(buf) => {
+				if sender.send(Message::Binary(buf.into())).is_err() {
+					tracing::debug!("tunnel websocket closed, dropping message");
+				}
+			}
+			Err(err) => {
+				tracing::error!(?err, "failed to serialize tunnel message");
+			}
+		}
+	}
+}
+
+fn build_request(start: &tp::ToServerRequestStart, body: Vec<u8>) -> Result<Request<Vec<u8>>> {
+	let mut builder = Request::builder()
+		.method(start.method.as_str())
+		.uri(start.path.as_str());
+	for (name, value) in start.headers.iter() {
+		builder = builder.header(name, value);
+	}
+
+	builder.body(body).map_err(Into::into)
+}
+
+fn build_websocket_request(open: &tp::ToServerWebSocketOpen) -> Result<Request<()>> {
+	let mut builder = Request::builder().uri(open.path.as_str());
+	for (name, value) in open.headers.iter() {
+		builder = builder.header(name, value);
+	}
+
+	builder.body(()).map_err(Into::into)
+}
+
+fn response_start(res: Response<Vec<u8>>) -> tp::ToClientResponseStart {
+	let (parts, body) = res.into_parts();
+
+	let mut headers = HashableMap::new();
+	for (name, value) in parts.headers.iter() {
+		let Result::Ok(value) = value.to_str() else {
+			tracing::warn!(%name, "dropping non-utf8 response header");
+			continue;
+		};
+
+		headers
+			.entry(name.to_string())
+			.and_modify(|x: &mut String| {
+				x.push_str(", ");
+				x.push_str(value);
+			})
+			.or_insert_with(|| value.to_string());
+	}
+	headers
+		.entry(http::header::CONTENT_LENGTH.to_string())
+		.or_insert_with(|| body.len().to_string());
+
+	tp::ToClientResponseStart {
+		status: parts.status.as_u16(),
+		headers,
+		body: Some(body),
+		stream: false,
+	}
+}
+
+fn text_response(status: u16, body: &str) -> Response<Vec<u8>> {
+	Response::builder()
+		.status(status)
+		.header(http::header::CONTENT_TYPE, "text/plain")
+		.body(body.as_bytes().to_vec())
+		.expect("valid response")
+}
@@ -0,0 +1,59 @@
+use std::time::Duration;
+
+use anyhow::*;
+use tokio::net::TcpStream;
+use tokio_tungstenite::{
+	MaybeTlsStream, WebSocketStream,
+	tungstenite::{client::IntoClientRequest, http::HeaderValue},
+};
+
+use crate::{PROTOCOL_VERSION, RunnerConfig};
+
+pub(crate) type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Base delay between reconnect attempts. Doubles with every failed attempt.
+const RECONNECT_BACKOFF_BASE_MS: usize = 1_000;
+/// Caps the reconnect delay at ~32s.
+const RECONNECT_BACKOFF_MAX_EXPONENT: usize = 5;
+
+/// Builds the websocket url for the runner or tunnel connection.
+pub(crate) fn build_url(endpoint: &str, config: &RunnerConfig) -> Result<String> {
+	let endpoint = endpoint
+		.replacen("http://", "ws://", 1)
+		.replacen("https://", "wss://", 1);
+	let mut url = url::Url::parse(&endpoint).context("invalid endpoint")?;
+
+	url.query_pairs_mut()
+		.append_pair("protocol_version", &PROTOCOL_VERSION.to_string())
+		.append_pair("namespace", &config.namespace)
+		.append_pair("runner_key", &config.runner_key);
+
+	Ok(url.to_string())
+}
+
+/// Opens a websocket to guard, routed to the given target service.
+pub(crate) async fn connect(url: &str, target: &'static str) -> Result<WsStream> {
+	let mut req = url.into_client_request()?;
+	req.headers_mut()
+		.insert("x-rivet-target", HeaderValue::from_static(target));
+
+	let (ws, _) = tokio_tungstenite::connect_async(req)
+		.await
+		.with_context(|| format!("failed to connect to {target}"))?;
+
+	Ok(ws)
+}
+
+/// How long to wait before the given reconnect attempt.
+pub(crate) fn reconnect_delay(attempt: usize) -> Duration {
+	let mut backoff = rivet_util::backoff::Backoff::new_at(
+		RECONNECT_BACKOFF_MAX_EXPONENT,
+		None,
+		RECONNECT_BACKOFF_BASE_MS,
+		500,
+		attempt,
+	);
+	let next = backoff.step().expect("should not have max retry");
+
+	next.saturating_duration_since(tokio::time::Instant::now())
+}
@@ -0,0 +1,73 @@
+use anyhow::*;
+use rivet_tunnel_protocol::{self as tp, RequestId};
+use tokio::sync::mpsc;
+
+use crate::tunnel::Tunnel;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WebSocketMessage {
+	Text(String),
+	Binary(Vec<u8>),
+	/// Sent by the client before the socket closes.
+	Close {
+		code: Option<u16>,
+		reason: Option<String>,
+	},
+}
+
+/// WebSocket tunneled from a client through the gateway to an actor.
+pub struct TunnelWebSocket {
+	request_id: RequestId,
+	tunnel: Tunnel,
+	rx: mpsc::UnboundedReceiver<WebSocketMessage>,
+}
+
+impl TunnelWebSocket {
+	pub(crate) fn new(
+		request_id: RequestId,
+		tunnel: Tunnel,
+		rx: mpsc::UnboundedReceiver<WebSocketMessage>,
+	) -> Self {
+		TunnelWebSocket {
+			request_id,
+			tunnel,
+			rx,
+		}
+	}
+
+	/// Returns the next message from the client. Returns `None` once the socket is closed.
+	pub async fn recv(&mut self) -> Option<WebSocketMessage> {
+		self.rx.recv().await
+	}
+
+	pub fn is_open(&self) -> bool {
+		self.tunnel.websocket_open(&self.request_id)
+	}
+
+	pub fn send_text(&self, text: impl Into<String>) -> Result<()> {
+		self.send(text.into().into_bytes(), false)
+	}
+
+	pub fn send_binary(&self, data: impl Into<Vec<u8>>) -> Result<()> {
+		self.send(data.into(), true)
+	}
+
+	/// Closes the socket. Does nothing if already closed.
+	pub fn close(&self, code: Option<u16>, reason: Option<String>) {
+		self.tunnel.close_websocket(self.request_id, code, reason);
+	}
+
+	fn send(&self, data: Vec<u8>, binary: bool) -> Result<()> {
+		ensure!(self.is_open(), "websocket closed");
+
+		self.tunnel.send_message(
+			self.request_id,
+			tp::MessageKind::ToClientWebSocketMessage(tp::ToClientWebSocketMessage {
+				data,
+				binary,
+			}),
+		);
+
+		Ok(())
+	}
+}
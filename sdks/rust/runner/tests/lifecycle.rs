@@ -0,0 +1,334 @@
+use std::{
+	collections::HashSet,
+	sync::{Arc, Mutex},
+	time::Duration,
+};
+
+use anyhow::*;
+use async_trait::async_trait;
+use futures_util::{SinkExt, StreamExt};
+use rivet_runner::{Actor, ActorHandler, Runner, RunnerConfig, http};
+use rivet_runner_protocol::{self as rp, PROTOCOL_VERSION, versioned};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_tungstenite::{
+	WebSocketStream,
+	tungstenite::{
+		Message,
+		handshake::server::{Request, Response},
+	},
+};
+use versioned_data_util::OwnedVersionedData;
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Default)]
+struct TestHandler {
+	/// Actor names that fail to start.
+	fail_start: HashSet<String>,
+	/// Actor names that fail to stop.
+	fail_stop: HashSet<String>,
+	/// Delays `on_actor_start` so the engine can observe the actor before it is running.
+	start_delay: Option<Duration>,
+	started: Mutex<Vec<String>>,
+	stopped: Mutex<Vec<String>>,
+}
+
+#[async_trait]
+impl ActorHandler for TestHandler {
+	async fn on_actor_start(&self, actor: Actor) -> Result<()> {
+		if let Some(delay) = self.start_delay {
+			tokio::time::sleep(delay).await;
+		}
+
+		if self.fail_start.contains(&actor.config.name) {
+			bail!("start failed");
+		}
+
+		self.started.lock().unwrap().push(actor.actor_id.clone());
+
+		Ok(())
+	}
+
+	async fn on_actor_stop(&self, actor: Actor) -> Result<()> {
+		self.stopped.lock().unwrap().push(actor.actor_id.clone());
+
+		if self.fail_stop.contains(&actor.config.name) {
+			bail!("stop failed");
+		}
+
+		Ok(())
+	}
+
+	async fn fetch(
+		&self,
+		_actor: Actor,
+		_req: http::Request<Vec<u8>>,
+	) -> Result<http::Response<Vec<u8>>> {
+		bail!("not implemented")
+	}
+}
+
+/// Stands in for the engine. Accepts the tunnel and runner websockets of a single runner.
+struct MockEngine {
+	ws: WebSocketStream<TcpStream>,
+	// Kept open so the runner does not reconnect
+	_tunnel_ws: WebSocketStream<TcpStream>,
+	next_command_idx: i64,
+}
+
+impl MockEngine {
+	async fn start<H: ActorHandler>(handler: H) -> Result<(MockEngine, Runner)> {
+		let listener = TcpListener::bind("127.0.0.1:0").await?;
+		let endpoint = format!("http://{}", listener.local_addr()?);
+
+		let accept = async {
+			let mut tunnel_ws = None;
+			let mut runner_ws = None;
+
+			while tunnel_ws.is_none() || runner_ws.is_none() {
+				let (stream, _) = listener.accept().await?;
+
+				let mut target = None;
+				let ws =
+					tokio_tungstenite::accept_hdr_async(stream, |req: &Request, res: Response| {
+						target = req
+							.headers()
+							.get("x-rivet-target")
+							.and_then(|x| x.to_str().ok())
+							.map(ToString::to_string);
+						std::result::Result::Ok(res)
+					})
+					.await?;
+
+				match target.as_deref() {
+					Some("tunnel") => tunnel_ws = Some(ws),
+					Some("runner-ws") => runner_ws = Some(ws),
+					target => bail!("unexpected target: {target:?}"),
+				}
+			}
+
+			Ok((tunnel_ws.unwrap(), runner_ws.unwrap()))
+		};
+
+		let mut config = RunnerConfig::new(endpoint, "default", "test-runner", "test-key");
+		config.total_slots = 10;
+
+		let (runner, accepted) = tokio::join!(Runner::start(config, handler), accept);
+		let runner = runner?;
+		let (tunnel_ws, ws) = accepted?;
+
+		let mut engine = MockEngine {
+			ws,
+			_tunnel_ws: tunnel_ws,
+			next_command_idx: 0,
+		};
+
+		let rp::ToServer::ToServerInit(init) = engine.recv().await? else {
+			bail!("expected init");
+		};
+		assert_eq!(init.name, "test-runner");
+		assert_eq!(init.total_slots, 10);
+
+		engine
+			.send(rp::ToClient::ToClientInit(rp::ToClientInit {
+				runner_id: "runner".to_string(),
+				last_event_idx: -1,
+				metadata: rp::ProtocolMetadata {
+					runner_lost_threshold: 0,
+				},
+			}))
+			.await?;
+		tokio::time::timeout(TIMEOUT, runner.wait_connected()).await??;
+
+		Ok((engine, runner))
+	}
+
+	async fn send(&mut self, msg: rp::ToClient) -> Result<()> {
+		let buf = versioned::ToClient::latest(msg).serialize(PROTOCOL_VERSION)?;
+		self.ws.send(Message::Binary(buf.into())).await?;
+
+		Ok(())
+	}
+
+	async fn recv(&mut self) -> Result<rp::ToServer> {
+		loop {
+			let msg = tokio::time::timeout(TIMEOUT, self.ws.next())
+				.await
+				.context("timed out waiting for runner message")?
+				.context("runner websocket closed")??;
+
+			if let Message::Binary(buf) = msg {
+				return versioned::ToServer::deserialize(&buf, PROTOCOL_VERSION);
+			}
+		}
+	}
+
+	/// Waits for the next actor state update, skipping pings and other messages.
+	async fn recv_state_update(&mut self) -> Result<rp::EventActorStateUpdate> {
+		loop {
+			if let rp::ToServer::ToServerEvents(events) = self.recv().await? {
+				for event in events {
+					if let rp::Event::EventActorStateUpdate(update) = event.inner {
+						return Ok(update);
+					}
+				}
+			}
+		}
+	}
+
+	async fn command(&mut self, inner: rp::Command) -> Result<()> {
+		let index = self.next_command_idx;
+		self.next_command_idx += 1;
+
+		self.send(rp::ToClient::ToClientCommands(vec![rp::CommandWrapper {
+			index,
+			inner,
+		}]))
+		.await
+	}
+
+	async fn start_actor(&mut self, actor_id: &str, name: &str) -> Result<()> {
+		self.command(rp::Command::CommandStartActor(rp::CommandStartActor {
+			actor_id: actor_id.to_string(),
+			generation: 0,
+			config: rp::ActorConfig {
+				name: name.to_string(),
+				key: None,
+				create_ts: 0,
+				input: None,
+			},
+		}))
+		.await
+	}
+
+	async fn stop_actor(&mut self, actor_id: &str) -> Result<()> {
+		self.command(rp::Command::CommandStopActor(rp::CommandStopActor {
+			actor_id: actor_id.to_string(),
+			generation: 0,
+			intent: rp::StopIntent::Stop,
+		}))
+		.await
+	}
+}
+
+#[tokio::test]
+async fn start_and_stop_actor() -> Result<()> {
+	let handler = Arc::new(TestHandler::default());
+	let (mut engine, runner) = MockEngine::start(ArcHandler(handler.clone())).await?;
+
+	engine.start_actor("actor", "ok").await?;
+
+	let update = engine.recv_state_update().await?;
+	assert_eq!(update.actor_id, "actor");
+	assert!(matches!(update.state, rp::ActorState::ActorStateRunning));
+	assert_eq!(*handler.started.lock().unwrap(), vec!["actor"]);
+	assert!(runner.has_actor("actor", Some(0)));
+
+	engine.stop_actor("actor").await?;
+
+	let update = engine.recv_state_update().await?;
+	let rp::ActorState::ActorStateStopped(stopped) = update.state else {
+		bail!("expected stopped");
+	};
+	assert!(matches!(stopped.code, rp::StopCode::Ok));
+	assert_eq!(*handler.stopped.lock().unwrap(), vec!["actor"]);
+	assert!(!runner.has_actor("actor", None));
+
+	runner.shutdown(true).await;
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn running_reported_after_start() -> Result<()> {
+	let handler = Arc::new(TestHandler {
+		start_delay: Some(Duration::from_millis(500)),
+		..Default::default()
+	});
+	let (mut engine, runner) = MockEngine::start(ArcHandler(handler.clone())).await?;
+
+	engine.start_actor("actor", "ok").await?;
+
+	// Running is only reported once `on_actor_start` returned
+	let update = engine.recv_state_update().await?;
+	assert!(matches!(update.state, rp::ActorState::ActorStateRunning));
+	assert_eq!(*handler.started.lock().unwrap(), vec!["actor"]);
+
+	runner.shutdown(true).await;
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn failed_start_reports_error() -> Result<()> {
+	let handler = Arc::new(TestHandler {
+		fail_start: HashSet::from(["fail".to_string()]),
+		..Default::default()
+	});
+	let (mut engine, runner) = MockEngine::start(ArcHandler(handler.clone())).await?;
+
+	engine.start_actor("actor", "fail").await?;
+
+	// Never reported as running
+	let update = engine.recv_state_update().await?;
+	let rp::ActorState::ActorStateStopped(stopped) = update.state else {
+		bail!("expected stopped");
+	};
+	assert!(matches!(stopped.code, rp::StopCode::Error));
+	assert!(stopped.message.unwrap().contains("start failed"));
+	assert!(!runner.has_actor("actor", None));
+	assert!(handler.stopped.lock().unwrap().is_empty());
+
+	runner.shutdown(true).await;
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn failed_stop_reports_error() -> Result<()> {
+	let handler = Arc::new(TestHandler {
+		fail_stop: HashSet::from(["fail".to_string()]),
+		..Default::default()
+	});
+	let (mut engine, runner) = MockEngine::start(ArcHandler(handler.clone())).await?;
+
+	engine.start_actor("actor", "fail").await?;
+
+	let update = engine.recv_state_update().await?;
+	assert!(matches!(update.state, rp::ActorState::ActorStateRunning));
+
+	engine.stop_actor("actor").await?;
+
+	let update = engine.recv_state_update().await?;
+	let rp::ActorState::ActorStateStopped(stopped) = update.state else {
+		bail!("expected stopped");
+	};
+	assert!(matches!(stopped.code, rp::StopCode::Error));
+	assert!(stopped.message.unwrap().contains("stop failed"));
+
+	runner.shutdown(true).await;
+
+	Ok(())
+}
+
+/// Lets tests inspect the handler after passing it to the runner.
+struct ArcHandler(Arc<TestHandler>);
+
+#[async_trait]
+impl ActorHandler for ArcHandler {
+	async fn on_actor_start(&self, actor: Actor) -> Result<()> {
+		self.0.on_actor_start(actor).await
+	}
+
+	async fn on_actor_stop(&self, actor: Actor) -> Result<()> {
+		self.0.on_actor_stop(actor).await
+	}
+
+	async fn fetch(
+		&self,
+		actor: Actor,
+		req: http::Request<Vec<u8>>,
+	) -> Result<http::Response<Vec<u8>>> {
+		self.0.fetch(actor, req).await
+	}
+}
@@ -357,10 +357,144 @@ export function writeEventActorSetAlarm(bc: bare.ByteCursor, x: EventActorSetAla
     write1(bc, x.alarmTs)
 }
 
+function read2(bc: bare.ByteCursor): ArrayBuffer | null {
+    return bare.readBool(bc) ? bare.readData(bc) : null
+}
+
+function write2(bc: bare.ByteCursor, x: ArrayBuffer | null): void {
+    bare.writeBool(bc, x != null)
+    if (x != null) {
+        bare.writeData(bc, x)
+    }
+}
+
+export type ActorAlarm = {
+    readonly name: string
+    readonly ts: i64
+    readonly payload: ArrayBuffer | null
+}
+
+export function readActorAlarm(bc: bare.ByteCursor): ActorAlarm {
+    return {
+        name: bare.readString(bc),
+        ts: bare.readI64(bc),
+        payload: read2(bc),
+    }
+}
+
+export function writeActorAlarm(bc: bare.ByteCursor, x: ActorAlarm): void {
+    bare.writeString(bc, x.name)
+    bare.writeI64(bc, x.ts)
+    write2(bc, x.payload)
+}
+
+export type EventActorSetNamedAlarm = {
+    readonly actorId: Id
+    readonly generation: u32
+    readonly alarm: ActorAlarm
+}
+
+export function readEventActorSetNamedAlarm(bc: bare.ByteCursor): EventActorSetNamedAlarm {
+    return {
+        actorId: readId(bc),
+        generation: bare.readU32(bc),
+        alarm: readActorAlarm(bc),
+    }
+}
+
+export function writeEventActorSetNamedAlarm(bc: bare.ByteCursor, x: EventActorSetNamedAlarm): void {
+    writeId(bc, x.actorId)
+    bare.writeU32(bc, x.generation)
+    writeActorAlarm(bc, x.alarm)
+}
+
+export type EventActorCancelAlarm = {
+    readonly actorId: Id
+    readonly generation: u32
+    readonly name: string
+}
+
+export function readEventActorCancelAlarm(bc: bare.ByteCursor): EventActorCancelAlarm {
+    return {
+        actorId: readId(bc),
+        generation: bare.readU32(bc),
+        name: bare.readString(bc),
+    }
+}
+
+export function writeEventActorCancelAlarm(bc: bare.ByteCursor, x: EventActorCancelAlarm): void {
+    writeId(bc, x.actorId)
+    bare.writeU32(bc, x.generation)
+    bare.writeString(bc, x.name)
+}
+
+export type ActorAlarmAck = {
+    readonly name: string
+    readonly ts: i64
+}
+
+export function readActorAlarmAck(bc: bare.ByteCursor): ActorAlarmAck {
+    return {
+        name: bare.readString(bc),
+        ts: bare.readI64(bc),
+    }
+}
+
+export function writeActorAlarmAck(bc: bare.ByteCursor, x: ActorAlarmAck): void {
+    bare.writeString(bc, x.name)
+    bare.writeI64(bc, x.ts)
+}
+
+function read3(bc: bare.ByteCursor): readonly ActorAlarmAck[] {
+    const len = bare.readUintSafe(bc)
+    if (len === 0) {
+        return []
+    }
+    const result = [readActorAlarmAck(bc)]
+    for (let i = 1; i < len; i++) {
+        result[i] = readActorAlarmAck(bc)
+    }
+    return result
+}
+
+function write3(bc: bare.ByteCursor, x: readonly ActorAlarmAck[]): void {
+    bare.writeUintSafe(bc, x.length)
+    for (let i = 0; i < x.length; i++) {
+        writeActorAlarmAck(bc, x[i])
+    }
+}
+
+/**
+ * Sent once fired alarms were handled. Alarms that are not acknowledged are fired again when the
+ * actor is next started. An alarm that was replaced since it fired is not removed.
+ */
+export type EventActorAckAlarms = {
+    readonly actorId: Id
+    readonly generation: u32
+    readonly alarms: readonly ActorAlarmAck[]
+}
+
+export function readEventActorAckAlarms(bc: bare.ByteCursor): EventActorAckAlarms {
+    return {
+        actorId: readId(bc),
+        generation: bare.readU32(bc),
+        alarms: read3(bc),
+    }
+}
+
+export function writeEventActorAckAlarms(bc: bare.ByteCursor, x: EventActorAckAlarms): void {
+    writeId(bc, x.actorId)
+    bare.writeU32(bc, x.generation)
+    write3(bc, x.alarms)
+}
+
 export type Event =
     | { readonly tag: "EventActorIntent"; readonly val: EventActorIntent }
     | { readonly tag: "EventActorStateUpdate"; readonly val: EventActorStateUpdate }
     | { readonly tag: "EventActorSetAlarm"; readonly val: EventActorSetAlarm }
+    | { readonly tag: "EventActorSetNamedAlarm"; readonly val: EventActorSetNamedAlarm }
+    | { readonly tag: "EventActorCancelAlarm"; readonly val: EventActorCancelAlarm }
+    | { readonly tag: "EventActorAckAlarms"; readonly val: EventActorAckAlarms }
 
 export function readEvent(bc: bare.ByteCursor): Event {
     const offset = bc.offset
@@ -372,6 +506,12 @@ export function readEvent(bc: bare.ByteCursor): Event {
             return { tag: "EventActorStateUpdate", val: readEventActorStateUpdate(bc) }
         case 2:
             return { tag: "EventActorSetAlarm", val: readEventActorSetAlarm(bc) }
+        case 3:
+            return { tag: "EventActorSetNamedAlarm", val: readEventActorSetNamedAlarm(bc) }
+        case 4:
+            return { tag: "EventActorCancelAlarm", val: readEventActorCancelAlarm(bc) }
+        case 5:
+            return { tag: "EventActorAckAlarms", val: readEventActorAckAlarms(bc) }
         default: {
             bc.offset = offset
             throw new bare.BareError(offset, "invalid tag")
@@ -396,6 +536,21 @@ export function writeEvent(bc: bare.ByteCursor, x: Event): void {
             writeEventActorSetAlarm(bc, x.val)
             break
         }
+        case "EventActorSetNamedAlarm": {
+            bare.writeU8(bc, 3)
+            writeEventActorSetNamedAlarm(bc, x.val)
+            break
+        }
+        case "EventActorCancelAlarm": {
+            bare.writeU8(bc, 4)
+            writeEventActorCancelAlarm(bc, x.val)
+            break
+        }
+        case "EventActorAckAlarms": {
+            bare.writeU8(bc, 5)
+            writeEventActorAckAlarms(bc, x.val)
+            break
+        }
     }
 }
 
@@ -416,17 +571,6 @@ export function writeEventWrapper(bc: bare.ByteCursor, x: EventWrapper): void {
     writeEvent(bc, x.inner)
 }
 
-function read2(bc: bare.ByteCursor): ArrayBuffer | null {
-    return bare.readBool(bc) ? bare.readData(bc) : null
-}
-
-function write2(bc: bare.ByteCursor, x: ArrayBuffer | null): void {
-    bare.writeBool(bc, x != null)
-    if (x != null) {
-        bare.writeData(bc, x)
-    }
-}
-
 export type ActorConfig = {
     readonly name: string
     readonly key: string | null
@@ -470,26 +614,109 @@ export function writeCommandStartActor(bc: bare.ByteCursor, x: CommandStartActor
     writeActorConfig(bc, x.config)
 }
 
+export enum StopIntent {
+    Stop = "Stop",
+    Sleep = "Sleep",
+    Migrate = "Migrate",
+}
+
+export function readStopIntent(bc: bare.ByteCursor): StopIntent {
+    const offset = bc.offset
+    const tag = bare.readU8(bc)
+    switch (tag) {
+        case 0:
+            return StopIntent.Stop
+        case 1:
+            return StopIntent.Sleep
+        case 2:
+            return StopIntent.Migrate
+        default: {
+            bc.offset = offset
+            throw new bare.BareError(offset, "invalid tag")
+        }
+    }
+}
+
+export function writeStopIntent(bc: bare.ByteCursor, x: StopIntent): void {
+    switch (x) {
+        case StopIntent.Stop: {
+            bare.writeU8(bc, 0)
+            break
+        }
+        case StopIntent.Sleep: {
+            bare.writeU8(bc, 1)
+            break
+        }
+        case StopIntent.Migrate: {
+            bare.writeU8(bc, 2)
+            break
+        }
+    }
+}
+
 export type CommandStopActor = {
     readonly actorId: Id
     readonly generation: u32
+    readonly intent: StopIntent
 }
 
 export function readCommandStopActor(bc: bare.ByteCursor): CommandStopActor {
     return {
         actorId: readId(bc),
         generation: bare.readU32(bc),
+        intent: readStopIntent(bc),
     }
 }
 
 export function writeCommandStopActor(bc: bare.ByteCursor, x: CommandStopActor): void {
     writeId(bc, x.actorId)
     bare.writeU32(bc, x.generation)
+    writeStopIntent(bc, x.intent)
+}
+
+function read4(bc: bare.ByteCursor): readonly ActorAlarm[] {
+    const len = bare.readUintSafe(bc)
+    if (len === 0) {
+        return []
+    }
+    const result = [readActorAlarm(bc)]
+    for (let i = 1; i < len; i++) {
+        result[i] = readActorAlarm(bc)
+    }
+    return result
+}
+
+function write4(bc: bare.ByteCursor, x: readonly ActorAlarm[]): void {
+    bare.writeUintSafe(bc, x.length)
+    for (let i = 0; i < x.length; i++) {
+        writeActorAlarm(bc, x[i])
+    }
+}
+
+export type CommandFireAlarms = {
+    readonly actorId: Id
+    readonly generation: u32
+    readonly alarms: readonly ActorAlarm[]
+}
+
+export function readCommandFireAlarms(bc: bare.ByteCursor): CommandFireAlarms {
+    return {
+        actorId: readId(bc),
+        generation: bare.readU32(bc),
+        alarms: read4(bc),
+    }
+}
+
+export function writeCommandFireAlarms(bc: bare.ByteCursor, x: CommandFireAlarms): void {
+    writeId(bc, x.actorId)
+    bare.writeU32(bc, x.generation)
+    write4(bc, x.alarms)
 }
 
 export type Command =
     | { readonly tag: "CommandStartActor"; readonly val: CommandStartActor }
     | { readonly tag: "CommandStopActor"; readonly val: CommandStopActor }
+    | { readonly tag: "CommandFireAlarms"; readonly val: CommandFireAlarms }
 
 export function readCommand(bc: bare.ByteCursor): Command {
     const offset = bc.offset
@@ -499,6 +726,8 @@ export function readCommand(bc: bare.ByteCursor): Command {
             return { tag: "CommandStartActor", val: readCommandStartActor(bc) }
         case 1:
             return { tag: "CommandStopActor", val: readCommandStopActor(bc) }
+        case 2:
+            return { tag: "CommandFireAlarms", val: readCommandFireAlarms(bc) }
         default: {
             bc.offset = offset
             throw new bare.BareError(offset, "invalid tag")
@@ -518,6 +747,11 @@ export function writeCommand(bc: bare.ByteCursor, x: Command): void {
             writeCommandStopActor(bc, x.val)
             break
         }
+        case "CommandFireAlarms": {
+            bare.writeU8(bc, 2)
+            writeCommandFireAlarms(bc, x.val)
+            break
+        }
     }
 }
 
@@ -538,7 +772,7 @@ export function writeCommandWrapper(bc: bare.ByteCursor, x: CommandWrapper): voi
     writeCommand(bc, x.inner)
 }
 
-function read3(bc: bare.ByteCursor): ReadonlyMap<string, ActorName> {
+function read5(bc: bare.ByteCursor): ReadonlyMap<string, ActorName> {
     const len = bare.readUintSafe(bc)
     const result = new Map<string, ActorName>()
     for (let i = 0; i < len; i++) {
@@ -553,7 +787,7 @@ function read3(bc: bare.ByteCursor): ReadonlyMap<string, ActorName> {
     return result
 }
 
-function write3(bc: bare.ByteCursor, x: ReadonlyMap<string, ActorName>): void {
+function write5(bc: bare.ByteCursor, x: ReadonlyMap<string, ActorName>): void {
     bare.writeUintSafe(bc, x.size)
     for (const kv of x) {
         bare.writeString(bc, kv[0])
@@ -561,28 +795,62 @@ function write3(bc: bare.ByteCursor, x: ReadonlyMap<string, ActorName>): void {
     }
 }
 
-function read4(bc: bare.ByteCursor): ReadonlyMap<string, ActorName> | null {
-    return bare.readBool(bc) ? read3(bc) : null
+function read6(bc: bare.ByteCursor): ReadonlyMap<string, ActorName> | null {
+    return bare.readBool(bc) ? read5(bc) : null
 }
 
-function write4(bc: bare.ByteCursor, x: ReadonlyMap<string, ActorName> | null): void {
+function write6(bc: bare.ByteCursor, x: ReadonlyMap<string, ActorName> | null): void {
     bare.writeBool(bc, x != null)
     if (x != null) {
-        write3(bc, x)
+        write5(bc, x)
     }
 }
 
-function read5(bc: bare.ByteCursor): Json | null {
+function read7(bc: bare.ByteCursor): Json | null {
     return bare.readBool(bc) ? readJson(bc) : null
 }
 
-function write5(bc: bare.ByteCursor, x: Json | null): void {
+function write7(bc: bare.ByteCursor, x: Json | null): void {
     bare.writeBool(bc, x != null)
     if (x != null) {
         writeJson(bc, x)
     }
 }
 
+function read8(bc: bare.ByteCursor): ReadonlyMap<string, string> {
+    const len = bare.readUintSafe(bc)
+    const result = new Map<string, string>()
+    for (let i = 0; i < len; i++) {
+        const offset = bc.offset
+        const key = bare.readString(bc)
+        if (result.has(key)) {
+            bc.offset = offset
+            throw new bare.BareError(offset, "duplicated key")
+        }
+        result.set(key, bare.readString(bc))
+    }
+    return result
+}
+
+function write8(bc: bare.ByteCursor, x: ReadonlyMap<string, string>): void {
+    bare.writeUintSafe(bc, x.size)
+    for (const kv of x) {
+        bare.writeString(bc, kv[0])
+        bare.writeString(bc, kv[1])
+    }
+}
+
+function read9(bc: bare.ByteCursor): ReadonlyMap<string, string> | null {
+    return bare.readBool(bc) ? read8(bc) : null
+}
+
+function write9(bc: bare.ByteCursor, x: ReadonlyMap<string, string> | null): void {
+    bare.writeBool(bc, x != null)
+    if (x != null) {
+        write8(bc, x)
+    }
+}
+
 export type ToServerInit = {
     readonly name: string
     readonly version: u32
@@ -590,6 +858,7 @@ export type ToServerInit = {
     readonly lastCommandIdx: i64 | null
     readonly prepopulateActorNames: ReadonlyMap<string, ActorName> | null
     readonly metadata: Json | null
+    readonly labels: ReadonlyMap<string, string> | null
 }
 
 export function readToServerInit(bc: bare.ByteCursor): ToServerInit {
@@ -598,8 +867,9 @@ export function readToServerInit(bc: bare.ByteCursor): ToServerInit {
         version: bare.readU32(bc),
         totalSlots: bare.readU32(bc),
         lastCommandIdx: read1(bc),
-        prepopulateActorNames: read4(bc),
-        metadata: read5(bc),
+        prepopulateActorNames: read6(bc),
+        metadata: read7(bc),
+        labels: read9(bc),
     }
 }
 
@@ -608,8 +878,9 @@ export function writeToServerInit(bc: bare.ByteCursor, x: ToServerInit): void {
     bare.writeU32(bc, x.version)
     bare.writeU32(bc, x.totalSlots)
     write1(bc, x.lastCommandIdx)
-    write4(bc, x.prepopulateActorNames)
-    write5(bc, x.metadata)
+    write6(bc, x.prepopulateActorNames)
+    write7(bc, x.metadata)
+    write9(bc, x.labels)
 }
 
 export type ToServerEvents = readonly EventWrapper[]
@@ -663,7 +934,7 @@ export function writeToServerPing(bc: bare.ByteCursor, x: ToServerPing): void {
     bare.writeI64(bc, x.ts)
 }
 
-function read6(bc: bare.ByteCursor): readonly KvKey[] {
+function read10(bc: bare.ByteCursor): readonly KvKey[] {
     const len = bare.readUintSafe(bc)
     if (len === 0) {
         return []
@@ -675,7 +946,7 @@ function read6(bc: bare.ByteCursor): readonly KvKey[] {
     return result
 }
 
-function write6(bc: bare.ByteCursor, x: readonly KvKey[]): void {
+function write10(bc: bare.ByteCursor, x: readonly KvKey[]): void {
     bare.writeUintSafe(bc, x.length)
     for (let i = 0; i < x.length; i++) {
         writeKvKey(bc, x[i])
@@ -688,30 +959,30 @@ export type KvGetRequest = {
 
 export function readKvGetRequest(bc: bare.ByteCursor): KvGetRequest {
     return {
-        keys: read6(bc),
+        keys: read10(bc),
     }
 }
 
 export function writeKvGetRequest(bc: bare.ByteCursor, x: KvGetRequest): void {
-    write6(bc, x.keys)
+    write10(bc, x.keys)
 }
 
-function read7(bc: bare.ByteCursor): boolean | null {
+function read11(bc: bare.ByteCursor): boolean | null {
     return bare.readBool(bc) ? bare.readBool(bc) : null
 }
 
-function write7(bc: bare.ByteCursor, x: boolean | null): void {
+function write11(bc: bare.ByteCursor, x: boolean | null): void {
     bare.writeBool(bc, x != null)
     if (x != null) {
         bare.writeBool(bc, x)
     }
 }
 
-function read8(bc: bare.ByteCursor): u64 | null {
+function read12(bc: bare.ByteCursor): u64 | null {
     return bare.readBool(bc) ? bare.readU64(bc) : null
 }
 
-function write8(bc: bare.ByteCursor, x: u64 | null): void {
+function write12(bc: bare.ByteCursor, x: u64 | null): void {
     bare.writeBool(bc, x != null)
     if (x != null) {
         bare.writeU64(bc, x)
@@ -727,18 +998,18 @@ export type KvListRequest = {
 export function readKvListRequest(bc: bare.ByteCursor): KvListRequest {
     return {
         query: readKvListQuery(bc),
-        reverse: read7(bc),
-        limit: read8(bc),
+        reverse: read11(bc),
+        limit: read12(bc),
     }
 }
 
 export function writeKvListRequest(bc: bare.ByteCursor, x: KvListRequest): void {
     writeKvListQuery(bc, x.query)
-    write7(bc, x.reverse)
-    write8(bc, x.limit)
+    write11(bc, x.reverse)
+    write12(bc, x.limit)
 }
 
-function read9(bc: bare.ByteCursor): readonly KvValue[] {
+function read13(bc: bare.ByteCursor): readonly KvValue[] {
     const len = bare.readUintSafe(bc)
     if (len === 0) {
         return []
@@ -750,7 +1021,7 @@ function read9(bc: bare.ByteCursor): readonly KvValue[] {
     return result
 }
 
-function write9(bc: bare.ByteCursor, x: readonly KvValue[]): void {
+function write13(bc: bare.ByteCursor, x: readonly KvValue[]): void {
     bare.writeUintSafe(bc, x.length)
     for (let i = 0; i < x.length; i++) {
         writeKvValue(bc, x[i])
@@ -764,14 +1035,14 @@ export type KvPutRequest = {
 
 export function readKvPutRequest(bc: bare.ByteCursor): KvPutRequest {
     return {
-        keys: read6(bc),
-        values: read9(bc),
+        keys: read10(bc),
+        values: read13(bc),
     }
 }
 
 export function writeKvPutRequest(bc: bare.ByteCursor, x: KvPutRequest): void {
-    write6(bc, x.keys)
-    write9(bc, x.values)
+    write10(bc, x.keys)
+    write13(bc, x.values)
 }
 
 export type KvDeleteRequest = {
@@ -780,12 +1051,12 @@ export type KvDeleteRequest = {
 
 export function readKvDeleteRequest(bc: bare.ByteCursor): KvDeleteRequest {
     return {
-        keys: read6(bc),
+        keys: read10(bc),
     }
 }
 
 export function writeKvDeleteRequest(bc: bare.ByteCursor, x: KvDeleteRequest): void {
-    write6(bc, x.keys)
+    write10(bc, x.keys)
 }
 
 export type KvDropRequest = null
@@ -867,6 +1138,98 @@ export function writeToServerKvRequest(bc: bare.ByteCursor, x: ToServerKvRequest
     writeKvRequestData(bc, x.data)
 }
 
+export enum ActorLogStream {
+    Stdout = "Stdout",
+    Stderr = "Stderr",
+}
+
+export function readActorLogStream(bc: bare.ByteCursor): ActorLogStream {
+    const offset = bc.offset
+    const tag = bare.readU8(bc)
+    switch (tag) {
+        case 0:
+            return ActorLogStream.Stdout
+        case 1:
+            return ActorLogStream.Stderr
+        default: {
+            bc.offset = offset
+            throw new bare.BareError(offset, "invalid tag")
+        }
+    }
+}
+
+export function writeActorLogStream(bc: bare.ByteCursor, x: ActorLogStream): void {
+    switch (x) {
+        case ActorLogStream.Stdout: {
+            bare.writeU8(bc, 0)
+            break
+        }
+        case ActorLogStream.Stderr: {
+            bare.writeU8(bc, 1)
+            break
+        }
+    }
+}
+
+export type ActorLogEntry = {
+    readonly stream: ActorLogStream
+    readonly ts: i64
+    readonly message: string
+}
+
+export function readActorLogEntry(bc: bare.ByteCursor): ActorLogEntry {
+    return {
+        stream: readActorLogStream(bc),
+        ts: bare.readI64(bc),
+        message: bare.readString(bc),
+    }
+}
+
+export function writeActorLogEntry(bc: bare.ByteCursor, x: ActorLogEntry): void {
+    writeActorLogStream(bc, x.stream)
+    bare.writeI64(bc, x.ts)
+    bare.writeString(bc, x.message)
+}
+
+function read14(bc: bare.ByteCursor): readonly ActorLogEntry[] {
+    const len = bare.readUintSafe(bc)
+    if (len === 0) {
+        return []
+    }
+    const result = [readActorLogEntry(bc)]
+    for (let i = 1; i < len; i++) {
+        result[i] = readActorLogEntry(bc)
+    }
+    return result
+}
+
+function write14(bc: bare.ByteCursor, x: readonly ActorLogEntry[]): void {
+    bare.writeUintSafe(bc, x.length)
+    for (let i = 0; i < x.length; i++) {
+        writeActorLogEntry(bc, x[i])
+    }
+}
+
+export type ToServerActorLogs = {
+    readonly actorId: Id
+    readonly generation: u32
+    readonly entries: readonly ActorLogEntry[]
+}
+
+export function readToServerActorLogs(bc: bare.ByteCursor): ToServerActorLogs {
+    return {
+        actorId: readId(bc),
+        generation: bare.readU32(bc),
+        entries: read14(bc),
+    }
+}
+
+export function writeToServerActorLogs(bc: bare.ByteCursor, x: ToServerActorLogs): void {
+    writeId(bc, x.actorId)
+    bare.writeU32(bc, x.generation)
+    write14(bc, x.entries)
+}
+
 export type ToServer =
     | { readonly tag: "ToServerInit"; readonly val: ToServerInit }
     | { readonly tag: "ToServerEvents"; readonly val: ToServerEvents }
@@ -874,6 +1237,7 @@ export type ToServer =
     | { readonly tag: "ToServerStopping"; readonly val: ToServerStopping }
     | { readonly tag: "ToServerPing"; readonly val: ToServerPing }
     | { readonly tag: "ToServerKvRequest"; readonly val: ToServerKvRequest }
+    | { readonly tag: "ToServerActorLogs"; readonly val: ToServerActorLogs }
 
 export function readToServer(bc: bare.ByteCursor): ToServer {
     const offset = bc.offset
@@ -891,6 +1255,8 @@ export function readToServer(bc: bare.ByteCursor): ToServer {
             return { tag: "ToServerPing", val: readToServerPing(bc) }
         case 5:
             return { tag: "ToServerKvRequest", val: readToServerKvRequest(bc) }
+        case 6:
+            return { tag: "ToServerActorLogs", val: readToServerActorLogs(bc) }
         default: {
             bc.offset = offset
             throw new bare.BareError(offset, "invalid tag")
@@ -929,6 +1295,11 @@ export function writeToServer(bc: bare.ByteCursor, x: ToServer): void {
             writeToServerKvRequest(bc, x.val)
             break
         }
+        case "ToServerActorLogs": {
+            bare.writeU8(bc, 6)
+            writeToServerActorLogs(bc, x.val)
+            break
+        }
     }
 }
 
@@ -1034,7 +1405,7 @@ export function writeKvErrorResponse(bc: bare.ByteCursor, x: KvErrorResponse): v
     bare.writeString(bc, x.message)
 }
 
-function read10(bc: bare.ByteCursor): readonly KvMetadata[] {
+function read15(bc: bare.ByteCursor): readonly KvMetadata[] {
     const len = bare.readUintSafe(bc)
     if (len === 0) {
         return []
@@ -1046,7 +1417,7 @@ function read10(bc: bare.ByteCursor): readonly KvMetadata[] {
     return result
 }
 
-function write10(bc: bare.ByteCursor, x: readonly KvMetadata[]): void {
+function write15(bc: bare.ByteCursor, x: readonly KvMetadata[]): void {
     bare.writeUintSafe(bc, x.length)
     for (let i = 0; i < x.length; i++) {
         writeKvMetadata(bc, x[i])
@@ -1061,16 +1432,16 @@ export type KvGetResponse = {
 
 export function readKvGetResponse(bc: bare.ByteCursor): KvGetResponse {
     return {
-        keys: read6(bc),
-        values: read9(bc),
-        metadata: read10(bc),
+        keys: read10(bc),
+        values: read13(bc),
+        metadata: read15(bc),
     }
 }
 
 export function writeKvGetResponse(bc: bare.ByteCursor, x: KvGetResponse): void {
-    write6(bc, x.keys)
-    write9(bc, x.values)
-    write10(bc, x.metadata)
+    write10(bc, x.keys)
+    write13(bc, x.values)
+    write15(bc, x.metadata)
 }
 
 export type KvListResponse = {
@@ -1081,16 +1452,16 @@ export type KvListResponse = {
 
 export function readKvListResponse(bc: bare.ByteCursor): KvListResponse {
     return {
-        keys: read6(bc),
-        values: read9(bc),
-        metadata: read10(bc),
+        keys: read10(bc),
+        values: read13(bc),
+        metadata: read15(bc),
     }
 }
 
 export function writeKvListResponse(bc: bare.ByteCursor, x: KvListResponse): void {
-    write6(bc, x.keys)
-    write9(bc, x.values)
-    write10(bc, x.metadata)
+    write10(bc, x.keys)
+    write13(bc, x.values)
+    write15(bc, x.metadata)
 }
 
 export type KvPutResponse = null
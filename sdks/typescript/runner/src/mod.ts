@@ -24,6 +24,12 @@ export interface ActorConfig {
 	input: Uint8Array | null;
 }
 
+export interface ActorAlarm {
+	name: string;
+	ts: bigint;
+	payload: Uint8Array | null;
+}
+
 export interface RunnerConfig {
 	logger?: Logger;
 	version: number;
@@ -36,6 +42,8 @@ export interface RunnerConfig {
 	runnerKey: string;
 	prepopulateActorNames: Record<string, { metadata: Record<string, any> }>;
 	metadata?: Record<string, any>;
+	/** Labels matched against actor placement constraints. */
+	labels?: Record<string, string>;
 	onConnected: () => void;
 	onDisconnected: () => void;
 	onShutdown: () => void;
@@ -47,6 +55,15 @@ export interface RunnerConfig {
 		config: ActorConfig,
 	) => Promise<void>;
 	onActorStop: (actorId: string, generation: number) => Promise<void>;
+	/**
+	 * Called when named alarms of an actor fire. Alarms are delivered again the next time the actor
+	 * is started unless this resolves.
+	 */
+	onAlarmsFired?: (
+		actorId: string,
+		generation: number,
+		alarms: ActorAlarm[],
+	) => Promise<void>;
 	noAutoShutdown?: boolean;
 }
 
@@ -394,7 +411,7 @@ export class Runner {
 		const wsEndpoint = endpoint
 			.replace("http://", "ws://")
 			.replace("https://", "wss://");
-		return `${wsEndpoint}?protocol_version=1&namespace=${encodeURIComponent(this.#config.namespace)}&runner_key=${encodeURIComponent(this.#config.runnerKey)}`;
+		return `${wsEndpoint}?protocol_version=2&namespace=${encodeURIComponent(this.#config.namespace)}&runner_key=${encodeURIComponent(this.#config.runnerKey)}`;
 	}
 
 	get pegboardTunnelUrl() {
@@ -405,7 +422,7 @@ export class Runner {
 		const wsEndpoint = endpoint
 			.replace("http://", "ws://")
 			.replace("https://", "wss://");
-		return `${wsEndpoint}?protocol_version=1&namespace=${encodeURIComponent(this.#config.namespace)}&runner_key=${this.#config.runnerKey}`;
+		return `${wsEndpoint}?protocol_version=2&namespace=${encodeURIComponent(this.#config.namespace)}&runner_key=${this.#config.runnerKey}`;
 	}
 
 	async #openTunnelAndWait(): Promise<void> {
@@ -489,6 +506,9 @@ export class Runner {
 					),
 				),
 				metadata: JSON.stringify(this.#config.metadata),
+				labels: this.#config.labels
+					? new Map(Object.entries(this.#config.labels))
+					: null,
 			};
 
 			this.#sendToServer({
@@ -633,6 +653,8 @@ export class Runner {
 				this.#handleCommandStartActor(commandWrapper);
 			} else if (commandWrapper.inner.tag === "CommandStopActor") {
 				this.#handleCommandStopActor(commandWrapper);
+			} else if (commandWrapper.inner.tag === "CommandFireAlarms") {
+				this.#handleCommandFireAlarms(commandWrapper);
 			}
 
 			this.#lastCommandIdx = Number(commandWrapper.index);
@@ -693,6 +715,90 @@ export class Runner {
 		this.stopActor(actorId, generation);
 	}
 
+	#handleCommandFireAlarms(commandWrapper: protocol.CommandWrapper) {
+		const fireCommand = commandWrapper.inner
+			.val as protocol.CommandFireAlarms;
+
+		const actorId = fireCommand.actorId;
+		const generation = fireCommand.generation;
+
+		const actor = this.getActor(actorId, generation);
+		if (!actor) {
+			logger()?.warn({
+				msg: "alarms fired for unknown actor",
+				actorId,
+				generation,
+			});
+			return;
+		}
+
+		const alarms: ActorAlarm[] = fireCommand.alarms.map((alarm) => ({
+			name: alarm.name,
+			ts: alarm.ts,
+			payload: alarm.payload ? new Uint8Array(alarm.payload) : null,
+		}));
+
+		if (!this.#config.onAlarmsFired) {
+			// Acknowledged anyway so the alarms are not delivered on every start
+			logger()?.warn({
+				msg: "no onAlarmsFired handler, dropping alarms",
+				actorId,
+				count: alarms.length,
+			});
+			this.#sendAlarmsAck(actorId, generation, alarms);
+			return;
+		}
+
+		this.#config
+			.onAlarmsFired(actorId, generation, alarms)
+			.then(() => this.#sendAlarmsAck(actorId, generation, alarms))
+			.catch((err) => {
+				// Not acknowledged, the alarms are fired again when the actor is next started
+				logger()?.error({
+					msg: "error in onalarmsfired for actor",
+					actorId,
+					err,
+				});
+			});
+	}
+
+	#sendAlarmsAck(
+		actorId: string,
+		generation: number,
+		alarms: ActorAlarm[],
+	) {
+		if (this.#shutdown) {
+			logger()?.warn("Runner is shut down, cannot acknowledge alarms");
+			return;
+		}
+
+		const ackEvent: protocol.EventActorAckAlarms = {
+			actorId,
+			generation,
+			alarms: alarms.map((alarm) => ({ name: alarm.name, ts: alarm.ts })),
+		};
+
+		const eventIndex = this.#nextEventIdx++;
+		const eventWrapper: protocol.EventWrapper = {
+			index: eventIndex,
+			inner: {
+				tag: "EventActorAckAlarms",
+				val: ackEvent,
+			},
+		};
+
+		// Store event in history for potential resending
+		this.#eventHistory.push({
+			event: eventWrapper,
+			timestamp: Date.now(),
+		});
+
+		this.#sendToServer({
+			tag: "ToServerEvents",
+			val: [eventWrapper],
+		});
+	}
+
 	#sendActorIntent(
 		actorId: string,
 		generation: number,
@@ -1164,6 +1270,36 @@ export class Runner {
 		this.setAlarm(actorId, null, generation);
 	}
 
+	// MARK: Log Operations
+	/**
+	 * Ships log lines to the engine. Logs are best effort and are dropped while disconnected.
+	 */
+	sendLogs(
+		actorId: string,
+		entries: { stream: "stdout" | "stderr"; message: string }[],
+		generation?: number,
+	) {
+		const actor = this.getActor(actorId, generation);
+		if (!actor) return;
+
+		const ts = BigInt(Date.now());
+		this.#sendToServer({
+			tag: "ToServerActorLogs",
+			val: {
+				actorId,
+				generation: actor.generation,
+				entries: entries.map((entry) => ({
+					stream:
+						entry.stream === "stdout"
+							? protocol.ActorLogStream.Stdout
+							: protocol.ActorLogStream.Stderr,
+					ts,
+					message: entry.message,
+				})),
+			},
+		});
+	}
+
 	#sendKvRequest(
 		actorId: string,
 		requestData: protocol.KvRequestData,